@@ -1,3 +1,5 @@
+mod offline;
+
 use crate::api::check;
 use crate::{cli_input::CheckCommand, errors::CliError, report};
 use std::{
@@ -10,29 +12,22 @@ const FAILED_CHECK_EXIT_STATUS: i32 = 1;
 
 #[tokio::main]
 pub(crate) async fn check(command: CheckCommand) -> Result<(), CliError> {
+    if command.base.is_some() {
+        return offline::check(command);
+    }
+
     let CheckCommand {
-        graph_ref,
-        subgraph_name,
+        graph_ref: Some(graph_ref),
+        subgraph_name: Some(subgraph_name),
         schema,
-    } = command;
+        ..
+    } = command
+    else {
+        return Err(CliError::MissingArgument("a graph reference and --name, or --base"));
+    };
 
     let git_commit = find_git_commit();
-
-    let schema = match schema {
-        Some(schema) => fs::read_to_string(schema).map_err(CliError::SchemaReadError)?,
-        None if std::io::stdin().is_terminal() => {
-            return Err(CliError::MissingArgument("--schema or a schema piped through stdin"));
-        }
-        None => {
-            let mut schema = String::new();
-
-            std::io::stdin()
-                .read_to_string(&mut schema)
-                .map_err(CliError::SchemaReadError)?;
-
-            schema
-        }
-    };
+    let schema = read_schema(schema)?;
 
     report::checking();
 
@@ -71,6 +66,27 @@ pub(crate) async fn check(command: CheckCommand) -> Result<(), CliError> {
     Ok(())
 }
 
+/// Reads the schema to check from the given path, or from stdin.
+fn read_schema(schema: Option<String>) -> Result<String, CliError> {
+    let schema = match schema {
+        Some(schema) => fs::read_to_string(schema).map_err(CliError::SchemaReadError)?,
+        None if std::io::stdin().is_terminal() => {
+            return Err(CliError::MissingArgument("--schema or a schema piped through stdin"));
+        }
+        None => {
+            let mut schema = String::new();
+
+            std::io::stdin()
+                .read_to_string(&mut schema)
+                .map_err(CliError::SchemaReadError)?;
+
+            schema
+        }
+    };
+
+    Ok(schema)
+}
+
 fn find_git_commit() -> Option<check::SchemaCheckGitCommitInput> {
     let git_author = git_author();
     let git_sha = git_sha();
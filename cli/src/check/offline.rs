@@ -0,0 +1,536 @@
+use super::{FAILED_CHECK_EXIT_STATUS, read_schema};
+use crate::{
+    cli_input::{CheckCommand, CheckOutputFormat},
+    common::trusted_documents::TrustedDocumentsManifest,
+    errors::CliError,
+    report,
+};
+use async_graphql_parser::types::{DocumentOperations, ExecutableDocument};
+use graphql_schema_diff::Change;
+use operation_checks::{CheckDiagnostic, CheckParams, FieldUsage, Operation, Schema, Severity};
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const OPERATION_FILE_EXTENSIONS: [&str; 2] = ["graphql", "gql"];
+
+/// Checks the proposed schema against a schema on disk, without going through the Grafbase
+/// platform. The operations, if any, are read from local files.
+pub(super) fn check(command: CheckCommand) -> Result<(), CliError> {
+    let CheckCommand {
+        base,
+        schema,
+        operations,
+        request_count_threshold,
+        format,
+        ..
+    } = command;
+
+    let Some(base_path) = base else {
+        return Err(CliError::MissingArgument("--base"));
+    };
+
+    let base = fs::read_to_string(&base_path).map_err(|error| CliError::CheckReadFile(base_path.clone(), error))?;
+    let proposed_path = schema.as_ref().map(PathBuf::from);
+    let proposed = read_schema(schema)?;
+
+    let mut used_operations = Vec::new();
+
+    for path in &operations {
+        used_operations.extend(load_operations(path)?);
+    }
+
+    let (changes, diagnostics) = run_check(&base, &proposed, &used_operations, request_count_threshold)?;
+
+    let has_errors = diagnostics
+        .iter()
+        .any(|diagnostic| matches!(diagnostic.severity, Severity::Error));
+
+    let base = SchemaFile {
+        path: Some(&base_path),
+        sdl: &base,
+    };
+
+    let proposed = SchemaFile {
+        path: proposed_path.as_deref(),
+        sdl: &proposed,
+    };
+
+    let locations = diagnostics
+        .iter()
+        .map(|diagnostic| location(diagnostic, &changes, &base, &proposed))
+        .collect::<Vec<_>>();
+
+    match format {
+        CheckOutputFormat::Human if diagnostics.is_empty() => report::check_success(),
+        CheckOutputFormat::Human => report::offline_check_errors(has_errors, &diagnostics),
+        CheckOutputFormat::Json => {
+            let report = json_report(&diagnostics, &locations);
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+        CheckOutputFormat::Sarif => {
+            let report = sarif_report(&diagnostics, &locations);
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        }
+    }
+
+    if has_errors {
+        std::process::exit(FAILED_CHECK_EXIT_STATUS);
+    }
+
+    Ok(())
+}
+
+struct UsedOperation {
+    operation: Operation,
+    /// How many requests used the operation.
+    count: u64,
+}
+
+/// Operations exported with their request counts, for example from the graph analytics.
+#[derive(serde::Deserialize)]
+struct UsageExport {
+    operations: Vec<UsageExportOperation>,
+}
+
+#[derive(serde::Deserialize)]
+struct UsageExportOperation {
+    document: String,
+    #[serde(default = "default_request_count")]
+    count: u64,
+}
+
+fn default_request_count() -> u64 {
+    1
+}
+
+fn run_check(
+    base: &str,
+    proposed: &str,
+    operations: &[UsedOperation],
+    request_count_threshold: Option<u64>,
+) -> Result<(Vec<Change>, Vec<CheckDiagnostic>), CliError> {
+    let source: Schema = async_graphql_parser::parse_schema(base)
+        .map_err(|error| CliError::CheckInvalidSchema("base", error.to_string()))?
+        .into();
+
+    let target: Schema = async_graphql_parser::parse_schema(proposed)
+        .map_err(|error| CliError::CheckInvalidSchema("proposed", error.to_string()))?
+        .into();
+
+    let changes = graphql_schema_diff::diff(base, proposed)
+        .map_err(|error| CliError::CheckInvalidSchema("base or proposed", error.to_string()))?;
+
+    if operations.is_empty() {
+        let diagnostics = operation_checks::check_assuming_all_used(&source, &target, &changes);
+        return Ok((changes, diagnostics));
+    }
+
+    let mut field_usage = FieldUsage::default();
+
+    for UsedOperation { operation, count } in operations {
+        field_usage.set_increment(*count);
+        operation_checks::aggregate_field_usage(operation, &source, &mut field_usage);
+    }
+
+    if let Some(threshold) = request_count_threshold {
+        field_usage.apply_request_count_threshold(threshold);
+    }
+
+    let diagnostics = operation_checks::check(&CheckParams {
+        source: &source,
+        target: &target,
+        diff: &changes,
+        field_usage: &field_usage,
+    });
+
+    Ok((changes, diagnostics))
+}
+
+/// Reads operations from a directory of GraphQL files, a single GraphQL file, a trusted documents
+/// manifest or a usage export.
+fn load_operations(path: &Path) -> Result<Vec<UsedOperation>, CliError> {
+    let invalid = |message: String| CliError::CheckInvalidOperations(path.to_owned(), message);
+
+    if path.is_dir() {
+        let mut files = Vec::new();
+        find_operation_files(path, &mut files)?;
+        files.sort();
+
+        let mut documents = Vec::with_capacity(files.len());
+
+        for file in files {
+            let text = fs::read_to_string(&file).map_err(|error| CliError::CheckReadFile(file.clone(), error))?;
+            let document = async_graphql_parser::parse_query(&text)
+                .map_err(|error| CliError::CheckInvalidOperations(file, error.to_string()))?;
+
+            documents.push(document);
+        }
+
+        // Fragments can be defined in any file of the directory.
+        return Ok(split_operations(documents)
+            .map(|operation| UsedOperation { operation, count: 1 })
+            .collect());
+    }
+
+    let text = fs::read_to_string(path).map_err(|error| CliError::CheckReadFile(path.to_owned(), error))?;
+
+    if path.extension().is_some_and(|extension| extension == "json") {
+        return parse_json_operations(&text).map_err(invalid);
+    }
+
+    let document = async_graphql_parser::parse_query(&text).map_err(|error| invalid(error.to_string()))?;
+
+    Ok(split_operations(vec![document])
+        .map(|operation| UsedOperation { operation, count: 1 })
+        .collect())
+}
+
+fn parse_json_operations(text: &str) -> Result<Vec<UsedOperation>, String> {
+    let documents: Vec<(String, u64)> = match serde_json::from_str::<UsageExport>(text) {
+        Ok(export) => export
+            .operations
+            .into_iter()
+            .map(|operation| (operation.document, operation.count))
+            .collect(),
+        Err(_) => serde_json::from_str::<TrustedDocumentsManifest>(text)
+            .map_err(|_| "expecting a trusted documents manifest or a usage export".to_owned())?
+            .into_documents()
+            .map(|document| (document.document_text, 1))
+            .collect(),
+    };
+
+    let mut operations = Vec::new();
+
+    for (text, count) in documents {
+        let document = async_graphql_parser::parse_query(&text).map_err(|error| error.to_string())?;
+        operations.extend(split_operations(vec![document]).map(|operation| UsedOperation { operation, count }));
+    }
+
+    Ok(operations)
+}
+
+fn find_operation_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), CliError> {
+    let read_error = |error: io::Error| CliError::CheckReadFile(dir.to_owned(), error);
+
+    for entry in fs::read_dir(dir).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+
+        if path.is_dir() {
+            find_operation_files(&path, files)?;
+        } else if path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| OPERATION_FILE_EXTENSIONS.contains(&extension))
+        {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Operation checks only look at the first operation of a document, so each operation gets its
+/// own document, with the fragments of all the documents.
+fn split_operations(documents: Vec<ExecutableDocument>) -> impl Iterator<Item = Operation> {
+    let mut fragments = HashMap::new();
+
+    for document in &documents {
+        fragments.extend(document.fragments.clone());
+    }
+
+    documents.into_iter().flat_map(move |document| {
+        document
+            .operations
+            .iter()
+            .map(|(_, operation)| {
+                Operation::from(ExecutableDocument {
+                    operations: DocumentOperations::Single(operation.clone()),
+                    fragments: fragments.clone(),
+                })
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+struct SchemaFile<'a> {
+    /// Not available when the schema is read from stdin.
+    path: Option<&'a Path>,
+    sdl: &'a str,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location<'a> {
+    file: &'a Path,
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+}
+
+/// Removals are located in the base schema, all the other changes in the proposed schema.
+fn location<'a>(
+    diagnostic: &CheckDiagnostic,
+    changes: &[Change],
+    base: &SchemaFile<'a>,
+    proposed: &SchemaFile<'a>,
+) -> Option<Location<'a>> {
+    let change = changes
+        .iter()
+        .find(|change| change.kind == diagnostic.change_kind && change.path == diagnostic.path)?;
+
+    // Some changes have no span.
+    if change.span.start == change.span.end {
+        return None;
+    }
+
+    let schema = if change.kind.as_str().starts_with("Remove") {
+        base
+    } else {
+        proposed
+    };
+
+    let (start_line, start_column) = line_column(schema.sdl, change.span.start);
+    let (end_line, end_column) = line_column(schema.sdl, change.span.end);
+
+    Some(Location {
+        file: schema.path?,
+        start_line,
+        start_column,
+        end_line,
+        end_column,
+    })
+}
+
+/// One-based line and column of a byte offset.
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = text.get(..offset).unwrap_or(text);
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+
+    (line, column)
+}
+
+fn severity_str(severity: &Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+fn json_report(diagnostics: &[CheckDiagnostic], locations: &[Option<Location<'_>>]) -> serde_json::Value {
+    let success = !diagnostics
+        .iter()
+        .any(|diagnostic| matches!(diagnostic.severity, Severity::Error));
+
+    let diagnostics = diagnostics
+        .iter()
+        .zip(locations)
+        .map(|(diagnostic, location)| {
+            json!({
+                "severity": severity_str(&diagnostic.severity),
+                "message": diagnostic.message,
+                "path": diagnostic.path,
+                "changeKind": diagnostic.change_kind.as_str(),
+                "location": location,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "success": success,
+        "diagnostics": diagnostics,
+    })
+}
+
+/// A SARIF 2.1.0 log, the format code scanning tools such as GitHub's expect.
+fn sarif_report(diagnostics: &[CheckDiagnostic], locations: &[Option<Location<'_>>]) -> serde_json::Value {
+    let mut rules = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.change_kind.as_str())
+        .collect::<Vec<_>>();
+
+    rules.sort_unstable();
+    rules.dedup();
+
+    let rules = rules.into_iter().map(|rule| json!({ "id": rule })).collect::<Vec<_>>();
+
+    let results = diagnostics
+        .iter()
+        .zip(locations)
+        .map(|(diagnostic, location)| {
+            let mut sarif_location = json!({
+                "logicalLocations": [{ "fullyQualifiedName": diagnostic.path }],
+            });
+
+            if let Some(location) = location {
+                sarif_location["physicalLocation"] = json!({
+                    "artifactLocation": {
+                        "uri": location.file.to_string_lossy().replace('\\', "/"),
+                    },
+                    "region": {
+                        "startLine": location.start_line,
+                        "startColumn": location.start_column,
+                        "endLine": location.end_line,
+                        "endColumn": location.end_column,
+                    },
+                });
+            }
+
+            json!({
+                "ruleId": diagnostic.change_kind.as_str(),
+                "level": severity_str(&diagnostic.severity),
+                "message": { "text": diagnostic.message },
+                "locations": [sarif_location],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "grafbase",
+                    "informationUri": "https://grafbase.com",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"
+        type Query {
+            user(id: ID!): User
+            users: [User!]!
+        }
+
+        type User {
+            id: ID!
+            name: String
+            email: String
+        }
+    "#;
+
+    const PROPOSED: &str = r#"
+        type Query {
+            user(id: ID!): User
+            users: [User!]!
+        }
+
+        type User {
+            id: ID!
+            name: String
+        }
+    "#;
+
+    fn messages(diagnostics: &[CheckDiagnostic]) -> Vec<(&'static str, &str)> {
+        diagnostics
+            .iter()
+            .map(|diagnostic| (severity_str(&diagnostic.severity), diagnostic.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn assumes_everything_is_used_without_operations() {
+        let (_, diagnostics) = run_check(BASE, PROPOSED, &[], None).unwrap();
+
+        assert_eq!(
+            messages(&diagnostics),
+            [(
+                "error",
+                "The field `User.email` was removed but it is still used by clients."
+            )]
+        );
+    }
+
+    #[test]
+    fn usage_export_with_request_count_threshold() {
+        let export = r#"
+            {
+              "operations": [
+                { "document": "query { users { id name } }", "count": 100 },
+                { "document": "query GetUser { user(id: 1) { email } }", "count": 5 }
+              ]
+            }
+        "#;
+
+        let operations = parse_json_operations(export).unwrap();
+
+        let (_, diagnostics) = run_check(BASE, PROPOSED, &operations, None).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+
+        let (_, diagnostics) = run_check(BASE, PROPOSED, &operations, Some(10)).unwrap();
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn trusted_documents_manifest() {
+        let manifest = r#"
+            {
+              "a": "query A { users { id } }",
+              "b": "query B { users { name } } query C { user(id: 1) { email } }"
+            }
+        "#;
+
+        let operations = parse_json_operations(manifest).unwrap();
+        assert_eq!(operations.len(), 3);
+
+        let (_, diagnostics) = run_check(BASE, PROPOSED, &operations, None).unwrap();
+
+        assert_eq!(
+            messages(&diagnostics),
+            [(
+                "error",
+                "The field `User.email` was removed but it is still used by clients."
+            )]
+        );
+    }
+
+    #[test]
+    fn fragments_are_shared_across_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("fragments")).unwrap();
+        fs::write(
+            dir.path().join("fragments/user.graphql"),
+            "fragment UserFields on User { id email }",
+        )
+        .unwrap();
+        fs::write(dir.path().join("users.gql"), "query Users { users { ...UserFields } }").unwrap();
+        fs::write(dir.path().join("README.md"), "not an operation").unwrap();
+
+        let operations = load_operations(dir.path()).unwrap();
+        assert_eq!(operations.len(), 1);
+
+        let (_, diagnostics) = run_check(BASE, PROPOSED, &operations, None).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn invalid_operations_file() {
+        let error = parse_json_operations(r#"{ "operations": 1 }"#).err().unwrap();
+
+        assert_eq!(error, "expecting a trusted documents manifest or a usage export");
+    }
+
+    #[test]
+    fn line_and_column() {
+        let text = "type Query {\n  éa: Int\n}";
+
+        assert_eq!(line_column(text, 0), (1, 1));
+        assert_eq!(line_column(text, 17), (2, 4));
+        assert_eq!(line_column(text, text.len()), (3, 2));
+    }
+}
@@ -15,11 +15,19 @@ mod mcp;
 mod publish;
 mod schema;
 mod schema_proposal;
+mod sign;
 mod sub_command;
 mod subgraph;
 mod trust;
 
-pub(crate) use self::{check::CheckCommand, compose::*, extension::*, mcp::*, schema_proposal::*, trust::TrustCommand};
+pub(crate) use self::{
+    check::{CheckCommand, CheckOutputFormat},
+    compose::*,
+    extension::*,
+    mcp::*,
+    schema_proposal::*,
+    trust::TrustCommand,
+};
 pub(crate) use branch::BranchSubCommand;
 pub(crate) use branch_ref::BranchRef;
 pub(crate) use completions::CompletionsCommand;
@@ -31,6 +39,7 @@ pub(crate) use lint::LintCommand;
 pub(crate) use login::LoginCommand;
 pub(crate) use publish::PublishCommand;
 pub(crate) use schema::SchemaCommand;
+pub(crate) use sign::SignCommand;
 pub(crate) use sub_command::RequiresLogin;
 pub(crate) use sub_command::SubCommand;
 pub(crate) use subgraph::{SubgraphCommand, SubgraphSubCommand};
@@ -1,15 +1,47 @@
+use std::path::PathBuf;
+
 use super::FullGraphRef;
 
 #[derive(Debug, clap::Args)]
 pub struct CheckCommand {
-    #[arg(help = FullGraphRef::ARG_DESCRIPTION)]
-    pub graph_ref: FullGraphRef,
+    #[arg(help = FullGraphRef::ARG_DESCRIPTION, required_unless_present = "base")]
+    pub graph_ref: Option<FullGraphRef>,
     /// The name of the subgraph to check
-    #[arg(long("name"))]
-    pub(crate) subgraph_name: String,
+    #[arg(long("name"), required_unless_present = "base")]
+    pub(crate) subgraph_name: Option<String>,
 
     /// The path to the GraphQL schema to check. If this is not provided, the schema will be read
     /// from stdin.
     #[arg(long)]
     pub schema: Option<String>,
+
+    /// Check offline against the subgraph or federated schema at this path, instead of the
+    /// schema in the registry.
+    #[arg(long, conflicts_with_all = ["graph_ref", "subgraph_name"])]
+    pub base: Option<PathBuf>,
+
+    /// The operations to check the schema changes against, in offline mode. Either a trusted
+    /// documents manifest, a directory of GraphQL files or a usage export. Can be repeated. If not
+    /// provided, the whole schema is assumed to be in use.
+    #[arg(long, requires = "base")]
+    pub operations: Vec<PathBuf>,
+
+    /// Ignore the schema elements used by fewer requests than this, in usage exports.
+    #[arg(long, requires = "base")]
+    pub request_count_threshold: Option<u64>,
+
+    /// The output format of the offline check
+    #[arg(long, value_enum, default_value_t = CheckOutputFormat::Human)]
+    pub format: CheckOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum, strum::AsRefStr, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum CheckOutputFormat {
+    /// Human readable output
+    Human,
+    /// A JSON report
+    Json,
+    /// A SARIF 2.1.0 log, for code scanning tools
+    Sarif,
 }
@@ -13,6 +13,18 @@ pub(crate) struct ComposeCommand {
     /// The path of the gateway configuration file
     #[arg(short('c'), long("config"))]
     config_path: Option<PathBuf>,
+    /// The output format of the composition diagnostics
+    #[arg(long, value_enum, default_value_t = ComposeOutputFormat::Human)]
+    pub(crate) format: ComposeOutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum, strum::AsRefStr, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub(crate) enum ComposeOutputFormat {
+    /// Human readable output, with the relevant parts of the subgraph schemas
+    Human,
+    /// A JSON report, for CI annotations
+    Json,
 }
 
 impl ComposeCommand {
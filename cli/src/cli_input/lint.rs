@@ -6,4 +6,12 @@ use std::path::PathBuf;
 pub struct LintCommand {
     /// The path of the schema to lint
     pub schema: Option<PathBuf>,
+    /// The path of the linter configuration. Defaults to `grafbase-lint.toml` in the current
+    /// directory, if it exists.
+    #[arg(short, long)]
+    pub config: Option<PathBuf>,
+    /// Rewrite the naming convention violations in place. With a schema from stdin, the fixed
+    /// schema is printed to stdout.
+    #[arg(long)]
+    pub fix: bool,
 }
@@ -0,0 +1,15 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Sign a federated schema, for gateways verifying schema signatures
+#[derive(Debug, Parser)]
+pub(crate) struct SignCommand {
+    /// The path of the federated schema to sign. Read from stdin if not provided.
+    pub(crate) schema: Option<PathBuf>,
+    /// The path of the PEM encoded Ed25519 or ECDSA P-256 private key
+    #[arg(short, long, env = "GRAFBASE_SCHEMA_SIGNING_KEY_PATH")]
+    pub(crate) key: PathBuf,
+    /// Write the signed schema to this path instead of stdout
+    #[arg(short, long)]
+    pub(crate) output: Option<PathBuf>,
+}
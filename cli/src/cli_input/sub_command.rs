@@ -4,8 +4,8 @@ use crate::{cli_input::ExtensionSubCommand, is_not_direct_install};
 
 use super::{
     CheckCommand, CompletionsCommand, CreateCommand, DevCommand, ExtensionCommand, IntrospectCommand, LintCommand,
-    LoginCommand, PublishCommand, SchemaCommand, SchemaProposalCommand, SubgraphCommand, branch::BranchCommand,
-    compose::ComposeCommand, mcp::McpCommand, trust::TrustCommand,
+    LoginCommand, PublishCommand, SchemaCommand, SchemaProposalCommand, SignCommand, SubgraphCommand,
+    branch::BranchCommand, compose::ComposeCommand, mcp::McpCommand, trust::TrustCommand,
 };
 
 #[derive(Debug, Parser, strum::AsRefStr, strum::Display)]
@@ -44,6 +44,8 @@ pub(crate) enum SubCommand {
     Upgrade,
     /// Lint a schema
     Lint(LintCommand),
+    /// Sign a federated schema
+    Sign(SignCommand),
     /// Start the development server
     Dev(DevCommand),
     /// Start the MCP server
@@ -67,7 +69,7 @@ impl RequiresLogin for SubCommand {
                 | SubCommand::Trust(_)
                 | SubCommand::Subgraph(_)
                 | SubCommand::SchemaProposal(_)
-                | SubCommand::Check(_)
+                | SubCommand::Check(CheckCommand { graph_ref: Some(_), .. })
                 | SubCommand::Branch(_)
                 | SubCommand::Schema(_)
                 | SubCommand::Compose(ComposeCommand { graph_ref: Some(_), .. })
@@ -1,4 +1,14 @@
-use crate::{cli_input::ComposeCommand, dev::SubgraphCache, output::report};
+mod snippet;
+
+pub(crate) use snippet::snippet;
+
+use crate::{
+    cli_input::{ComposeCommand, ComposeOutputFormat},
+    dev::SubgraphCache,
+    output::report,
+};
+use graphql_composition::diagnostics::{CompositeSchemasErrorCode, Diagnostic, Severity};
+use serde_json::json;
 
 #[tokio::main]
 pub(crate) async fn compose(args: ComposeCommand) -> anyhow::Result<()> {
@@ -21,8 +31,89 @@ pub(crate) async fn compose(args: ComposeCommand) -> anyhow::Result<()> {
             Ok(())
         }
         Err(diagnostics) => {
-            report::composition_diagnostics(&diagnostics);
+            match args.format {
+                ComposeOutputFormat::Human => {
+                    let sdls = subgraph_cache.subgraph_sdls().await;
+                    report::composition_diagnostics(&diagnostics, &sdls);
+                }
+                ComposeOutputFormat::Json => {
+                    let report = json_report(&diagnostics);
+                    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+                }
+            }
+
             std::process::exit(1)
         }
     }
 }
+
+fn json_report(diagnostics: &graphql_composition::Diagnostics) -> serde_json::Value {
+    let diagnostics = diagnostics.iter().map(json_diagnostic).collect::<Vec<_>>();
+
+    json!({
+        "success": false,
+        "diagnostics": diagnostics,
+    })
+}
+
+fn json_diagnostic(diagnostic: &Diagnostic) -> serde_json::Value {
+    let span = diagnostic.span().map(|span| {
+        json!({
+            "start": { "line": span.start.line, "column": span.start.column },
+            "end": { "line": span.end.line, "column": span.end.column },
+        })
+    });
+
+    json!({
+        "severity": match diagnostic.severity() {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        },
+        "message": diagnostic.message(),
+        "subgraph": diagnostic.subgraph(),
+        "span": span,
+        "code": diagnostic.composite_schemas_error_code().map(error_code),
+    })
+}
+
+fn error_code(code: CompositeSchemasErrorCode) -> String {
+    match code {
+        CompositeSchemasErrorCode::SourceSchema(code) => format!("{code:?}"),
+        CompositeSchemasErrorCode::PreMerge(code) => format!("{code:?}"),
+        CompositeSchemasErrorCode::PostMerge(code) => format!("{code:?}"),
+        code => format!("{code:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_diagnostics() {
+        let mut subgraphs = graphql_composition::Subgraphs::default();
+
+        subgraphs
+            .ingest_str(
+                "type Query {\n  a: A\n}\n\nunion U = A | B\n\ntype A {\n  id: ID\n}\n",
+                "products",
+                Some("http://example.com/products"),
+            )
+            .unwrap();
+
+        let result = graphql_composition::compose(&mut subgraphs);
+        let report = json_report(result.diagnostics());
+
+        let diagnostic = report["diagnostics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|diagnostic| diagnostic["message"].as_str().unwrap().contains("Union member `B`"))
+            .unwrap();
+
+        assert_eq!(diagnostic["severity"], "error");
+        assert_eq!(diagnostic["subgraph"], "products");
+        assert_eq!(diagnostic["span"]["start"], json!({ "line": 5, "column": 1 }));
+        assert_eq!(diagnostic["code"], serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,97 @@
+use graphql_composition::diagnostics::SourceSpan;
+use std::fmt::Write as _;
+
+/// Renders the location of a diagnostic in a subgraph schema, the way rustc does:
+///
+/// ```text
+///   --> products:5:1
+///    |
+///  5 | union U = A | B
+///    | ^^^^^^^^^^^^^^^
+/// ```
+///
+/// Spans over several lines are underlined up to the end of their first line.
+pub(crate) fn snippet(subgraph: &str, span: Option<SourceSpan>, sdl: Option<&str>) -> String {
+    let Some(span) = span else {
+        return format!("  --> {subgraph}");
+    };
+
+    let start = span.start;
+    let header = format!("  --> {subgraph}:{}:{}", start.line, start.column);
+
+    let Some(line) = sdl.and_then(|sdl| sdl.lines().nth(start.line.saturating_sub(1))) else {
+        return header;
+    };
+
+    let line_length = line.chars().count();
+    let end_column = if span.end.line == start.line {
+        span.end.column
+    } else {
+        line_length + 1
+    };
+
+    let indent = start.column.saturating_sub(1).min(line_length);
+    let underline = end_column
+        .saturating_sub(start.column)
+        .clamp(1, line_length - indent + 1);
+
+    let line_number = start.line.to_string();
+    let gutter = " ".repeat(line_number.len());
+
+    let mut out = header;
+    write!(out, "\n {gutter} |").unwrap();
+    write!(out, "\n {line_number} | {line}").unwrap();
+    write!(out, "\n {gutter} | {}{}", " ".repeat(indent), "^".repeat(underline)).unwrap();
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphql_composition::diagnostics::Location;
+
+    const SDL: &str = "type Query {\n  products: [Product]\n}\n\ntype Product {\n  id: ID!\n  name: String\n}\n";
+
+    fn span(start: (usize, usize), end: (usize, usize)) -> Option<SourceSpan> {
+        Some(SourceSpan {
+            start: Location {
+                line: start.0,
+                column: start.1,
+            },
+            end: Location {
+                line: end.0,
+                column: end.1,
+            },
+        })
+    }
+
+    #[test]
+    fn single_line_span() {
+        let rendered = snippet("products", span((6, 3), (6, 10)), Some(SDL));
+
+        let expected = ["  --> products:6:3", "   |", " 6 |   id: ID!", "   |   ^^^^^^^"];
+
+        assert_eq!(rendered, expected.join("\n"));
+    }
+
+    #[test]
+    fn multiline_span() {
+        let rendered = snippet("products", span((5, 1), (8, 2)), Some(SDL));
+
+        let expected = [
+            "  --> products:5:1",
+            "   |",
+            " 5 | type Product {",
+            "   | ^^^^^^^^^^^^^^",
+        ];
+
+        assert_eq!(rendered, expected.join("\n"));
+    }
+
+    #[test]
+    fn without_source() {
+        assert_eq!(snippet("products", span((5, 1), (8, 2)), None), "  --> products:5:1");
+        assert_eq!(snippet("products", None, Some(SDL)), "  --> products");
+    }
+}
@@ -1,6 +1,7 @@
 mod assets;
 mod data_json;
 mod hot_reload;
+mod query_plan;
 mod subgraphs;
 
 pub(crate) use self::subgraphs::SubgraphCache;
@@ -73,6 +74,7 @@ async fn start(args: DevCommand, logging_filter: String) -> anyhow::Result<()> {
 
     let introspection_forced = config.graph.introspection == Some(false);
     config.graph.introspection = Some(true);
+    enable_query_plan_details(&mut config);
 
     let listen_address = args
         .listen_address
@@ -115,7 +117,9 @@ async fn start(args: DevCommand, logging_filter: String) -> anyhow::Result<()> {
         .await
         .expect("this really has to succeed");
 
-    let (config_sender, config_receiver) = watch::channel(config.clone());
+    let (config_sender, mut config_receiver) = watch::channel(config.clone());
+    // the server config reloader drops the first change, which must be the initial configuration
+    config_receiver.mark_changed();
 
     let config = Arc::new(config);
     tokio::spawn(hot_reload(
@@ -129,7 +133,8 @@ async fn start(args: DevCommand, logging_filter: String) -> anyhow::Result<()> {
     let server_config = ServeConfig {
         listen_address,
         config_path: None,
-        config_hot_reload: false,
+        // the configuration is reloaded by the dev hot reload, this only forwards it to the engine
+        config_hot_reload: true,
         config_receiver,
         graph_loader: GraphLoader::FromChannel { sdl_receiver },
         grafbase_access_token: None,
@@ -151,6 +156,16 @@ async fn start(args: DevCommand, logging_filter: String) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Used by the query plan explorer.
+pub(crate) fn enable_query_plan_details(config: &mut gateway_config::Config) {
+    config
+        .telemetry
+        .exporters
+        .response_extension
+        .get_or_insert_default()
+        .query_plan_details = true;
+}
+
 fn output_handler(
     mut url_receiver: broadcast::Receiver<String>,
     mut warnings_receiver: mpsc::Receiver<Vec<String>>,
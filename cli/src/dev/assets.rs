@@ -12,7 +12,11 @@ use tar::Archive;
 use tokio::fs;
 use tower_http::services::ServeDir;
 
-use super::{SubgraphCache, data_json::DataJson};
+use super::{
+    SubgraphCache,
+    data_json::DataJson,
+    query_plan::{query_plan, query_plan_panel},
+};
 
 const INDEX_FILE_NAME: &str = "index.html";
 const DOT_GRAFBASE_DIR: &str = ".grafbase";
@@ -99,6 +103,7 @@ pub(crate) fn get_base_router<S>(
             get_service(ServeDir::new(assets_dir).fallback(tower_http::services::ServeFile::new(index_path))),
         )
         .route("/app/data.json", get(data_json))
+        .route("/app/query-plan", get(query_plan_panel).post(query_plan))
         .with_state(AppState {
             html: Html(index_html),
             graphql_url,
@@ -147,9 +152,9 @@ async fn data_json(State(state): State<AppState>) -> axum::response::Response<ax
 }
 
 #[derive(Clone)]
-struct AppState {
+pub(super) struct AppState {
     html: Html<String>,
-    graphql_url: Arc<tokio::sync::OnceCell<String>>,
+    pub(super) graphql_url: Arc<tokio::sync::OnceCell<String>>,
     mcp_url: Option<String>,
     subgraph_cache: Arc<SubgraphCache>,
 }
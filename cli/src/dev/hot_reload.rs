@@ -1,6 +1,7 @@
+mod extension_watcher;
 mod subgraph_watcher;
 
-use self::subgraph_watcher::*;
+use self::{extension_watcher::*, subgraph_watcher::*};
 use super::subgraphs::SubgraphCache;
 use crate::errors::BackendError;
 use gateway_config::Config;
@@ -71,6 +72,7 @@ pub(crate) async fn hot_reload(
     };
 
     let mut subgraph_watcher = SubgraphWatcher::new();
+    let mut extension_watcher = ExtensionWatcher::new();
 
     let overridden_subgraphs = Arc::new(
         config
@@ -93,27 +95,47 @@ pub(crate) async fn hot_reload(
             sdl_sender.clone(),
             subgraph_cache.clone(),
             overridden_subgraphs.clone(),
-            config,
+            config.clone(),
         )
         .inspect_err(|error| tracing::error!("{}", error.to_string().trim()));
 
+    let _ = extension_watcher
+        .start(config_sender.clone(), &config)
+        .inspect_err(|error| tracing::error!("{}", error.to_string().trim()));
+
     let mut stream = ReceiverStream::new(watcher_receiver);
     while stream.next().await.is_some() {
         let subgraph_cache = subgraph_cache.clone();
         subgraph_watcher.stop();
+        extension_watcher.stop();
 
         let config = match Config::load(&config_path) {
             Ok(Some(mut config)) => {
                 config.graph.introspection = Some(true);
+                super::enable_query_plan_details(&mut config);
+                config
+            }
+            Ok(None) => {
+                let mut config = Config::default();
+                super::enable_query_plan_details(&mut config);
                 config
             }
-            Ok(None) => Config::default(),
             Err(error) => {
                 tracing::error!("{}", error.to_string().trim());
                 continue;
             }
         };
 
+        // the server keeps running with the previous configuration until the new one is valid
+        if let Err(error) = validate_config(&config).await {
+            tracing::error!("{}", error.to_string().trim());
+            continue;
+        }
+
+        let _ = extension_watcher
+            .start(config_sender.clone(), &config)
+            .inspect_err(|error| tracing::error!("{}", error.to_string().trim()));
+
         if let Err(err) = config_sender.send(config.clone()) {
             tracing::error!("Could not update config: {err}");
             continue;
@@ -134,6 +156,16 @@ pub(crate) async fn hot_reload(
     }
 }
 
+async fn validate_config(config: &Config) -> Result<(), BackendError> {
+    if !config.extensions.is_empty() {
+        crate::extension::install::execute(config)
+            .await
+            .map_err(|err| BackendError::Error(err.to_string()))?;
+    }
+
+    validate_local_extensions(config)
+}
+
 async fn reload_subgraphs(
     sender: mpsc::Sender<String>,
     subgraph_cache: Arc<SubgraphCache>,
@@ -0,0 +1,254 @@
+use super::{WATCHER_DEBOUNCE_DURATION, validate_config};
+use crate::errors::BackendError;
+use gateway_config::Config;
+use notify_debouncer_full::{
+    DebounceEventResult, DebouncedEvent, Debouncer, RecommendedCache, new_debouncer,
+    notify::{EventKind, RecommendedWatcher, RecursiveMode},
+};
+use std::{future::Future, path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::{mpsc, watch};
+use tokio_util::sync::CancellationToken;
+
+/// The files of a locally built extension, the directory is watched as builds usually replace them.
+const EXTENSION_FILES: [&str; 2] = ["extension.wasm", "manifest.json"];
+
+/// Builds write the extension files one after the other, the reload waits until they stop changing.
+const BUILD_SETTLE_DURATION: Duration = Duration::from_secs(1);
+
+pub(super) struct ExtensionWatcher {
+    watcher: Option<Debouncer<RecommendedWatcher, RecommendedCache>>,
+    cancellation_token: Option<CancellationToken>,
+}
+
+impl ExtensionWatcher {
+    pub(super) fn new() -> Self {
+        Self {
+            watcher: None,
+            cancellation_token: None,
+        }
+    }
+
+    pub(super) fn stop(&mut self) {
+        self.watcher = None;
+        if let Some(ref cancellation_token) = self.cancellation_token {
+            cancellation_token.cancel();
+        }
+        self.cancellation_token = None;
+    }
+
+    /// Watches the extensions with a local path. Once a build settles and the extensions are valid,
+    /// the current configuration is re-sent, which rebuilds the engine and loads the extensions
+    /// again.
+    pub(super) fn start(&mut self, config_sender: watch::Sender<Config>, config: &Config) -> Result<(), BackendError> {
+        let extension_dirs = local_extension_dirs(config)?;
+
+        // skip if there's no local extensions
+        if extension_dirs.is_empty() {
+            return Ok(());
+        }
+
+        let (change_sender, change_receiver) = mpsc::channel::<()>(24);
+
+        let mut watcher = new_debouncer(WATCHER_DEBOUNCE_DURATION, None, move |result: DebounceEventResult| {
+            let Ok(result) = result else {
+                return;
+            };
+
+            if result.iter().any(is_extension_change) {
+                change_sender.blocking_send(()).ok();
+            }
+        })
+        .map_err(BackendError::SetUpWatcher)?;
+
+        for dir in extension_dirs {
+            watcher
+                .watch(&dir, RecursiveMode::NonRecursive)
+                .map_err(BackendError::SetUpWatcher)?;
+        }
+
+        let cancellation_token = CancellationToken::new();
+        let config = Arc::new(config.clone());
+
+        tokio::spawn(
+            cancellation_token
+                .clone()
+                .run_until_cancelled_owned(reload_when_settled(change_receiver, BUILD_SETTLE_DURATION, move || {
+                    let config = config.clone();
+                    let config_sender = config_sender.clone();
+
+                    async move {
+                        // the server keeps running with the previous extensions until the new ones are valid
+                        if let Err(error) = validate_config(&config).await {
+                            tracing::error!("{}", error.to_string().trim());
+                            return;
+                        }
+
+                        tracing::info!("detected an extension change, reloading");
+
+                        config_sender.send_modify(|_| ());
+                    }
+                })),
+        );
+
+        self.watcher = Some(watcher);
+        self.cancellation_token = Some(cancellation_token);
+
+        Ok(())
+    }
+}
+
+fn is_extension_change(event: &DebouncedEvent) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_)
+    ) && event.paths.iter().any(|path| {
+        path.file_name()
+            .is_some_and(|name| EXTENSION_FILES.iter().any(|file| name == *file))
+    })
+}
+
+/// Reloads once no change was received for the settle duration, so that a build is only picked up
+/// once it wrote all the extension files.
+async fn reload_when_settled<F, Fut>(mut changes: mpsc::Receiver<()>, settle_duration: Duration, mut reload: F)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()>,
+{
+    while changes.recv().await.is_some() {
+        loop {
+            match tokio::time::timeout(settle_duration, changes.recv()).await {
+                Ok(Some(())) => continue,
+                Ok(None) => return,
+                Err(_) => break,
+            }
+        }
+
+        reload().await;
+    }
+}
+
+/// Extension paths are relative to the working directory, as when the gateway loads them.
+fn local_extension_dirs(config: &Config) -> Result<Vec<PathBuf>, BackendError> {
+    let cwd = std::env::current_dir().map_err(|error| BackendError::Error(error.to_string()))?;
+
+    Ok(config
+        .extensions
+        .values()
+        .filter_map(|extension| extension.path())
+        .map(|path| cwd.join(path))
+        .collect())
+}
+
+/// Validates the local extensions can be loaded before swapping the engine.
+pub(super) fn validate_local_extensions(config: &Config) -> Result<(), BackendError> {
+    for (name, extension) in &config.extensions {
+        let Some(path) = extension.path() else {
+            continue;
+        };
+
+        let missing = EXTENSION_FILES
+            .iter()
+            .filter(|file| !path.join(file).is_file())
+            .copied()
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            return Err(BackendError::InvalidLocalExtension {
+                name: name.clone(),
+                path: path.to_path_buf(),
+                missing: missing.join(", "),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify_debouncer_full::notify::{
+        Event,
+        event::{AccessKind, CreateKind, ModifyKind},
+    };
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Instant,
+    };
+
+    fn event(kind: EventKind, path: &str) -> DebouncedEvent {
+        DebouncedEvent::new(Event::new(kind).add_path(PathBuf::from(path)), Instant::now())
+    }
+
+    #[test]
+    fn only_extension_files_changes_trigger_a_reload() {
+        let modify = EventKind::Modify(ModifyKind::Any);
+
+        assert!(is_extension_change(&event(modify, "/ext/build/extension.wasm")));
+        assert!(is_extension_change(&event(
+            EventKind::Create(CreateKind::File),
+            "/ext/build/manifest.json"
+        )));
+        assert!(!is_extension_change(&event(modify, "/ext/build/extension.wasm.tmp")));
+        assert!(!is_extension_change(&event(
+            EventKind::Access(AccessKind::Any),
+            "/ext/build/extension.wasm"
+        )));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reloads_once_the_build_settles() {
+        let (sender, receiver) = mpsc::channel(24);
+        let reloads = Arc::new(AtomicUsize::new(0));
+
+        let task = tokio::spawn(reload_when_settled(receiver, BUILD_SETTLE_DURATION, {
+            let reloads = reloads.clone();
+            move || {
+                reloads.fetch_add(1, Ordering::SeqCst);
+                async {}
+            }
+        }));
+
+        // a build writing the files over a few debounce periods
+        for _ in 0..3 {
+            sender.send(()).await.unwrap();
+            tokio::time::sleep(BUILD_SETTLE_DURATION / 2).await;
+        }
+        assert_eq!(reloads.load(Ordering::SeqCst), 0);
+
+        tokio::time::sleep(BUILD_SETTLE_DURATION).await;
+        assert_eq!(reloads.load(Ordering::SeqCst), 1);
+
+        sender.send(()).await.unwrap();
+        tokio::time::sleep(BUILD_SETTLE_DURATION * 2).await;
+        assert_eq!(reloads.load(Ordering::SeqCst), 2);
+
+        drop(sender);
+        task.await.unwrap();
+    }
+
+    #[test]
+    fn local_extensions_require_their_build_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().display().to_string().replace('\\', "/");
+        let config: Config = toml::from_str(&format!(
+            r#"
+            [extensions.local]
+            version = "1.0"
+            path = "{path}"
+            "#
+        ))
+        .unwrap();
+
+        let error = validate_local_extensions(&config).unwrap_err();
+        assert!(error.to_string().contains("extension.wasm, manifest.json"), "{error}");
+
+        std::fs::write(dir.path().join("extension.wasm"), b"").unwrap();
+        let error = validate_local_extensions(&config).unwrap_err();
+        assert!(error.to_string().contains("manifest.json"), "{error}");
+        assert!(!error.to_string().contains("extension.wasm"), "{error}");
+
+        std::fs::write(dir.path().join("manifest.json"), b"{}").unwrap();
+        validate_local_extensions(&config).unwrap();
+    }
+}
@@ -0,0 +1,345 @@
+//! The query plan explorer. Operations are sent to the dev gateway which details how they are
+//! split across subgraphs in the query plan of its grafbase response extension. The gateway
+//! executes them to do so, hence only queries are accepted.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::State,
+    http::{HeaderValue, StatusCode, header},
+    response::{Html, IntoResponse, Response},
+};
+use cynic_parser::common::OperationType;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+use super::assets::AppState;
+
+/// Enough for the gateway to include its response extension with the default access control.
+const RESPONSE_EXTENSION_HEADER: &str = "x-grafbase-telemetry";
+
+const PANEL_HTML: &str = include_str!("query_plan.html");
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryPlanRequest {
+    query: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    operation_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    variables: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryPlanResponse {
+    plan: Option<QueryPlan>,
+    dot: Option<String>,
+    errors: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryPlan {
+    nodes: Vec<QueryPlanNode>,
+    edges: Vec<(usize, usize)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    details: Option<Vec<QueryPlanNodeDetails>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "__typename", rename_all_fields = "camelCase")]
+enum QueryPlanNode {
+    IntrospectionResolver,
+    GraphqlResolver {
+        subgraph_name: String,
+        request: GraphqlRequest,
+    },
+    Extension {
+        id: ExtensionId,
+        directive_name: Option<String>,
+        subgraph_name: String,
+    },
+    Lookup {
+        node: Box<QueryPlanNode>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExtensionId {
+    name: String,
+    version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct GraphqlRequest {
+    query: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryPlanNodeDetails {
+    entity: String,
+    required_fields: String,
+}
+
+pub(super) async fn query_plan_panel() -> impl IntoResponse {
+    Html(PANEL_HTML)
+}
+
+pub(super) async fn query_plan(State(state): State<AppState>, body: Bytes) -> Response<Body> {
+    let request: QueryPlanRequest = match serde_json::from_slice(&body) {
+        Ok(request) => request,
+        Err(err) => return (StatusCode::BAD_REQUEST, format!("Invalid request: {err}")).into_response(),
+    };
+
+    let response = if let Some(operation_type) = executed_non_query(&request) {
+        QueryPlanResponse {
+            plan: None,
+            dot: None,
+            errors: vec![serde_json::json!({
+                "message": format!(
+                    "Only queries can be explored, the dev gateway would execute the {operation_type} to retrieve its query plan."
+                )
+            })],
+        }
+    } else {
+        let Some(graphql_url) = state.graphql_url.get() else {
+            return (StatusCode::SERVICE_UNAVAILABLE, "The gateway is not ready yet").into_response();
+        };
+
+        match fetch_query_plan(graphql_url, &request).await {
+            Ok(response) => response,
+            Err(err) => {
+                tracing::error!("Error retrieving the query plan: {err}");
+                return (StatusCode::BAD_GATEWAY, err).into_response();
+            }
+        }
+    };
+
+    match serde_json::to_vec(&response) {
+        Ok(body) => {
+            let mut response = Response::new(Body::from(body));
+            response
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+            response
+        }
+        Err(err) => {
+            tracing::error!("Error serializing the query plan: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// The type of the operation the gateway would execute if it isn't a query. Documents the gateway
+/// can't pick an operation from are rejected by it before execution, so they're sent as is.
+fn executed_non_query(request: &QueryPlanRequest) -> Option<&'static str> {
+    let document = cynic_parser::parse_executable_document(&request.query).ok()?;
+
+    let operation = match request.operation_name.as_deref() {
+        Some(name) => document.operations().find(|operation| operation.name() == Some(name))?,
+        None if document.operations().count() > 1 => return None,
+        None => document.operations().next()?,
+    };
+
+    match operation.operation_type() {
+        OperationType::Query => None,
+        OperationType::Mutation => Some("mutation"),
+        OperationType::Subscription => Some("subscription"),
+    }
+}
+
+async fn fetch_query_plan(graphql_url: &str, request: &QueryPlanRequest) -> Result<QueryPlanResponse, String> {
+    let response: serde_json::Value = reqwest::Client::new()
+        .post(graphql_url)
+        .header(header::ACCEPT, "application/json")
+        .header(RESPONSE_EXTENSION_HEADER, "")
+        .json(request)
+        .send()
+        .await
+        .map_err(|err| format!("Could not reach the gateway: {err}"))?
+        .json()
+        .await
+        .map_err(|err| format!("Invalid gateway response: {err}"))?;
+
+    let mut errors = match response.get("errors") {
+        Some(serde_json::Value::Array(errors)) => errors.clone(),
+        _ => Vec::new(),
+    };
+
+    let plan = match response.pointer("/extensions/grafbase/queryPlan") {
+        Some(plan) => Some(
+            serde_json::from_value::<QueryPlan>(plan.clone())
+                .map_err(|err| format!("Unexpected query plan format: {err}"))?,
+        ),
+        None => {
+            // Either the operation couldn't be planned and we have its errors, or the response extension is disabled.
+            if errors.is_empty() {
+                errors.push(serde_json::json!({
+                    "message": format!(
+                        "The gateway did not return a query plan, check that `telemetry.exporters.response_extension` allows the `{RESPONSE_EXTENSION_HEADER}` header and the query plan."
+                    )
+                }));
+            }
+            None
+        }
+    };
+
+    Ok(QueryPlanResponse {
+        dot: plan.as_ref().map(to_dot),
+        plan,
+        errors,
+    })
+}
+
+/// Graphviz export of the query plan, one node per subgraph request.
+fn to_dot(plan: &QueryPlan) -> String {
+    let mut dot = String::from("digraph QueryPlan {\n    node [shape=box, fontname=\"monospace\"];\n");
+
+    for (id, node) in plan.nodes.iter().enumerate() {
+        let mut label = node_title(node);
+
+        if let Some(details) = plan.details.as_ref().and_then(|details| details.get(id)) {
+            write!(label, "\nentity: {}", details.entity).unwrap();
+            if !details.required_fields.is_empty() {
+                write!(label, "\nrequires: {}", details.required_fields).unwrap();
+            }
+        }
+
+        if let Some(query) = node_query(node) {
+            write!(label, "\n\n{query}").unwrap();
+        }
+
+        writeln!(dot, "    {id} [label=\"{}\"];", escape_label(&label)).unwrap();
+    }
+
+    for (parent, child) in &plan.edges {
+        writeln!(dot, "    {parent} -> {child};").unwrap();
+    }
+
+    dot.push('}');
+    dot.push('\n');
+    dot
+}
+
+fn node_title(node: &QueryPlanNode) -> String {
+    match node {
+        QueryPlanNode::IntrospectionResolver => "introspection".to_owned(),
+        QueryPlanNode::GraphqlResolver { subgraph_name, .. } => subgraph_name.clone(),
+        QueryPlanNode::Extension {
+            id,
+            directive_name,
+            subgraph_name,
+        } => {
+            let ExtensionId { name, version } = id;
+            match directive_name {
+                Some(directive_name) => format!("{subgraph_name} (extension {name}-{version}, @{directive_name})"),
+                None => format!("{subgraph_name} (extension {name}-{version})"),
+            }
+        }
+        QueryPlanNode::Lookup { node } => format!("{} (lookup)", node_title(node)),
+    }
+}
+
+fn node_query(node: &QueryPlanNode) -> Option<&str> {
+    match node {
+        QueryPlanNode::GraphqlResolver { request, .. } => Some(&request.query),
+        QueryPlanNode::Lookup { node } => node_query(node),
+        QueryPlanNode::IntrospectionResolver | QueryPlanNode::Extension { .. } => None,
+    }
+}
+
+fn escape_label(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+
+    for c in label.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            // left-justified lines
+            '\n' => escaped.push_str("\\l"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push_str("\\l");
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dot_export() {
+        let plan: QueryPlan = serde_json::from_value(serde_json::json!({
+            "nodes": [
+                {
+                    "__typename": "GraphqlResolver",
+                    "subgraphName": "accounts",
+                    "request": { "query": "query { me { id } }" }
+                },
+                {
+                    "__typename": "GraphqlResolver",
+                    "subgraphName": "reviews",
+                    "request": { "query": "query($var0: [_Any!]!) { _entities(representations: $var0) { ... on User { reviews { body } } } }" }
+                },
+                {
+                    "__typename": "Extension",
+                    "id": { "name": "rest", "version": "1.0.0" },
+                    "directiveName": "rest",
+                    "subgraphName": "weather"
+                }
+            ],
+            "edges": [[0, 1]],
+            "details": [
+                { "entity": "Query", "requiredFields": "" },
+                { "entity": "User", "requiredFields": "id" },
+                { "entity": "Query", "requiredFields": "" }
+            ]
+        }))
+        .unwrap();
+
+        insta::assert_snapshot!(to_dot(&plan), @r#"
+        digraph QueryPlan {
+            node [shape=box, fontname="monospace"];
+            0 [label="accounts\lentity: Query\l\lquery { me { id } }\l"];
+            1 [label="reviews\lentity: User\lrequires: id\l\lquery($var0: [_Any!]!) { _entities(representations: $var0) { ... on User { reviews { body } } } }\l"];
+            2 [label="weather (extension rest-1.0.0, @rest)\lentity: Query\l"];
+            0 -> 1;
+        }
+        "#);
+    }
+
+    #[test]
+    fn only_queries_are_sent() {
+        let request = |query: &str, operation_name: Option<&str>| QueryPlanRequest {
+            query: query.to_owned(),
+            operation_name: operation_name.map(str::to_owned),
+            variables: None,
+        };
+
+        assert_eq!(executed_non_query(&request("{ me { id } }", None)), None);
+        assert_eq!(
+            executed_non_query(&request("mutation { deleteMe }", None)),
+            Some("mutation")
+        );
+        assert_eq!(
+            executed_non_query(&request("subscription { messages }", None)),
+            Some("subscription")
+        );
+
+        let document = "query Me { me { id } } mutation DeleteMe { deleteMe }";
+        assert_eq!(executed_non_query(&request(document, Some("Me"))), None);
+        assert_eq!(
+            executed_non_query(&request(document, Some("DeleteMe"))),
+            Some("mutation")
+        );
+    }
+
+    #[test]
+    fn dot_label_escaping() {
+        assert_eq!(escape_label("a \"b\"\\c\nd"), "a \\\"b\\\"\\\\c\\ld\\l");
+    }
+}
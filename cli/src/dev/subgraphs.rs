@@ -156,6 +156,18 @@ impl SubgraphCache {
         }
     }
 
+    /// The SDL of each cached subgraph, by subgraph name.
+    pub(crate) async fn subgraph_sdls(&self) -> HashMap<String, String> {
+        let mut sdls = HashMap::new();
+
+        self.for_each_subgraph(|subgraph| {
+            sdls.insert(subgraph.name.clone(), subgraph.sdl.clone());
+        })
+        .await;
+
+        sdls
+    }
+
     /// Compose all cached subgraphs.
     pub(crate) async fn compose(&self) -> anyhow::Result<Result<String, graphql_composition::Diagnostics>> {
         let mut futs = futures::stream::FuturesOrdered::new();
@@ -195,7 +207,12 @@ impl SubgraphCache {
         while let Some(result) = stream.next().await {
             match result {
                 Ok((subgraph, parsed_schema)) => {
-                    subgraphs.ingest(&parsed_schema, &subgraph.name, subgraph.url.as_deref());
+                    subgraphs.ingest_with_source(
+                        &parsed_schema,
+                        &subgraph.sdl,
+                        &subgraph.name,
+                        subgraph.url.as_deref(),
+                    );
                 }
                 Err((_, mut errors)) => {
                     validation_errors.append(&mut errors);
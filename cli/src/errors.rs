@@ -44,6 +44,15 @@ pub(crate) enum CliError {
     TrustedDocumentsManifestParseError(#[source] serde_json::Error),
     #[error("could not read the GraphQL schema")]
     SchemaReadError(#[source] io::Error),
+    /// returned if a schema or an operations file passed to an offline check could not be read
+    #[error("could not read '{0}'\nCaused by: {1}")]
+    CheckReadFile(PathBuf, io::Error),
+    /// returned if one of the schemas passed to an offline check is not valid GraphQL SDL
+    #[error("could not parse the {0} schema\nCaused by: {1}")]
+    CheckInvalidSchema(&'static str, String),
+    /// returned if the operations passed to an offline check could not be parsed
+    #[error("could not parse the operations in '{0}'\nCaused by: {1}")]
+    CheckInvalidOperations(PathBuf, String),
     #[error(transparent)]
     UpgradeError(#[from] UpgradeError),
     /// returned if the CLI was installed via a package manager and not directly (when trying to upgrade)
@@ -54,6 +63,15 @@ pub(crate) enum CliError {
     /// returned if a linted schema could not be read
     #[error("could not read '{0}'\nCaused by: {1}")]
     ReadLintSchema(PathBuf, io::Error),
+    /// returned if the linter configuration could not be read
+    #[error("could not read the linter configuration at '{0}'\nCaused by: {1}")]
+    ReadLintConfig(PathBuf, io::Error),
+    /// returned if the linter configuration is not valid
+    #[error("could not parse the linter configuration at '{0}'\nCaused by: {1}")]
+    ParseLintConfig(PathBuf, toml::de::Error),
+    /// returned if a fixed schema could not be written
+    #[error("could not write the fixed schema to '{0}'\nCaused by: {1}")]
+    WriteLintFix(PathBuf, io::Error),
     /// returned if a directory or file without an extension is passed to lint
     #[error("attempted to lint a directory or a file without an extension")]
     LintNoExtension,
@@ -116,6 +134,12 @@ pub(crate) enum BackendError {
     BranchDoesntExist,
     #[error("could not set up a file watcher\nCaused by: {0}")]
     SetUpWatcher(notify::Error),
+    #[error("could not load the extension '{name}', missing {missing} in {}", path.display())]
+    InvalidLocalExtension {
+        name: String,
+        path: PathBuf,
+        missing: String,
+    },
     #[error("could not determine the path of the home directory")]
     HomeDirectory,
     #[error("could not unpack CLI app\nCaused by: {0}")]
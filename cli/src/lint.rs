@@ -1,16 +1,27 @@
-use crate::{errors::CliError, output::report};
-use graphql_lint::Severity;
+use crate::{cli_input::LintCommand, errors::CliError, output::report};
+use graphql_lint::{LintConfig, Severity};
 use std::{
     borrow::Borrow,
     fs,
     io::{IsTerminal, Read},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 const ALLOWED_EXTENSIONS: [&str; 4] = ["gql", "graphql", "graphqls", "sdl"];
+const DEFAULT_CONFIG_PATH: &str = "grafbase-lint.toml";
 
-pub fn lint(schema_path: Option<PathBuf>) -> Result<(), CliError> {
-    let schema = match schema_path {
+const FAILED_LINT_EXIT_STATUS: i32 = 1;
+
+pub fn lint(command: LintCommand) -> Result<(), CliError> {
+    let LintCommand {
+        schema: schema_path,
+        config: config_path,
+        fix,
+    } = command;
+
+    let config = read_config(config_path)?;
+
+    let schema = match &schema_path {
         Some(schema_path) => {
             let extension = schema_path
                 .extension()
@@ -21,7 +32,7 @@ pub fn lint(schema_path: Option<PathBuf>) -> Result<(), CliError> {
                 return Err(CliError::LintUnsupportedFileExtension(extension.into_owned()));
             }
 
-            fs::read_to_string(&schema_path).map_err(|error| CliError::ReadLintSchema(schema_path, error))?
+            fs::read_to_string(schema_path).map_err(|error| CliError::ReadLintSchema(schema_path.clone(), error))?
         }
         None if std::io::stdin().is_terminal() => {
             return Err(CliError::MissingArgument("[schema] or a schema piped through stdin"));
@@ -37,18 +48,62 @@ pub fn lint(schema_path: Option<PathBuf>) -> Result<(), CliError> {
         }
     };
 
-    let diagnostics = graphql_lint::lint(&schema)?;
+    let mut diagnostics = graphql_lint::lint_with_config(&schema, &config)?;
+
+    if fix {
+        let fixed_count = diagnostics
+            .iter()
+            .filter(|diagnostic| !diagnostic.fixes.is_empty())
+            .count();
+
+        let fixed = graphql_lint::apply_fixes(&schema, &diagnostics);
+
+        match &schema_path {
+            Some(schema_path) => {
+                fs::write(schema_path, &fixed).map_err(|error| CliError::WriteLintFix(schema_path.clone(), error))?;
+                report::lint_fixed(fixed_count);
+            }
+            None => {
+                // The fixed schema goes to stdout, diagnostics would be mixed with it.
+                print!("{fixed}");
+                return Ok(());
+            }
+        }
+
+        diagnostics = graphql_lint::lint_with_config(&fixed, &config)?;
+    }
 
     if diagnostics.is_empty() {
         report::lint_success();
         return Ok(());
     }
 
-    for (message, severity) in diagnostics {
-        match severity {
-            Severity::Warning => report::lint_warning(message),
+    let has_errors = diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error);
+
+    for diagnostic in diagnostics {
+        match diagnostic.severity {
+            Severity::Warning => report::lint_warning(diagnostic.message),
+            Severity::Error => report::lint_error(diagnostic.message),
         }
     }
 
+    if has_errors {
+        std::process::exit(FAILED_LINT_EXIT_STATUS);
+    }
+
     Ok(())
 }
+
+fn read_config(path: Option<PathBuf>) -> Result<LintConfig, CliError> {
+    let path = match path {
+        Some(path) => path,
+        None if Path::new(DEFAULT_CONFIG_PATH).exists() => PathBuf::from(DEFAULT_CONFIG_PATH),
+        None => return Ok(LintConfig::default()),
+    };
+
+    let config = fs::read_to_string(&path).map_err(|error| CliError::ReadLintConfig(path.clone(), error))?;
+
+    toml::from_str(&config).map_err(|error| CliError::ParseLintConfig(path, error))
+}
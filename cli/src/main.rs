@@ -22,6 +22,7 @@ mod prompts;
 mod publish;
 mod schema;
 mod schema_proposal;
+mod sign;
 mod subgraph;
 mod trust;
 mod upgrade;
@@ -170,7 +171,8 @@ fn try_main(args: Args) -> Result<(), CliError> {
             }
             upgrade::install_grafbase().map_err(Into::into)
         }
-        SubCommand::Lint(cmd) => lint::lint(cmd.schema),
+        SubCommand::Lint(cmd) => lint::lint(cmd),
+        SubCommand::Sign(cmd) => Ok(sign::sign(cmd)?),
         SubCommand::Plugins => Ok(plugins::list()?),
         SubCommand::Branch(cmd) => match cmd.command {
             BranchSubCommand::Delete(cmd) => branch::delete(cmd.branch_ref),
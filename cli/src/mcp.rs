@@ -13,7 +13,9 @@ use engine::{CachedOperation, RequestExtensions};
 use gateway_config::{Config, HeaderForward, HeaderInsert, HeaderRule, NameOrPattern};
 use grafbase_telemetry::metrics::{EngineMetrics, meter_from_global_provider};
 use regex::Regex;
-use runtime::{entity_cache::EntityCache, rate_limiting::RateLimiter, trusted_documents_client};
+use runtime::{
+    entity_cache::EntityCache, rate_limiting::RateLimiter, response_cache::ResponseCache, trusted_documents_client,
+};
 use runtime_local::{InMemoryEntityCache, InMemoryOperationCache, NativeFetcher};
 use std::io::stdout;
 use wasi_component_loader::extension::EngineWasmExtensions;
@@ -170,6 +172,10 @@ impl engine::Runtime for MinimalRuntime {
         &self.entity_cache
     }
 
+    fn response_cache(&self) -> &dyn ResponseCache {
+        &()
+    }
+
     fn metrics(&self) -> &EngineMetrics {
         &self.metrics
     }
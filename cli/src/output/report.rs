@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::{
     api::{
@@ -32,18 +32,35 @@ pub fn error(error: &CliError) {
     }
 }
 
-pub(crate) fn composition_diagnostics(diagnostics: &graphql_composition::Diagnostics) {
-    for diagnostic in diagnostics.iter_warnings() {
-        watercolor::output!("- ⚠️ Warning: {}", diagnostic, @BrightYellow);
+/// `sdls` are the subgraph schemas by subgraph name, to show the relevant parts of them.
+pub(crate) fn composition_diagnostics(diagnostics: &graphql_composition::Diagnostics, sdls: &HashMap<String, String>) {
+    let (warnings, errors): (Vec<_>, Vec<_>) = diagnostics
+        .iter()
+        .partition(|diagnostic| diagnostic.severity().is_warning());
+
+    for diagnostic in warnings {
+        watercolor::output!("- ⚠️ Warning: {}", diagnostic.message(), @BrightYellow);
+        composition_diagnostic_snippet(diagnostic, sdls);
         println!();
     }
 
-    for diagnostic in diagnostics.iter_errors() {
-        watercolor::output!("- ❌ Error: {}", diagnostic, @BrightRed);
+    for diagnostic in errors {
+        watercolor::output!("- ❌ Error: {}", diagnostic.message(), @BrightRed);
+        composition_diagnostic_snippet(diagnostic, sdls);
         println!();
     }
 }
 
+fn composition_diagnostic_snippet(
+    diagnostic: &graphql_composition::diagnostics::Diagnostic,
+    sdls: &HashMap<String, String>,
+) {
+    if let Some(subgraph) = diagnostic.subgraph() {
+        let sdl = sdls.get(subgraph).map(String::as_str);
+        println!("{}", crate::compose::snippet(subgraph, diagnostic.span(), sdl));
+    }
+}
+
 pub fn warnings(warnings: &[Warning]) {
     for warning in warnings {
         let msg = warning.message();
@@ -159,6 +176,27 @@ pub(crate) fn check_errors(has_errors: bool, diagnostics: &[SchemaCheckDiagnosti
     }
 }
 
+pub(crate) fn offline_check_errors(has_errors: bool, diagnostics: &[operation_checks::CheckDiagnostic]) {
+    if has_errors {
+        watercolor::output!("\nErrors were found in your schema check:\n", @BrightRed);
+    } else {
+        watercolor::output!("\nWarnings were found in your schema check:\n", @BrightYellow);
+    }
+
+    for diagnostic in diagnostics {
+        let error = &diagnostic.message;
+
+        match diagnostic.severity {
+            operation_checks::Severity::Error => {
+                watercolor::output!("❌ [Error] {error}", @BrightRed);
+            }
+            operation_checks::Severity::Warning => {
+                watercolor::output!("⚠️ [Warning] {error}", @BrightYellow);
+            }
+        }
+    }
+}
+
 pub(crate) fn subgraph_list_command_success<'a>(branch_name: &str, subgraphs: impl ExactSizeIterator<Item = &'a str>) {
     if subgraphs.len() == 0 {
         println!("🈳 There are no published subgraphs in the {branch_name} branch\n");
@@ -295,6 +333,14 @@ pub(crate) fn lint_warning(warning: String) {
     watercolor::output!("⚠️ [Warning] {warning}", @BrightYellow);
 }
 
+pub(crate) fn lint_error(error: String) {
+    watercolor::output!("❌ [Error] {error}", @BrightRed);
+}
+
+pub(crate) fn lint_fixed(count: usize) {
+    watercolor::output!("🔧 Fixed {count} issue(s) in your schema", @BrightBlue);
+}
+
 pub(crate) fn extension_build_start() {
     watercolor::output!("🔨 Building extension...", @BrightBlue);
 }
@@ -0,0 +1,36 @@
+use crate::cli_input::SignCommand;
+use anyhow::Context as _;
+use std::io::{IsTerminal as _, Read as _};
+
+pub(crate) fn sign(command: SignCommand) -> anyhow::Result<()> {
+    let SignCommand { schema, key, output } = command;
+
+    let sdl = match &schema {
+        Some(path) => {
+            std::fs::read_to_string(path).with_context(|| format!("could not read the schema at {}", path.display()))?
+        }
+        None if std::io::stdin().is_terminal() => {
+            anyhow::bail!("provide the path of the schema to sign or pipe it through stdin");
+        }
+        None => {
+            let mut sdl = String::new();
+            std::io::stdin()
+                .read_to_string(&mut sdl)
+                .context("could not read the schema from stdin")?;
+            sdl
+        }
+    };
+
+    let private_key = std::fs::read_to_string(&key)
+        .with_context(|| format!("could not read the private key at {}", key.display()))?;
+
+    let signed = federated_server::sign_sdl(&sdl, &private_key)?;
+
+    match output {
+        Some(path) => std::fs::write(&path, signed)
+            .with_context(|| format!("could not write the signed schema to {}", path.display()))?,
+        None => print!("{signed}"),
+    }
+
+    Ok(())
+}
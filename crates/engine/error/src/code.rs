@@ -29,6 +29,8 @@ pub enum ErrorCode {
     SubgraphError,
     SubgraphInvalidResponseError,
     SubgraphRequestError,
+    // The subgraph circuit breaker is open, the request wasn't sent.
+    SubgraphCircuitBreakerOpen,
     // Auth
     Unauthenticated,
     Unauthorized,
@@ -68,6 +70,7 @@ impl ErrorCode {
             ErrorCode::SubgraphError | ErrorCode::SubgraphInvalidResponseError | ErrorCode::SubgraphRequestError => {
                 (http::StatusCode::BAD_GATEWAY, 300)
             }
+            ErrorCode::SubgraphCircuitBreakerOpen => (http::StatusCode::SERVICE_UNAVAILABLE, 300),
             ErrorCode::GatewayTimeout => (http::StatusCode::GATEWAY_TIMEOUT, 200),
             // least helpful error codes
             ErrorCode::ExtensionError | ErrorCode::InternalServerError => (http::StatusCode::INTERNAL_SERVER_ERROR, 0),
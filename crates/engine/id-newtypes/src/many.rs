@@ -1,6 +1,6 @@
 use itertools::Itertools;
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct IdToMany<Id, V>(Vec<(Id, V)>);
 
 impl<Id, V> Default for IdToMany<Id, V> {
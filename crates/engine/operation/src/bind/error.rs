@@ -94,6 +94,22 @@ pub(crate) enum BindError {
         directive: String,
         span: Span,
     },
+    #[error("Unknown argument named '{name}' for directive '{directive}'")]
+    UnknownDirectiveArgument {
+        directive: &'static str,
+        name: String,
+        span: Span,
+    },
+    #[error("Directive '@{directive}' can only be used on {expected}.")]
+    MisplacedDirective {
+        directive: &'static str,
+        expected: &'static str,
+        span: Span,
+    },
+    #[error("The label of @defer and @stream must be a static string.")]
+    InvalidIncrementalDeliveryLabel { span: Span },
+    #[error("The label '{label}' is used more than once by @defer or @stream, labels must be unique.")]
+    DuplicateIncrementalDeliveryLabel { label: String, span: Span },
 }
 
 impl BindError {
@@ -110,7 +126,11 @@ impl BindError {
             | BindError::InvalidVariableType { span, .. }
             | BindError::LeafMustBeAScalarOrEnum { span, .. }
             | BindError::MissingArgument { span, .. }
-            | BindError::MissingDirectiveArgument { span, .. } => Some(operation.span_to_location(*span)),
+            | BindError::MissingDirectiveArgument { span, .. }
+            | BindError::UnknownDirectiveArgument { span, .. }
+            | BindError::MisplacedDirective { span, .. }
+            | BindError::InvalidIncrementalDeliveryLabel { span }
+            | BindError::DuplicateIncrementalDeliveryLabel { span, .. } => Some(operation.span_to_location(*span)),
             BindError::DuplicateVariable { location, .. } | BindError::UnusedVariable { location, .. } => {
                 Some(*location)
             }
@@ -133,6 +153,10 @@ impl BindError {
             | BindError::NoSubscriptionDefined
             | BindError::InvalidVariableType { .. }
             | BindError::MissingDirectiveArgument { .. }
+            | BindError::UnknownDirectiveArgument { .. }
+            | BindError::MisplacedDirective { .. }
+            | BindError::InvalidIncrementalDeliveryLabel { .. }
+            | BindError::DuplicateIncrementalDeliveryLabel { .. }
             | BindError::UnknownType { .. }
             | BindError::UnknownFragment { .. }
             | BindError::DuplicateVariable { .. }
@@ -2,7 +2,7 @@ mod coercion;
 pub mod error;
 mod operation;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use coercion::coerce_variable;
 use error::{BindError, ErrorOperationName};
@@ -26,6 +26,7 @@ struct OperationBinder<'schema, 'p> {
     variable_definition_in_use: Vec<bool>,
     fragment_name_to_id: HashMap<&'p str, FragmentId>,
     selection_buffers: Vec<Vec<SelectionId>>,
+    incremental_delivery_labels: HashSet<&'p str>,
 
     response_keys: ResponseKeys,
     data_fields: Vec<DataFieldRecord>,
@@ -65,6 +66,7 @@ pub(crate) fn bind_operation(
         variable_definition_in_use: Vec::new(),
         fragment_name_to_id: HashMap::with_capacity(parsed_operation.document().fragments().count()),
         selection_buffers: Vec::new(),
+        incremental_delivery_labels: HashSet::new(),
         errors: Vec::new(),
     };
 
@@ -1,5 +1,5 @@
 use cynic_parser::{
-    Span,
+    Span, Value,
     common::OperationType,
     executable::{Argument, Directive, FieldSelection, Iter, Selection},
 };
@@ -9,8 +9,9 @@ use std::collections::HashSet;
 use walker::Walk;
 
 use crate::{
-    ExecutableDirectiveId, FieldArgumentId, IncludeDirectiveRecord, InlineFragmentId, InlineFragmentRecord,
-    QueryInputValueRecord, SelectionSetRecord, SkipDirectiveRecord, VariableDefinitionRecord,
+    DeferDirectiveRecord, ExecutableDirectiveId, FieldArgumentId, IncludeDirectiveRecord, InlineFragmentId,
+    InlineFragmentRecord, QueryInputValueId, QueryInputValueRecord, SelectionSetRecord, SkipDirectiveRecord,
+    StreamDirectiveRecord, VariableDefinitionRecord,
 };
 
 use super::{
@@ -93,7 +94,8 @@ impl<'schema, 'p> OperationBinder<'schema, 'p> {
     }
 
     fn bind_typename_field(&mut self, field: FieldSelection<'p>) -> BindResult<crate::TypenameFieldId> {
-        let directive_ids = self.bind_executable_directive(field.directives());
+        let directive_ids =
+            self.bind_executable_directive(field.directives(), ExecutableDirectiveSite::Field { is_list: false });
         let response_key = self.response_keys.get_or_intern(field.alias().unwrap_or(field.name()));
         self.typename_fields.push(crate::TypenameFieldRecord {
             response_key,
@@ -157,7 +159,12 @@ impl<'schema, 'p> OperationBinder<'schema, 'p> {
         };
 
         let sorted_argument_ids = self.bind_field_arguments_sorted(definition, field.name_span(), field.arguments());
-        let directive_ids = self.bind_executable_directive(field.directives());
+        let directive_ids = self.bind_executable_directive(
+            field.directives(),
+            ExecutableDirectiveSite::Field {
+                is_list: definition.ty().wrapping.is_list(),
+            },
+        );
         let response_key = self.response_keys.get_or_intern(field.alias().unwrap_or(field.name()));
 
         self.data_fields.push(crate::DataFieldRecord {
@@ -240,7 +247,7 @@ impl<'schema, 'p> OperationBinder<'schema, 'p> {
             .transpose()?;
         let selection_set_record =
             self.bind_selection_set(type_condition.unwrap_or(parent_output_type), fragment.selection_set())?;
-        let directive_ids = self.bind_executable_directive(fragment.directives());
+        let directive_ids = self.bind_executable_directive(fragment.directives(), ExecutableDirectiveSite::Fragment);
 
         self.inline_fragments.push(InlineFragmentRecord {
             type_condition_id: type_condition.map(|ty| ty.id()),
@@ -279,7 +286,7 @@ impl<'schema, 'p> OperationBinder<'schema, 'p> {
                 id
             }
         };
-        let directive_ids = self.bind_executable_directive(spread.directives());
+        let directive_ids = self.bind_executable_directive(spread.directives(), ExecutableDirectiveSite::Fragment);
         self.fragment_spreads.push(crate::FragmentSpreadRecord {
             fragment_id,
             directive_ids,
@@ -342,16 +349,24 @@ impl<'schema, 'p> OperationBinder<'schema, 'p> {
         })
     }
 
-    fn bind_executable_directive(&mut self, directives: Iter<'p, Directive<'p>>) -> Vec<ExecutableDirectiveId> {
+    fn bind_executable_directive(
+        &mut self,
+        directives: Iter<'p, Directive<'p>>,
+        site: ExecutableDirectiveSite,
+    ) -> Vec<ExecutableDirectiveId> {
         let mut out = Vec::new();
         for directive in directives {
-            if matches!(directive.name(), "skip" | "include") {
-                match self.bind_skip_or_include_executable_directive(directive) {
-                    Ok(directive_id) => out.push(directive_id),
-                    Err(err) => {
-                        self.errors.push(err);
-                        continue;
-                    }
+            let result = match directive.name() {
+                "skip" | "include" => self.bind_skip_or_include_executable_directive(directive),
+                "defer" => self.bind_defer_executable_directive(directive, site),
+                "stream" => self.bind_stream_executable_directive(directive, site),
+                _ => continue,
+            };
+            match result {
+                Ok(directive_id) => out.push(directive_id),
+                Err(err) => {
+                    self.errors.push(err);
+                    continue;
                 }
             }
         }
@@ -373,12 +388,7 @@ impl<'schema, 'p> OperationBinder<'schema, 'p> {
                 directive: directive.name().to_string(),
             })?;
 
-        let ty = TypeRecord {
-            definition_id: self.schema.type_definition_by_name("Boolean").expect("must exist").id(),
-            wrapping: schema::Wrapping::default().non_null(),
-        }
-        .walk(self.schema);
-
+        let ty = self.non_null_type("Boolean");
         let condition = coerce_query_value(self, ty, argument.value());
 
         Ok(if directive.name() == "skip" {
@@ -388,6 +398,119 @@ impl<'schema, 'p> OperationBinder<'schema, 'p> {
         })
     }
 
+    fn bind_defer_executable_directive(
+        &mut self,
+        directive: Directive<'p>,
+        site: ExecutableDirectiveSite,
+    ) -> BindResult<ExecutableDirectiveId> {
+        if !matches!(site, ExecutableDirectiveSite::Fragment) {
+            return Err(BindError::MisplacedDirective {
+                directive: "defer",
+                expected: "fragment spreads and inline fragments",
+                span: directive.name_span(),
+            });
+        }
+
+        let mut record = DeferDirectiveRecord {
+            condition: None,
+            label: None,
+        };
+        for argument in directive.arguments() {
+            match argument.name() {
+                "if" => {
+                    let ty = self.non_null_type("Boolean");
+                    record.condition = Some(coerce_query_value(self, ty, argument.value()))
+                }
+                "label" => record.label = Some(self.bind_incremental_delivery_label(argument)?),
+                name => {
+                    return Err(BindError::UnknownDirectiveArgument {
+                        directive: "defer",
+                        name: name.to_string(),
+                        span: argument.name_span(),
+                    });
+                }
+            }
+        }
+
+        Ok(ExecutableDirectiveId::Defer(record))
+    }
+
+    fn bind_stream_executable_directive(
+        &mut self,
+        directive: Directive<'p>,
+        site: ExecutableDirectiveSite,
+    ) -> BindResult<ExecutableDirectiveId> {
+        if !matches!(site, ExecutableDirectiveSite::Field { is_list: true }) {
+            return Err(BindError::MisplacedDirective {
+                directive: "stream",
+                expected: "list fields",
+                span: directive.name_span(),
+            });
+        }
+
+        let mut condition = None;
+        let mut label = None;
+        let mut initial_count = None;
+        for argument in directive.arguments() {
+            match argument.name() {
+                "if" => {
+                    let ty = self.non_null_type("Boolean");
+                    condition = Some(coerce_query_value(self, ty, argument.value()))
+                }
+                "label" => label = Some(self.bind_incremental_delivery_label(argument)?),
+                "initialCount" => {
+                    let ty = self.non_null_type("Int");
+                    initial_count = Some(coerce_query_value(self, ty, argument.value()))
+                }
+                name => {
+                    return Err(BindError::UnknownDirectiveArgument {
+                        directive: "stream",
+                        name: name.to_string(),
+                        span: argument.name_span(),
+                    });
+                }
+            }
+        }
+
+        let initial_count =
+            initial_count.unwrap_or_else(|| self.query_input_values.push_value(QueryInputValueRecord::Int(0)));
+
+        Ok(ExecutableDirectiveId::Stream(StreamDirectiveRecord {
+            condition,
+            label,
+            initial_count,
+        }))
+    }
+
+    /// GraphQL spec (incremental delivery RFC):
+    ///   The `label` argument must be a static string and unique across all @defer and @stream
+    ///   directives of the operation.
+    fn bind_incremental_delivery_label(&mut self, argument: Argument<'p>) -> BindResult<QueryInputValueId> {
+        let Value::String(label) = argument.value() else {
+            return Err(BindError::InvalidIncrementalDeliveryLabel {
+                span: argument.value().span(),
+            });
+        };
+        let label = label.as_str();
+        if !self.incremental_delivery_labels.insert(label) {
+            return Err(BindError::DuplicateIncrementalDeliveryLabel {
+                label: label.to_string(),
+                span: argument.value().span(),
+            });
+        }
+        Ok(self
+            .query_input_values
+            .push_value(QueryInputValueRecord::String(label.to_string())))
+    }
+
+    fn non_null_type(&self, name: &str) -> schema::Type<'schema> {
+        TypeRecord {
+            definition_id: self.schema.type_definition_by_name(name).expect("must exist").id(),
+            wrapping: schema::Wrapping::default().non_null(),
+        }
+        .walk(self.schema)
+    }
+
     fn bind_variable_definitions(
         &mut self,
         variables: cynic_parser::executable::Iter<'_, cynic_parser::executable::VariableDefinition<'_>>,
@@ -501,3 +624,9 @@ impl<'schema, 'p> OperationBinder<'schema, 'p> {
         })
     }
 }
+
+#[derive(Clone, Copy)]
+enum ExecutableDirectiveSite {
+    Field { is_list: bool },
+    Fragment,
+}
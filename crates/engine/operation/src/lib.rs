@@ -16,7 +16,7 @@ pub use error::*;
 pub use model::*;
 pub use request::*;
 use schema::Schema;
-pub use validation::complexity::{ComplexityCost, ComplexityError};
+pub use validation::complexity::{ComplexityCost, ComplexityError, field_base_cost, operation_base_cost};
 
 impl Operation {
     pub fn parse(schema: &Schema, operation_name: Option<&str>, document: &str) -> Result<Operation> {
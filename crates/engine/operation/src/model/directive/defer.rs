@@ -0,0 +1,76 @@
+use serde::Deserialize as _;
+use walker::Walk;
+
+use crate::{InputValueContext, OperationContext, QueryInputValueId, QueryInputValueRecord};
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct DeferDirectiveRecord {
+    /// `if` argument, absent means `true`.
+    pub condition: Option<QueryInputValueId>,
+    /// Always a static string if present.
+    pub label: Option<QueryInputValueId>,
+}
+
+#[derive(Clone, Copy)]
+pub struct DeferDirective<'a> {
+    pub(in crate::model) ctx: OperationContext<'a>,
+    pub(in crate::model) item: DeferDirectiveRecord,
+}
+
+impl std::ops::Deref for DeferDirective<'_> {
+    type Target = DeferDirectiveRecord;
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl DeferDirectiveRecord {
+    /// Whether the fragment is deferred, `@defer(if: false)` is ignored.
+    pub fn is_active(&self, ctx: InputValueContext<'_>) -> bool {
+        super::is_condition_true(ctx, self.condition)
+    }
+}
+
+impl<'a> DeferDirective<'a> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn as_ref(&self) -> &DeferDirectiveRecord {
+        &self.item
+    }
+
+    pub fn label(&self) -> Option<&'a str> {
+        label_str(self.ctx, self.item.label)
+    }
+}
+
+pub(super) fn label_str(ctx: OperationContext<'_>, id: Option<QueryInputValueId>) -> Option<&str> {
+    id.and_then(|id| match &ctx.operation.query_input_values[id] {
+        QueryInputValueRecord::String(label) => Some(label.as_str()),
+        _ => None,
+    })
+}
+
+impl<'a> Walk<OperationContext<'a>> for DeferDirectiveRecord {
+    type Walker<'w>
+        = DeferDirective<'w>
+    where
+        'a: 'w;
+    fn walk<'w>(self, ctx: impl Into<OperationContext<'a>>) -> Self::Walker<'w>
+    where
+        Self: 'w,
+        'a: 'w,
+    {
+        DeferDirective {
+            ctx: ctx.into(),
+            item: self,
+        }
+    }
+}
+
+impl std::fmt::Debug for DeferDirective<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeferDirective")
+            .field("condition", &super::condition_debug(self.ctx, self.item.condition))
+            .field("label", &self.label())
+            .finish()
+    }
+}
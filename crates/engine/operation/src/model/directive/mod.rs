@@ -1,5 +1,33 @@
+mod defer;
 mod include;
 mod skip;
+mod stream;
 
+pub use defer::*;
 pub use include::*;
+use serde::Deserialize as _;
 pub use skip::*;
+pub use stream::*;
+use walker::Walk;
+
+use crate::{InputValueContext, OperationContext, QueryInputValueId, QueryInputValueRecord, VariableDefinitionId};
+
+/// `if` argument of @defer and @stream, absent means `true`. Its type was validated when binding
+/// the operation.
+fn is_condition_true(ctx: InputValueContext<'_>, condition: Option<QueryInputValueId>) -> bool {
+    condition.is_none_or(|condition| bool::deserialize(condition.walk(ctx)).unwrap_or_default())
+}
+
+fn condition_debug(ctx: OperationContext<'_>, condition: Option<QueryInputValueId>) -> String {
+    let Some(condition) = condition else {
+        return "true".to_string();
+    };
+    match ctx.operation.query_input_values[condition] {
+        QueryInputValueRecord::Boolean(b) => b.to_string(),
+        QueryInputValueRecord::Variable(id) => format!(
+            "${}",
+            <VariableDefinitionId as Walk<OperationContext<'_>>>::walk(id, ctx).name
+        ),
+        _ => "???".to_string(),
+    }
+}
@@ -0,0 +1,87 @@
+use serde::Deserialize as _;
+use walker::Walk;
+
+use crate::{InputValueContext, OperationContext, QueryInputValueId, QueryInputValueRecord};
+
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct StreamDirectiveRecord {
+    /// `if` argument, absent means `true`.
+    pub condition: Option<QueryInputValueId>,
+    /// Always a static string if present.
+    pub label: Option<QueryInputValueId>,
+    /// Non-null `Int`, defaults to 0.
+    pub initial_count: QueryInputValueId,
+}
+
+#[derive(Clone, Copy)]
+pub struct StreamDirective<'a> {
+    pub(in crate::model) ctx: OperationContext<'a>,
+    pub(in crate::model) item: StreamDirectiveRecord,
+}
+
+impl std::ops::Deref for StreamDirective<'_> {
+    type Target = StreamDirectiveRecord;
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl StreamDirectiveRecord {
+    /// Whether the list is streamed, `@stream(if: false)` is ignored.
+    pub fn is_active(&self, ctx: InputValueContext<'_>) -> bool {
+        super::is_condition_true(ctx, self.condition)
+    }
+
+    /// Number of items sent with the initial payload.
+    pub fn initial_count(&self, ctx: InputValueContext<'_>) -> usize {
+        i32::deserialize(self.initial_count.walk(ctx))
+            .unwrap_or_default()
+            .max(0) as usize
+    }
+}
+
+impl<'a> StreamDirective<'a> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn as_ref(&self) -> &StreamDirectiveRecord {
+        &self.item
+    }
+
+    pub fn label(&self) -> Option<&'a str> {
+        super::defer::label_str(self.ctx, self.item.label)
+    }
+}
+
+impl<'a> Walk<OperationContext<'a>> for StreamDirectiveRecord {
+    type Walker<'w>
+        = StreamDirective<'w>
+    where
+        'a: 'w;
+    fn walk<'w>(self, ctx: impl Into<OperationContext<'a>>) -> Self::Walker<'w>
+    where
+        Self: 'w,
+        'a: 'w,
+    {
+        StreamDirective {
+            ctx: ctx.into(),
+            item: self,
+        }
+    }
+}
+
+impl std::fmt::Debug for StreamDirective<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let initial_count = match self.ctx.operation.query_input_values[self.item.initial_count] {
+            QueryInputValueRecord::Int(n) => n.to_string(),
+            QueryInputValueRecord::Variable(id) => format!(
+                "${}",
+                <crate::VariableDefinitionId as Walk<OperationContext<'_>>>::walk(id, self.ctx).name
+            ),
+            _ => "???".to_string(),
+        };
+        f.debug_struct("StreamDirective")
+            .field("condition", &super::condition_debug(self.ctx, self.item.condition))
+            .field("label", &self.label())
+            .field("initial_count", &initial_count)
+            .finish()
+    }
+}
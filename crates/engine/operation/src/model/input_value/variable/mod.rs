@@ -9,7 +9,7 @@ pub use walker::*;
 
 use super::InputValueContext;
 
-#[derive(Default, IndexedFields, Debug)]
+#[derive(Default, IndexedFields, Debug, Clone)]
 pub struct VariableInputValues {
     /// Individual input values and list values
     #[indexed_by(VariableInputValueId)]
@@ -86,7 +86,7 @@ impl<'ctx> Walk<InputValueContext<'ctx>> for VariableInputKeyValueId {
     }
 }
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub enum VariableInputValueRecord {
     #[default]
     Null,
@@ -71,7 +71,7 @@ impl OperationAttributes {
     }
 }
 
-#[derive(id_derives::IndexedFields)]
+#[derive(Clone, id_derives::IndexedFields)]
 pub struct Variables {
     pub input_values: VariableInputValues,
     #[indexed_by(VariableDefinitionId)]
@@ -33,10 +33,7 @@ pub fn calculate_complexity(
     ctx: OperationContext<'_>,
     variables: &Variables,
 ) -> Result<ComplexityCost, ComplexityError> {
-    let base_cost = match ctx.operation.attributes.ty {
-        OperationType::Query | OperationType::Subscription => 0,
-        OperationType::Mutation => 10,
-    };
+    let base_cost = operation_base_cost(ctx.operation.attributes.ty);
 
     let selection_set = ctx.root_selection_set();
 
@@ -57,6 +54,31 @@ pub fn calculate_complexity(
     Ok(ComplexityCost(cost))
 }
 
+/// Cost of the operation itself, regardless of its selection set.
+pub fn operation_base_cost(ty: OperationType) -> usize {
+    match ty {
+        OperationType::Query | OperationType::Subscription => 0,
+        OperationType::Mutation => 10,
+    }
+}
+
+/// Cost of a single occurrence of a field without its selection set: the weight of its type and
+/// the cost of its arguments.
+pub fn field_base_cost(field: DataField<'_>, variables: &Variables) -> usize {
+    let type_cost = field
+        .definition()
+        .cost()
+        .map(|cost| cost.weight)
+        .unwrap_or_else(|| cost_for_type(field.definition().ty().definition())) as usize;
+
+    let argument_cost = field
+        .sorted_arguments()
+        .map(|argument| cost_for_argument(argument, variables))
+        .sum::<usize>();
+
+    type_cost + argument_cost
+}
+
 #[derive(Clone, Copy)]
 pub struct ComplexityCost(pub usize);
 
@@ -97,27 +119,18 @@ fn field_complexity(
     field: DataField<'_>,
     preset_list_size: Option<usize>,
 ) -> Result<usize, ComplexityError> {
-    let type_cost = field
-        .definition()
-        .cost()
-        .map(|cost| cost.weight)
-        .unwrap_or_else(|| cost_for_type(field.definition().ty().definition())) as usize;
+    let base_cost = field_base_cost(field, context.variables);
 
     let list_size_directive = field.definition().list_size();
 
     let child_count = calculate_child_count(context, field, list_size_directive, preset_list_size)?;
 
-    let argument_cost = field
-        .sorted_arguments()
-        .map(|argument| cost_for_argument(argument, context.variables))
-        .sum::<usize>();
-
     let this_field_count = child_count.this_field_count();
     let child_field_count = child_count.child_field_count();
 
     let child_cost = selection_set_complexity(context, field.selection_set(), child_field_count)?;
 
-    Ok(this_field_count * (type_cost + argument_cost + child_cost))
+    Ok(this_field_count * (base_cost + child_cost))
 }
 
 fn cost_for_argument(argument: FieldArgument<'_>, variables: &Variables) -> usize {
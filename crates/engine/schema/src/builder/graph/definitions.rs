@@ -35,7 +35,7 @@ impl std::ops::DerefMut for Ingester<'_> {
 }
 
 pub(crate) fn ingest_definitions(
-    ctx: BuildContext<'_>,
+    mut ctx: BuildContext<'_>,
 ) -> Result<(GraphBuilder<'_>, IntrospectionSubgraph), Vec<Error>> {
     let sdl = ctx.sdl;
     let graph = Graph {
@@ -77,7 +77,8 @@ pub(crate) fn ingest_definitions(
         list_size_directives: Vec::new(),
         extension_directives: Vec::new(),
         extension_directive_arguments: Vec::new(),
-        templates: Vec::new(),
+        // Header value templates come first, extension directive arguments are added afterwards.
+        templates: std::mem::take(&mut ctx.subgraphs.templates),
         lookup_resolver_definitions: Vec::new(),
         derive_definitions: Vec::new(),
     };
@@ -0,0 +1,38 @@
+use cynic_parser_deser::ConstDeserializer as _;
+
+use crate::{
+    CacheControlDirectiveRecord, TypeSystemDirectiveId,
+    builder::{Error, graph::directives::DirectivesIngester, sdl},
+};
+
+impl<'sdl> DirectivesIngester<'_, 'sdl> {
+    /// Without a `maxAge` the directive doesn't restrict anything, so no directive is created.
+    pub fn create_cache_control_directive(
+        &mut self,
+        def: sdl::SdlDefinition<'sdl>,
+        directive: sdl::Directive<'sdl>,
+    ) -> Result<Option<TypeSystemDirectiveId>, Error> {
+        if !matches!(
+            def,
+            sdl::SdlDefinition::Object(_)
+                | sdl::SdlDefinition::Interface(_)
+                | sdl::SdlDefinition::Union(_)
+                | sdl::SdlDefinition::FieldDefinition(_)
+        ) {
+            return Err((
+                format!("Invalid @cacheControl directive location: {}", def.location()),
+                directive.name_span(),
+            )
+                .into());
+        }
+        let dir = directive.deserialize::<sdl::CacheControlDirective>().map_err(|err| {
+            (
+                format!("Invalid @cacheControl directive: {err}"),
+                directive.arguments_span(),
+            )
+        })?;
+        Ok(dir
+            .max_age
+            .map(|max_age| TypeSystemDirectiveId::CacheControl(CacheControlDirectiveRecord { max_age })))
+    }
+}
@@ -1,3 +1,4 @@
+mod cache_control;
 mod cost;
 mod deprecated;
 mod list_size;
@@ -37,6 +38,10 @@ impl<'sdl> DirectivesIngester<'_, 'sdl> {
                     Ok(id) => directive_ids.push(id),
                     Err(err) => self.errors.push(err),
                 },
+                "cacheControl" => match self.create_cache_control_directive(def, directive) {
+                    Ok(id) => directive_ids.extend(id),
+                    Err(err) => self.errors.push(err),
+                },
                 "oneOf" => {
                     let sdl::SdlDefinition::InputObject(_) = def else {
                         self.errors
@@ -101,7 +101,7 @@ impl<'a> Builder<'a> {
                 ExtensionsContext::load(&sdl, &extension_catalog).await?
             };
 
-            BuildContext::new(&sdl, &extensions, &config).build(for_operation_analytics_only)
+            BuildContext::new(&sdl, &extensions, &config)?.build(for_operation_analytics_only)
         } else {
             let sdl = Default::default();
             let extensions = if for_operation_analytics_only {
@@ -110,7 +110,7 @@ impl<'a> Builder<'a> {
                 ExtensionsContext::load(&sdl, &extension_catalog).await?
             };
 
-            BuildContext::new(&sdl, &extensions, &config).build(for_operation_analytics_only)
+            BuildContext::new(&sdl, &extensions, &config)?.build(for_operation_analytics_only)
         }
     }
 }
@@ -179,6 +179,29 @@ fn build_settings(config: &Config) -> PartialConfig {
         retry: config.gateway.retry.enabled.then_some(config.gateway.retry.into()),
         batching: config.gateway.batching.clone(),
         complexity_control: (&config.complexity_control).into(),
+        cost_budget: config.complexity_control.budget.as_ref().and_then(|budget| {
+            if config.complexity_control.mode.is_none() {
+                tracing::warn!("A cost budget is configured but complexity control is disabled, it will be ignored");
+                return None;
+            }
+            Some(budget.into())
+        }),
+        rate_limit_headers: config
+            .gateway
+            .rate_limit
+            .as_ref()
+            .is_some_and(|rate_limit| rate_limit.headers),
+        response_caching: config
+            .response_caching
+            .enabled
+            .then(|| (&config.response_caching).into()),
+        entity_cache_mutation_invalidations: config
+            .entity_caching
+            .invalidation
+            .mutations
+            .iter()
+            .map(Into::into)
+            .collect(),
         response_extension: config
             .telemetry
             .exporters
@@ -98,6 +98,15 @@ pub struct ListSizeDirective<'a> {
     pub require_one_slicing_argument: bool,
 }
 
+/// ```ignore,graphql
+/// directive @cacheControl(maxAge: Int) on OBJECT | INTERFACE | UNION | FIELD_DEFINITION
+/// ```
+#[derive(ValueDeserialize)]
+pub struct CacheControlDirective {
+    #[deser(rename = "maxAge")]
+    pub max_age: Option<u32>,
+}
+
 #[derive(ValueDeserialize)]
 pub struct DeprecatedDirective<'a> {
     pub reason: Option<&'a str>,
@@ -4,7 +4,8 @@ use rapidhash::fast::RapidHashMap;
 
 use crate::{
     ForwardHeaderRuleRecord, HeaderRuleId, HeaderRuleRecord, InsertHeaderRuleRecord, NameOrPatternId,
-    RemoveHeaderRuleRecord, RenameDuplicateHeaderRuleRecord, SubGraphs, introspection::IntrospectionSubgraph,
+    RemoveHeaderRuleRecord, RenameDuplicateHeaderRuleRecord, ResponseHeaderRuleRecord, SubGraphs, TemplateEscaping,
+    TemplateId, TemplateRecord, introspection::IntrospectionSubgraph,
 };
 
 use super::{
@@ -24,10 +25,17 @@ pub(crate) struct SubgraphsBuilder<'sdl> {
     pub virtual_subgraphs: Vec<VirtualSubgraphRecord>,
     pub default_header_rules: IdRange<HeaderRuleId>,
     pub header_rules: Vec<HeaderRuleRecord>,
+    /// Templates of the header values, moved into the graph once it's created.
+    pub templates: Vec<TemplateRecord>,
+    errors: Vec<Error>,
 }
 
 impl<'sdl> SubgraphsBuilder<'sdl> {
-    pub(super) fn new(sdl: &'sdl Sdl<'sdl>, config: &gateway_config::Config, interners: &mut Interners) -> Self {
+    pub(super) fn new(
+        sdl: &'sdl Sdl<'sdl>,
+        config: &gateway_config::Config,
+        interners: &mut Interners,
+    ) -> Result<Self, Vec<Error>> {
         let mut subgraphs = SubgraphsBuilder {
             all: Vec::with_capacity(sdl.subgraphs.len()),
             mapping: RapidHashMap::with_capacity_and_hasher(sdl.subgraphs.len(), Default::default()),
@@ -35,9 +43,11 @@ impl<'sdl> SubgraphsBuilder<'sdl> {
             virtual_subgraphs: Vec::new(),
             header_rules: Vec::new(),
             default_header_rules: IdRange::default(),
+            templates: Vec::new(),
+            errors: Vec::new(),
         };
 
-        subgraphs.default_header_rules = ingest_header_rules(&mut subgraphs.header_rules, &config.headers, interners);
+        subgraphs.default_header_rules = subgraphs.ingest_header_rules(&config.headers, interners);
 
         let default_cache_ttl = if config.entity_caching.enabled {
             Some(config.entity_caching.ttl)
@@ -58,16 +68,19 @@ impl<'sdl> SubgraphsBuilder<'sdl> {
             let SubgraphConfig {
                 url,
                 headers,
+                response_headers,
                 websocket_url,
                 timeout,
                 retry,
+                hedging,
+                circuit_breaker,
                 entity_caching,
                 subscription_protocol,
                 ..
             } = config.subgraphs.get(name).cloned().unwrap_or_default();
             let url = url.or(subgraph.url.clone());
 
-            let header_rule_ids = ingest_header_rules(&mut subgraphs.header_rules, &headers, interners);
+            let header_rule_ids = subgraphs.ingest_header_rules(&headers, interners);
             let subgraph_id = if let Some(url) = url {
                 subgraphs.graphql_endpoints.push(GraphqlSubgraphRecord {
                     name_id: subgraph_name_id,
@@ -82,6 +95,14 @@ impl<'sdl> SubgraphsBuilder<'sdl> {
                     config: super::SubgraphConfig {
                         timeout,
                         retry: retry.map(Into::into),
+                        hedging: hedging.as_ref().filter(|cfg| cfg.enabled).map(Into::into),
+                        circuit_breaker: Some(
+                            circuit_breaker
+                                .as_ref()
+                                .unwrap_or(&config.traffic_shaping.circuit_breaker),
+                        )
+                        .filter(|cfg| cfg.enabled)
+                        .map(Into::into),
                         cache_ttl: entity_caching
                             .as_ref()
                             .and_then(|cfg| {
@@ -91,6 +112,22 @@ impl<'sdl> SubgraphsBuilder<'sdl> {
                                     .flatten()
                             })
                             .or(default_cache_ttl),
+                        cache_stale_while_revalidate: entity_caching
+                            .as_ref()
+                            .and_then(|cfg| cfg.stale_while_revalidate)
+                            .or(config.entity_caching.stale_while_revalidate)
+                            .unwrap_or_default(),
+                        cache_stale_if_error: entity_caching
+                            .as_ref()
+                            .and_then(|cfg| cfg.stale_if_error)
+                            .or(config.entity_caching.stale_if_error)
+                            .unwrap_or_default(),
+                        response_header_rules: config
+                            .response_headers
+                            .iter()
+                            .chain(&response_headers)
+                            .map(|rule| ingest_response_header_rule(rule, interners))
+                            .collect(),
                     },
                     schema_directive_ids: Vec::new(),
                 });
@@ -107,7 +144,96 @@ impl<'sdl> SubgraphsBuilder<'sdl> {
             subgraphs.mapping.insert(graph_enum_name, subgraph_id);
         }
 
-        subgraphs
+        if !subgraphs.errors.is_empty() {
+            return Err(subgraphs.errors);
+        }
+
+        Ok(subgraphs)
+    }
+
+    fn ingest_header_rules(
+        &mut self,
+        rules: &[gateway_config::HeaderRule],
+        interners: &mut Interners,
+    ) -> IdRange<HeaderRuleId> {
+        use gateway_config::*;
+        let start = self.header_rules.len();
+        for rule in rules {
+            let record = match rule {
+                HeaderRule::Forward(rule) => {
+                    let name_id = match &rule.name {
+                        NameOrPattern::Pattern(pattern) => {
+                            NameOrPatternId::Pattern(interners.regexps.get_or_insert(pattern.0.clone()))
+                        }
+                        NameOrPattern::Name(name) => NameOrPatternId::Name(interners.strings.get_or_new(name.as_str())),
+                    };
+
+                    let default_id = rule.default.as_ref().map(|s| interners.strings.get_or_new(s.as_str()));
+                    let rename_id = rule.rename.as_ref().map(|s| interners.strings.get_or_new(s.as_str()));
+
+                    HeaderRuleRecord::Forward(ForwardHeaderRuleRecord {
+                        name_id,
+                        default_id,
+                        rename_id,
+                    })
+                }
+                HeaderRule::Insert(rule) => {
+                    let name_id = interners.strings.get_or_new(rule.name.as_str());
+                    let value_id = interners.strings.get_or_new(rule.value.as_str());
+                    let value_template_id = self.ingest_header_value_template(rule);
+
+                    HeaderRuleRecord::Insert(InsertHeaderRuleRecord {
+                        name_id,
+                        value_id,
+                        value_template_id,
+                    })
+                }
+                HeaderRule::Remove(rule) => {
+                    let name_id = match &rule.name {
+                        NameOrPattern::Pattern(pattern) => {
+                            NameOrPatternId::Pattern(interners.regexps.get_or_insert(pattern.0.clone()))
+                        }
+                        NameOrPattern::Name(name) => NameOrPatternId::Name(interners.strings.get_or_new(name.as_str())),
+                    };
+
+                    HeaderRuleRecord::Remove(RemoveHeaderRuleRecord { name_id })
+                }
+                HeaderRule::RenameDuplicate(rule) => {
+                    HeaderRuleRecord::RenameDuplicate(RenameDuplicateHeaderRuleRecord {
+                        name_id: interners.strings.get_or_new(rule.name.as_str()),
+                        default_id: rule
+                            .default
+                            .as_ref()
+                            .map(|default| interners.strings.get_or_new(default.as_str())),
+                        rename_id: interners.strings.get_or_new(rule.rename.as_str()),
+                    })
+                }
+            };
+            self.header_rules.push(record);
+        }
+        (start..self.header_rules.len()).into()
+    }
+
+    /// Values with mustache tags, such as `{{ jwt.sub }}`, are rendered for each request.
+    fn ingest_header_value_template(&mut self, rule: &gateway_config::HeaderInsert) -> Option<TemplateId> {
+        if !rule.value.as_str().contains("{{") {
+            return None;
+        }
+
+        match TemplateRecord::new(rule.value.to_string(), TemplateEscaping::Header) {
+            Ok(template) => {
+                let id = self.templates.len().into();
+                self.templates.push(template);
+                Some(id)
+            }
+            Err(err) => {
+                self.errors.push(Error::new(format!(
+                    "Invalid template in the value of the inserted header '{}': {err}",
+                    rule.name
+                )));
+                None
+            }
+        }
     }
 
     pub(super) fn try_get(&self, name: GraphName<'_>, span: sdl::Span) -> Result<SubgraphId, Error> {
@@ -135,57 +261,25 @@ impl<'sdl> SubgraphsBuilder<'sdl> {
     }
 }
 
-fn ingest_header_rules(
-    header_rules: &mut Vec<HeaderRuleRecord>,
-    rules: &[gateway_config::HeaderRule],
+fn ingest_response_header_rule(
+    rule: &gateway_config::ResponseHeaderRule,
     interners: &mut Interners,
-) -> IdRange<HeaderRuleId> {
+) -> ResponseHeaderRuleRecord {
     use gateway_config::*;
-    let start = header_rules.len();
-    header_rules.extend(rules.iter().map(|rule| -> HeaderRuleRecord {
-        match rule {
-            HeaderRule::Forward(rule) => {
-                let name_id = match &rule.name {
-                    NameOrPattern::Pattern(pattern) => {
-                        NameOrPatternId::Pattern(interners.regexps.get_or_insert(pattern.0.clone()))
-                    }
-                    NameOrPattern::Name(name) => NameOrPatternId::Name(interners.strings.get_or_new(name.as_str())),
-                };
-
-                let default_id = rule.default.as_ref().map(|s| interners.strings.get_or_new(s.as_str()));
-                let rename_id = rule.rename.as_ref().map(|s| interners.strings.get_or_new(s.as_str()));
-
-                HeaderRuleRecord::Forward(ForwardHeaderRuleRecord {
-                    name_id,
-                    default_id,
-                    rename_id,
-                })
-            }
-            HeaderRule::Insert(rule) => {
-                let name_id = interners.strings.get_or_new(rule.name.as_str());
-                let value_id = interners.strings.get_or_new(rule.value.as_str());
+    match rule {
+        ResponseHeaderRule::Propagate(rule) => ResponseHeaderRuleRecord {
+            name_id: ingest_name_or_pattern(&rule.name, interners),
+            rename_id: rule.rename.as_ref().map(|s| interners.strings.get_or_new(s.as_str())),
+            merge: rule.merge,
+        },
+    }
+}
 
-                HeaderRuleRecord::Insert(InsertHeaderRuleRecord { name_id, value_id })
-            }
-            HeaderRule::Remove(rule) => {
-                let name_id = match &rule.name {
-                    NameOrPattern::Pattern(pattern) => {
-                        NameOrPatternId::Pattern(interners.regexps.get_or_insert(pattern.0.clone()))
-                    }
-                    NameOrPattern::Name(name) => NameOrPatternId::Name(interners.strings.get_or_new(name.as_str())),
-                };
-
-                HeaderRuleRecord::Remove(RemoveHeaderRuleRecord { name_id })
-            }
-            HeaderRule::RenameDuplicate(rule) => HeaderRuleRecord::RenameDuplicate(RenameDuplicateHeaderRuleRecord {
-                name_id: interners.strings.get_or_new(rule.name.as_str()),
-                default_id: rule
-                    .default
-                    .as_ref()
-                    .map(|default| interners.strings.get_or_new(default.as_str())),
-                rename_id: interners.strings.get_or_new(rule.rename.as_str()),
-            }),
+fn ingest_name_or_pattern(name: &gateway_config::NameOrPattern, interners: &mut Interners) -> NameOrPatternId {
+    match name {
+        gateway_config::NameOrPattern::Pattern(pattern) => {
+            NameOrPatternId::Pattern(interners.regexps.get_or_insert(pattern.0.clone()))
         }
-    }));
-    (start..header_rules.len()).into()
+        gateway_config::NameOrPattern::Name(name) => NameOrPatternId::Name(interners.strings.get_or_new(name.as_str())),
+    }
 }
@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+const DEFAULT_CONSECUTIVE_FAILURES: u32 = 5;
+
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Opens the circuit after this many consecutive failed requests.
+    pub consecutive_failures: Option<u32>,
+    /// Opens the circuit when the ratio of failed requests within the window reaches this value.
+    pub error_rate: Option<f64>,
+    /// Minimum number of requests within the window before the error rate is considered.
+    pub minimum_requests: u32,
+    /// Sliding window over which the error rate is computed.
+    pub window: Duration,
+    /// How long the circuit stays open before probing the subgraph again.
+    pub cooldown: Duration,
+    /// Number of probe requests allowed through once the cooldown has elapsed.
+    pub half_open_requests: u32,
+}
+
+impl From<&gateway_config::CircuitBreakerConfig> for CircuitBreakerConfig {
+    fn from(config: &gateway_config::CircuitBreakerConfig) -> Self {
+        let consecutive_failures = match (config.consecutive_failures, config.error_rate) {
+            (None, None) => Some(DEFAULT_CONSECUTIVE_FAILURES),
+            (consecutive_failures, _) => consecutive_failures,
+        };
+
+        CircuitBreakerConfig {
+            consecutive_failures,
+            error_rate: config.error_rate,
+            minimum_requests: config.minimum_requests,
+            window: config.window,
+            cooldown: config.cooldown,
+            half_open_requests: config.half_open_requests.max(1),
+        }
+    }
+}
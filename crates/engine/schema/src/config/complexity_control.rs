@@ -74,3 +74,40 @@ impl From<&gateway_config::ComplexityControlConfig> for ComplexityControl {
         }
     }
 }
+
+/// Budget of cost each client may spend over time.
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
+pub struct CostBudget {
+    pub key: CostBudgetKey,
+    /// Whether requests without the key are rejected rather than exempted from the budget.
+    pub reject_missing_key: bool,
+}
+
+impl From<&gateway_config::CostBudgetConfig> for CostBudget {
+    fn from(budget: &gateway_config::CostBudgetConfig) -> Self {
+        CostBudget {
+            key: (&budget.key).into(),
+            reject_missing_key: matches!(budget.missing_key, gateway_config::CostBudgetMissingKey::Reject),
+        }
+    }
+}
+
+/// Identifies the client a cost budget applies to.
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
+pub enum CostBudgetKey {
+    /// Lower-cased name of the request header.
+    Header(String),
+    /// Path of the JWT claim.
+    JwtClaim(Vec<String>),
+}
+
+impl From<&gateway_config::CostBudgetKey> for CostBudgetKey {
+    fn from(key: &gateway_config::CostBudgetKey) -> Self {
+        match key {
+            gateway_config::CostBudgetKey::Header(name) => CostBudgetKey::Header(name.to_lowercase()),
+            gateway_config::CostBudgetKey::JwtClaim(path) => {
+                CostBudgetKey::JwtClaim(path.split('.').map(str::to_string).collect())
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EntityCacheMutationInvalidation {
+    /// Name of the mutation field.
+    pub field: String,
+    /// Entity type to invalidate.
+    pub type_name: String,
+    /// Key fields of the entity with the argument path providing their value. If absent, all
+    /// entities of the type are invalidated.
+    pub key: Option<Vec<(String, Vec<String>)>>,
+}
+
+impl From<&gateway_config::EntityCachingMutationInvalidation> for EntityCacheMutationInvalidation {
+    fn from(config: &gateway_config::EntityCachingMutationInvalidation) -> Self {
+        EntityCacheMutationInvalidation {
+            field: config.field.clone(),
+            type_name: config.type_name.clone(),
+            key: config.key.as_ref().map(|key| {
+                key.iter()
+                    .map(|(field, argument)| (field.clone(), argument.split('.').map(str::to_string).collect()))
+                    .collect()
+            }),
+        }
+    }
+}
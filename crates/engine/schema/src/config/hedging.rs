@@ -0,0 +1,19 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct HedgingConfig {
+    /// How long to wait for the first request before sending a second one.
+    pub delay: Duration,
+    /// Percentile of the recent subgraph latencies after which the second request is sent,
+    /// between 0 and 1. Falls back to the delay until enough latencies have been observed.
+    pub percentile: Option<f64>,
+}
+
+impl From<&gateway_config::HedgingConfig> for HedgingConfig {
+    fn from(config: &gateway_config::HedgingConfig) -> Self {
+        HedgingConfig {
+            delay: config.delay,
+            percentile: config.percentile.map(|percentile| (percentile / 100.0).clamp(0.0, 1.0)),
+        }
+    }
+}
@@ -1,9 +1,17 @@
+mod circuit_breaker;
 mod complexity_control;
+mod entity_caching;
+mod hedging;
+mod response_caching;
 mod response_extensions;
 mod retry;
 mod trusted_documents;
 
+pub use circuit_breaker::*;
 pub use complexity_control::*;
+pub use entity_caching::*;
+pub use hedging::*;
+pub use response_caching::*;
 pub use response_extensions::*;
 pub use retry::*;
 pub use trusted_documents::*;
@@ -16,6 +24,10 @@ pub struct PartialConfig {
     pub retry: Option<RetryConfig>,
     pub batching: gateway_config::BatchingConfig,
     pub complexity_control: ComplexityControl,
+    pub cost_budget: Option<CostBudget>,
+    pub rate_limit_headers: bool,
+    pub response_caching: Option<ResponseCachingConfig>,
+    pub entity_cache_mutation_invalidations: Vec<EntityCacheMutationInvalidation>,
     pub response_extension: ResponseExtensionConfig,
     pub apq_enabled: bool,
     pub executable_document_limit_bytes: usize,
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ResponseCachingConfig {
+    /// Max-age of a response when none of the queried fields has a `@cacheControl` hint.
+    pub default_max_age: Duration,
+    /// Lower-cased names of the request headers included in the cache key.
+    pub vary_headers: Vec<String>,
+    /// Paths of the JWT claims included in the cache key.
+    pub vary_jwt_claims: Vec<Vec<String>>,
+}
+
+impl From<&gateway_config::ResponseCachingConfig> for ResponseCachingConfig {
+    fn from(config: &gateway_config::ResponseCachingConfig) -> Self {
+        ResponseCachingConfig {
+            default_max_age: config.ttl,
+            vary_headers: config.vary.headers.iter().map(|name| name.to_lowercase()).collect(),
+            vary_jwt_claims: config
+                .vary
+                .jwt_claims
+                .iter()
+                .map(|path| path.split('.').map(str::to_string).collect())
+                .collect(),
+        }
+    }
+}
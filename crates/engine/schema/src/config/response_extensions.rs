@@ -8,6 +8,10 @@ pub struct ResponseExtensionConfig {
     pub include_trace_id: bool,
     /// Whether the query plan is exposed in the grafbase response extension. Defaults to true.
     pub include_query_plan: bool,
+    /// Whether the query plan details the entity and required fields of each step. Defaults to false.
+    pub include_query_plan_details: bool,
+    /// Whether the operation cost is exposed in the grafbase response extension. Defaults to true.
+    pub include_cost: bool,
     /// Defines under which conditions the grafbase response extension will be added.
     /// Defaults to a simple header rule, the presence of `x-grafbase-telemetry` is enough.
     pub access_control: Vec<AccessControl>,
@@ -24,6 +28,8 @@ impl From<ResponseExtensionExporterConfig> for ResponseExtensionConfig {
         ResponseExtensionConfig {
             include_trace_id: config.trace_id,
             include_query_plan: config.query_plan,
+            include_query_plan_details: config.query_plan_details,
+            include_cost: config.cost,
             access_control: config
                 .access_control
                 .into_iter()
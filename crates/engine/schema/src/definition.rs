@@ -86,6 +86,11 @@ impl<'a> TypeDefinition<'a> {
         })
     }
 
+    pub fn cache_control_max_age(&self) -> Option<u32> {
+        self.directives()
+            .find_map(|directive| directive.as_cache_control().map(|dir| dir.max_age))
+    }
+
     pub fn is_inaccessible(&self) -> bool {
         match self {
             TypeDefinition::Enum(enm) => enm.is_inaccessible(),
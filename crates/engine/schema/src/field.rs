@@ -54,6 +54,14 @@ impl<'a> FieldDefinition<'a> {
     pub fn has_deprecated(&self) -> Option<DeprecatedDirective<'_>> {
         self.directives().find_map(|directive| directive.as_deprecated())
     }
+
+    /// Max-age in seconds from `@cacheControl`. A hint on the field takes precedence over the
+    /// one of its output type.
+    pub fn cache_control_max_age(&self) -> Option<u32> {
+        self.directives()
+            .find_map(|directive| directive.as_cache_control().map(|dir| dir.max_age))
+            .or_else(|| self.ty().definition().cache_control_max_age())
+    }
 }
 
 impl std::fmt::Debug for FieldDefinition<'_> {
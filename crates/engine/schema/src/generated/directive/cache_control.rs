@@ -0,0 +1,67 @@
+//! ===================
+//! !!! DO NOT EDIT !!!
+//! ===================
+//! Generated with: `cargo run -p engine-codegen`
+//! Source file: <engine-codegen dir>/domain/schema.graphql
+use crate::prelude::*;
+#[allow(unused_imports)]
+use walker::{Iter, Walk};
+
+/// Generated from:
+///
+/// ```custom,{.language-graphql}
+/// type CacheControlDirective
+///   @meta(module: "directive/cache_control", derive: ["PartialEq", "Eq", "PartialOrd", "Ord", "Hash"])
+///   @copy {
+///   max_age: u32!
+/// }
+/// ```
+#[derive(Debug, serde::Serialize, serde::Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct CacheControlDirectiveRecord {
+    pub max_age: u32,
+}
+
+#[derive(Clone, Copy)]
+pub struct CacheControlDirective<'a> {
+    pub(crate) schema: &'a Schema,
+    pub(crate) item: CacheControlDirectiveRecord,
+}
+
+impl std::ops::Deref for CacheControlDirective<'_> {
+    type Target = CacheControlDirectiveRecord;
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl<'a> CacheControlDirective<'a> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn as_ref(&self) -> &CacheControlDirectiveRecord {
+        &self.item
+    }
+}
+
+impl<'a> Walk<&'a Schema> for CacheControlDirectiveRecord {
+    type Walker<'w>
+        = CacheControlDirective<'w>
+    where
+        'a: 'w;
+    fn walk<'w>(self, schema: impl Into<&'a Schema>) -> Self::Walker<'w>
+    where
+        Self: 'w,
+        'a: 'w,
+    {
+        CacheControlDirective {
+            schema: schema.into(),
+            item: self,
+        }
+    }
+}
+
+impl std::fmt::Debug for CacheControlDirective<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CacheControlDirective")
+            .field("max_age", &self.max_age)
+            .finish()
+    }
+}
@@ -3,7 +3,7 @@
 //! ===================
 //! Generated with: `cargo run -p engine-codegen`
 //! Source file: <engine-codegen dir>/domain/schema.graphql
-use crate::{StringId, prelude::*};
+use crate::{StringId, TemplateId, prelude::*};
 #[allow(unused_imports)]
 use walker::{Iter, Walk};
 
@@ -13,12 +13,16 @@ use walker::{Iter, Walk};
 /// type InsertHeaderRule @meta(module: "header_rule/insert") @copy {
 ///   name: String!
 ///   value: String!
+///   "Present if the value is a template rendered for each request"
+///   value_template_id: TemplateId
 /// }
 /// ```
 #[derive(Debug, serde::Serialize, serde::Deserialize, Clone, Copy)]
 pub struct InsertHeaderRuleRecord {
     pub name_id: StringId,
     pub value_id: StringId,
+    /// Present if the value is a template rendered for each request
+    pub value_template_id: Option<TemplateId>,
 }
 
 #[derive(Clone, Copy)]
@@ -69,6 +73,7 @@ impl std::fmt::Debug for InsertHeaderRule<'_> {
         f.debug_struct("InsertHeaderRule")
             .field("name", &self.name())
             .field("value", &self.value())
+            .field("value_template_id", &self.value_template_id)
             .finish()
     }
 }
@@ -2,7 +2,12 @@ use std::time::Duration;
 
 use walker::{Iter, Walk};
 
-use crate::{ExtensionDirective, ExtensionDirectiveId, HeaderRule, RetryConfig, Subgraph};
+use crate::{
+    CircuitBreakerConfig, ExtensionDirective, ExtensionDirectiveId, GraphqlSubgraph, HeaderRule, HedgingConfig,
+    NameOrPattern, NameOrPatternId, RetryConfig, Schema, StringId, Subgraph,
+};
+
+pub use gateway_config::HeaderMergeStrategy;
 
 impl<'a> Subgraph<'a> {
     pub fn name(&self) -> &'a str {
@@ -36,11 +41,86 @@ impl<'a> Subgraph<'a> {
     }
 }
 
+impl<'a> GraphqlSubgraph<'a> {
+    /// Rules propagating the headers of this subgraph responses to the client response. The
+    /// default rules come first, followed by the ones specific to this subgraph.
+    pub fn response_header_rules(&self) -> impl Iter<Item = ResponseHeaderRule<'a>> + 'a {
+        self.as_ref().config.response_header_rules.as_slice().walk(self.schema)
+    }
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct SubgraphConfig {
     pub timeout: Duration,
     pub retry: Option<RetryConfig>,
+    // If None, requests to this subgraph are never hedged.
+    pub hedging: Option<HedgingConfig>,
+    // If None the circuit breaker is disabled for this subgraph.
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
     // The ttl to use for caching for this subgraph.
     // If None then caching is disabled for this subgraph
     pub cache_ttl: Option<Duration>,
+    // How long expired cache entries are served while being refreshed in the background.
+    pub cache_stale_while_revalidate: Duration,
+    // How long expired cache entries are served when the subgraph request fails.
+    pub cache_stale_if_error: Duration,
+    // Subgraph response headers propagated to the client response.
+    pub response_header_rules: Vec<ResponseHeaderRuleRecord>,
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ResponseHeaderRuleRecord {
+    pub name_id: NameOrPatternId,
+    pub rename_id: Option<StringId>,
+    pub merge: HeaderMergeStrategy,
+}
+
+#[derive(Clone, Copy)]
+pub struct ResponseHeaderRule<'a> {
+    pub(crate) schema: &'a Schema,
+    pub(crate) item: ResponseHeaderRuleRecord,
+}
+
+impl std::ops::Deref for ResponseHeaderRule<'_> {
+    type Target = ResponseHeaderRuleRecord;
+    fn deref(&self) -> &Self::Target {
+        &self.item
+    }
+}
+
+impl<'a> ResponseHeaderRule<'a> {
+    pub fn name(&self) -> NameOrPattern<'a> {
+        self.name_id.walk(self.schema)
+    }
+
+    pub fn rename(&self) -> Option<&'a str> {
+        self.rename_id.walk(self.schema)
+    }
+}
+
+impl<'a> Walk<&'a Schema> for ResponseHeaderRuleRecord {
+    type Walker<'w>
+        = ResponseHeaderRule<'w>
+    where
+        'a: 'w;
+    fn walk<'w>(self, schema: impl Into<&'a Schema>) -> Self::Walker<'w>
+    where
+        Self: 'w,
+        'a: 'w,
+    {
+        ResponseHeaderRule {
+            schema: schema.into(),
+            item: self,
+        }
+    }
+}
+
+impl std::fmt::Debug for ResponseHeaderRule<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseHeaderRule")
+            .field("name", &self.name())
+            .field("rename", &self.rename())
+            .field("merge", &self.merge)
+            .finish()
+    }
 }
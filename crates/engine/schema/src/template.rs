@@ -34,6 +34,8 @@ impl Clone for TemplateRecord {
 pub enum TemplateEscaping {
     Json,
     Url,
+    /// Strings are rendered as is, the result must be a valid header value.
+    Header,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -1,4 +1,7 @@
 pub(crate) mod cache;
+pub(crate) mod cache_refresh;
+mod circuit_breaker;
+pub(crate) mod hedging;
 pub mod mcp;
 mod retry_budget;
 mod runtime;
@@ -9,15 +12,18 @@ use ::runtime::{
 };
 use bytes::Bytes;
 use cache::CacheKey;
+use cache_refresh::CacheRefreshes;
+use circuit_breaker::CircuitBreakers;
 use error::{ErrorCode, ErrorResponse, GraphqlError};
 use event_queue::EventQueue;
 use futures::{StreamExt, TryFutureExt};
 use futures_util::Stream;
 use graphql_tools::{parser::parse_schema, static_graphql::schema::Document};
+use hedging::HedgingPolicies;
 use hive_console_sdk::agent::usage_agent::{UsageAgent, UsageAgentExt};
 use retry_budget::RetryBudgets;
 use schema::Schema;
-use std::{borrow::Cow, env, future::Future, sync::Arc};
+use std::{borrow::Cow, env, future::Future, net::IpAddr, sync::Arc};
 use tokio_util::sync::CancellationToken;
 
 use crate::{
@@ -44,6 +50,8 @@ pub struct RequestExtensions {
     pub contract_key: Option<String>,
     pub event_queue: Arc<EventQueue>,
     pub hooks_context: Arc<[u8]>,
+    /// IP address of the client, if known.
+    pub ip: Option<IpAddr>,
 }
 
 impl Default for RequestExtensions {
@@ -53,6 +61,7 @@ impl Default for RequestExtensions {
             contract_key: None,
             event_queue: Arc::new(EventQueue::default()),
             hooks_context: Arc::new([]),
+            ip: None,
         }
     }
 }
@@ -89,6 +98,25 @@ impl<R: Runtime> ContractAwareEngine<R> {
         }
     }
 
+    /// Removes the entity cache entries associated with any of the given tags, for the main
+    /// engine and all contracts.
+    pub async fn invalidate_entity_cache(&self, tags: &[String]) -> Result<(), String> {
+        let engines = std::iter::once(self.no_contract.clone())
+            .chain(self.by_contract_key.iter().map(|(_, engine)| engine))
+            .collect::<Vec<_>>();
+
+        for engine in engines {
+            engine
+                .runtime
+                .entity_cache()
+                .invalidate(tags)
+                .await
+                .map_err(|err| err.to_string())?;
+        }
+
+        Ok(())
+    }
+
     pub async fn create_websocket_session(
         self: &Arc<Self>,
         mut parts: http::request::Parts,
@@ -175,6 +203,9 @@ pub struct Engine<R: Runtime> {
     pub schema: Arc<Schema>,
     pub runtime: R,
     pub(crate) retry_budgets: RetryBudgets,
+    pub(crate) circuit_breakers: CircuitBreakers,
+    pub(crate) hedging_policies: HedgingPolicies,
+    pub(crate) cache_refreshes: CacheRefreshes,
     pub hive_usage_reporter: Option<HiveUsageReporter>,
 }
 
@@ -218,6 +249,9 @@ impl<R: Runtime> Engine<R> {
         }
         Self {
             retry_budgets: RetryBudgets::build(&schema),
+            circuit_breakers: CircuitBreakers::build(&schema, runtime.metrics()),
+            hedging_policies: HedgingPolicies::build(&schema),
+            cache_refreshes: CacheRefreshes::default(),
             schema,
             runtime,
             hive_usage_reporter,
@@ -6,6 +6,7 @@ use schema::Schema;
 
 mod namespaces {
     pub const OPERATION: &str = "op";
+    pub const RESPONSE: &str = "resp";
 }
 
 /// Unique cache key that generates a URL-safe string.
@@ -14,12 +15,27 @@ pub(crate) enum CacheKey<'a> {
         schema: &'a Schema,
         document: &'a DocumentKey<'a>,
     },
+    Response {
+        operation: &'a str,
+        variables: &'a [u8],
+        vary: &'a [Option<Vec<u8>>],
+    },
 }
 
 impl CacheKey<'_> {
     pub(crate) fn document(schema: &Schema, document: &DocumentKey<'_>) -> String {
         CacheKey::Operation { schema, document }.to_string()
     }
+
+    /// The operation must be the cache key of the operation document, which already includes the schema hash.
+    pub(crate) fn response(operation: &str, variables: &[u8], vary: &[Option<Vec<u8>>]) -> String {
+        CacheKey::Response {
+            operation,
+            variables,
+            vary,
+        }
+        .to_string()
+    }
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
@@ -125,6 +141,37 @@ impl std::fmt::Display for CacheKey<'_> {
                     Base64Display::new(hash.as_bytes(), &URL_SAFE_NO_PAD)
                 ))
             }
+            CacheKey::Response {
+                operation,
+                variables,
+                vary,
+            } => {
+                let mut hasher = blake3::Hasher::new();
+                hasher.update(operation.as_bytes());
+                hasher.update(&[0x00]);
+                hasher.update(&variables.len().to_ne_bytes());
+                hasher.update(variables);
+                for value in vary.iter() {
+                    // Distinguishes a missing value from an empty one.
+                    match value {
+                        Some(value) => {
+                            hasher.update(&[0x01]);
+                            hasher.update(&value.len().to_ne_bytes());
+                            hasher.update(value);
+                        }
+                        None => {
+                            hasher.update(&[0x00]);
+                        }
+                    }
+                }
+                let hash = hasher.finalize();
+
+                f.write_fmt(format_args!(
+                    "{}.blake3.{}",
+                    namespaces::RESPONSE,
+                    Base64Display::new(hash.as_bytes(), &URL_SAFE_NO_PAD)
+                ))
+            }
         }
     }
 }
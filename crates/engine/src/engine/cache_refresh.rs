@@ -0,0 +1,57 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+
+/// Cache keys currently refreshed in the background, so that concurrent requests serving the
+/// same stale entries trigger a single subgraph request.
+#[derive(Default)]
+pub(crate) struct CacheRefreshes {
+    in_flight: Arc<Mutex<HashSet<String>>>,
+}
+
+impl CacheRefreshes {
+    /// Claims the keys which aren't already being refreshed. The claim is released once the
+    /// returned guard is dropped.
+    pub fn claim<'k>(&self, keys: impl IntoIterator<Item = &'k str>) -> CacheRefreshGuard {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        let keys = keys
+            .into_iter()
+            .filter(|key| in_flight.insert((*key).to_string()))
+            .map(str::to_string)
+            .collect();
+
+        CacheRefreshGuard {
+            in_flight: self.in_flight.clone(),
+            keys,
+        }
+    }
+}
+
+pub(crate) struct CacheRefreshGuard {
+    in_flight: Arc<Mutex<HashSet<String>>>,
+    keys: Vec<String>,
+}
+
+impl CacheRefreshGuard {
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.iter().any(|claimed| claimed == key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl Drop for CacheRefreshGuard {
+    fn drop(&mut self) {
+        if self.keys.is_empty() {
+            return;
+        }
+
+        let mut in_flight = self.in_flight.lock().unwrap();
+        for key in &self.keys {
+            in_flight.remove(key);
+        }
+    }
+}
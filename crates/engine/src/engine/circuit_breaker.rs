@@ -0,0 +1,258 @@
+use std::{
+    collections::VecDeque,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use grafbase_telemetry::metrics::{CircuitBreakerState, EngineMetrics, SubgraphCircuitBreakerAttributes};
+use schema::{CircuitBreakerConfig, GraphqlSubgraphId, Schema};
+
+use super::Runtime;
+
+/// Number of buckets the error rate window is divided into.
+const WINDOW_BUCKETS: u32 = 10;
+
+#[derive(id_derives::IndexedFields)]
+pub(crate) struct CircuitBreakers {
+    #[indexed_by(GraphqlSubgraphId)]
+    by_graphql_endpoints: Vec<Option<CircuitBreaker>>,
+}
+
+impl CircuitBreakers {
+    pub fn build(schema: &Schema, metrics: &EngineMetrics) -> Self {
+        Self {
+            by_graphql_endpoints: schema
+                .graphql_subgraphs()
+                .map(|subgraph| {
+                    let config = subgraph.config.circuit_breaker?;
+                    Some(CircuitBreaker::new(
+                        subgraph.name().to_string(),
+                        config,
+                        metrics.clone(),
+                    ))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<R: Runtime> super::Engine<R> {
+    pub(crate) fn get_circuit_breaker(&self, subgraph_id: GraphqlSubgraphId) -> Option<&CircuitBreaker> {
+        self.circuit_breakers[subgraph_id].as_ref()
+    }
+}
+
+/// Stops sending requests to a failing subgraph for a while, so that requests fail fast instead
+/// of waiting for the subgraph timeout.
+///
+/// The circuit opens once the consecutive failures or the error rate threshold is reached.
+/// After the cooldown, a limited number of probe requests are let through in the half-open
+/// state: the first result either closes the circuit again or re-opens it.
+pub(crate) struct CircuitBreaker {
+    subgraph_name: String,
+    config: CircuitBreakerConfig,
+    metrics: EngineMetrics,
+    state: Mutex<State>,
+}
+
+enum State {
+    Closed {
+        consecutive_failures: u32,
+        window: FailureWindow,
+    },
+    Open {
+        until: Instant,
+    },
+    HalfOpen {
+        remaining_probes: u32,
+        // Probes may never complete if their request is cancelled, so they're let through again
+        // after the cooldown.
+        probing_since: Instant,
+    },
+}
+
+impl CircuitBreaker {
+    fn new(subgraph_name: String, config: CircuitBreakerConfig, metrics: EngineMetrics) -> Self {
+        let breaker = Self {
+            subgraph_name,
+            config,
+            metrics,
+            state: Mutex::new(State::closed()),
+        };
+        breaker.record_state(CircuitBreakerState::Closed);
+        breaker
+    }
+
+    /// Whether a request can be sent to the subgraph.
+    pub fn try_acquire(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        let allowed = match &mut *state {
+            State::Closed { .. } => true,
+            State::Open { until } if Instant::now() < *until => false,
+            State::Open { .. } => {
+                *state = self.half_open();
+                self.record_state(CircuitBreakerState::HalfOpen);
+                true
+            }
+            State::HalfOpen {
+                remaining_probes: 0,
+                probing_since,
+            } if probing_since.elapsed() < self.config.cooldown => false,
+            State::HalfOpen {
+                remaining_probes: 0, ..
+            } => {
+                *state = self.half_open();
+                true
+            }
+            State::HalfOpen { remaining_probes, .. } => {
+                *remaining_probes -= 1;
+                true
+            }
+        };
+
+        if !allowed {
+            self.metrics
+                .record_subgraph_circuit_breaker_rejection(SubgraphCircuitBreakerAttributes {
+                    name: self.subgraph_name.clone(),
+                });
+        }
+
+        allowed
+    }
+
+    /// Records the outcome of a request which was sent to the subgraph.
+    pub fn record(&self, success: bool) {
+        let mut state = self.state.lock().unwrap();
+
+        match &mut *state {
+            State::Closed {
+                consecutive_failures,
+                window,
+            } => {
+                window.record(&self.config, success);
+
+                if success {
+                    *consecutive_failures = 0;
+                    return;
+                }
+                *consecutive_failures += 1;
+
+                let too_many_consecutive_failures = self
+                    .config
+                    .consecutive_failures
+                    .is_some_and(|threshold| *consecutive_failures >= threshold);
+                let error_rate_too_high = self
+                    .config
+                    .error_rate
+                    .is_some_and(|threshold| window.error_rate(self.config.minimum_requests) >= Some(threshold));
+
+                if too_many_consecutive_failures || error_rate_too_high {
+                    tracing::warn!("Opening the circuit breaker of subgraph '{}'", self.subgraph_name);
+                    *state = self.open();
+                }
+            }
+            // Responses of requests sent before the circuit opened.
+            State::Open { .. } => {}
+            State::HalfOpen { .. } if success => {
+                tracing::info!("Closing the circuit breaker of subgraph '{}'", self.subgraph_name);
+                *state = State::closed();
+                self.record_state(CircuitBreakerState::Closed);
+            }
+            State::HalfOpen { .. } => {
+                tracing::warn!("Re-opening the circuit breaker of subgraph '{}'", self.subgraph_name);
+                *state = self.open();
+            }
+        }
+    }
+
+    /// Half-open state after letting the first probe through.
+    fn half_open(&self) -> State {
+        State::HalfOpen {
+            remaining_probes: self.config.half_open_requests - 1,
+            probing_since: Instant::now(),
+        }
+    }
+
+    fn open(&self) -> State {
+        self.record_state(CircuitBreakerState::Open);
+        State::Open {
+            until: Instant::now() + self.config.cooldown,
+        }
+    }
+
+    fn record_state(&self, state: CircuitBreakerState) {
+        self.metrics.record_subgraph_circuit_breaker_state(
+            SubgraphCircuitBreakerAttributes {
+                name: self.subgraph_name.clone(),
+            },
+            state,
+        );
+    }
+}
+
+impl State {
+    fn closed() -> Self {
+        State::Closed {
+            consecutive_failures: 0,
+            window: FailureWindow::default(),
+        }
+    }
+}
+
+/// Request outcomes over the sliding window, aggregated in buckets.
+#[derive(Default)]
+struct FailureWindow {
+    buckets: VecDeque<Bucket>,
+}
+
+struct Bucket {
+    start: Instant,
+    requests: u32,
+    failures: u32,
+}
+
+impl FailureWindow {
+    fn record(&mut self, config: &CircuitBreakerConfig, success: bool) {
+        let now = Instant::now();
+        let bucket_duration = (config.window / WINDOW_BUCKETS).max(Duration::from_millis(1));
+
+        while self
+            .buckets
+            .front()
+            .is_some_and(|bucket| now.duration_since(bucket.start) >= config.window)
+        {
+            self.buckets.pop_front();
+        }
+
+        if self
+            .buckets
+            .back()
+            .is_none_or(|bucket| now.duration_since(bucket.start) >= bucket_duration)
+        {
+            self.buckets.push_back(Bucket {
+                start: now,
+                requests: 0,
+                failures: 0,
+            });
+        }
+
+        let bucket = self.buckets.back_mut().expect("A bucket was just pushed");
+        bucket.requests += 1;
+        if !success {
+            bucket.failures += 1;
+        }
+    }
+
+    fn error_rate(&self, minimum_requests: u32) -> Option<f64> {
+        let (requests, failures) = self.buckets.iter().fold((0, 0), |(requests, failures), bucket| {
+            (requests + bucket.requests, failures + bucket.failures)
+        });
+
+        if requests == 0 || requests < minimum_requests {
+            return None;
+        }
+
+        Some(failures as f64 / requests as f64)
+    }
+}
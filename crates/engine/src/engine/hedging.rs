@@ -0,0 +1,117 @@
+use std::{collections::VecDeque, sync::Mutex, time::Duration};
+
+use schema::{GraphqlSubgraphId, HedgingConfig, Schema};
+
+use super::Runtime;
+
+/// Number of recent latencies kept to compute the hedging delay percentile.
+const LATENCY_SAMPLES: usize = 1000;
+/// Minimum number of latencies before relying on the percentile rather than the fixed delay.
+const MINIMUM_SAMPLES: usize = 20;
+/// The percentile is only re-computed every so often, as it requires sorting the samples.
+const RECOMPUTE_INTERVAL: u32 = 50;
+
+#[derive(id_derives::IndexedFields)]
+pub(crate) struct HedgingPolicies {
+    #[indexed_by(GraphqlSubgraphId)]
+    by_graphql_endpoints: Vec<Option<HedgingPolicy>>,
+}
+
+impl HedgingPolicies {
+    pub fn build(schema: &Schema) -> Self {
+        Self {
+            by_graphql_endpoints: schema
+                .graphql_subgraphs()
+                .map(|subgraph| {
+                    let config = subgraph.config.hedging?;
+
+                    // Hedges are withdrawn from the retry budget, there is nothing to draw from
+                    // without it.
+                    if subgraph.config.retry.is_none() && schema.config.retry.is_none() {
+                        tracing::warn!(
+                            "Hedging is enabled for subgraph '{}' but retries are not, requests won't be hedged.",
+                            subgraph.name()
+                        );
+                        return None;
+                    }
+
+                    Some(HedgingPolicy::new(config))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<R: Runtime> super::Engine<R> {
+    /// Only queries may be hedged, it's up to the caller to check the operation type.
+    pub(crate) fn get_hedging_policy(&self, subgraph_id: GraphqlSubgraphId) -> Option<&HedgingPolicy> {
+        self.hedging_policies[subgraph_id].as_ref()
+    }
+}
+
+/// Decides how long to wait for a subgraph response before sending a second identical request.
+pub(crate) struct HedgingPolicy {
+    config: HedgingConfig,
+    latencies: Mutex<LatencyWindow>,
+}
+
+impl HedgingPolicy {
+    fn new(config: HedgingConfig) -> Self {
+        Self {
+            config,
+            latencies: Mutex::new(LatencyWindow {
+                samples: VecDeque::with_capacity(LATENCY_SAMPLES),
+                recorded_since_compute: 0,
+                percentile_latency: None,
+            }),
+        }
+    }
+
+    pub fn delay(&self) -> Duration {
+        match self.config.percentile {
+            Some(_) => self
+                .latencies
+                .lock()
+                .unwrap()
+                .percentile_latency
+                .unwrap_or(self.config.delay),
+            None => self.config.delay,
+        }
+    }
+
+    pub fn record_latency(&self, latency: Duration) {
+        let Some(percentile) = self.config.percentile else {
+            return;
+        };
+
+        let mut window = self.latencies.lock().unwrap();
+        if window.samples.len() == LATENCY_SAMPLES {
+            window.samples.pop_front();
+        }
+        window.samples.push_back(latency);
+        window.recorded_since_compute += 1;
+
+        if window.samples.len() >= MINIMUM_SAMPLES
+            && (window.percentile_latency.is_none() || window.recorded_since_compute >= RECOMPUTE_INTERVAL)
+        {
+            window.compute_percentile(percentile);
+        }
+    }
+}
+
+struct LatencyWindow {
+    samples: VecDeque<Duration>,
+    recorded_since_compute: u32,
+    percentile_latency: Option<Duration>,
+}
+
+impl LatencyWindow {
+    fn compute_percentile(&mut self, percentile: f64) {
+        let mut sorted = self.samples.iter().copied().collect::<Vec<_>>();
+        sorted.sort_unstable();
+
+        let index = ((sorted.len() as f64 * percentile).ceil() as usize).clamp(1, sorted.len()) - 1;
+        self.percentile_latency = Some(sorted[index]);
+        self.recorded_since_compute = 0;
+    }
+}
@@ -1,7 +1,9 @@
 use std::{future::Future, sync::Arc};
 
 use grafbase_telemetry::metrics::EngineMetrics;
-use runtime::{entity_cache::EntityCache, extension::EngineExtensions, rate_limiting::RateLimiter};
+use runtime::{
+    entity_cache::EntityCache, extension::EngineExtensions, rate_limiting::RateLimiter, response_cache::ResponseCache,
+};
 use schema::Schema;
 
 use crate::{CachedOperation, EngineOperationContext, EngineRequestContext};
@@ -18,6 +20,7 @@ pub trait Runtime: Send + Sync + Sized + 'static {
     fn rate_limiter(&self) -> &RateLimiter;
     fn sleep(&self, duration: std::time::Duration) -> impl Future<Output = ()> + Send;
     fn entity_cache(&self) -> &dyn EntityCache;
+    fn response_cache(&self) -> &dyn ResponseCache;
     fn extensions(&self) -> &Self::Extensions;
 
     fn clone_and_adjust_for_contract(&self, schema: &Arc<Schema>) -> impl Future<Output = Result<Self, String>> + Send;
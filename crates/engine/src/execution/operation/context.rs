@@ -7,7 +7,7 @@ use schema::{HeaderRule, Schema};
 
 use crate::{
     Engine, Runtime,
-    execution::{RequestContext, apply_header_rules},
+    execution::{HeaderRuleContext, RequestContext, apply_header_rules},
     prepare::{CachedOperationContext, OperationPlanContext, PreparedOperation, Shapes},
 };
 
@@ -41,7 +41,13 @@ impl<'ctx, R: Runtime> ExecutionContext<'ctx, R> {
             .as_ref()
             .unwrap_or(&self.request_context.subgraph_default_headers)
             .clone();
-        apply_header_rules(&self.request_context.headers, rules, &mut subgraph_headers);
+        let request_context = self.request_context;
+        let header_rule_context =
+            HeaderRuleContext::new(self.schema(), &request_context.headers, &request_context.token)
+                .with_ip(request_context.ip)
+                .with_client(request_context.client.as_ref())
+                .with_operation(&self.operation.cached.operation.attributes);
+        apply_header_rules(&header_rule_context, rules, &mut subgraph_headers);
         subgraph_headers
     }
 
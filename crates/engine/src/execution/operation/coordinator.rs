@@ -22,6 +22,13 @@ pub(crate) trait ResponseSender: Send {
     fn send(&mut self, response: Response) -> impl Future<Output = Result<(), Self::Error>> + Send;
 }
 
+impl<S: ResponseSender> ResponseSender for &mut S {
+    type Error = S::Error;
+    fn send(&mut self, response: Response) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        (**self).send(response)
+    }
+}
+
 impl<R: Runtime> PrepareContext<'_, R> {
     pub async fn execute_query_or_mutation(mut self, operation: PreparedOperation) -> Response {
         let background_futures: FuturesUnordered<_> =
@@ -87,7 +94,7 @@ impl<'ctx, R: Runtime> ExecutionContext<'ctx, R> {
         Response::execution_error(&self.engine.schema, self.operation, errors)
     }
 
-    async fn response_for_root_errors(self, mut builder: ExecutedOperationBuilder<'_>) -> Response {
+    pub(super) async fn response_for_root_errors(self, mut builder: ExecutedOperationBuilder<'_>) -> Response {
         builder.status(GraphqlResponseStatus::FieldError {
             count: self.operation.plan.query_modifications.root_error_ids.len() as u64,
             data_is_null: true,
@@ -110,20 +117,13 @@ impl<'ctx, R: Runtime> ExecutionContext<'ctx, R> {
         )
     }
 
-    async fn execute(self, builder: ExecutedOperationBuilder<'_>) -> Response {
+    async fn execute(self, builder: ExecutedOperationBuilder<'ctx>) -> Response {
         assert!(
             !matches!(self.operation.cached.ty(), OperationType::Subscription),
             "execute shouldn't be called for subscriptions"
         );
 
-        OperationExecution {
-            state: self.new_execution_state(),
-            executed_operation_builder: builder,
-            response: ResponseBuilder::new(&self.engine.schema, self.operation),
-            ctx: self,
-        }
-        .run(VecDeque::new())
-        .await
+        OperationExecution::new(self, builder).run(VecDeque::new()).await
     }
 
     async fn execute_subscription(
@@ -240,11 +240,11 @@ where
     }
 }
 
-struct OperationExecution<'ctx, R: Runtime> {
+pub(super) struct OperationExecution<'ctx, R: Runtime> {
     ctx: ExecutionContext<'ctx, R>,
     executed_operation_builder: ExecutedOperationBuilder<'ctx>,
-    state: OperationExecutionState<'ctx, R>,
-    response: ResponseBuilder<'ctx>,
+    pub(super) state: OperationExecutionState<'ctx, R>,
+    pub(super) response: ResponseBuilder<'ctx>,
 }
 
 impl<'ctx, R: Runtime> std::ops::Deref for OperationExecution<'ctx, R> {
@@ -265,22 +265,50 @@ enum TaskResult<U, V> {
 }
 
 impl<'ctx, R: Runtime> OperationExecution<'ctx, R> {
+    pub(super) fn new(
+        ctx: ExecutionContext<'ctx, R>,
+        executed_operation_builder: ExecutedOperationBuilder<'ctx>,
+    ) -> Self {
+        OperationExecution {
+            state: ctx.new_execution_state(),
+            executed_operation_builder,
+            response: ResponseBuilder::new(&ctx.engine.schema, ctx.operation),
+            ctx,
+        }
+    }
+
     /// Runs a single execution to completion, returning its response
-    async fn run(mut self, mut results: VecDeque<PlanExecutionResult<'ctx>>) -> Response {
+    async fn run(self, results: VecDeque<PlanExecutionResult<'ctx>>) -> Response {
+        self.drive(results).await.finish().await
+    }
+
+    /// Executes plans until none is left to execute, which for an incremental delivery means
+    /// until the end of the current phase.
+    pub(super) async fn drive(self, results: VecDeque<PlanExecutionResult<'ctx>>) -> Self {
+        self.drive_with(results, |_| {}).await
+    }
+
+    /// Same as `drive`, calling `on_ingested` with the response after each plan result is
+    /// ingested.
+    pub(super) async fn drive_with(
+        mut self,
+        mut results: VecDeque<PlanExecutionResult<'ctx>>,
+        mut on_ingested: impl FnMut(&mut ResponseBuilder<'ctx>) + Send,
+    ) -> Self {
         let futures = FuturesUnordered::new();
-        let initial_plans = self.state.get_executable_plans().collect::<Vec<_>>();
-        let event_queue = self.ctx.event_queue();
+        let initial_plans = self.state.get_executable_plans();
 
-        for plan in initial_plans {
-            if let Some(fut) = self.create_plan_execution_future(plan) {
-                futures.push(fut);
-            }
+        for fut in self
+            .execute_executables(initial_plans.into_iter().map(Executable::Plan).collect())
+            .await
+        {
+            futures.push(fut);
         }
 
         let mut state = State::Execution(self);
         futures_util::pin_mut!(futures);
 
-        let mut this = loop {
+        loop {
             state = match state {
                 State::Ingestion(mut ingestion_fut) => {
                     let task_result = futures_util::select_biased! {
@@ -289,7 +317,8 @@ impl<'ctx, R: Runtime> OperationExecution<'ctx, R> {
                     };
 
                     match task_result {
-                        TaskResult::Ingestion((this, next_futures)) => {
+                        TaskResult::Ingestion((mut this, next_futures)) => {
+                            on_ingested(&mut this.response);
                             for fut in next_futures {
                                 futures.push(fut);
                             }
@@ -300,7 +329,8 @@ impl<'ctx, R: Runtime> OperationExecution<'ctx, R> {
                             State::Ingestion(ingestion_fut)
                         }
                         TaskResult::Execution(None) => {
-                            let (self_, next_futures) = ingestion_fut.await;
+                            let (mut self_, next_futures) = ingestion_fut.await;
+                            on_ingested(&mut self_.response);
                             for fut in next_futures {
                                 futures.push(fut)
                             }
@@ -319,25 +349,45 @@ impl<'ctx, R: Runtime> OperationExecution<'ctx, R> {
                     }
                 }
             };
-        };
+        }
+    }
 
-        let operation = this.ctx.operation;
+    pub(super) async fn finish(mut self) -> Response {
+        let event_queue = self.ctx.event_queue();
+        let operation = self.ctx.operation;
+        let status = self.response.graphql_status();
+        let mut response = self.response.build(operation.attributes());
+
+        let actual_cost = match operation.complexity_cost {
+            Some(estimated_cost) => {
+                let actual_cost = response.actual_cost();
+                if let Some(actual_cost) = actual_cost {
+                    self.ctx.spend_cost(estimated_cost, actual_cost, &mut response).await;
+                }
+                actual_cost
+            }
+            None => None,
+        };
 
         if let Some(name) = operation.cached.operation.attributes.name.original() {
-            this.executed_operation_builder.name(name);
+            self.executed_operation_builder.name(name);
         }
 
-        this.executed_operation_builder
+        self.executed_operation_builder
             .document(&operation.cached.operation.attributes.sanitized_query)
-            .status(this.response.graphql_status());
+            .status(status);
 
         if let Some(complexity) = operation.complexity_cost {
-            this.executed_operation_builder.complexity(complexity.0 as u64);
+            self.executed_operation_builder.complexity(complexity.0 as u64);
+        }
+
+        if let Some(complexity) = actual_cost {
+            self.executed_operation_builder.actual_complexity(complexity.0 as u64);
         }
 
-        event_queue.push_operation(this.executed_operation_builder);
+        event_queue.push_operation(self.executed_operation_builder);
 
-        this.response.build(operation.attributes())
+        response
     }
 
     async fn ingest_execution_result<'exec>(
@@ -347,7 +397,7 @@ impl<'ctx, R: Runtime> OperationExecution<'ctx, R> {
     where
         'ctx: 'exec,
     {
-        let PartIngestionResult::Data { response_object_sets } = self.response.ingest(response_part) else {
+        let PartIngestionResult::Data { response_object_sets } = self.response.ingest(plan_id, response_part) else {
             tracing::trace!(%plan_id, "Failed");
             return (self, Vec::new());
         };
@@ -359,7 +409,19 @@ impl<'ctx, R: Runtime> OperationExecution<'ctx, R> {
         }
 
         let plan = plan_id.walk(&self.ctx);
-        let mut stack = self.state.get_next_executables(plan);
+        let stack = self.state.get_next_executables(plan);
+        let next_futures = self.execute_executables(stack).await;
+
+        (self, next_futures)
+    }
+
+    async fn execute_executables<'exec>(
+        &mut self,
+        mut stack: Vec<Executable<'ctx>>,
+    ) -> Vec<BoxFuture<'exec, PlanExecutionResult<'ctx>>>
+    where
+        'ctx: 'exec,
+    {
         let mut next_futures = Vec::new();
 
         while let Some(executable) = stack.pop() {
@@ -369,6 +431,8 @@ impl<'ctx, R: Runtime> OperationExecution<'ctx, R> {
                 Executable::Plan(plan) => {
                     if let Some(fut) = self.create_plan_execution_future(plan) {
                         next_futures.push(fut);
+                    } else if self.state.completes_plans_without_input() {
+                        stack.append(&mut self.state.get_next_executables(plan));
                     }
                 }
                 Executable::ResponseModifier(response_modifier) => {
@@ -380,7 +444,7 @@ impl<'ctx, R: Runtime> OperationExecution<'ctx, R> {
             }
         }
 
-        (self, next_futures)
+        next_futures
     }
 
     fn create_plan_execution_future<'exec>(
@@ -0,0 +1,41 @@
+use grafbase_telemetry::metrics::OperationCostAttributes;
+use operation::ComplexityCost;
+use runtime::rate_limiting::RateLimitKey;
+
+use crate::{Runtime, execution::ExecutionContext, response::Response};
+
+impl<R: Runtime> ExecutionContext<'_, R> {
+    /// Reports the cost of the executed operation and spends it from the client's cost budget.
+    pub(crate) async fn spend_cost(&self, estimated: ComplexityCost, actual: ComplexityCost, response: &mut Response) {
+        let attributes = &self.operation.cached.operation.attributes;
+
+        self.metrics().record_operation_cost(
+            OperationCostAttributes {
+                operation_name: attributes.name.original().map(str::to_string),
+                client: self.request_context.client.clone(),
+            },
+            estimated.0,
+            actual.0,
+        );
+
+        if self.request_context.include_grafbase_response_extension
+            && self.schema().config.response_extension.include_cost
+        {
+            let extensions = response.extensions_mut();
+            extensions.grafbase = Some(
+                extensions
+                    .grafbase
+                    .take()
+                    .unwrap_or_default()
+                    .with_cost(estimated.0, actual.0),
+            );
+        }
+
+        if let Some(client) = self.request_context.cost_budget_client(self.schema()) {
+            let key = RateLimitKey::CostBudget(client.into());
+            if let Err(err) = self.runtime().rate_limiter().consume(&key, actual.0 as u64).await {
+                tracing::error!("Failed to spend the operation cost from the budget: {err}");
+            }
+        }
+    }
+}
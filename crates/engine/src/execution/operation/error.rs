@@ -12,6 +12,8 @@ pub enum ExecutionError {
     Fetch { subgraph_name: String, error: FetchError },
     #[error(transparent)]
     RateLimit(#[from] runtime::rate_limiting::Error),
+    #[error("Request to subgraph '{subgraph_name}' was not sent, its circuit breaker is open.")]
+    CircuitBreakerOpen { subgraph_name: String },
     #[error("{0}")]
     Graphql(GraphqlError),
 }
@@ -40,6 +42,7 @@ impl From<ExecutionError> for GraphqlError {
             ExecutionError::Internal(_) => ErrorCode::InternalServerError,
             ExecutionError::Fetch { .. } => ErrorCode::SubgraphRequestError,
             ExecutionError::RateLimit(_) => ErrorCode::RateLimited,
+            ExecutionError::CircuitBreakerOpen { .. } => ErrorCode::SubgraphCircuitBreakerOpen,
             ExecutionError::Graphql(err) => err.code,
         };
         GraphqlError::new(message, code)
@@ -0,0 +1,105 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use futures::channel::mpsc;
+use futures_util::{StreamExt, stream::FuturesUnordered};
+use operation::OperationContext;
+
+use crate::{
+    Runtime,
+    execution::{ExecutionContext, ResponseSender},
+    prepare::{PrepareContext, PreparedOperation},
+    response::{IncrementalPayloads, ResponseBuilder},
+};
+
+use super::coordinator::OperationExecution;
+
+impl<R: Runtime> PrepareContext<'_, R> {
+    /// Executes a query with @defer and/or @stream. The operation is executed once: deferred
+    /// plans and plans depending on streamed items beyond the initial count are held until the
+    /// initial payload is sent, see `IncrementalPlan`. Each subsequent payload then carries the
+    /// deferred fragments and streamed items completed by the plans executed since the previous
+    /// one.
+    pub async fn execute_incremental_query(mut self, operation: PreparedOperation, mut responses: impl ResponseSender) {
+        let background_futures: FuturesUnordered<_> =
+            std::mem::take(&mut self.background_futures).into_iter().collect();
+
+        let background_fut = background_futures.collect::<Vec<_>>();
+        let operation = Arc::new(operation);
+
+        let ctx = ExecutionContext {
+            engine: self.engine,
+            request_context: self.request_context,
+            operation: &operation,
+        };
+        let mut payloads = IncrementalPayloads::new(
+            OperationContext {
+                schema: &self.engine.schema,
+                operation: &operation.cached.operation,
+            },
+            &operation.variables,
+        );
+
+        tracing::trace!("Starting incremental execution...");
+
+        let delivery_fut = async {
+            if !operation.plan.query_modifications.root_error_ids.is_empty() {
+                let response = ctx.response_for_root_errors(self.executed_operation_builder).await;
+                responses.send(payloads.complete(response)).await.ok();
+                return;
+            }
+
+            let mut execution = OperationExecution::new(ctx, self.executed_operation_builder)
+                .drive(VecDeque::new())
+                .await;
+
+            let initial = execution
+                .response
+                .with_snapshot(operation.attributes(), |response| payloads.initial(response));
+
+            if responses.send(initial).await.is_err() || !payloads.has_next() {
+                execution.finish().await;
+                return;
+            }
+
+            execution.state.start_subsequent_phase();
+
+            // Subsequent payloads are sent as soon as plans complete deferred fragments or
+            // streamed items, while the execution goes on.
+            let (sender, mut receiver) = mpsc::unbounded();
+            let execution_fut = async {
+                let mut send_completed = |response: &mut ResponseBuilder<'_>| {
+                    if let Some(payload) =
+                        response.with_snapshot(operation.attributes(), |response| payloads.next(response))
+                    {
+                        sender.unbounded_send(payload).ok();
+                    }
+                };
+
+                // Deferred fragments may be entirely resolved by the plans of the initial phase.
+                send_completed(&mut execution.response);
+                let response = execution
+                    .drive_with(VecDeque::new(), send_completed)
+                    .await
+                    .finish()
+                    .await;
+
+                if let Some(payload) = payloads.last(response) {
+                    sender.unbounded_send(payload).ok();
+                }
+                drop(sender);
+            };
+
+            let forward_fut = async {
+                while let Some(payload) = receiver.next().await {
+                    if responses.send(payload).await.is_err() {
+                        break;
+                    }
+                }
+            };
+
+            futures_util::join!(execution_fut, forward_fut);
+        };
+
+        futures_util::join!(delivery_fut, background_fut);
+    }
+}
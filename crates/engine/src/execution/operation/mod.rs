@@ -1,6 +1,8 @@
 mod context;
 mod coordinator;
+mod cost;
 mod error;
+mod incremental;
 mod response_modifier;
 mod state;
 
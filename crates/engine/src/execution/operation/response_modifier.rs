@@ -22,14 +22,14 @@ impl<'ctx, R: Runtime> ExecutionContext<'ctx, R> {
         response_modifier: ResponseModifier<'ctx>,
     ) {
         for target in response_modifier.sorted_targets() {
-            let Some(refs) = state[target.set_id].as_ref() else {
+            let Some(refs) = state.get_response_objects(response_modifier.id.into(), target.set_id) else {
                 continue;
             };
 
             let parent_objects = if self.operation.cached.query_plan[target.set_id].ty_id == target.ty_id {
-                ParentObjectSet::default().with_response_objects(refs.clone())
+                ParentObjectSet::default().with_response_objects(refs)
             } else {
-                ParentObjectSet::default().with_filtered_response_objects(self.schema(), target.ty_id, refs.clone())
+                ParentObjectSet::default().with_filtered_response_objects(self.schema(), target.ty_id, refs)
             };
 
             if parent_objects.is_empty() {
@@ -1,12 +1,13 @@
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 
 use id_derives::IndexedFields;
+use id_newtypes::BitSet;
 use walker::Walk;
 
 use crate::{
     Runtime,
-    prepare::{Executable, Plan, PlanId, ResponseModifierId, ResponseObjectSetId},
-    response::{ParentObjectSet, ResponseBuilder, ResponseObjectSet},
+    prepare::{Executable, ExecutableId, IncrementalPlan, Plan, PlanId, ResponseModifierId, ResponseObjectSetId},
+    response::{ParentObjectSet, ResponseBuilder, ResponseObjectRef, ResponseObjectSet, ResponseValueId},
 };
 
 use super::ExecutionContext;
@@ -30,6 +31,19 @@ pub(crate) struct OperationExecutionState<'ctx, R: Runtime> {
     plan_to_parent_count: Vec<usize>,
     #[indexed_by(ResponseModifierId)]
     response_modifier_to_parent_count: Vec<usize>,
+    incremental: Option<IncrementalState>,
+}
+
+/// An incremental delivery is executed in two phases. The initial one executes all the plans
+/// needed for the initial payload, ignoring the items of streamed lists beyond their initial
+/// count. The subsequent phase executes everything else: the deferred plans on all their
+/// objects, and the plans and response modifiers of the initial phase on the remaining streamed
+/// items.
+#[derive(Clone)]
+struct IncrementalState {
+    is_initial_phase: bool,
+    initial_plan_ids: BitSet<PlanId>,
+    initial_response_modifier_ids: BitSet<ResponseModifierId>,
 }
 
 impl<R: Runtime> Clone for OperationExecutionState<'_, R> {
@@ -39,6 +53,7 @@ impl<R: Runtime> Clone for OperationExecutionState<'_, R> {
             response_object_sets: self.response_object_sets.clone(),
             plan_to_parent_count: self.plan_to_parent_count.clone(),
             response_modifier_to_parent_count: self.response_modifier_to_parent_count.clone(),
+            incremental: self.incremental.clone(),
         }
     }
 }
@@ -56,12 +71,45 @@ impl<'ctx, R: Runtime> OperationExecutionState<'ctx, R> {
                 .iter()
                 .map(|exec| exec.parent_count)
                 .collect(),
+            incremental: ctx.operation.plan.incremental.as_ref().map(|_| IncrementalState {
+                is_initial_phase: true,
+                initial_plan_ids: BitSet::with_capacity(ctx.operation.plan.plans.len()),
+                initial_response_modifier_ids: BitSet::with_capacity(ctx.operation.plan.response_modifiers.len()),
+            }),
+        }
+    }
+
+    /// Switches to the subsequent phase of an incremental delivery, all plans and response
+    /// modifiers are scheduled again from the start.
+    pub fn start_subsequent_phase(&mut self) {
+        let Some(incremental) = &mut self.incremental else {
+            return;
+        };
+        incremental.is_initial_phase = false;
+
+        for (count, plan) in self.plan_to_parent_count.iter_mut().zip(&self.ctx.operation.plan.plans) {
+            *count = plan.parent_count;
+        }
+        for (count, modifier) in self
+            .response_modifier_to_parent_count
+            .iter_mut()
+            .zip(&self.ctx.operation.plan.response_modifiers)
+        {
+            *count = modifier.parent_count;
         }
     }
 
+    /// During the subsequent phase of an incremental delivery, plans without any objects to work
+    /// on are considered executed to unblock the ones depending on them.
+    pub fn completes_plans_without_input(&self) -> bool {
+        self.incremental
+            .as_ref()
+            .is_some_and(|incremental| !incremental.is_initial_phase)
+    }
+
     pub fn pop_subscription_plan(&mut self) -> Plan<'ctx> {
         let plan = {
-            let mut executable = self.get_executable_plans();
+            let mut executable = self.get_executable_plans().into_iter();
             let plan = executable.next().expect("Must have at least one plan");
             assert!(executable.next().is_none());
             plan
@@ -71,21 +119,118 @@ impl<'ctx, R: Runtime> OperationExecutionState<'ctx, R> {
         plan
     }
 
-    pub fn get_executable_plans(&self) -> impl Iterator<Item = Plan<'ctx>> + '_ {
-        self.plan_to_parent_count.iter().enumerate().filter_map(|(i, &count)| {
-            if count == 0 {
-                Some(PlanId::from(i).walk(&self.ctx))
-            } else {
-                None
+    pub fn get_executable_plans(&mut self) -> Vec<Plan<'ctx>> {
+        let ctx = self.ctx;
+        let ready = self
+            .plan_to_parent_count
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &count)| if count == 0 { Some(PlanId::from(i)) } else { None })
+            .collect::<Vec<_>>();
+
+        ready
+            .into_iter()
+            .filter(|id| self.schedule((*id).into()))
+            .map(|id| id.walk(&ctx))
+            .collect()
+    }
+
+    /// Whether the executable can be executed in the current phase. Deferred plans wait for the
+    /// subsequent phase of an incremental delivery.
+    fn schedule(&mut self, id: ExecutableId) -> bool {
+        let Some(incremental) = self
+            .incremental
+            .as_mut()
+            .filter(|incremental| incremental.is_initial_phase)
+        else {
+            return true;
+        };
+        match id {
+            ExecutableId::Plan(id) => {
+                if self
+                    .ctx
+                    .operation
+                    .plan
+                    .incremental
+                    .as_ref()
+                    .is_some_and(|plan| plan.deferred_plan_ids[id])
+                {
+                    return false;
+                }
+                incremental.initial_plan_ids.set(id, true);
             }
-        })
+            ExecutableId::ResponseModifier(id) => {
+                incremental.initial_response_modifier_ids.set(id, true);
+            }
+        }
+        true
     }
 
     pub fn push_response_objects(&mut self, set_id: ResponseObjectSetId, response_object_refs: ResponseObjectSet) {
         tracing::trace!("Pushing response objects for {set_id}: {}", response_object_refs.len());
+        let is_subsequent_phase = self.completes_plans_without_input();
         let current = &mut self[set_id];
-        if current.is_none() {
-            *current = Some(Arc::new(response_object_refs));
+        match current {
+            None => *current = Some(Arc::new(response_object_refs)),
+            // Objects of the remaining streamed items are added to the ones of the initial phase.
+            Some(refs) if is_subsequent_phase => {
+                let known = refs.iter().map(|obj| obj.id).collect::<BTreeSet<_>>();
+                let mut extended = Vec::clone(refs);
+                extended.extend(response_object_refs.into_iter().filter(|obj| !known.contains(&obj.id)));
+                *refs = Arc::new(extended);
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Response objects a plan or response modifier must work on in the current phase of an
+    /// incremental delivery. Outside of it, all objects are returned.
+    pub fn get_response_objects(
+        &self,
+        executable_id: ExecutableId,
+        set_id: ResponseObjectSetId,
+    ) -> Option<Arc<ResponseObjectSet>> {
+        let refs = self[set_id].as_ref()?;
+        self.filter_response_objects(executable_id, Arc::clone(refs))
+    }
+
+    fn filter_response_objects(
+        &self,
+        executable_id: ExecutableId,
+        refs: Arc<ResponseObjectSet>,
+    ) -> Option<Arc<ResponseObjectSet>> {
+        let (Some(incremental), Some(plan)) = (&self.incremental, &self.ctx.operation.plan.incremental) else {
+            return Some(refs);
+        };
+
+        let keep_streamed_later = if incremental.is_initial_phase {
+            false
+        } else {
+            let executed_initially = match executable_id {
+                ExecutableId::Plan(id) => incremental.initial_plan_ids[id],
+                ExecutableId::ResponseModifier(id) => incremental.initial_response_modifier_ids[id],
+            };
+            // Deferred plans and their dependents work on all objects.
+            if !executed_initially {
+                return Some(refs);
+            }
+            true
+        };
+
+        if plan.streamed_lists.is_empty() && !keep_streamed_later {
+            return Some(refs);
+        }
+
+        let filtered = refs
+            .iter()
+            .filter(|obj| is_streamed_later(plan, obj) == keep_streamed_later)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if filtered.is_empty() {
+            None
+        } else {
+            Some(Arc::new(filtered))
         }
     }
 
@@ -101,13 +246,15 @@ impl<'ctx, R: Runtime> OperationExecutionState<'ctx, R> {
 
         let output = ParentObjectSet::default();
         if let Some(refs) = &self[input_id] {
-            output.with_filtered_response_objects(
-                self.ctx.schema(),
-                plan.entity_definition().id().into(),
-                Arc::clone(refs),
-            )
+            let Some(refs) = self.filter_response_objects(plan.id.into(), Arc::clone(refs)) else {
+                return output;
+            };
+            output.with_filtered_response_objects(self.ctx.schema(), plan.entity_definition().id().into(), refs)
         } else if usize::from(input_id) == 0 {
-            output.with_response_objects(Arc::new(vec![root_ref]))
+            match self.filter_response_objects(plan.id.into(), Arc::new(vec![root_ref])) {
+                Some(refs) => output.with_response_objects(refs),
+                None => output,
+            }
         } else {
             output
         }
@@ -124,7 +271,7 @@ impl<'ctx, R: Runtime> OperationExecutionState<'ctx, R> {
                 Executable::Plan(plan) => {
                     self[plan.id] -= 1;
                     tracing::trace!("Plan {} has {} dependencies left", plan.id, self[plan.id],);
-                    if self[plan.id] == 0 {
+                    if self[plan.id] == 0 && self.schedule(plan.id.into()) {
                         executable.push(child);
                     }
                 }
@@ -135,7 +282,7 @@ impl<'ctx, R: Runtime> OperationExecutionState<'ctx, R> {
                         modifier.id,
                         self[modifier.id]
                     );
-                    if self[modifier.id] == 0 {
+                    if self[modifier.id] == 0 && self.schedule(modifier.id.into()) {
                         executable.push(child);
                     }
                 }
@@ -144,3 +291,24 @@ impl<'ctx, R: Runtime> OperationExecutionState<'ctx, R> {
         executable
     }
 }
+
+/// Whether the object belongs to an item of a streamed list beyond its initial count.
+fn is_streamed_later(plan: &IncrementalPlan, obj: &ResponseObjectRef) -> bool {
+    let mut keys = Vec::new();
+    let mut path = obj.path.iter().peekable();
+    while let Some(value) = path.next() {
+        let ResponseValueId::Field { key, .. } = value else {
+            continue;
+        };
+        keys.push(key.response_key);
+        if let Some(ResponseValueId::Index { index, .. }) = path.peek()
+            && plan
+                .streamed_lists
+                .iter()
+                .any(|list| list.path == keys && *index as usize >= list.initial_count)
+        {
+            return true;
+        }
+    }
+    false
+}
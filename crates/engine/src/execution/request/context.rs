@@ -1,8 +1,8 @@
-use std::sync::Arc;
+use std::{net::IpAddr, sync::Arc};
 
 use event_queue::EventQueue;
 use grafbase_telemetry::grafbase_client::Client;
-use runtime::extension::Token;
+use runtime::{extension::Token, rate_limiting::RateLimitState};
 
 use crate::graphql_over_http::{ContentType, ResponseFormat};
 
@@ -32,4 +32,7 @@ pub(crate) struct RequestContext {
     pub include_mcp_response_extension: bool,
     pub event_queue: Arc<EventQueue>,
     pub hooks_context: Arc<[u8]>,
+    pub ip: Option<IpAddr>,
+    /// State of the global rate limit, only kept if it's exposed with the response headers.
+    pub rate_limit: Option<RateLimitState>,
 }
@@ -0,0 +1,26 @@
+use schema::{CostBudgetKey, Schema};
+
+use super::RequestContext;
+
+impl RequestContext {
+    /// Identifies the client for the cost budget, if any is configured and the request provides
+    /// the configured header or JWT claim.
+    pub(crate) fn cost_budget_client(&self, schema: &Schema) -> Option<String> {
+        match &schema.config.cost_budget.as_ref()?.key {
+            CostBudgetKey::Header(name) => self
+                .headers
+                .get(name.as_str())
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+            CostBudgetKey::JwtClaim(path) => {
+                let claims = self.token.claims()?;
+
+                match path.iter().try_fold(&claims, |value, key| value.get(key))? {
+                    serde_json::Value::String(value) => Some(value.clone()),
+                    serde_json::Value::Null => None,
+                    value => Some(value.to_string()),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use operation::{DataField, Field, OperationContext};
+use runtime::entity_cache;
+
+use crate::{
+    Runtime,
+    prepare::{PrepareContext, PreparedOperation},
+    response::Response,
+};
+
+/// Cache tags to invalidate for each root field of a mutation, identified by its response key.
+#[derive(Default)]
+pub(super) struct EntityCacheInvalidations {
+    fields: Vec<(String, Vec<String>)>,
+}
+
+impl<R: Runtime> PrepareContext<'_, R> {
+    /// Cache tags of the entities to invalidate once the mutation has been executed, as defined by
+    /// the `entity_caching.invalidation.mutations` configuration.
+    pub(super) fn entity_cache_invalidations(&self, operation: &PreparedOperation) -> EntityCacheInvalidations {
+        let invalidations = &self.schema().config.entity_cache_mutation_invalidations;
+        if invalidations.is_empty() || !operation.cached.ty().is_mutation() {
+            return EntityCacheInvalidations::default();
+        }
+
+        let ctx = OperationContext {
+            schema: self.schema(),
+            operation: &operation.cached.operation,
+        };
+
+        let mut fields = Vec::new();
+        for field in ctx.root_selection_set().fields() {
+            let Field::Data(field) = field else {
+                continue;
+            };
+            let field_name = field.definition().name();
+
+            let mut tags = Vec::new();
+            for invalidation in invalidations
+                .iter()
+                .filter(|invalidation| invalidation.field == field_name)
+            {
+                let Some(key) = &invalidation.key else {
+                    tags.push(entity_cache::type_tag(&invalidation.type_name));
+                    continue;
+                };
+
+                match entity_key(field, operation, key) {
+                    Some(key) => tags.push(entity_cache::entity_tag(&invalidation.type_name, &key)),
+                    None => {
+                        tracing::warn!(
+                            "Could not find the key of the {} entity to invalidate in the arguments of the mutation field {field_name}, invalidating all of them.",
+                            invalidation.type_name
+                        );
+                        tags.push(entity_cache::type_tag(&invalidation.type_name));
+                    }
+                }
+            }
+
+            if !tags.is_empty() {
+                fields.push((field.response_key_str().to_string(), tags));
+            }
+        }
+
+        EntityCacheInvalidations { fields }
+    }
+}
+
+impl EntityCacheInvalidations {
+    pub(super) fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Tags of the mutation fields which succeeded: their value isn't null and there is no error
+    /// within them.
+    fn tags(&self, response: &Response) -> Vec<String> {
+        let Ok(serde_json::Value::Object(payload)) = serde_json::to_value(response) else {
+            return Vec::new();
+        };
+        let data = payload.get("data").and_then(|data| data.as_object());
+        let errors = payload.get("errors").and_then(|errors| errors.as_array());
+
+        let mut tags = self
+            .fields
+            .iter()
+            .filter(|(response_key, _)| {
+                let has_value = data
+                    .and_then(|data| data.get(response_key))
+                    .is_some_and(|value| !value.is_null());
+                let has_error = errors.into_iter().flatten().any(|error| {
+                    error
+                        .get("path")
+                        .and_then(|path| path.get(0))
+                        .and_then(|key| key.as_str())
+                        .is_some_and(|key| key == response_key)
+                });
+                has_value && !has_error
+            })
+            .flat_map(|(_, tags)| tags.iter().cloned())
+            .collect::<Vec<_>>();
+
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+}
+
+fn entity_key(
+    field: DataField<'_>,
+    operation: &PreparedOperation,
+    key: &[(String, Vec<String>)],
+) -> Option<serde_json::Map<String, serde_json::Value>> {
+    let mut entity_key = serde_json::Map::new();
+
+    for (key_field, path) in key {
+        let (argument_name, path) = path.split_first()?;
+        let argument = field
+            .sorted_arguments()
+            .find(|argument| argument.definition().name() == argument_name)?;
+        let value = serde_json::to_value(argument.value(&operation.variables)).ok()?;
+        let value = path.iter().try_fold(&value, |value, key| value.get(key))?;
+
+        entity_key.insert(key_field.clone(), value.clone());
+    }
+
+    Some(entity_key)
+}
+
+/// Invalidation must not fail the mutation, errors are only logged.
+pub(super) async fn invalidate_entities<R: Runtime>(
+    runtime: &R,
+    invalidations: &EntityCacheInvalidations,
+    response: &Response,
+) {
+    if invalidations.is_empty() {
+        return;
+    }
+
+    let tags = invalidations.tags(response);
+    if tags.is_empty() {
+        return;
+    }
+
+    if let Err(err) = runtime.entity_cache().invalidate(&tags).await {
+        tracing::error!("Could not invalidate the entity cache: {err}");
+    }
+}
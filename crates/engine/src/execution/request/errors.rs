@@ -91,11 +91,32 @@ pub(crate) mod response {
         response::{GraphqlError, Response},
     };
 
-    pub(crate) fn gateway_rate_limited(error_code_mapping: ErrorCodeMapping) -> Response {
+    pub(crate) fn gateway_rate_limited(error_code_mapping: ErrorCodeMapping, headers: http::HeaderMap) -> Response {
         Response::refused_request(
             error_code_mapping,
             http::StatusCode::TOO_MANY_REQUESTS,
             [GraphqlError::new("Rate limited", ErrorCode::RateLimited)],
+            headers,
+        )
+    }
+
+    pub(crate) fn cost_budget_exceeded(error_code_mapping: ErrorCodeMapping) -> Response {
+        Response::refused_request(
+            error_code_mapping,
+            http::StatusCode::TOO_MANY_REQUESTS,
+            [GraphqlError::new("Cost budget exceeded", ErrorCode::RateLimited)],
+            Default::default(),
+        )
+    }
+
+    pub(crate) fn cost_budget_key_missing(error_code_mapping: ErrorCodeMapping) -> Response {
+        Response::refused_request(
+            error_code_mapping,
+            http::StatusCode::BAD_REQUEST,
+            [GraphqlError::new(
+                "The request doesn't identify the client of the cost budget",
+                ErrorCode::BadRequest,
+            )],
             Default::default(),
         )
     }
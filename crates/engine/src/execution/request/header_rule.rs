@@ -1,23 +1,115 @@
-use std::{borrow::Cow, str::FromStr, sync::OnceLock};
+use std::{borrow::Cow, cell::OnceCell, net::IpAddr, str::FromStr, sync::OnceLock};
 
+use grafbase_telemetry::{grafbase_client::Client, graphql::GraphqlOperationAttributes};
 use http::{HeaderName, header};
+use runtime::extension::Token;
 use schema::{
     ForwardHeaderRule, HeaderRule, HeaderRuleVariant, InsertHeaderRule, NameOrPattern, RemoveHeaderRule,
-    RenameDuplicateHeaderRule,
+    RenameDuplicateHeaderRule, Schema,
 };
+use walker::Walk as _;
+
+use crate::prepare::JsonContent;
+
+/// Everything header rules may depend on. Templated header values are rendered against:
+/// - `jwt`: the claims of the authentication token,
+/// - `client`: the `ip`, `name` and `version` of the client,
+/// - `operation`: the `name` and `type` of the operation, only known for subgraph rules as the
+///   default ones are applied before the operation is parsed,
+/// - `headers`: the headers of the client request, with lowercase names.
+pub(crate) struct HeaderRuleContext<'a> {
+    pub schema: &'a Schema,
+    pub gateway_headers: &'a http::HeaderMap,
+    pub token: &'a Token,
+    pub ip: Option<IpAddr>,
+    pub client: Option<&'a Client>,
+    pub operation: Option<&'a GraphqlOperationAttributes>,
+    template_values: OnceCell<serde_json::Value>,
+}
+
+impl<'a> HeaderRuleContext<'a> {
+    pub fn new(schema: &'a Schema, gateway_headers: &'a http::HeaderMap, token: &'a Token) -> Self {
+        Self {
+            schema,
+            gateway_headers,
+            token,
+            ip: None,
+            client: None,
+            operation: None,
+            template_values: OnceCell::new(),
+        }
+    }
+
+    pub fn with_ip(mut self, ip: Option<IpAddr>) -> Self {
+        self.ip = ip;
+        self
+    }
+
+    pub fn with_client(mut self, client: Option<&'a Client>) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn with_operation(mut self, operation: &'a GraphqlOperationAttributes) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+
+    /// Created only if a templated value needs them. Unknown values are left out to render nothing.
+    fn template_values(&self) -> &serde_json::Value {
+        self.template_values.get_or_init(|| {
+            let mut values = serde_json::Map::new();
+
+            if let Some(claims) = self.token.claims() {
+                values.insert("jwt".into(), claims);
+            }
+
+            let mut client = serde_json::Map::new();
+            if let Some(ip) = self.ip {
+                client.insert("ip".into(), ip.to_string().into());
+            }
+            if let Some(Client { name, version }) = self.client {
+                client.insert("name".into(), name.as_str().into());
+                if let Some(version) = version {
+                    client.insert("version".into(), version.as_str().into());
+                }
+            }
+            values.insert("client".into(), client.into());
+
+            if let Some(operation) = self.operation {
+                let mut attributes = serde_json::Map::new();
+                if let Some(name) = operation.name.original() {
+                    attributes.insert("name".into(), name.into());
+                }
+                attributes.insert("type".into(), operation.ty.as_str().into());
+                values.insert("operation".into(), attributes.into());
+            }
+
+            let headers = self
+                .gateway_headers
+                .iter()
+                .filter_map(|(name, value)| Some((name.as_str().to_owned(), value.to_str().ok()?.into())))
+                .collect::<serde_json::Map<_, _>>();
+            values.insert("headers".into(), headers.into());
+
+            values.into()
+        })
+    }
+}
 
 pub(crate) fn apply_header_rules<'ctx>(
-    gateway_headers: &http::HeaderMap,
+    ctx: &HeaderRuleContext<'_>,
     rules: impl Iterator<Item = HeaderRule<'ctx>>,
     subgraph_headers: &mut http::HeaderMap,
 ) {
+    let gateway_headers = ctx.gateway_headers;
     for rule in rules {
         match rule.variant() {
             HeaderRuleVariant::Forward(rule) => {
                 handle_forward(gateway_headers, rule, subgraph_headers);
             }
             HeaderRuleVariant::Insert(rule) => {
-                handle_insert(rule, subgraph_headers);
+                handle_insert(ctx, rule, subgraph_headers);
             }
             HeaderRuleVariant::Remove(rule) => handle_remove(rule, subgraph_headers),
             HeaderRuleVariant::RenameDuplicate(rule) => {
@@ -76,9 +168,23 @@ fn handle_remove(rule: RemoveHeaderRule<'_>, subgraph_headers: &mut http::Header
     }
 }
 
-fn handle_insert(rule: InsertHeaderRule<'_>, subgraph_headers: &mut http::HeaderMap) {
+fn handle_insert(ctx: &HeaderRuleContext<'_>, rule: InsertHeaderRule<'_>, subgraph_headers: &mut http::HeaderMap) {
     let name = http::HeaderName::from_bytes(rule.name().as_bytes()).ok();
-    let value = http::HeaderValue::from_str(rule.value()).ok();
+    let value = match rule.value_template_id {
+        Some(id) => {
+            let template = id.walk(ctx.schema);
+            let rendered = template.inner.render(&JsonContent {
+                value: Cow::Borrowed(ctx.template_values()),
+                escaping: template.escaping,
+            });
+
+            // A template rendering to nothing, typically for an anonymous request, inserts no header.
+            Some(rendered)
+                .filter(|value| !value.is_empty())
+                .and_then(|value| http::HeaderValue::from_str(&value).ok())
+        }
+        None => http::HeaderValue::from_str(rule.value()).ok(),
+    };
 
     if let Some((name, value)) = name.zip(value) {
         if is_header_denied(&name) {
@@ -188,3 +294,32 @@ pub(crate) fn find_matching_denied_header(name: &HeaderName) -> Option<&'static
     });
     blacklist.iter().find(|denied| **denied == name.as_str()).copied()
 }
+
+#[cfg(test)]
+mod tests {
+    use base64::Engine as _;
+
+    use super::*;
+
+    fn token(bytes: &[u8]) -> Token {
+        Token::Bytes(bytes.into())
+    }
+
+    #[test]
+    fn claims_of_json_and_jwt_tokens() {
+        let claims = serde_json::json!({"sub": "user-1", "scope": "read"});
+
+        assert_eq!(
+            token(&serde_json::to_vec(&claims).unwrap()).claims(),
+            Some(claims.clone())
+        );
+
+        let payload = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).unwrap());
+        let jwt = format!("eyJhbGciOiJIUzI1NiJ9.{payload}.signature");
+        assert_eq!(token(jwt.as_bytes()).claims(), Some(claims));
+
+        assert_eq!(token(b"opaque").claims(), None);
+        assert_eq!(token(b"\"string\"").claims(), None);
+        assert_eq!(Token::Anonymous.claims(), None);
+    }
+}
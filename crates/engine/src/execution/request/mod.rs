@@ -1,15 +1,23 @@
 mod context;
+mod cost_budget;
+mod entity_cache_invalidation;
 pub(crate) mod errors;
 mod header_rule;
+mod rate_limiting;
+mod response_cache;
 mod response_extension;
+mod response_header_rule;
 mod single;
 mod stream;
 mod well_formed_graphql_request;
 
 pub(crate) use context::*;
 pub(crate) use header_rule::*;
+pub(crate) use rate_limiting::RateLimitHeaders;
+pub(crate) use response_cache::ResponseCacheControl;
 use response_extension::should_include_grafbase_response_extension;
 pub(crate) use response_extension::*;
+pub(crate) use response_header_rule::*;
 pub(crate) use stream::*;
 
 use ::runtime::rate_limiting::RateLimitKey;
@@ -109,18 +117,30 @@ impl<R: Runtime> Engine<R> {
         let client = Client::extract_from(&headers);
 
         // Currently it doesn't rely on authentication, but likely will at some point.
-        if self.runtime.rate_limiter().limit(&RateLimitKey::Global).await.is_err() {
-            return Err(errors::response::gateway_rate_limited(
-                self.schema.config.error_code_mapping.clone(),
-            ));
-        }
+        let rate_limit = match self.runtime.rate_limiter().limit(&RateLimitKey::Global).await {
+            Ok(state) => state.filter(|_| self.schema.config.rate_limit_headers),
+            Err(err) => {
+                return Err(errors::response::gateway_rate_limited(
+                    self.schema.config.error_code_mapping.clone(),
+                    rate_limiting::rate_limited_headers(&self.schema, &err),
+                ));
+            }
+        };
 
-        let mut subgraph_default_headers = http::HeaderMap::new();
-        apply_header_rules(
-            &headers,
-            self.schema.default_header_rules(),
-            &mut subgraph_default_headers,
-        );
+        let subgraph_default_headers = {
+            // The operation isn't known yet, default rules can't rely on it.
+            let header_rule_context = HeaderRuleContext::new(&self.schema, &headers, &extensions.token)
+                .with_ip(extensions.ip)
+                .with_client(client.as_ref());
+
+            let mut subgraph_default_headers = http::HeaderMap::new();
+            apply_header_rules(
+                &header_rule_context,
+                self.schema.default_header_rules(),
+                &mut subgraph_default_headers,
+            );
+            subgraph_default_headers
+        };
 
         let request_context = RequestContext {
             websocket_init_payload: websocket_init_payload.and_then(|payload| payload.0),
@@ -134,6 +154,8 @@ impl<R: Runtime> Engine<R> {
             include_mcp_response_extension: ctx.include_mcp_response_extension,
             event_queue: extensions.event_queue,
             hooks_context: extensions.hooks_context,
+            ip: extensions.ip,
+            rate_limit,
         };
 
         Ok(Arc::new(request_context))
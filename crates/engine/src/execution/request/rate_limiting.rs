@@ -0,0 +1,147 @@
+use std::{net::IpAddr, sync::OnceLock};
+
+use runtime::rate_limiting::{Error, RateLimitKey, RateLimitState, RateLimiterContext};
+use schema::Schema;
+
+use crate::{
+    Runtime,
+    prepare::{PrepareContext, PreparedOperation},
+    response::Response,
+};
+
+use super::{RequestContext, errors};
+
+const RATE_LIMIT_LIMIT: http::HeaderName = http::HeaderName::from_static("ratelimit-limit");
+const RATE_LIMIT_REMAINING: http::HeaderName = http::HeaderName::from_static("ratelimit-remaining");
+const RATE_LIMIT_RESET: http::HeaderName = http::HeaderName::from_static("ratelimit-reset");
+
+/// Rate limit related HTTP headers of a response, only set if enabled in the configuration.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RateLimitHeaders {
+    pub state: RateLimitState,
+    /// Whether the request was rejected, in which case clients are told when to retry.
+    pub exceeded: bool,
+}
+
+impl RateLimitHeaders {
+    pub(crate) fn insert_headers(&self, headers: &mut http::HeaderMap) {
+        // Headers use whole seconds, rounding up to not invite clients to retry too early.
+        let reset = self.state.reset.as_secs_f64().ceil() as u64;
+
+        headers.insert(RATE_LIMIT_LIMIT, self.state.limit.into());
+        headers.insert(RATE_LIMIT_REMAINING, self.state.remaining.into());
+        headers.insert(RATE_LIMIT_RESET, reset.into());
+
+        if self.exceeded {
+            headers.insert(http::header::RETRY_AFTER, reset.max(1).into());
+        }
+    }
+}
+
+pub(super) fn rate_limited_headers(schema: &Schema, error: &Error) -> http::HeaderMap {
+    let mut headers = http::HeaderMap::new();
+
+    if schema.config.rate_limit_headers
+        && let Error::ExceededCapacity(Some(state)) = error
+    {
+        RateLimitHeaders {
+            state: *state,
+            exceeded: true,
+        }
+        .insert_headers(&mut headers);
+    }
+
+    headers
+}
+
+impl<R: Runtime> PrepareContext<'_, R> {
+    /// Applies the cost budget and the rate limit rules, which can only be evaluated once the
+    /// operation is known. The request only counts against the rules if all of them allow it.
+    /// Returns the headers for the most restrictive rate limit, including the global one, if
+    /// enabled.
+    pub(super) async fn check_operation_rate_limits(
+        &self,
+        operation: &PreparedOperation,
+    ) -> Result<Option<RateLimitHeaders>, Response> {
+        let rate_limiter = self.runtime().rate_limiter();
+        let error_code_mapping = &self.schema().config.error_code_mapping;
+
+        let context = OperationRateLimiterContext {
+            request_context: self.request_context,
+            operation_name: operation.cached.operation.attributes.name.original(),
+            claims: OnceLock::new(),
+        };
+
+        // The actual cost is spent once the operation is executed, we only check that the client
+        // has some budget left. It's checked first so that a rejected request doesn't count
+        // against the rate limit rules.
+        if let Some(budget) = &self.schema().config.cost_budget {
+            match self.request_context.cost_budget_client(self.schema()) {
+                Some(client) => {
+                    if rate_limiter
+                        .limit(&RateLimitKey::CostBudget(client.into()))
+                        .await
+                        .is_err()
+                    {
+                        return Err(errors::response::cost_budget_exceeded(error_code_mapping.clone()));
+                    }
+                }
+                None if budget.reject_missing_key => {
+                    return Err(errors::response::cost_budget_key_missing(error_code_mapping.clone()));
+                }
+                None => (),
+            }
+        }
+
+        let rules_state = match rate_limiter.limit(&context).await {
+            Ok(state) => state,
+            Err(err) => {
+                return Err(errors::response::gateway_rate_limited(
+                    error_code_mapping.clone(),
+                    rate_limited_headers(self.schema(), &err),
+                ));
+            }
+        };
+
+        if !self.schema().config.rate_limit_headers {
+            return Ok(None);
+        }
+
+        let state = match (self.request_context.rate_limit, rules_state) {
+            (Some(global), Some(rules)) => Some(global.most_restrictive(rules)),
+            (global, rules) => global.or(rules),
+        };
+
+        Ok(state.map(|state| RateLimitHeaders { state, exceeded: false }))
+    }
+}
+
+struct OperationRateLimiterContext<'a> {
+    request_context: &'a RequestContext,
+    operation_name: Option<&'a str>,
+    /// Claims are only parsed from the token if a rule relies on them.
+    claims: OnceLock<Option<serde_json::Value>>,
+}
+
+impl RateLimiterContext for OperationRateLimiterContext<'_> {
+    fn header(&self, name: http::HeaderName) -> Option<&http::HeaderValue> {
+        self.request_context.headers.get(name)
+    }
+
+    fn graphql_operation_name(&self) -> Option<&str> {
+        self.operation_name
+    }
+
+    fn ip(&self) -> Option<IpAddr> {
+        self.request_context.ip
+    }
+
+    fn jwt_claim(&self, key: &str) -> Option<&serde_json::Value> {
+        let claims = self
+            .claims
+            .get_or_init(|| self.request_context.token.claims())
+            .as_ref()?;
+
+        key.split('.').try_fold(claims, |value, key| value.get(key))
+    }
+}
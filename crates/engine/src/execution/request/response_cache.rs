@@ -0,0 +1,252 @@
+use std::{
+    borrow::Cow,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use grafbase_telemetry::graphql::OperationType;
+use headers::HeaderMapExt;
+use operation::ComplexityCost;
+use schema::ResponseCachingConfig;
+use walker::Walk;
+
+use crate::{
+    Runtime,
+    engine::cache::CacheKey,
+    execution::ExecutionContext,
+    prepare::{PrepareContext, PreparedOperation},
+    response::{CachedResponse, Response},
+};
+
+/// Cache related HTTP headers of a response, only set for cacheable responses.
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseCacheControl {
+    pub max_age: Duration,
+    /// Time spent in the cache, only present for cached responses.
+    pub age: Option<Duration>,
+    /// Whether the response depends on who sent the request, in which case shared caches must not
+    /// store it.
+    pub private: bool,
+    /// Request headers included in the cache key, sent as the `Vary` header.
+    pub vary: Option<http::HeaderValue>,
+}
+
+impl ResponseCacheControl {
+    pub(crate) fn insert_headers(&self, headers: &mut http::HeaderMap) {
+        let cache_control = headers::CacheControl::new().with_max_age(self.max_age);
+        headers.typed_insert(if self.private {
+            cache_control.with_private()
+        } else {
+            cache_control.with_public()
+        });
+
+        if let Some(vary) = &self.vary {
+            headers.insert(http::header::VARY, vary.clone());
+        }
+
+        if let Some(age) = self.age {
+            headers.typed_insert(headers::Age::from_secs(age.as_secs()));
+        }
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResponseCacheEntry {
+    /// Unix timestamp in milliseconds
+    stored_at: u64,
+    /// In seconds
+    max_age: u64,
+    /// Actual cost of the operation when it was executed, spent again for each cache hit.
+    #[serde(default)]
+    actual_cost: Option<usize>,
+    response: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<R: Runtime> PrepareContext<'_, R> {
+    /// Executes a query through the response cache if it's enabled. Only successful responses are
+    /// stored, without their extensions as those are specific to each request. Subgraph response
+    /// headers propagated to the client, such as `Set-Cookie`, are never stored either as they may
+    /// be specific to the client which triggered the execution.
+    pub(super) async fn execute_query_or_mutation_with_response_cache(
+        mut self,
+        operation: PreparedOperation,
+        variables: Option<Vec<u8>>,
+    ) -> Response {
+        let (Some(config), Some(variables)) = (&self.schema().config.response_caching, variables) else {
+            return self.execute_query_or_mutation(operation).await;
+        };
+
+        // The cache key doesn't include the authorization decisions, so operations depending on
+        // them are never cached.
+        if operation.cached.ty() != OperationType::Query || depends_on_authorization(&operation) {
+            return self.execute_query_or_mutation(operation).await;
+        }
+
+        let max_age = operation
+            .cached
+            .operation
+            .data_fields
+            .iter()
+            .filter_map(|field| {
+                let definition = field.definition_id.walk(self.schema());
+                match definition.cache_control_max_age() {
+                    Some(max_age) => Some(Duration::from_secs(max_age as u64)),
+                    // Leaf fields without any hint inherit the max-age of their parent field.
+                    None if !definition.ty().definition().is_composite_type()
+                        && definition.parent_entity_id != self.schema().query().id.into() =>
+                    {
+                        None
+                    }
+                    None => Some(config.default_max_age),
+                }
+            })
+            .min()
+            .unwrap_or(config.default_max_age);
+
+        if max_age.is_zero() {
+            return self.execute_query_or_mutation(operation).await;
+        }
+
+        let key = self.response_cache_key(config, &operation, &variables);
+        // Responses varying on JWT claims or request headers depend on who sent the request.
+        // `Vary` still lets the client cache tell apart the responses for different header values.
+        let private = !config.vary_headers.is_empty() || !config.vary_jwt_claims.is_empty();
+        let vary = if config.vary_headers.is_empty() {
+            None
+        } else {
+            http::HeaderValue::try_from(config.vary_headers.join(", ")).ok()
+        };
+        let cache = self.runtime().response_cache();
+
+        match cache.get(&key).await {
+            Ok(Some(bytes)) => match serde_json::from_slice::<ResponseCacheEntry>(&bytes) {
+                Ok(entry) => {
+                    let background_futures = std::mem::take(&mut self.background_futures);
+                    futures_util::future::join_all(background_futures).await;
+
+                    let age = unix_timestamp_ms().saturating_sub(entry.stored_at);
+                    let mut response = Response::Cached(CachedResponse {
+                        payload: entry.response,
+                        operation_attributes: operation.attributes(),
+                        error_code_counter: Default::default(),
+                        extensions: Default::default(),
+                    });
+                    response.extensions_mut().cache_control = Some(ResponseCacheControl {
+                        max_age: Duration::from_secs(entry.max_age),
+                        age: Some(Duration::from_millis(age)),
+                        private,
+                        vary,
+                    });
+
+                    // Cached responses cost as much as executed ones for the client budget.
+                    if let (Some(estimated), Some(actual)) = (operation.complexity_cost, entry.actual_cost) {
+                        let operation = Arc::new(operation);
+                        let ctx = ExecutionContext {
+                            engine: self.engine,
+                            request_context: self.request_context,
+                            operation: &operation,
+                        };
+                        ctx.spend_cost(estimated, ComplexityCost(actual), &mut response).await;
+                    }
+
+                    return response;
+                }
+                Err(err) => {
+                    tracing::warn!("Could not deserialize cached response: {err}");
+                }
+            },
+            Ok(None) => {}
+            Err(err) => {
+                tracing::error!("Could not retrieve response from cache: {err}");
+            }
+        }
+
+        let mut response = self.execute_query_or_mutation(operation).await;
+        if !response.graphql_status().is_success() {
+            return response;
+        }
+
+        match serde_json::to_value(&response) {
+            Ok(serde_json::Value::Object(mut payload)) => {
+                payload.remove("extensions");
+                let entry = ResponseCacheEntry {
+                    stored_at: unix_timestamp_ms(),
+                    max_age: max_age.as_secs(),
+                    actual_cost: response.actual_cost().map(|cost| cost.0),
+                    response: payload,
+                };
+                match serde_json::to_vec(&entry) {
+                    Ok(bytes) => {
+                        if let Err(err) = cache.put(&key, Cow::Owned(bytes), max_age).await {
+                            tracing::error!("Could not store response in cache: {err}");
+                        }
+                    }
+                    Err(err) => tracing::error!("Could not serialize response for the cache: {err}"),
+                }
+            }
+            Ok(_) => {}
+            Err(err) => tracing::error!("Could not serialize response for the cache: {err}"),
+        }
+
+        response.extensions_mut().cache_control = Some(ResponseCacheControl {
+            max_age,
+            age: None,
+            private,
+            vary,
+        });
+
+        response
+    }
+
+    fn response_cache_key(
+        &self,
+        config: &ResponseCachingConfig,
+        operation: &PreparedOperation,
+        variables: &[u8],
+    ) -> String {
+        let mut vary = Vec::with_capacity(config.vary_headers.len() + config.vary_jwt_claims.len());
+
+        for name in &config.vary_headers {
+            let mut values = self.headers().get_all(name.as_str()).iter().peekable();
+            if values.peek().is_none() {
+                vary.push(None);
+                continue;
+            }
+            let mut bytes = Vec::new();
+            for value in values {
+                bytes.extend_from_slice(value.as_bytes());
+                bytes.push(b'\n');
+            }
+            vary.push(Some(bytes));
+        }
+
+        if !config.vary_jwt_claims.is_empty() {
+            let claims = self.request_context.token.claims();
+
+            for path in &config.vary_jwt_claims {
+                let claim = claims
+                    .as_ref()
+                    .and_then(|claims| path.iter().try_fold(claims, |value, key| value.get(key)));
+                vary.push(claim.and_then(|claim| serde_json::to_vec(claim).ok()));
+            }
+        }
+
+        let operation_key = CacheKey::document(self.schema(), &operation.cached.document.key);
+        CacheKey::response(&operation_key, variables, &vary)
+    }
+}
+
+/// Whether the response depends on authorization decisions made by extensions, either on the
+/// query or on the response.
+fn depends_on_authorization(operation: &PreparedOperation) -> bool {
+    !operation.cached.query_plan.query_modifiers.by_extension.is_empty()
+        || !operation.plan.response_modifiers.is_empty()
+}
+
+fn unix_timestamp_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
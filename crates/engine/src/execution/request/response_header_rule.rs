@@ -0,0 +1,285 @@
+use std::str::FromStr;
+
+use headers::{CacheControl, Header as _};
+use http::{HeaderMap, HeaderName, HeaderValue, header};
+use schema::{GraphqlSubgraph, HeaderMergeStrategy, NameOrPattern, ResponseHeaderRule};
+
+use super::find_matching_denied_header;
+
+/// Subgraph response headers propagated to the client response. Headers returned by multiple
+/// subgraphs are merged with the strategy of the rule that propagated them.
+#[derive(Default, Debug)]
+pub(crate) struct PropagatedHeaders {
+    headers: HeaderMap,
+    strategies: HeaderMap<HeaderMergeStrategy>,
+}
+
+impl PropagatedHeaders {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+
+    /// Propagates the headers of a subgraph response matching the rules.
+    pub(crate) fn collect<'a>(
+        &mut self,
+        rules: impl Iterator<Item = ResponseHeaderRule<'a>>,
+        subgraph_headers: &HeaderMap,
+    ) {
+        for rule in rules {
+            let rename = match rule.rename() {
+                Some(rename) => match HeaderName::from_str(rename) {
+                    Ok(name) => Some(name),
+                    Err(_) => continue,
+                },
+                None => None,
+            };
+
+            match rule.name() {
+                NameOrPattern::Pattern(regex) => {
+                    for name in subgraph_headers.keys().filter(|name| regex.is_match(name.as_str())) {
+                        let target = rename.clone().unwrap_or_else(|| name.clone());
+                        self.merge(
+                            target,
+                            subgraph_headers.get_all(name).iter().cloned().collect(),
+                            rule.merge,
+                        );
+                    }
+                }
+                NameOrPattern::Name(name) => {
+                    let Ok(name) = HeaderName::from_str(name) else {
+                        continue;
+                    };
+
+                    let values: Vec<_> = subgraph_headers.get_all(&name).iter().cloned().collect();
+                    if !values.is_empty() {
+                        self.merge(rename.unwrap_or(name), values, rule.merge);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Merges the headers propagated by another subgraph response, planned after ours.
+    pub(crate) fn extend(&mut self, other: PropagatedHeaders) {
+        for (name, strategy) in &other.strategies {
+            let values = other.headers.get_all(name).iter().cloned().collect();
+            self.merge(name.clone(), values, *strategy);
+        }
+    }
+
+    /// Adds the propagated headers to the client response. Headers set by the gateway itself
+    /// must be inserted afterwards to take precedence.
+    pub(crate) fn insert_headers(self, headers: &mut HeaderMap) {
+        headers.extend(self.headers);
+    }
+
+    fn merge(&mut self, name: HeaderName, values: Vec<HeaderValue>, strategy: HeaderMergeStrategy) {
+        if is_header_denied(&name) {
+            return;
+        }
+
+        if !self.headers.contains_key(&name) {
+            self.strategies.insert(name.clone(), strategy);
+            for value in values {
+                self.headers.append(name.clone(), value);
+            }
+            return;
+        }
+
+        match strategy {
+            HeaderMergeStrategy::First => {}
+            HeaderMergeStrategy::Last => {
+                self.headers.remove(&name);
+                for value in values {
+                    self.headers.append(name.clone(), value);
+                }
+            }
+            HeaderMergeStrategy::Append => {
+                for value in values {
+                    self.headers.append(name.clone(), value);
+                }
+            }
+            HeaderMergeStrategy::MinMaxAge => {
+                let current = CacheControl::decode(&mut self.headers.get_all(&name).iter());
+                let other = CacheControl::decode(&mut values.iter());
+
+                // Invalid values can't be merged, we keep the ones we can make sense of.
+                let merged = match (current, other) {
+                    (Ok(current), Ok(other)) => most_restrictive_cache_control(&current, &other),
+                    (Err(_), Ok(other)) => other,
+                    (_, Err(_)) => return,
+                };
+
+                let mut encoded = Vec::new();
+                merged.encode(&mut encoded);
+
+                self.headers.remove(&name);
+                for value in encoded {
+                    self.headers.append(name.clone(), value);
+                }
+            }
+        }
+    }
+}
+
+/// Keeps the directives restricting caching from both sides and the lowest max ages.
+fn most_restrictive_cache_control(a: &CacheControl, b: &CacheControl) -> CacheControl {
+    let mut merged = CacheControl::new();
+
+    if a.no_store() || b.no_store() {
+        return merged.with_no_store();
+    }
+
+    if a.no_cache() || b.no_cache() {
+        merged = merged.with_no_cache();
+    }
+
+    if a.must_revalidate() || b.must_revalidate() {
+        merged = merged.with_must_revalidate();
+    }
+
+    if a.no_transform() && b.no_transform() {
+        merged = merged.with_no_transform();
+    }
+
+    if a.immutable() && b.immutable() {
+        merged = merged.with_immutable();
+    }
+
+    if a.private() || b.private() {
+        merged = merged.with_private();
+    } else if a.public() || b.public() {
+        merged = merged.with_public();
+    }
+
+    if let Some(max_age) = min_option(a.max_age(), b.max_age()) {
+        merged = merged.with_max_age(max_age);
+    }
+
+    // A shared cache would use the max-age of a response without s-maxage.
+    if let Some(s_max_age) = min_option(a.s_max_age().or(a.max_age()), b.s_max_age().or(b.max_age()))
+        .filter(|_| a.s_max_age().is_some() || b.s_max_age().is_some())
+    {
+        merged = merged.with_s_max_age(s_max_age);
+    }
+
+    merged
+}
+
+fn min_option<T: Ord>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn is_header_denied(name: &HeaderName) -> bool {
+    // Those depend on the client response body, not on the subgraph one.
+    *name == header::CONTENT_ENCODING || find_matching_denied_header(name).is_some()
+}
+
+/// Keeps only the subgraph response headers matched by the rules.
+pub(crate) fn select_propagated_headers(subgraph: GraphqlSubgraph<'_>, headers: &HeaderMap) -> HeaderMap {
+    let mut selected = HeaderMap::new();
+
+    if subgraph.as_ref().config.response_header_rules.is_empty() {
+        return selected;
+    }
+
+    for (name, value) in headers {
+        let matched = subgraph.response_header_rules().any(|rule| match rule.name() {
+            NameOrPattern::Pattern(regex) => regex.is_match(name.as_str()),
+            NameOrPattern::Name(rule_name) => name.as_str().eq_ignore_ascii_case(rule_name),
+        });
+
+        if matched {
+            selected.append(name.clone(), value.clone());
+        }
+    }
+
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merged(strategy: HeaderMergeStrategy, responses: &[&[&'static str]]) -> Vec<String> {
+        let mut propagated = PropagatedHeaders::default();
+
+        for values in responses {
+            let mut other = PropagatedHeaders::default();
+            other.merge(
+                header::CACHE_CONTROL,
+                values.iter().map(|value| HeaderValue::from_static(value)).collect(),
+                strategy,
+            );
+            propagated.extend(other);
+        }
+
+        let mut headers = HeaderMap::new();
+        propagated.insert_headers(&mut headers);
+
+        headers
+            .get_all(header::CACHE_CONTROL)
+            .iter()
+            .map(|value| value.to_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn first_last_and_append() {
+        let responses: &[&[&str]] = &[&["max-age=10"], &["max-age=20", "private"]];
+
+        assert_eq!(merged(HeaderMergeStrategy::First, responses), ["max-age=10"]);
+        assert_eq!(merged(HeaderMergeStrategy::Last, responses), ["max-age=20", "private"]);
+        assert_eq!(
+            merged(HeaderMergeStrategy::Append, responses),
+            ["max-age=10", "max-age=20", "private"]
+        );
+    }
+
+    #[test]
+    fn min_max_age() {
+        assert_eq!(
+            merged(
+                HeaderMergeStrategy::MinMaxAge,
+                &[&["public, max-age=60"], &["max-age=30"]]
+            ),
+            ["public, max-age=30"]
+        );
+        assert_eq!(
+            merged(
+                HeaderMergeStrategy::MinMaxAge,
+                &[&["public, max-age=60"], &["private, max-age=120"]]
+            ),
+            ["private, max-age=60"]
+        );
+        assert_eq!(
+            merged(
+                HeaderMergeStrategy::MinMaxAge,
+                &[&["max-age=60"], &["no-store"], &["max-age=10"]]
+            ),
+            ["no-store"]
+        );
+        assert_eq!(
+            merged(
+                HeaderMergeStrategy::MinMaxAge,
+                &[&["max-age=60, s-maxage=300"], &["max-age=120"]]
+            ),
+            ["max-age=60, s-maxage=120"]
+        );
+    }
+
+    #[test]
+    fn denied_headers_are_not_propagated() {
+        let mut propagated = PropagatedHeaders::default();
+        propagated.merge(
+            header::CONTENT_LENGTH,
+            vec![HeaderValue::from_static("10")],
+            HeaderMergeStrategy::Last,
+        );
+
+        assert!(propagated.is_empty());
+    }
+}
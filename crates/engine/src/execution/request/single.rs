@@ -14,7 +14,10 @@ use crate::{
     response::{ErrorCode, GraphqlError, Response},
 };
 
-use super::{RequestContext, default_response_extensions, response_extension_for_prepared_operation};
+use super::{
+    RequestContext, default_response_extensions, entity_cache_invalidation::invalidate_entities,
+    response_extension_for_prepared_operation,
+};
 
 impl<R: Runtime> Engine<R> {
     pub(super) async fn execute_single(
@@ -66,6 +69,14 @@ impl<R: Runtime> Engine<R> {
 
 impl<R: Runtime> PrepareContext<'_, R> {
     async fn execute_single(mut self, request: Request) -> Response {
+        // Variables are consumed by the preparation, so we keep what we need for the response cache key.
+        let variables = self
+            .schema()
+            .config
+            .response_caching
+            .as_ref()
+            .and_then(|_| serde_json::to_vec(&request.variables).ok());
+
         let operation = match self.prepare_operation(request).await {
             Ok(operation) => operation,
             Err(response) => {
@@ -75,7 +86,7 @@ impl<R: Runtime> PrepareContext<'_, R> {
 
         let attributes = operation.attributes();
 
-        let extensions = response_extension_for_prepared_operation(self.schema(), self.request_context, &operation);
+        let mut extensions = response_extension_for_prepared_operation(self.schema(), self.request_context, &operation);
 
         if matches!(operation.cached.ty(), OperationType::Subscription) {
             let error = GraphqlError::new(
@@ -88,8 +99,26 @@ impl<R: Runtime> PrepareContext<'_, R> {
                 .with_extensions(extensions);
         }
 
-        self.execute_query_or_mutation(operation)
-            .await
+        let runtime = self.runtime();
+
+        match self.check_operation_rate_limits(&operation).await {
+            Ok(rate_limit) => extensions.rate_limit = rate_limit,
+            Err(response) => {
+                return response
+                    .with_operation_attributes(attributes)
+                    .with_extensions(extensions);
+            }
+        }
+
+        let invalidations = self.entity_cache_invalidations(&operation);
+
+        let response = self
+            .execute_query_or_mutation_with_response_cache(operation, variables)
+            .await;
+
+        invalidate_entities(runtime, &invalidations, &response).await;
+
+        response
             .with_operation_attributes(attributes)
             .with_extensions(extensions)
     }
@@ -22,7 +22,7 @@ use crate::{
     utils::StreamJoinExt,
 };
 
-use super::RequestContext;
+use super::{RequestContext, entity_cache_invalidation::invalidate_entities};
 
 pub(crate) struct StreamResponse {
     pub stream: BoxStream<'static, Response>,
@@ -149,10 +149,44 @@ impl<R: Runtime> PrepareContext<'_, R> {
                     }
                 };
 
+                // Rate limit headers aren't supported for streaming responses.
+                if let Err(response) = self.check_operation_rate_limits(&operation).await {
+                    let attributes = operation.attributes();
+                    let extensions =
+                        response_extension_for_prepared_operation(self.schema(), self.request_context, &operation);
+                    sender
+                        .send(
+                            response
+                                .with_operation_attributes(attributes.clone())
+                                .with_extensions(extensions),
+                        )
+                        .await
+                        .ok();
+                    return Err(Some(attributes));
+                }
+
                 if matches!(operation.cached.ty(), OperationType::Query | OperationType::Mutation) {
                     let extensions =
                         response_extension_for_prepared_operation(self.schema(), self.request_context, &operation);
+
+                    if operation.is_incremental() {
+                        let attributes = operation.attributes();
+                        self.execute_incremental_query(
+                            operation,
+                            AddExtToFirstResponse {
+                                sender: &mut sender,
+                                extensions: Some(extensions),
+                            },
+                        )
+                        .await;
+                        return Err(Some(attributes));
+                    }
+
+                    let runtime = self.runtime();
+                    let invalidations = self.entity_cache_invalidations(&operation);
+
                     let response = self.execute_query_or_mutation(operation).await;
+                    invalidate_entities(runtime, &invalidations, &response).await;
 
                     let attributes = response.operation_attributes().cloned();
                     sender.send(response.with_extensions(extensions)).await.ok();
@@ -183,23 +217,6 @@ impl<R: Runtime> PrepareContext<'_, R> {
 
         let attributes = operation.attributes();
 
-        struct AddExtToFirstResponse<Sender> {
-            sender: Sender,
-            extensions: Option<ResponseExtensions>,
-        }
-
-        impl<S: ResponseSender> ResponseSender for AddExtToFirstResponse<S> {
-            type Error = S::Error;
-            async fn send(&mut self, response: Response) -> Result<(), Self::Error> {
-                let response = if let Some(extensions) = self.extensions.take() {
-                    response.with_extensions(extensions)
-                } else {
-                    response
-                };
-                self.sender.send(response).await
-            }
-        }
-
         let extensions = response_extension_for_prepared_operation(schema, request_context, &operation);
         ctx.execute_subscription(
             operation,
@@ -213,3 +230,20 @@ impl<R: Runtime> PrepareContext<'_, R> {
         Some(attributes)
     }
 }
+
+struct AddExtToFirstResponse<Sender> {
+    sender: Sender,
+    extensions: Option<ResponseExtensions>,
+}
+
+impl<S: ResponseSender> ResponseSender for AddExtToFirstResponse<S> {
+    type Error = S::Error;
+    async fn send(&mut self, response: Response) -> Result<(), Self::Error> {
+        let response = if let Some(extensions) = self.extensions.take() {
+            response.with_extensions(extensions)
+        } else {
+            response
+        };
+        self.sender.send(response).await
+    }
+}
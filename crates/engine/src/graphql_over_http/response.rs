@@ -42,12 +42,28 @@ impl Http {
 
     pub(crate) fn single(format: CompleteResponseFormat, mut response: Response) -> http::Response<Body> {
         let mcp_ext = response.extensions_mut().mcp.take();
+        let cache_control = response.extensions_mut().cache_control.take();
+        let rate_limit = response.extensions_mut().rate_limit.take();
+        let propagated_headers = response.extensions_mut().propagated_headers.take();
         let mut http_response = Self::from_complete_response_with_telemetry(format, response);
 
         if let Some(mcp_ext) = mcp_ext {
             http_response.extensions_mut().insert(mcp_ext);
         }
 
+        // Before the gateway's own headers, so they take precedence.
+        if let Some(propagated_headers) = propagated_headers {
+            propagated_headers.insert_headers(http_response.headers_mut());
+        }
+
+        if let Some(cache_control) = cache_control {
+            cache_control.insert_headers(http_response.headers_mut());
+        }
+
+        if let Some(rate_limit) = rate_limit {
+            rate_limit.insert_headers(http_response.headers_mut());
+        }
+
         http_response
     }
 
@@ -237,7 +253,7 @@ fn compute_status_code(format: ResponseFormat, response: &Response) -> http::Sta
                 }
             }
         }
-        Response::Executed(_) => {
+        Response::Executed(_) | Response::Incremental(_) | Response::Cached(_) => {
             // GraphQL-over-HTTP spec:
             //   If the GraphQL response contains the {data} entry and it is {null}, then the server SHOULD
             //   reply with a 2xx status code and it is RECOMMENDED it replies with 200 status code.
@@ -6,6 +6,7 @@ use operation::Variables;
 pub(crate) use query::*;
 pub(crate) use response::*;
 use schema::{ExtensionDirective, ExtensionDirectiveArgumentsStaticView, InjectionStage, Schema};
+pub(crate) use template::JsonContent;
 
 use crate::response::ParentObjects;
 
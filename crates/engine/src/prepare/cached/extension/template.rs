@@ -2,7 +2,7 @@ use std::borrow::Cow;
 
 use schema::TemplateEscaping;
 
-pub(super) struct JsonContent<'a> {
+pub(crate) struct JsonContent<'a> {
     pub value: Cow<'a, serde_json::Value>,
     pub escaping: TemplateEscaping,
 }
@@ -75,6 +75,7 @@ impl ramhorns::Content for JsonContent<'_> {
                     encoder.format_unescaped(urlencode(s))?;
                     Ok(())
                 }
+                TemplateEscaping::Header => encoder.write_unescaped(s),
             },
             serde_json::Value::Array(a) => match self.escaping {
                 TemplateEscaping::Url => {
@@ -82,7 +83,9 @@ impl ramhorns::Content for JsonContent<'_> {
                     encoder.format_unescaped(urlencode(&s))?;
                     Ok(())
                 }
-                TemplateEscaping::Json => encoder.write_unescaped(&serde_json::to_string(a).unwrap()),
+                TemplateEscaping::Json | TemplateEscaping::Header => {
+                    encoder.write_unescaped(&serde_json::to_string(a).unwrap())
+                }
             },
             serde_json::Value::Object(o) => match self.escaping {
                 TemplateEscaping::Url => {
@@ -90,7 +93,9 @@ impl ramhorns::Content for JsonContent<'_> {
                     encoder.format_unescaped(urlencode(&s))?;
                     Ok(())
                 }
-                TemplateEscaping::Json => encoder.write_unescaped(&serde_json::to_string(o).unwrap()),
+                TemplateEscaping::Json | TemplateEscaping::Header => {
+                    encoder.write_unescaped(&serde_json::to_string(o).unwrap())
+                }
             },
         }
     }
@@ -125,6 +125,12 @@ pub(crate) struct PreparedOperation {
 }
 
 impl PreparedOperation {
+    /// Whether the response must be delivered incrementally because of active @defer or @stream
+    /// directives.
+    pub fn is_incremental(&self) -> bool {
+        self.plan.incremental.is_some()
+    }
+
     pub fn attributes(&self) -> GraphqlOperationAttributes {
         self.cached
             .operation
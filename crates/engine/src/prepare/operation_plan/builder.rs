@@ -35,6 +35,7 @@ impl OperationPlan {
                 query_modifications,
                 plans: Vec::with_capacity(cached.query_plan.partitions.len()),
                 response_modifiers: Vec::with_capacity(cached.query_plan.response_modifier_definitions.len()),
+                incremental: None,
             },
             dependencies: Vec::new(),
             partition_to_plan: vec![None; cached.query_plan.partitions.len()],
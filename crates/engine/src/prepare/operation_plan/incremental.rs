@@ -0,0 +1,180 @@
+use id_newtypes::BitSet;
+use operation::{InputValueContext, OperationContext, ResponseKey, Selection, SelectionSet, Variables};
+
+use crate::prepare::{CachedOperation, CachedOperationContext};
+
+use super::{ExecutableId, OperationPlan, PlanId, QueryModifications};
+
+/// How a query with active `@defer` or `@stream` directives is delivered incrementally.
+///
+/// Deferred fragments rely on the query partitions: plans of partitions only resolving deferred
+/// fields are executed once the initial payload is sent. Deferred fields resolved by the same
+/// partition as the initial ones are retrieved with them and only hidden from the initial payload.
+/// Streamed lists are retrieved with their parent, but none of the plans depending on their
+/// items beyond the initial count are executed before the initial payload is sent.
+pub(crate) struct IncrementalPlan {
+    pub deferred_plan_ids: BitSet<PlanId>,
+    pub has_active_defer: bool,
+    pub streamed_lists: Vec<StreamedList>,
+}
+
+/// A list with an active `@stream` directive.
+pub(crate) struct StreamedList {
+    /// Response keys from the root to the list field, ignoring list indices.
+    pub path: Vec<ResponseKey>,
+    pub initial_count: usize,
+}
+
+impl IncrementalPlan {
+    pub(super) fn build(
+        ctx: CachedOperationContext<'_>,
+        variables: &Variables,
+        plan: &OperationPlan,
+        initial_query_modifications: Option<&QueryModifications>,
+    ) -> Option<Self> {
+        let input_value_ctx = InputValueContext {
+            schema: ctx.schema,
+            query_input_values: &ctx.cached.operation.query_input_values,
+            variables,
+        };
+        let operation_ctx = OperationContext {
+            schema: ctx.schema,
+            operation: &ctx.cached.operation,
+        };
+
+        let mut streamed_lists = Vec::new();
+        collect_streamed_lists(
+            input_value_ctx,
+            operation_ctx.root_selection_set(),
+            &mut Vec::new(),
+            &mut streamed_lists,
+        );
+
+        if initial_query_modifications.is_none() && streamed_lists.is_empty() {
+            return None;
+        }
+
+        let deferred_plan_ids = match initial_query_modifications {
+            Some(initial) => deferred_plan_ids(ctx.cached, plan, initial),
+            None => BitSet::with_capacity(plan.plans.len()),
+        };
+
+        Some(Self {
+            deferred_plan_ids,
+            has_active_defer: initial_query_modifications.is_some(),
+            streamed_lists,
+        })
+    }
+}
+
+/// A plan is deferred if none of the fields of its query partition are needed for the initial
+/// payload, including requirements of other partitions.
+fn deferred_plan_ids(cached: &CachedOperation, plan: &OperationPlan, initial: &QueryModifications) -> BitSet<PlanId> {
+    let partitions_count = cached.query_plan.partitions.len();
+    let mut has_fields = vec![false; partitions_count];
+    let mut has_initial_fields = vec![false; partitions_count];
+
+    for id in plan.query_modifications.included_subgraph_request_data_fields.ones() {
+        let ix = usize::from(cached.query_plan[id].query_partition_id);
+        has_fields[ix] = true;
+        has_initial_fields[ix] |= initial.included_subgraph_request_data_fields[id];
+    }
+
+    let mut deferred_plan_ids = BitSet::with_capacity(plan.plans.len());
+    for (ix, record) in plan.plans.iter().enumerate() {
+        let partition_ix = usize::from(record.query_partition_id);
+        if has_fields[partition_ix] && !has_initial_fields[partition_ix] {
+            deferred_plan_ids.set(PlanId::from(ix), true);
+        }
+    }
+
+    // A deferred plan must not delay any plan of the initial payload, typically with a response
+    // modifier between them.
+    let mut reaches_initial_plan = ReachesInitialPlan {
+        plan,
+        deferred_plan_ids: &deferred_plan_ids,
+        plans: vec![None; plan.plans.len()],
+        response_modifiers: vec![None; plan.response_modifiers.len()],
+    };
+    let blocking_plan_ids = deferred_plan_ids
+        .ones()
+        .filter(|id| {
+            plan[*id]
+                .children_ids
+                .iter()
+                .any(|child| reaches_initial_plan.check(*child))
+        })
+        .collect::<Vec<_>>();
+
+    for id in blocking_plan_ids {
+        deferred_plan_ids.set(id, false);
+    }
+
+    deferred_plan_ids
+}
+
+struct ReachesInitialPlan<'a> {
+    plan: &'a OperationPlan,
+    deferred_plan_ids: &'a BitSet<PlanId>,
+    plans: Vec<Option<bool>>,
+    response_modifiers: Vec<Option<bool>>,
+}
+
+impl ReachesInitialPlan<'_> {
+    fn check(&mut self, id: ExecutableId) -> bool {
+        let (cached, children_ids) = match id {
+            ExecutableId::Plan(id) => {
+                if !self.deferred_plan_ids[id] {
+                    return true;
+                }
+                (self.plans[usize::from(id)], &self.plan[id].children_ids)
+            }
+            ExecutableId::ResponseModifier(id) => {
+                (self.response_modifiers[usize::from(id)], &self.plan[id].children_ids)
+            }
+        };
+
+        if let Some(result) = cached {
+            return result;
+        }
+
+        let result = children_ids.iter().any(|child| self.check(*child));
+
+        match id {
+            ExecutableId::Plan(id) => self.plans[usize::from(id)] = Some(result),
+            ExecutableId::ResponseModifier(id) => self.response_modifiers[usize::from(id)] = Some(result),
+        }
+
+        result
+    }
+}
+
+fn collect_streamed_lists(
+    ctx: InputValueContext<'_>,
+    selection_set: SelectionSet<'_>,
+    path: &mut Vec<ResponseKey>,
+    out: &mut Vec<StreamedList>,
+) {
+    for selection in selection_set {
+        match selection {
+            Selection::Field(field) => {
+                path.push(field.response_key());
+
+                let stream = field.directive_ids().iter().find_map(|id| id.as_stream());
+                if let Some(stream) = stream.filter(|stream| stream.is_active(ctx)) {
+                    out.push(StreamedList {
+                        path: path.clone(),
+                        initial_count: stream.initial_count(ctx),
+                    });
+                }
+
+                collect_streamed_lists(ctx, field.selection_set(), path, out);
+                path.pop();
+            }
+            Selection::FragmentSpread(spread) => {
+                collect_streamed_lists(ctx, spread.fragment().selection_set(), path, out)
+            }
+            Selection::InlineFragment(fragment) => collect_streamed_lists(ctx, fragment.selection_set(), path, out),
+        }
+    }
+}
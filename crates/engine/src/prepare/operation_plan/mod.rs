@@ -1,20 +1,26 @@
 mod builder;
 mod error;
+mod incremental;
 mod model;
 mod query_modifications;
 
 use ::error::ErrorResponse;
 pub(crate) use error::*;
+use grafbase_telemetry::graphql::OperationType;
+pub(crate) use incremental::*;
 pub(crate) use model::*;
 use operation::Variables;
 pub(crate) use query_modifications::*;
 
 use crate::{
     ErrorCode, Runtime,
-    prepare::{CachedOperation, PrepareContext},
+    graphql_over_http::ResponseFormat,
+    prepare::{CachedOperation, CachedOperationContext, PrepareContext},
     response::{GraphqlError, Response},
 };
 
+/// Plans the operation. For queries sent with a streaming response format, the plan also
+/// describes how active `@defer` and `@stream` directives are delivered incrementally.
 #[tracing::instrument(name = "plan", level = "debug", skip_all)]
 pub async fn plan(
     ctx: &mut PrepareContext<'_, impl Runtime>,
@@ -22,9 +28,19 @@ pub async fn plan(
     variables: &Variables,
 ) -> Result<OperationPlan, Response> {
     let schema = ctx.schema();
+    let incremental = operation.ty() == OperationType::Query
+        && matches!(ctx.request_context.response_format, ResponseFormat::Streaming(_));
     async move {
-        let query_modifications = QueryModifications::build(ctx, operation, variables).await?;
-        let plan = OperationPlan::plan(ctx, operation, query_modifications).await?;
+        let IncrementalQueryModifications { complete, initial } =
+            QueryModifications::build_incremental(ctx, operation, variables, incremental).await?;
+        let mut plan = OperationPlan::plan(ctx, operation, complete).await?;
+        if incremental {
+            let cached_ctx = CachedOperationContext {
+                schema,
+                cached: operation,
+            };
+            plan.incremental = IncrementalPlan::build(cached_ctx, variables, &plan, initial.as_ref());
+        }
         Ok(plan)
     }
     .await
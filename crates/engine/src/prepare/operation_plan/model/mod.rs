@@ -12,7 +12,7 @@ use walker::{Iter, Walk};
 
 use crate::prepare::{CachedOperation, CachedOperationContext, PreparedOperation, Shapes};
 
-use super::QueryModifications;
+use super::{IncrementalPlan, QueryModifications};
 
 pub(crate) use field::*;
 pub(crate) use generated::*;
@@ -70,4 +70,6 @@ pub(crate) struct OperationPlan {
     pub plans: Vec<PlanRecord>,
     #[indexed_by(ResponseModifierId)]
     pub response_modifiers: Vec<ResponseModifierRecord>,
+    /// Only present for queries delivered incrementally with active `@defer` or `@stream`.
+    pub incremental: Option<IncrementalPlan>,
 }
@@ -1,7 +1,7 @@
 use schema::{EntityDefinition, ResolverDefinition};
 use walker::Walk;
 
-use crate::prepare::{DataOrLookupFieldId, QueryPartition, ResponseObjectSetId, RootFieldsShape};
+use crate::prepare::{DataOrLookupFieldId, QueryPartition, RequiredFieldSet, ResponseObjectSetId, RootFieldsShape};
 
 use super::{Plan, SubgraphField, SubgraphSelectionSet};
 
@@ -26,6 +26,10 @@ impl<'a> Plan<'a> {
     pub(crate) fn selection_set(&self) -> SubgraphSelectionSet<'a> {
         self.ctx.view(self.query_partition_id).selection_set()
     }
+    /// Fields retrieved by parent plans this one depends on, entity keys and `@requires`.
+    pub(crate) fn required_fields(&self) -> RequiredFieldSet<'a> {
+        self.query_partition().required_fields()
+    }
     pub(crate) fn shape(&self) -> RootFieldsShape<'a> {
         self.query_partition().shape_id.walk(self.ctx)
     }
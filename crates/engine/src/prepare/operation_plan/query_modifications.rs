@@ -24,7 +24,7 @@ use crate::{
 
 use super::PlanResult;
 
-#[derive(Default, id_derives::IndexedFields)]
+#[derive(Default, Clone, id_derives::IndexedFields)]
 pub(crate) struct QueryModifications {
     pub included_response_data_fields: BitSet<DataFieldId>,
     pub included_response_typename_fields: BitSet<TypenameFieldId>,
@@ -37,7 +37,7 @@ pub(crate) struct QueryModifications {
     pub extension: ExtensionPreparedOperation,
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub(crate) struct ExtensionPreparedOperation {
     // Arc for Wasmtime because we can't return an non 'static value from a function.
     pub authorization_context: Vec<(ExtensionId, Arc<[u8]>)>,
@@ -48,12 +48,20 @@ pub(crate) struct ExtensionPreparedOperation {
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, serde::Serialize, serde::Deserialize, id_derives::Id)]
 pub struct QueryErrorId(NonZero<u16>);
 
+/// Query modifications with and without the active `@defer` fragments.
+pub(crate) struct IncrementalQueryModifications {
+    pub complete: QueryModifications,
+    /// Only present if deferring was requested and there is at least one active `@defer` fragment.
+    pub initial: Option<QueryModifications>,
+}
+
 impl QueryModifications {
-    pub(crate) async fn build(
+    pub(crate) async fn build_incremental(
         ctx: &mut PrepareContext<'_, impl Runtime>,
         cached: &CachedOperation,
         variables: &Variables,
-    ) -> PlanResult<Self> {
+        defer: bool,
+    ) -> PlanResult<IncrementalQueryModifications> {
         Builder {
             operation_ctx: CachedOperationContext {
                 schema: ctx.schema(),
@@ -66,6 +74,8 @@ impl QueryModifications {
             },
             ctx,
             field_id_to_error_ids: Default::default(),
+            defer,
+            deferred_modifiers: Vec::new(),
             modifications: QueryModifications {
                 included_response_data_fields: cached.query_plan.response_data_fields.clone(),
                 included_response_typename_fields: cached.query_plan.response_typename_fields.clone(),
@@ -89,6 +99,8 @@ struct Builder<'ctx, 'op, R: Runtime> {
     operation_ctx: CachedOperationContext<'op>,
     input_value_ctx: InputValueContext<'op>,
     field_id_to_error_ids: Vec<(DataFieldId, QueryErrorId)>,
+    defer: bool,
+    deferred_modifiers: Vec<&'op QueryModifierRecord>,
     modifications: QueryModifications,
 }
 
@@ -96,7 +108,7 @@ impl<'ctx, 'op, R: Runtime> Builder<'ctx, 'op, R>
 where
     'ctx: 'op,
 {
-    pub(super) async fn build(mut self) -> PlanResult<QueryModifications> {
+    pub(super) async fn build(mut self) -> PlanResult<IncrementalQueryModifications> {
         // Hooks authorization will go away and @authenticiated &
         // @requiresScopes will be an extension. So we're left with skip/include in the host part
         // which don't need I/O. So no need to parallelize that today.
@@ -107,7 +119,26 @@ where
             self.handle_extensions().await?;
         }
 
-        Ok(self.finalize())
+        let Self {
+            modifications,
+            operation_ctx,
+            field_id_to_error_ids,
+            deferred_modifiers,
+            ..
+        } = self;
+
+        let initial = if deferred_modifiers.is_empty() {
+            None
+        } else {
+            let mut initial = modifications.clone();
+            for modifier in deferred_modifiers {
+                skip_field(operation_ctx, &mut initial, modifier);
+            }
+            Some(finalize(operation_ctx, initial, &field_id_to_error_ids))
+        };
+        let complete = finalize(operation_ctx, modifications, &field_id_to_error_ids);
+
+        Ok(IncrementalQueryModifications { complete, initial })
     }
 
     async fn handle_extensions(&mut self) -> PlanResult<()> {
@@ -240,6 +271,7 @@ where
     async fn handle_native_modifiers(&mut self, query_modifiers: &'op [QueryModifierRecord]) -> PlanResult<()> {
         for modifier in query_modifiers {
             if let QueryModifierRule::Executable { directives } = &modifier.rule {
+                let mut is_deferred = false;
                 // GraphQL spec:
                 //   Stated conversely, the field or fragment must not be queried if either the @skip condition is true or the @include condition is false.
                 let is_skipped = directives.iter().any(|directive| match directive {
@@ -251,10 +283,18 @@ where
                         bool::deserialize(directive.condition.walk(self.input_value_ctx))
                             .expect("at this point we've already checked the argument type")
                     }
+                    operation::ExecutableDirectiveId::Defer(directive) => {
+                        is_deferred |= self.defer && directive.is_active(self.input_value_ctx);
+                        false
+                    }
+                    // Streamed lists are handled by the execution, see `IncrementalPlan`.
+                    operation::ExecutableDirectiveId::Stream(_) => false,
                 });
 
                 if is_skipped {
-                    self.skip_field(modifier)
+                    skip_field(self.operation_ctx, &mut self.modifications, modifier)
+                } else if is_deferred {
+                    self.deferred_modifiers.push(modifier);
                 }
             } else {
                 unreachable!("Not a native modifier")
@@ -264,149 +304,147 @@ where
         Ok(())
     }
 
-    fn finalize(self) -> QueryModifications {
-        let Self {
-            mut modifications,
-            operation_ctx,
-            field_id_to_error_ids,
-            ..
-        } = self;
-        modifications.included_subgraph_request_data_fields = modifications.included_response_data_fields.clone();
-
-        // Unless required for other fields, they're not needed anymore.
-        let mut field_shape_id_to_error_id = Vec::with_capacity(field_id_to_error_ids.len());
-        for (id, error_id) in field_id_to_error_ids {
-            modifications.included_subgraph_request_data_fields.set(id, false);
-            for id in id.walk(operation_ctx).shape_ids() {
-                field_shape_id_to_error_id.push((id, error_id));
-            }
+    fn deny_field(&mut self, modifier: &'op QueryModifierRecord, error_id: QueryErrorId) {
+        if modifier.impacts_root_object {
+            self.modifications.root_error_ids.push(error_id);
         }
-        modifications.field_shape_id_to_error_ids = field_shape_id_to_error_id.into();
+        for field in modifier.impacted_field_ids.walk(self.operation_ctx) {
+            let PartitionField::Data(field) = field else {
+                unreachable!()
+            };
+            self.field_id_to_error_ids.push((field.id, error_id));
+        }
+    }
 
-        let mut requires_stack: Vec<&'op RequiredFieldSetRecord> =
-            Vec::with_capacity(operation_ctx.query_partitions().len() * 2);
-        let mut derive_stack: Vec<DataFieldId> = Vec::new();
+    fn push_error(&mut self, error: GraphqlError) -> QueryErrorId {
+        let id = QueryErrorId::from(self.modifications.errors.len());
+        self.modifications.errors.push(error);
+        id
+    }
+}
 
-        for id in modifications.included_response_data_fields.ones() {
-            let field = id.walk(operation_ctx);
-            if !field.required_fields_record.is_empty() {
-                requires_stack.push(&field.as_ref().required_fields_record);
-            }
-            if !field.required_fields_record_by_supergraph.is_empty() {
-                requires_stack.push(&field.as_ref().required_fields_record_by_supergraph);
-            }
-            if let Some(Derive::From(id))
-            | Some(Derive::Root {
-                batch_field_id: Some(id),
-            }) = field.derive
-            {
-                derive_stack.push(id);
-            }
+fn finalize<'op>(
+    operation_ctx: CachedOperationContext<'op>,
+    mut modifications: QueryModifications,
+    field_id_to_error_ids: &[(DataFieldId, QueryErrorId)],
+) -> QueryModifications {
+    modifications.included_subgraph_request_data_fields = modifications.included_response_data_fields.clone();
+
+    // Unless required for other fields, they're not needed anymore.
+    let mut field_shape_id_to_error_id = Vec::with_capacity(field_id_to_error_ids.len());
+    for &(id, error_id) in field_id_to_error_ids {
+        modifications.included_subgraph_request_data_fields.set(id, false);
+        for id in id.walk(operation_ctx).shape_ids() {
+            field_shape_id_to_error_id.push((id, error_id));
         }
+    }
+    modifications.field_shape_id_to_error_ids = field_shape_id_to_error_id.into();
 
-        // TODO: Don't include partitions without included subgraph fields.
-        for query_partition in operation_ctx.query_partitions() {
-            requires_stack.push(&query_partition.as_ref().required_fields_record);
+    let mut requires_stack: Vec<&'op RequiredFieldSetRecord> =
+        Vec::with_capacity(operation_ctx.query_partitions().len() * 2);
+    let mut derive_stack: Vec<DataFieldId> = Vec::new();
+
+    for id in modifications.included_response_data_fields.ones() {
+        let field = id.walk(operation_ctx);
+        if !field.required_fields_record.is_empty() {
+            requires_stack.push(&field.as_ref().required_fields_record);
+        }
+        if !field.required_fields_record_by_supergraph.is_empty() {
+            requires_stack.push(&field.as_ref().required_fields_record_by_supergraph);
         }
+        if let Some(Derive::From(id))
+        | Some(Derive::Root {
+            batch_field_id: Some(id),
+        }) = field.derive
+        {
+            derive_stack.push(id);
+        }
+    }
 
-        while !requires_stack.is_empty() || !derive_stack.is_empty() {
-            for id in derive_stack.drain(..) {
-                if !modifications.included_subgraph_request_data_fields.put(id) {
-                    let field = id.walk(operation_ctx);
-                    // if it wasn't already processed
-                    if !modifications.included_response_data_fields[field.id]
-                        && !field.required_fields_record.is_empty()
-                    {
-                        requires_stack.push(&field.as_ref().required_fields_record);
-                    }
+    // TODO: Don't include partitions without included subgraph fields.
+    for query_partition in operation_ctx.query_partitions() {
+        requires_stack.push(&query_partition.as_ref().required_fields_record);
+    }
+
+    while !requires_stack.is_empty() || !derive_stack.is_empty() {
+        for id in derive_stack.drain(..) {
+            if !modifications.included_subgraph_request_data_fields.put(id) {
+                let field = id.walk(operation_ctx);
+                // if it wasn't already processed
+                if !modifications.included_response_data_fields[field.id] && !field.required_fields_record.is_empty() {
+                    requires_stack.push(&field.as_ref().required_fields_record);
                 }
             }
-            while let Some(required_fields) = requires_stack.pop() {
-                for item in required_fields.deref() {
-                    modifications
-                        .included_subgraph_request_data_fields
-                        .set(item.data_field_id, true);
-                    requires_stack.push(&item.subselection_record);
-                    let field = item.data_field_id.walk(operation_ctx);
-                    if !field.required_fields_record.is_empty() {
-                        requires_stack.push(&field.as_ref().required_fields_record);
-                    }
-                    if let Some(Derive::From(id))
-                    | Some(Derive::Root {
-                        batch_field_id: Some(id),
-                    }) = field.derive
-                    {
-                        derive_stack.push(id);
-                    }
+        }
+        while let Some(required_fields) = requires_stack.pop() {
+            for item in required_fields.deref() {
+                modifications
+                    .included_subgraph_request_data_fields
+                    .set(item.data_field_id, true);
+                requires_stack.push(&item.subselection_record);
+                let field = item.data_field_id.walk(operation_ctx);
+                if !field.required_fields_record.is_empty() {
+                    requires_stack.push(&field.as_ref().required_fields_record);
+                }
+                if let Some(Derive::From(id))
+                | Some(Derive::Root {
+                    batch_field_id: Some(id),
+                }) = field.derive
+                {
+                    derive_stack.push(id);
                 }
             }
         }
+    }
 
-        // Identify all concrete shapes with errors.
-        let mut field_shape_ids_with_errors = modifications.field_shape_id_to_error_ids.ids();
-        if let Some(mut current) = field_shape_ids_with_errors.next() {
-            'outer: for (concrete_shape_id, shape) in operation_ctx.cached.shapes.concrete.iter().enumerate() {
-                if current < shape.field_shape_ids.end {
-                    let mut i = 0;
-                    while let Some(field_shape_id) = shape.field_shape_ids.get(i) {
-                        match field_shape_id.cmp(&current) {
-                            std::cmp::Ordering::Less => {
-                                i += 1;
-                            }
-                            std::cmp::Ordering::Equal => {
-                                modifications
-                                    .concrete_shape_has_error
-                                    .set(ConcreteShapeId::from(concrete_shape_id), true);
-                                break;
-                            }
-                            std::cmp::Ordering::Greater => {
-                                let Some(next) = field_shape_ids_with_errors.next() else {
-                                    break 'outer;
-                                };
-                                current = next;
-                            }
+    // Identify all concrete shapes with errors.
+    let mut field_shape_ids_with_errors = modifications.field_shape_id_to_error_ids.ids();
+    if let Some(mut current) = field_shape_ids_with_errors.next() {
+        'outer: for (concrete_shape_id, shape) in operation_ctx.cached.shapes.concrete.iter().enumerate() {
+            if current < shape.field_shape_ids.end {
+                let mut i = 0;
+                while let Some(field_shape_id) = shape.field_shape_ids.get(i) {
+                    match field_shape_id.cmp(&current) {
+                        std::cmp::Ordering::Less => {
+                            i += 1;
+                        }
+                        std::cmp::Ordering::Equal => {
+                            modifications
+                                .concrete_shape_has_error
+                                .set(ConcreteShapeId::from(concrete_shape_id), true);
+                            break;
+                        }
+                        std::cmp::Ordering::Greater => {
+                            let Some(next) = field_shape_ids_with_errors.next() else {
+                                break 'outer;
+                            };
+                            current = next;
                         }
                     }
                 }
             }
         }
-        drop(field_shape_ids_with_errors);
-
-        modifications
     }
+    drop(field_shape_ids_with_errors);
 
-    fn deny_field(&mut self, modifier: &'op QueryModifierRecord, error_id: QueryErrorId) {
-        if modifier.impacts_root_object {
-            self.modifications.root_error_ids.push(error_id);
-        }
-        for field in modifier.impacted_field_ids.walk(self.operation_ctx) {
-            let PartitionField::Data(field) = field else {
-                unreachable!()
-            };
-            self.field_id_to_error_ids.push((field.id, error_id));
-        }
-    }
+    modifications
+}
 
-    fn skip_field(&mut self, modifier: &'op QueryModifierRecord) {
-        for field in modifier.impacted_field_ids.walk(self.operation_ctx) {
-            match field {
-                PartitionField::Typename(field) => {
-                    self.modifications
-                        .included_response_typename_fields
-                        .set(field.id, false);
-                }
-                PartitionField::Data(field) => {
-                    self.modifications.included_response_data_fields.set(field.id, false);
-                }
-                // Can never be skipped, it's always necessary if any partition field is needed.
-                PartitionField::Lookup(_) => unreachable!(),
+fn skip_field<'op>(
+    operation_ctx: CachedOperationContext<'op>,
+    modifications: &mut QueryModifications,
+    modifier: &'op QueryModifierRecord,
+) {
+    for field in modifier.impacted_field_ids.walk(operation_ctx) {
+        match field {
+            PartitionField::Typename(field) => {
+                modifications.included_response_typename_fields.set(field.id, false);
             }
+            PartitionField::Data(field) => {
+                modifications.included_response_data_fields.set(field.id, false);
+            }
+            // Can never be skipped, it's always necessary if any partition field is needed.
+            PartitionField::Lookup(_) => unreachable!(),
         }
     }
-
-    fn push_error(&mut self, error: GraphqlError) -> QueryErrorId {
-        let id = QueryErrorId::from(self.modifications.errors.len());
-        self.modifications.errors.push(error);
-        id
-    }
 }
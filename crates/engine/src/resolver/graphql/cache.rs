@@ -1,16 +1,27 @@
 use bytes::Bytes;
 use futures::future::join_all;
-use grafbase_telemetry::graphql::GraphqlResponseStatus;
+use grafbase_telemetry::{
+    graphql::{GraphqlResponseStatus, OperationType},
+    span::subgraph::SubgraphRequestSpanBuilder,
+};
 use headers::HeaderMapExt;
 use http::HeaderMap;
-use itertools::Itertools;
-use runtime::entity_cache::EntityCache;
+use runtime::entity_cache::{self, EntityCache};
+use schema::{GraphqlSubgraph, SubgraphConfig};
+use serde::de::IgnoredAny;
 use serde_json::value::RawValue;
-use std::time::Duration;
+use std::{
+    borrow::Cow,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::Instrument as _;
 
-use crate::{Runtime, response::ParentObjectId};
+use crate::{Runtime, engine::cache_refresh::CacheRefreshGuard, execution::ExecutionContext, response::ParentObjectId};
 
-use super::{EntityToFetch, SubgraphContext};
+use super::{
+    EntityToFetch, SubgraphContext,
+    request::{fetch, prepare_fetch_request},
+};
 
 pub(super) fn calculate_cache_ttl(
     status: GraphqlResponseStatus,
@@ -58,7 +69,8 @@ pub(super) async fn fetch_response<R: Runtime>(
         .finalize()
         .to_string();
 
-    ctx.engine()
+    let entry = ctx
+        .engine()
         .runtime
         .entity_cache()
         .get(&key)
@@ -66,24 +78,39 @@ pub(super) async fn fetch_response<R: Runtime>(
         .inspect_err(|err| tracing::warn!("Failed to read the cache key {key}: {err}"))
         .ok()
         .flatten()
-        .map(|data| Ok(ResponseCacheHit { data }))
-        .unwrap_or(Err(ResponseCacheMiss { key }))
+        .and_then(|bytes| CacheEntry::decode(bytes, &ctx.endpoint().config));
+
+    match entry {
+        Some(CacheEntry::Fresh(data)) => Ok(ResponseCacheHit { data, key: None }),
+        Some(CacheEntry::StaleWhileRevalidate(data)) => Ok(ResponseCacheHit { data, key: Some(key) }),
+        Some(CacheEntry::StaleIfError(data)) => Err(ResponseCacheMiss {
+            key,
+            stale_data: Some(data),
+        }),
+        None => Err(ResponseCacheMiss { key, stale_data: None }),
+    }
 }
 
 pub(super) struct ResponseCacheHit {
     pub data: Bytes,
+    /// Present if the data is stale and must be refreshed in the background.
+    pub key: Option<String>,
 }
 
 pub(super) struct ResponseCacheMiss {
     pub key: String,
+    /// Expired data which may be used if the subgraph request fails.
+    pub stale_data: Option<Bytes>,
 }
 
 pub(super) async fn fetch_entities<R: Runtime>(
     ctx: &mut SubgraphContext<'_, R>,
     subgraph_headers: &http::HeaderMap,
+    key_fields: &[String],
     entities_to_fetch: Vec<EntityToFetch>,
 ) -> CacheFetchEntitiesOutcome {
     let entity_cache = ctx.runtime().entity_cache();
+    let config = &ctx.endpoint().as_ref().config;
 
     // let additional_scopes = plan
     //     .cache_scopes()
@@ -113,16 +140,33 @@ pub(super) async fn fetch_entities<R: Runtime>(
                 .update(representation.get().as_bytes())
                 .finalize()
                 .to_string();
-            fetch_entity(entity_cache, id, key, representation)
+            let tags = entity_tags(&representation, key_fields);
+            fetch_entity(entity_cache, config, id, key, tags, representation)
         });
 
-    let (hits, misses) = join_all(fetches).await.into_iter().partition_result();
-    CacheFetchEntitiesOutcome { hits, misses }
+    let mut outcome = CacheFetchEntitiesOutcome {
+        hits: Vec::new(),
+        misses: Vec::new(),
+        stale: Vec::new(),
+    };
+    for lookup in join_all(fetches).await {
+        match lookup {
+            EntityCacheLookup::Hit(hit) => outcome.hits.push(hit),
+            EntityCacheLookup::StaleHit(hit, stale) => {
+                outcome.hits.push(hit);
+                outcome.stale.push(stale);
+            }
+            EntityCacheLookup::Miss(miss) => outcome.misses.push(miss),
+        }
+    }
+    outcome
 }
 
 pub(super) struct CacheFetchEntitiesOutcome {
     pub hits: Vec<EntityCacheHit>,
     pub misses: Vec<EntityCacheMiss>,
+    /// Entities served from stale cache entries which must be refreshed in the background.
+    pub stale: Vec<EntityCacheMiss>,
 }
 
 pub(super) struct EntityCacheHit {
@@ -133,35 +177,76 @@ pub(super) struct EntityCacheHit {
 pub(super) struct EntityCacheMiss {
     pub id: ParentObjectId,
     pub key: String,
+    pub tags: Vec<String>,
     pub representation: Box<RawValue>,
+    /// Expired data which may be used if the subgraph request fails.
+    pub stale_data: Option<Bytes>,
+}
+
+enum EntityCacheLookup {
+    Hit(EntityCacheHit),
+    StaleHit(EntityCacheHit, EntityCacheMiss),
+    Miss(EntityCacheMiss),
 }
 
 async fn fetch_entity(
     entity_cache: &dyn EntityCache,
+    config: &SubgraphConfig,
     id: ParentObjectId,
     key: String,
+    tags: Vec<String>,
     representation: Box<RawValue>,
-) -> Result<EntityCacheHit, EntityCacheMiss> {
-    let data = entity_cache
+) -> EntityCacheLookup {
+    let entry = entity_cache
         .get(&key)
         .await
         .inspect_err(|err| tracing::warn!("Failed to read the cache key {key}: {err}"))
         .ok()
-        .flatten();
+        .flatten()
+        .and_then(|bytes| CacheEntry::decode(bytes, config));
 
+    let (data, stale_data) = match entry {
+        Some(CacheEntry::Fresh(data)) => return EntityCacheLookup::Hit(EntityCacheHit { id, data }),
+        Some(CacheEntry::StaleWhileRevalidate(data)) => (Some(data), None),
+        Some(CacheEntry::StaleIfError(data)) => (None, Some(data)),
+        None => (None, None),
+    };
+
+    let miss = EntityCacheMiss {
+        id,
+        key,
+        tags,
+        representation,
+        stale_data,
+    };
     match data {
-        Some(data) => Ok(EntityCacheHit { id, data }),
-        None => Err(EntityCacheMiss {
-            id,
-            key,
-            representation,
-        }),
+        Some(data) => EntityCacheLookup::StaleHit(EntityCacheHit { id, data }, miss),
+        None => EntityCacheLookup::Miss(miss),
     }
 }
 
+/// Tags an entity by its type and its key, extracted from the representation sent to the
+/// subgraph which may also contain fields required by the subgraph.
+fn entity_tags(representation: &RawValue, key_fields: &[String]) -> Vec<String> {
+    let Ok(serde_json::Value::Object(mut fields)) = serde_json::from_str(representation.get()) else {
+        return Vec::new();
+    };
+
+    let Some(serde_json::Value::String(type_name)) = fields.remove("__typename") else {
+        return Vec::new();
+    };
+
+    fields.retain(|name, _| key_fields.contains(name));
+
+    vec![
+        entity_cache::type_tag(&type_name),
+        entity_cache::entity_tag(&type_name, &fields),
+    ]
+}
+
 fn prepare_key_hasher(subgraph_name: &str, headers: &HeaderMap, additional_scopes: &[String]) -> blake3::Hasher {
     let mut hasher = blake3::Hasher::new();
-    hasher.update(b"v1");
+    hasher.update(b"v2");
     hasher.update(subgraph_name.as_bytes());
     hasher.update(&headers.len().to_le_bytes());
     for (name, value) in headers {
@@ -177,3 +262,242 @@ fn prepare_key_hasher(subgraph_name: &str, headers: &HeaderMap, additional_scope
     }
     hasher
 }
+
+/// State of a cache entry relative to the stale windows of its subgraph.
+enum CacheEntry {
+    Fresh(Bytes),
+    /// Expired, but can be served while it's refreshed in the background.
+    StaleWhileRevalidate(Bytes),
+    /// Expired, and can only be served if the subgraph request fails.
+    StaleIfError(Bytes),
+}
+
+impl CacheEntry {
+    /// Entries are prefixed by the time, in milliseconds since the Unix epoch, until which they're
+    /// fresh. They're stored for longer than their TTL to be served as stale data.
+    fn encode(data: &[u8], ttl: Duration) -> Vec<u8> {
+        let fresh_until = now_ms().saturating_add(ttl.as_millis() as u64);
+        let mut bytes = Vec::with_capacity(8 + data.len());
+        bytes.extend_from_slice(&fresh_until.to_be_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn decode(bytes: Bytes, config: &SubgraphConfig) -> Option<Self> {
+        let fresh_until = u64::from_be_bytes(bytes.get(..8)?.try_into().ok()?);
+        let data = bytes.slice(8..);
+
+        let Some(staleness) = now_ms().checked_sub(fresh_until) else {
+            return Some(Self::Fresh(data));
+        };
+        let staleness = Duration::from_millis(staleness);
+
+        if staleness <= config.cache_stale_while_revalidate {
+            Some(Self::StaleWhileRevalidate(data))
+        } else if staleness <= config.cache_stale_if_error {
+            Some(Self::StaleIfError(data))
+        } else {
+            None
+        }
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Writes a cache entry which stays fresh for the given TTL and is kept afterwards for as long as
+/// it may be served stale. Subgraph response headers are never cached, as they may be specific to
+/// the request, so cache hits don't propagate any.
+pub(super) async fn put_entry(
+    entity_cache: &dyn EntityCache,
+    config: &SubgraphConfig,
+    key: &str,
+    data: &[u8],
+    tags: &[String],
+    ttl: Duration,
+) {
+    let expiration_ttl = ttl + config.cache_stale_while_revalidate.max(config.cache_stale_if_error);
+    entity_cache
+        .put(key, Cow::Owned(CacheEntry::encode(data, ttl)), tags, expiration_ttl)
+        .await
+        .inspect_err(|err| tracing::warn!("Failed to write the cache key {key}: {err}"))
+        .ok();
+}
+
+/// Cache entries refreshed by a background subgraph request.
+pub(super) enum CacheRefresh {
+    /// Whole subgraph response for root fields.
+    Response { key: String, tags: Vec<String> },
+    /// Entities in the same order as the representations sent to the subgraph.
+    Entities(Vec<EntityCacheMiss>),
+}
+
+impl CacheRefresh {
+    /// Keeps only the entries which aren't already being refreshed by a concurrent request. The
+    /// returned guard must be kept until the refresh is done.
+    pub(super) fn claim<R: Runtime>(self, ctx: &SubgraphContext<'_, R>) -> Option<(Self, CacheRefreshGuard)> {
+        let refreshes = &ctx.engine().cache_refreshes;
+        match self {
+            CacheRefresh::Response { key, tags } => {
+                let guard = refreshes.claim([key.as_str()]);
+                (!guard.is_empty()).then_some((CacheRefresh::Response { key, tags }, guard))
+            }
+            CacheRefresh::Entities(mut entities) => {
+                let guard = refreshes.claim(entities.iter().map(|entity| entity.key.as_str()));
+                entities.retain(|entity| guard.contains(&entity.key));
+                (!entities.is_empty()).then_some((CacheRefresh::Entities(entities), guard))
+            }
+        }
+    }
+}
+
+/// Sends the subgraph request in the background to refresh stale cache entries, the current
+/// request being served with the stale data. The request goes through the same rate limit,
+/// circuit breaker, retry budget and metrics as any other subgraph request. Failures are only
+/// logged, the stale entries will be refreshed by a later request or expire.
+pub(super) fn spawn_refresh<R: Runtime>(
+    ctx: &SubgraphContext<'_, R>,
+    headers: http::HeaderMap,
+    query: &str,
+    body: Vec<u8>,
+    (refresh, guard): (CacheRefresh, CacheRefreshGuard),
+) {
+    let ExecutionContext {
+        engine,
+        request_context,
+        operation,
+    } = ctx.execution_context();
+    let engine = engine.clone();
+    let request_context = request_context.clone();
+    let operation = operation.clone();
+    let subgraph_id = ctx.endpoint().id;
+    let query = query.to_string();
+
+    tokio::spawn(async move {
+        // Released once the refresh is done, successful or not.
+        let _guard = guard;
+
+        let ctx = ExecutionContext {
+            engine: &engine,
+            request_context: &request_context,
+            operation: &operation,
+        };
+        let subgraph = subgraph_id.walk(ctx.schema());
+        let mut ctx = SubgraphContext::new(
+            ctx,
+            subgraph,
+            SubgraphRequestSpanBuilder {
+                subgraph_name: subgraph.name(),
+                operation_type: OperationType::Query,
+                sanitized_query: &query,
+            },
+        );
+        let span = ctx.span();
+
+        async move {
+            let result = match prepare_fetch_request(&ctx, headers, false, body.into()).await {
+                Ok(request) => fetch(&mut ctx, request).await,
+                Err(err) => Err(err),
+            };
+
+            match result {
+                Ok(response) => {
+                    let entity_cache = ctx.runtime().entity_cache();
+                    let status = refresh_entries(entity_cache, subgraph, refresh, response).await;
+                    if let Some(status) = status {
+                        ctx.set_graphql_response_status(status);
+                    } else {
+                        ctx.set_as_invalid_response();
+                    }
+                }
+                Err(err) => {
+                    tracing::debug!("Could not refresh stale cache entries: {}", err.message);
+                }
+            }
+
+            ctx.finish();
+        }
+        .instrument(span)
+        .await
+    });
+}
+
+async fn refresh_entries(
+    entity_cache: &dyn EntityCache,
+    subgraph: GraphqlSubgraph<'_>,
+    refresh: CacheRefresh,
+    response: http::Response<Bytes>,
+) -> Option<GraphqlResponseStatus> {
+    let config = &subgraph.config;
+
+    let Ok(RefreshResponse { data, errors }) = serde_json::from_slice::<RefreshResponse<'_>>(response.body()) else {
+        tracing::debug!("Could not refresh stale cache entries, invalid subgraph response");
+        return None;
+    };
+
+    if !errors.is_empty() {
+        tracing::debug!("Could not refresh stale cache entries, subgraph responded with errors");
+        return Some(GraphqlResponseStatus::FieldError {
+            count: errors.len() as u64,
+            data_is_null: data.is_none(),
+        });
+    }
+
+    if !response.status().is_success() {
+        tracing::debug!(
+            "Could not refresh stale cache entries, subgraph responded with status code {}",
+            response.status().as_u16()
+        );
+        return Some(GraphqlResponseStatus::Success);
+    }
+
+    let Some(ttl) = calculate_cache_ttl(GraphqlResponseStatus::Success, response.headers(), config.cache_ttl) else {
+        return Some(GraphqlResponseStatus::Success);
+    };
+
+    match refresh {
+        CacheRefresh::Response { key, tags } => {
+            put_entry(entity_cache, config, &key, response.body(), &tags, ttl).await;
+        }
+        CacheRefresh::Entities(entities) => {
+            let Some(RefreshEntitiesData { entities: values }) = data else {
+                return Some(GraphqlResponseStatus::Success);
+            };
+            if values.len() != entities.len() {
+                tracing::debug!("Could not refresh stale cache entries, unexpected number of entities");
+                return Some(GraphqlResponseStatus::Success);
+            }
+            join_all(entities.iter().zip(values).map(|(entity, value)| {
+                put_entry(
+                    entity_cache,
+                    config,
+                    &entity.key,
+                    value.get().as_bytes(),
+                    &entity.tags,
+                    ttl,
+                )
+            }))
+            .await;
+        }
+    }
+
+    Some(GraphqlResponseStatus::Success)
+}
+
+#[derive(serde::Deserialize)]
+struct RefreshResponse<'a> {
+    #[serde(borrow, default)]
+    data: Option<RefreshEntitiesData<'a>>,
+    #[serde(default)]
+    errors: Vec<IgnoredAny>,
+}
+
+#[derive(serde::Deserialize)]
+struct RefreshEntitiesData<'a> {
+    #[serde(borrow, rename = "_entities", default)]
+    entities: Vec<&'a RawValue>,
+}
@@ -1,7 +1,7 @@
 use bytes::Bytes;
 use event_queue::{CacheStatus, ExecutedSubgraphRequest, ExecutedSubgraphRequestBuilder, RequestExecution};
 use grafbase_telemetry::{
-    graphql::GraphqlResponseStatus,
+    graphql::{GraphqlResponseStatus, OperationType},
     span::subgraph::{SubgraphGraphqlRequestSpan, SubgraphHttpRequestSpan, SubgraphRequestSpanBuilder},
 };
 use schema::GraphqlSubgraph;
@@ -19,13 +19,17 @@ use grafbase_telemetry::{
     },
 };
 
-use crate::{Engine, Runtime, execution::ExecutionContext, resolver::ResolverResult, response::ResponsePartBuilder};
+use crate::{
+    Engine, Runtime, engine::hedging::HedgingPolicy, execution::ExecutionContext, resolver::ResolverResult,
+    response::ResponsePartBuilder,
+};
 
 #[derive(Clone)]
 pub(crate) struct SubgraphContext<'ctx, R: Runtime> {
     pub(super) ctx: ExecutionContext<'ctx, R>,
     pub(super) subgraph: GraphqlSubgraph<'ctx>,
     pub(super) retry_budget: Option<&'ctx TpsBudget>,
+    pub(super) hedging_policy: Option<&'ctx HedgingPolicy>,
     span: SubgraphGraphqlRequestSpan,
     start: Instant,
     executed_request_builder: ExecutedSubgraphRequestBuilder<'ctx>,
@@ -52,10 +56,16 @@ impl<'ctx, R: Runtime> SubgraphContext<'ctx, R> {
             ExecutedSubgraphRequest::builder(subgraph.name(), http::Method::POST, subgraph.url().as_str());
 
         let retry_budget = match span.operation_type {
-            "mutation" => ctx.engine.get_retry_budget_for_mutation(subgraph.id),
+            OperationType::Mutation => ctx.engine.get_retry_budget_for_mutation(subgraph.id),
             _ => ctx.engine.get_retry_budget_for_non_mutation(subgraph.id),
         };
 
+        // Only queries are hedged, mutations aren't idempotent and subscriptions are long-lived.
+        let hedging_policy = match span.operation_type {
+            OperationType::Query => ctx.engine.get_hedging_policy(subgraph.id),
+            _ => None,
+        };
+
         let span = span.build();
 
         Self {
@@ -65,6 +75,7 @@ impl<'ctx, R: Runtime> SubgraphContext<'ctx, R> {
             span,
             start: Instant::now(),
             retry_budget,
+            hedging_policy,
             status: None,
             http_status_code: None,
             send_count: 0,
@@ -91,7 +102,17 @@ impl<'ctx, R: Runtime> SubgraphContext<'ctx, R> {
         self.retry_budget
     }
 
+    pub fn hedging_policy(&self) -> Option<&'ctx HedgingPolicy> {
+        self.hedging_policy
+    }
+
     pub async fn finalize(self, response_part: ResponsePartBuilder<'ctx>) -> ResolverResult<'ctx> {
+        self.finish();
+        ResolverResult { response_part }
+    }
+
+    /// Records the request duration and pushes the executed request to the event queue.
+    pub fn finish(self) {
         let duration = self.start.elapsed();
 
         if let Some(status) = self.status {
@@ -110,8 +131,6 @@ impl<'ctx, R: Runtime> SubgraphContext<'ctx, R> {
                 .event_queue()
                 .push_subgraph_request(self.executed_request_builder);
         }
-
-        ResolverResult { response_part }
     }
 
     pub(super) fn increment_inflight_requests(&mut self) {
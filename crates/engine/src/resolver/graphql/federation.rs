@@ -20,6 +20,7 @@ use crate::{
 
 use super::{
     SubgraphContext,
+    cache::CacheRefresh,
     request::{PreparedFederationEntityOperation, execute_subgraph_request},
 };
 
@@ -28,6 +29,8 @@ pub(crate) struct FederationEntityResolver {
     pub subgraph_id: GraphqlSubgraphId,
     pub shape_id: RootFieldsShapeId,
     pub subgraph_operation: PreparedFederationEntityOperation,
+    /// Top level key fields, identifying the entity in the cache tags.
+    pub key_fields: Vec<String>,
 }
 
 impl FederationEntityResolver {
@@ -46,6 +49,11 @@ impl FederationEntityResolver {
             subgraph_id: definition.subgraph().id,
             shape_id: plan_query_partition.shape_id(),
             subgraph_operation,
+            key_fields: definition
+                .key_fields()
+                .items()
+                .map(|item| item.field().definition().name().to_string())
+                .collect(),
         })
     }
 
@@ -56,7 +64,7 @@ impl FederationEntityResolver {
             endpoint,
             SubgraphRequestSpanBuilder {
                 subgraph_name: endpoint.name(),
-                operation_type: OperationType::Query.as_str(),
+                operation_type: OperationType::Query,
                 sanitized_query: &self.subgraph_operation.query,
             },
         )
@@ -150,6 +158,7 @@ impl<'ctx> FederationEntityExecutor<'ctx> {
                 FederationEntityResolver {
                     subgraph_operation,
                     shape_id,
+                    key_fields,
                     ..
                 },
             parent_objects,
@@ -179,6 +188,7 @@ impl<'ctx> FederationEntityExecutor<'ctx> {
                     parent_objects,
                     subgraph_headers,
                     subgraph_operation,
+                    key_fields,
                     entities_to_fetch,
                     *shape_id,
                     response_part,
@@ -269,11 +279,40 @@ pub(super) async fn fetch_entities_with_cache<'ctx, R: Runtime>(
     parent_objects: ParentObjectSet,
     subgraph_headers: http::HeaderMap,
     subgraph_operation: &PreparedFederationEntityOperation,
+    key_fields: &[String],
     entities_to_fetch: Vec<EntityToFetch>,
     shape_id: RootFieldsShapeId,
     mut response_part: ResponsePartBuilder<'ctx>,
 ) -> ResponsePartBuilder<'ctx> {
-    let cache_fetch_outcome = super::cache::fetch_entities(ctx, &subgraph_headers, entities_to_fetch).await;
+    let mut cache_fetch_outcome =
+        super::cache::fetch_entities(ctx, &subgraph_headers, key_fields, entities_to_fetch).await;
+
+    if let Some((CacheRefresh::Entities(stale), guard)) =
+        CacheRefresh::Entities(std::mem::take(&mut cache_fetch_outcome.stale)).claim(ctx)
+    {
+        let variables = SubgraphVariables {
+            ctx: ctx.input_value_context(),
+            variables: &subgraph_operation.variables,
+            extra_variables: vec![(
+                &subgraph_operation.entities_variable_name,
+                RepresentationListView(stale.iter().map(|entity| entity.representation.as_ref())),
+            )],
+        };
+        match serde_json::to_vec(&SubgraphGraphqlRequest {
+            query: &subgraph_operation.query,
+            variables,
+        }) {
+            Ok(body) => super::cache::spawn_refresh(
+                ctx,
+                subgraph_headers.clone(),
+                &subgraph_operation.query,
+                body,
+                (CacheRefresh::Entities(stale), guard),
+            ),
+            Err(err) => tracing::error!("Failed to serialize query: {err}"),
+        }
+    }
+
     if cache_fetch_outcome.misses.is_empty() {
         ctx.record_cache_hit();
         let state = response_part.into_seed_state(shape_id);
@@ -325,7 +364,7 @@ pub(super) async fn fetch_entities_with_cache<'ctx, R: Runtime>(
         parent_objects,
         cache_fetch_outcome,
         shape_id,
-        subgraph_default_cache_ttl: ctx.endpoint().config.cache_ttl,
+        subgraph_config: &ctx.endpoint().as_ref().config,
     };
 
     execute_subgraph_request(ctx, subgraph_headers, false, body, response_part, ingester).await
@@ -1,8 +1,7 @@
-use std::{borrow::Cow, time::Duration};
-
 use bytes::Bytes;
 use futures::future::join_all;
 use grafbase_telemetry::graphql::GraphqlResponseStatus;
+use schema::SubgraphConfig;
 use serde::{
     Deserializer,
     de::{DeserializeSeed, IgnoredAny, SeqAccess, Visitor},
@@ -14,11 +13,11 @@ use crate::{
     execution::ExecutionContext,
     prepare::RootFieldsShapeId,
     resolver::graphql::{
-        cache::{CacheFetchEntitiesOutcome, EntityCacheHit, EntityCacheMiss, calculate_cache_ttl},
+        cache::{CacheFetchEntitiesOutcome, EntityCacheHit, EntityCacheMiss, calculate_cache_ttl, put_entry},
         deserialize::{EntitiesDataSeed, EntityErrorPathConverter, GraphqlErrorsSeed, GraphqlResponseSeed},
         request::ResponseIngester,
     },
-    response::{Deserializable, GraphqlError, ParentObjectSet, ResponsePartBuilder, SeedState},
+    response::{Deserializable, ErrorCode, GraphqlError, ParentObjectSet, ResponsePartBuilder, SeedState},
 };
 
 pub(super) fn ingest_hits<'parent>(
@@ -42,7 +41,7 @@ pub(super) struct PartiallyCachedEntitiesIngester<'ctx, R: Runtime> {
     pub parent_objects: ParentObjectSet,
     pub cache_fetch_outcome: CacheFetchEntitiesOutcome,
     pub shape_id: RootFieldsShapeId,
-    pub subgraph_default_cache_ttl: Option<Duration>,
+    pub subgraph_config: &'ctx SubgraphConfig,
 }
 
 impl<R> ResponseIngester for PartiallyCachedEntitiesIngester<'_, R>
@@ -57,12 +56,29 @@ where
         let Self {
             ctx,
             parent_objects,
-            cache_fetch_outcome: CacheFetchEntitiesOutcome { hits, misses },
+            cache_fetch_outcome: CacheFetchEntitiesOutcome { hits, misses, .. },
             shape_id,
-            subgraph_default_cache_ttl,
+            subgraph_config: config,
         } = self;
 
         let http_response = match result {
+            Ok(http_response) if http_response.status().is_success() => http_response,
+            result if misses.iter().any(|miss| miss.stale_data.is_some()) => {
+                let error = match result {
+                    Ok(http_response) => GraphqlError::new(
+                        format!(
+                            "Subgraph responded with status code {}",
+                            http_response.status().as_u16()
+                        ),
+                        ErrorCode::SubgraphRequestError,
+                    ),
+                    Err(err) => err,
+                };
+                return (
+                    None,
+                    ingest_stale(response_part, &parent_objects, shape_id, hits, misses, error),
+                );
+            }
             Ok(http_response) => http_response,
             Err(err) => {
                 response_part.insert_error_updates(&parent_objects, shape_id, [err]);
@@ -118,15 +134,11 @@ where
         };
 
         if let Some(status) = status.filter(|s| s.is_success())
-            && let Some(cache_ttl) = calculate_cache_ttl(status, http_response.headers(), subgraph_default_cache_ttl)
+            && let Some(cache_ttl) = calculate_cache_ttl(status, http_response.headers(), config.cache_ttl)
         {
             let cache = ctx.runtime().entity_cache();
-            join_all(cache_updates.into_iter().map(|(key, value)| async move {
-                cache
-                    .put(&key, Cow::Borrowed(value.get().as_bytes()), cache_ttl)
-                    .await
-                    .inspect_err(|err| tracing::warn!("Failed to write the cache key {key}: {err}"))
-                    .ok();
+            join_all(cache_updates.into_iter().map(|(key, tags, value)| async move {
+                put_entry(cache, config, &key, value.get().as_bytes(), &tags, cache_ttl).await;
             }))
             .await;
         }
@@ -134,11 +146,42 @@ where
     }
 }
 
+/// The subgraph request failed, so we fall back to the stale cache entries still within their
+/// stale-if-error window. Entities without any are treated as failed.
+fn ingest_stale<'ctx>(
+    response_part: ResponsePartBuilder<'ctx>,
+    parent_objects: &ParentObjectSet,
+    shape_id: RootFieldsShapeId,
+    mut hits: Vec<EntityCacheHit>,
+    misses: Vec<EntityCacheMiss>,
+    error: GraphqlError,
+) -> ResponsePartBuilder<'ctx> {
+    tracing::warn!(
+        "Subgraph request failed, serving stale cache entries: {}",
+        error.message
+    );
+
+    let mut failed = Vec::new();
+    for miss in misses {
+        match miss.stale_data {
+            Some(data) => hits.push(EntityCacheHit { id: miss.id, data }),
+            None => failed.push(miss.id),
+        }
+    }
+
+    let state = response_part.into_seed_state(shape_id);
+    ingest_hits(&state, parent_objects, hits);
+    if !failed.is_empty() {
+        state.insert_error_updates(failed.into_iter().map(|id| &parent_objects[id]), [error]);
+    }
+    state.into_response_part()
+}
+
 struct PartiallyCachedEntitiesSeed<'ctx, 'parent, 'state, 'de> {
     state: &'state SeedState<'ctx, 'parent>,
     parent_objects: &'parent ParentObjectSet,
     cache_misses: &'state mut std::vec::IntoIter<EntityCacheMiss>,
-    cache_updates: &'state mut Vec<(String, &'de RawValue)>,
+    cache_updates: &'state mut Vec<(String, Vec<String>, &'de RawValue)>,
 }
 
 impl<'de> DeserializeSeed<'de> for PartiallyCachedEntitiesSeed<'_, '_, '_, 'de> {
@@ -172,7 +215,7 @@ impl<'de> Visitor<'de> for PartiallyCachedEntitiesSeed<'_, '_, '_, 'de> {
 
         let mut result = Ok(());
 
-        for EntityCacheMiss { id, key, .. } in cache_misses.by_ref() {
+        for EntityCacheMiss { id, key, tags, .. } in cache_misses.by_ref() {
             let parent_object = &parent_objects[id];
             let raw_value = match seq.next_element::<&RawValue>() {
                 Ok(Some(value)) => value,
@@ -216,7 +259,7 @@ impl<'de> Visitor<'de> for PartiallyCachedEntitiesSeed<'_, '_, '_, 'de> {
                 break;
             }
 
-            cache_updates.push((key, raw_value));
+            cache_updates.push((key, tags, raw_value));
         }
 
         if cache_misses.len() > 0 {
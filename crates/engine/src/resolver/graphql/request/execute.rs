@@ -1,11 +1,18 @@
-use std::{borrow::Cow, time::Duration};
+use std::{
+    borrow::Cow,
+    pin::pin,
+    time::{Duration, Instant},
+};
 
 use bytes::Bytes;
 use event_queue::{RequestExecution, SubgraphResponseBuilder};
-use futures::Future;
+use futures::{
+    Future,
+    future::{self, Either},
+};
 use grafbase_telemetry::{
-    graphql::GraphqlResponseStatus, otel::tracing_opentelemetry::OpenTelemetrySpanExt as _,
-    span::subgraph::SubgraphHttpRequestSpan,
+    graphql::GraphqlResponseStatus, metrics::SubgraphRequestHedgeAttributes,
+    otel::tracing_opentelemetry::OpenTelemetrySpanExt as _, span::subgraph::SubgraphHttpRequestSpan,
 };
 use headers::HeaderMapExt;
 use runtime::{
@@ -13,12 +20,13 @@ use runtime::{
     fetch::{FetchError, FetchRequest, FetchResult, Fetcher},
     rate_limiting::RateLimitKey,
 };
-use tower::retry::budget::Budget;
+use tower::retry::budget::{Budget, TpsBudget};
 use tracing::{Instrument, Span};
 
 use crate::{
-    EngineOperationContext, Runtime,
-    execution::{ExecutionError, ExecutionResult},
+    Engine, EngineOperationContext, Runtime,
+    engine::hedging::HedgingPolicy,
+    execution::{ExecutionError, ExecutionResult, select_propagated_headers},
     resolver::graphql::SubgraphContext,
     response::{GraphqlError, ResponsePartBuilder},
 };
@@ -39,134 +47,173 @@ pub(crate) async fn execute_subgraph_request<'ctx, R: Runtime>(
     headers: http::HeaderMap,
     is_mutation: bool,
     body: impl Into<Bytes> + Send,
-    response_part: ResponsePartBuilder<'ctx>,
+    mut response_part: ResponsePartBuilder<'ctx>,
     ingester: impl ResponseIngester,
 ) -> ResponsePartBuilder<'ctx> {
     let subgraph = ctx.endpoint();
 
-    let result = async {
-        let ReqwestParts {
-            url,
-            method,
-            mut headers,
-        } = ctx
-            .extensions()
-            .on_graphql_subgraph_request(
-                EngineOperationContext::from(&ctx.ctx),
-                ctx.subgraph,
-                ReqwestParts {
-                    url: Cow::Borrowed(subgraph.url()),
-                    method: http::Method::POST,
-                    headers,
-                },
-            )
-            .await?;
-
-        let body: Bytes = body.into();
-
-        headers.typed_insert(headers::ContentType::json());
-        headers.typed_insert(headers::ContentLength(body.len() as u64));
-
-        headers.insert(
-            http::header::ACCEPT,
-            http::HeaderValue::from_static(
-                "application/graphql-response+json; charset=utf-8, application/json; charset=utf-8",
-            ),
-        );
-        headers.insert(http::header::CONNECTION, http::HeaderValue::from_static("keep-alive"));
-
-        let request = FetchRequest {
-            subgraph_id: subgraph.id,
-            url,
-            is_mutation,
-            headers,
-            method,
-            body,
-            timeout: subgraph.config.timeout,
-        };
-
-        ctx.record_request_size(request.body.len());
-
-        let fetcher = ctx.runtime().fetcher();
-
-        let fetch_result = retrying_fetch(ctx, || {
-            let mut request = request.clone();
-            let subgraph_name = subgraph.name().to_string();
-
-            async move {
-                let http_span = SubgraphHttpRequestSpan::new(request.url.as_ref(), &http::Method::POST);
-
-                grafbase_telemetry::otel::opentelemetry::global::get_text_map_propagator(|propagator| {
-                    let context = http_span.context();
-                    propagator.inject_context(
-                        &context,
-                        &mut grafbase_telemetry::http::HeaderInjector(&mut request.headers),
-                    );
-                });
+    let result = match prepare_fetch_request(ctx, headers, is_mutation, body.into()).await {
+        Ok(request) => fetch(ctx, request).await,
+        Err(err) => Err(err),
+    };
 
-                let (fetch_result, mut info) = fetcher.fetch(request).instrument(http_span.span()).await;
+    match result {
+        Ok(response) => {
+            response_part
+                .propagated_headers
+                .collect(subgraph.response_header_rules(), response.headers());
 
-                let result = fetch_result.and_then(|mut response| {
-                    tracing::debug!("Received response:\n{}", String::from_utf8_lossy(response.body()));
-                    // For those status codes we want to retry the request, so marking the request as
-                    // failed.
-                    let status = response.status();
+            let (status, response_part) = ingester.ingest(Ok(response), response_part).await;
 
-                    if let Some(ref mut info) = info {
-                        info.status(status);
+            if let Some(status) = status {
+                ctx.set_graphql_response_status(status);
+            } else {
+                ctx.set_as_invalid_response();
+            }
 
-                        // Performance optimization: Instead of cloning the entire HeaderMap,
-                        // we extract only the cache-related headers (Cache-Control and Age)
-                        // that are needed by the caching logic. This avoids an expensive clone
-                        // of all headers while still allowing telemetry/hooks to receive the
-                        // complete header information.
-                        let cache_control = response.headers().typed_get::<headers::CacheControl>();
-                        let age = response.headers().typed_get::<headers::Age>();
+            response_part
+        }
+        Err(err) => {
+            let (_, response_part) = ingester.ingest(Err(err), response_part).await;
+            response_part
+        }
+    }
+}
 
-                        // Move all headers to the hooks
-                        info.headers(std::mem::take(response.headers_mut()));
+/// Sends the request to the subgraph with the rate limit, circuit breaker, retry budget and
+/// hedging policy of the subgraph, recording the request metrics.
+pub(crate) async fn fetch<'ctx, R: Runtime>(
+    ctx: &mut SubgraphContext<'ctx, R>,
+    request: FetchRequest<'ctx>,
+) -> Result<http::Response<Bytes>, GraphqlError> {
+    let subgraph = ctx.endpoint();
 
-                        // Put back cache-related headers for cache control logic
-                        if let Some(cache_control) = cache_control {
-                            response.headers_mut().typed_insert(cache_control);
-                        }
+    ctx.record_request_size(request.body.len());
 
-                        if let Some(age) = age {
-                            response.headers_mut().typed_insert(age);
-                        }
-                    }
+    let fetcher = ctx.runtime().fetcher();
 
-                    if status.is_server_error() {
-                        Err(FetchError::InvalidStatusCode(status, Some(response)))
-                    } else if status == http::StatusCode::TOO_MANY_REQUESTS {
-                        Err(FetchError::InvalidStatusCode(status, None))
-                    } else {
-                        Ok(response)
+    let send = |mut request: FetchRequest<'ctx>| {
+        let subgraph_name = subgraph.name().to_string();
+
+        async move {
+            let http_span = SubgraphHttpRequestSpan::new(request.url.as_ref(), &http::Method::POST);
+
+            grafbase_telemetry::otel::opentelemetry::global::get_text_map_propagator(|propagator| {
+                let context = http_span.context();
+                propagator.inject_context(
+                    &context,
+                    &mut grafbase_telemetry::http::HeaderInjector(&mut request.headers),
+                );
+            });
+
+            let (fetch_result, mut info) = fetcher.fetch(request).instrument(http_span.span()).await;
+
+            let result = fetch_result.and_then(|mut response| {
+                tracing::debug!("Received response:\n{}", String::from_utf8_lossy(response.body()));
+                // For those status codes we want to retry the request, so marking the request as
+                // failed.
+                let status = response.status();
+
+                if let Some(ref mut info) = info {
+                    info.status(status);
+
+                    // Performance optimization: Instead of cloning the entire HeaderMap,
+                    // we extract only the cache-related headers (Cache-Control and Age)
+                    // that are needed by the caching logic. This avoids an expensive clone
+                    // of all headers while still allowing telemetry/hooks to receive the
+                    // complete header information.
+                    let cache_control = response.headers().typed_get::<headers::CacheControl>();
+                    let age = response.headers().typed_get::<headers::Age>();
+                    let propagated_headers = select_propagated_headers(subgraph, response.headers());
+
+                    // Move all headers to the hooks
+                    info.headers(std::mem::take(response.headers_mut()));
+
+                    // Put back the headers propagated to the client response
+                    response.headers_mut().extend(propagated_headers);
+
+                    // Put back cache-related headers for cache control logic
+                    if let Some(cache_control) = cache_control {
+                        response.headers_mut().typed_insert(cache_control);
                     }
-                });
 
-                match result {
-                    Ok(ref response) => {
-                        http_span.record_http_status_code(response.status());
+                    if let Some(age) = age {
+                        response.headers_mut().typed_insert(age);
                     }
-                    Err(ref err) => {
-                        tracing::error!("Request to subgraph {} failed with: {err}", subgraph_name);
-                        http_span.set_as_http_error(err.as_invalid_status_code());
-                        // Only clear info for non-status-code errors (e.g., network errors)
-                        // For status code errors, we want to preserve the response info
-                        if !matches!(err, FetchError::InvalidStatusCode(_, _)) {
-                            info = None;
-                        }
+                }
+
+                if status.is_server_error() {
+                    Err(FetchError::InvalidStatusCode(status, Some(response)))
+                } else if status == http::StatusCode::TOO_MANY_REQUESTS {
+                    Err(FetchError::InvalidStatusCode(status, None))
+                } else {
+                    Ok(response)
+                }
+            });
+
+            match result {
+                Ok(ref response) => {
+                    http_span.record_http_status_code(response.status());
+                }
+                Err(ref err) => {
+                    tracing::error!("Request to subgraph {} failed with: {err}", subgraph_name);
+                    http_span.set_as_http_error(err.as_invalid_status_code());
+                    // Only clear info for non-status-code errors (e.g., network errors)
+                    // For status code errors, we want to preserve the response info
+                    if !matches!(err, FetchError::InvalidStatusCode(_, _)) {
+                        info = None;
                     }
-                };
+                }
+            };
 
-                (result, info)
-            }
-        })
-        .await;
+            (result, info)
+        }
+    };
 
-        match fetch_result {
-            Ok(http_response) => {
+    let hedging = ctx
+        .hedging_policy()
+        .zip(ctx.retry_budget)
+        .map(|(policy, retry_budget)| Hedging {
+            policy,
+            retry_budget,
+            engine: ctx.execution_context().engine,
+            subgraph_name: subgraph.name(),
+        });
+
+    let fetch_result = retrying_fetch(ctx, || {
+        hedged_fetch(
+            hedging.as_ref(),
+            || send(request.clone()),
+            || {
+                send(FetchRequest {
+                    hedge: true,
+                    ..request.clone()
+                })
+            },
+        )
+    })
+    .await;
+
+    match fetch_result {
+        Ok(http_response) => {
+            ctx.record_http_response(&http_response);
+            // If the status code isn't a success as this point it means it's either a client error or
+            // we've exhausted our retry budget for server errors.
+            if !http_response.status().is_success() {
+                tracing::debug!(
+                    "Subgraph request failed with status code: {}\n{}",
+                    http_response.status().as_u16(),
+                    String::from_utf8_lossy(http_response.body())
+                );
+            }
+            Ok(http_response)
+        }
+        Err(err) => match err {
+            ExecutionError::Fetch {
+                error: FetchError::InvalidStatusCode(code, Some(http_response)),
+                ..
+            } => {
+                ctx.set_as_http_error(Some(code));
                 ctx.record_http_response(&http_response);
                 // If the status code isn't a success as this point it means it's either a client error or
                 // we've exhausted our retry budget for server errors.
@@ -177,53 +224,65 @@ pub(crate) async fn execute_subgraph_request<'ctx, R: Runtime>(
                         String::from_utf8_lossy(http_response.body())
                     );
                 }
-                Ok((http_response, ctx))
+                Ok(http_response)
             }
-            Err(err) => match err {
-                ExecutionError::Fetch {
-                    error: FetchError::InvalidStatusCode(code, Some(http_response)),
-                    ..
-                } => {
-                    ctx.set_as_http_error(Some(code));
-                    ctx.record_http_response(&http_response);
-                    // If the status code isn't a success as this point it means it's either a client error or
-                    // we've exhausted our retry budget for server errors.
-                    if !http_response.status().is_success() {
-                        tracing::debug!(
-                            "Subgraph request failed with status code: {}\n{}",
-                            http_response.status().as_u16(),
-                            String::from_utf8_lossy(http_response.body())
-                        );
-                    }
-                    Ok((http_response, ctx))
-                }
-                _ => {
-                    ctx.set_as_http_error(err.as_fetch_invalid_status_code());
-                    Err(err.into())
-                }
-            },
-        }
-    };
-
-    match result.await {
-        Ok((response, ctx)) => {
-            let (status, response_part) = ingester.ingest(Ok(response), response_part).await;
-
-            if let Some(status) = status {
-                ctx.set_graphql_response_status(status);
-            } else {
-                ctx.set_as_invalid_response();
+            _ => {
+                ctx.set_as_http_error(err.as_fetch_invalid_status_code());
+                Err(err.into())
             }
-
-            response_part
-        }
-        Err(err) => {
-            let (_, response_part) = ingester.ingest(Err(err), response_part).await;
-            response_part
-        }
+        },
     }
 }
 
+/// Builds the HTTP request sent to the subgraph, after the subgraph request hooks.
+pub(crate) async fn prepare_fetch_request<'ctx, R: Runtime>(
+    ctx: &SubgraphContext<'ctx, R>,
+    headers: http::HeaderMap,
+    is_mutation: bool,
+    body: Bytes,
+) -> Result<FetchRequest<'ctx>, GraphqlError> {
+    let subgraph = ctx.endpoint();
+
+    let ReqwestParts {
+        url,
+        method,
+        mut headers,
+    } = ctx
+        .extensions()
+        .on_graphql_subgraph_request(
+            EngineOperationContext::from(&ctx.ctx),
+            ctx.subgraph,
+            ReqwestParts {
+                url: Cow::Borrowed(subgraph.url()),
+                method: http::Method::POST,
+                headers,
+            },
+        )
+        .await?;
+
+    headers.typed_insert(headers::ContentType::json());
+    headers.typed_insert(headers::ContentLength(body.len() as u64));
+
+    headers.insert(
+        http::header::ACCEPT,
+        http::HeaderValue::from_static(
+            "application/graphql-response+json; charset=utf-8, application/json; charset=utf-8",
+        ),
+    );
+    headers.insert(http::header::CONNECTION, http::HeaderValue::from_static("keep-alive"));
+
+    Ok(FetchRequest {
+        subgraph_id: subgraph.id,
+        url,
+        is_mutation,
+        headers,
+        method,
+        body,
+        timeout: subgraph.config.timeout,
+        hedge: false,
+    })
+}
+
 pub(crate) async fn retrying_fetch<R: Runtime, F, T>(
     ctx: &mut SubgraphContext<'_, R>,
     fetch: impl Fn() -> F + Send + Sync,
@@ -242,6 +301,14 @@ where
 
     loop {
         match fetch_result {
+            // No point in retrying, the request wasn't even sent.
+            Err(err @ ExecutionError::CircuitBreakerOpen { .. }) => return Err(err),
+            Err(
+                err @ ExecutionError::Fetch {
+                    error: FetchError::ConcurrencyLimitRejected(_),
+                    ..
+                },
+            ) => return Err(err),
             Ok(response) => {
                 if let Some(b) = ctx.retry_budget() {
                     b.deposit()
@@ -289,10 +356,25 @@ where
             ctx.push_request_execution(RequestExecution::RateLimited);
         })?;
 
+    let circuit_breaker = ctx.execution_context().engine.get_circuit_breaker(ctx.endpoint().id);
+    if circuit_breaker.is_some_and(|breaker| !breaker.try_acquire()) {
+        return Err(ExecutionError::CircuitBreakerOpen {
+            subgraph_name: ctx.endpoint().name().to_string(),
+        });
+    }
+
     ctx.increment_inflight_requests();
     let (result, info) = fetch().await;
     ctx.decrement_inflight_requests();
 
+    // A rejection by the concurrency limit happens before the request is sent, so it doesn't
+    // reflect the subgraph health.
+    if let Some(breaker) = circuit_breaker
+        && !result.as_ref().is_err_and(FetchError::is_concurrency_limit_rejection)
+    {
+        breaker.record(result.is_ok());
+    }
+
     match info {
         Some(info) => ctx.push_request_execution(RequestExecution::Response(info.build())),
         None if result.is_err() => ctx.push_request_execution(RequestExecution::RequestError),
@@ -304,3 +386,47 @@ where
         error,
     })
 }
+
+struct Hedging<'ctx, R: Runtime> {
+    policy: &'ctx HedgingPolicy,
+    retry_budget: &'ctx TpsBudget,
+    engine: &'ctx Engine<R>,
+    subgraph_name: &'ctx str,
+}
+
+/// Sends a second identical request if the first one takes longer than the hedging delay, and
+/// keeps whichever response arrives first. Hedges are withdrawn from the retry budget.
+async fn hedged_fetch<R: Runtime, F: Future + Send>(
+    hedging: Option<&Hedging<'_, R>>,
+    first: impl FnOnce() -> F + Send,
+    hedge: impl FnOnce() -> F + Send,
+) -> F::Output {
+    let Some(hedging) = hedging else {
+        return first().await;
+    };
+
+    let start = Instant::now();
+    let mut first = pin!(first());
+    let delay = pin!(hedging.engine.runtime.sleep(hedging.policy.delay()));
+
+    let output = match future::select(first.as_mut(), delay).await {
+        Either::Left((output, _)) => output,
+        Either::Right(((), _)) if hedging.retry_budget.withdraw() => {
+            hedging
+                .engine
+                .runtime
+                .metrics()
+                .record_subgraph_hedge(SubgraphRequestHedgeAttributes {
+                    name: hedging.subgraph_name.to_string(),
+                });
+
+            let hedge = pin!(hedge());
+            future::select(first, hedge).await.factor_first().0
+        }
+        Either::Right(((), _)) => first.await,
+    };
+
+    hedging.policy.record_latency(start.elapsed());
+
+    output
+}
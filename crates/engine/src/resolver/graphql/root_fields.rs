@@ -1,16 +1,15 @@
-use std::{borrow::Cow, time::Duration};
-
 use bytes::Bytes;
 use grafbase_telemetry::graphql::OperationType;
 use grafbase_telemetry::{graphql::GraphqlResponseStatus, span::subgraph::SubgraphRequestSpanBuilder};
+use itertools::Itertools as _;
 use operation::OperationContext;
-use schema::{GraphqlRootFieldResolverDefinition, GraphqlSubgraphId};
+use schema::{GraphqlRootFieldResolverDefinition, GraphqlSubgraphId, SubgraphConfig};
 use tracing::Instrument;
 use walker::Walk;
 
 use super::{
     SubgraphContext,
-    cache::{ResponseCacheHit, ResponseCacheMiss},
+    cache::{CacheRefresh, ResponseCacheHit, ResponseCacheMiss, put_entry},
     deserialize::{GraphqlErrorsSeed, GraphqlResponseSeed},
     request::{PreparedGraphqlOperation, ResponseIngester, SubgraphVariables, execute_subgraph_request},
 };
@@ -27,6 +26,9 @@ pub(crate) struct GraphqlResolver {
     pub subgraph_id: GraphqlSubgraphId,
     pub subgraph_operation: PreparedGraphqlOperation,
     pub ty: OperationType,
+    /// Type tags of the root fields output, used to invalidate cached responses. Entities within
+    /// the response aren't known, so invalidating a single entity keeps the response cached.
+    pub cache_tags: Vec<String>,
 }
 
 impl GraphqlResolver {
@@ -61,10 +63,19 @@ impl GraphqlResolver {
                     PlanError::Internal
                 },
             )?;
+        let cache_tags = selection_set
+            .fields()
+            .map(|field| field.definition().ty().definition())
+            .filter(|definition| definition.is_composite_type())
+            .map(|definition| runtime::entity_cache::type_tag(definition.name()))
+            .unique()
+            .collect();
+
         Ok(Self {
             subgraph_id: definition.subgraph().id,
             subgraph_operation,
             ty,
+            cache_tags,
         })
     }
 
@@ -75,7 +86,7 @@ impl GraphqlResolver {
             endpoint,
             SubgraphRequestSpanBuilder {
                 subgraph_name: endpoint.name(),
-                operation_type: self.subgraph_operation.ty.as_str(),
+                operation_type: self.subgraph_operation.ty,
                 sanitized_query: &self.subgraph_operation.query,
             },
         )
@@ -128,7 +139,9 @@ impl GraphqlResolver {
                     parent_objects,
                     subgraph_headers,
                     self.ty.is_mutation(),
+                    &self.subgraph_operation.query,
                     body,
+                    &self.cache_tags,
                     plan.shape().id,
                     response_part,
                 )
@@ -210,24 +223,37 @@ async fn fetch_response_with_cache<'ctx, R: Runtime>(
     parent_objects: ParentObjectSet,
     subgraph_headers: http::HeaderMap,
     is_mutation: bool,
+    query: &str,
     body: Vec<u8>,
+    cache_tags: &'ctx [String],
     shape_id: RootFieldsShapeId,
     response_part: ResponsePartBuilder<'ctx>,
 ) -> ResponsePartBuilder<'ctx> {
     match super::cache::fetch_response(ctx, &subgraph_headers, &body).await {
-        Ok(ResponseCacheHit { data }) => {
+        Ok(ResponseCacheHit { data, key }) => {
             ctx.record_cache_hit();
+            if let Some(refresh) = key.filter(|_| !is_mutation).and_then(|key| {
+                CacheRefresh::Response {
+                    key,
+                    tags: cache_tags.to_vec(),
+                }
+                .claim(ctx)
+            }) {
+                super::cache::spawn_refresh(ctx, subgraph_headers, query, body, refresh);
+            }
             let (_, response_part) =
                 ingest_graphql_data(response_part, &parent_objects, shape_id, Deserializable::Json(&data));
             response_part
         }
-        Err(ResponseCacheMiss { key }) => {
+        Err(ResponseCacheMiss { key, stale_data }) => {
             ctx.record_cache_miss();
             let ingester = GraphqlWithCachePutIngester {
                 ctx: ctx.execution_context(),
                 parent_objects,
-                subgraph_default_cache_ttl: ctx.endpoint().config.cache_ttl,
+                subgraph_config: &ctx.endpoint().as_ref().config,
                 cache_key: key,
+                cache_tags,
+                stale_data,
                 shape_id,
             };
 
@@ -240,8 +266,11 @@ struct GraphqlWithCachePutIngester<'ctx, R: Runtime> {
     ctx: ExecutionContext<'ctx, R>,
     parent_objects: ParentObjectSet,
     shape_id: RootFieldsShapeId,
-    subgraph_default_cache_ttl: Option<Duration>,
+    subgraph_config: &'ctx SubgraphConfig,
     cache_key: String,
+    cache_tags: &'ctx [String],
+    /// Served if the subgraph request fails.
+    stale_data: Option<Bytes>,
 }
 
 impl<R> ResponseIngester for GraphqlWithCachePutIngester<'_, R>
@@ -257,13 +286,32 @@ where
             ctx,
             shape_id,
             parent_objects,
-            subgraph_default_cache_ttl,
+            subgraph_config,
             cache_key,
+            cache_tags,
+            stale_data,
         } = self;
 
-        let http_response = match result {
-            Ok(http_response) => http_response,
-            Err(err) => {
+        let http_response = match (result, stale_data) {
+            (Ok(http_response), _) if http_response.status().is_success() => http_response,
+            (result, Some(stale_data)) => {
+                match result {
+                    Ok(http_response) => tracing::warn!(
+                        "Subgraph responded with status code {}, serving stale cache entry",
+                        http_response.status().as_u16()
+                    ),
+                    Err(err) => tracing::warn!("Subgraph request failed, serving stale cache entry: {}", err.message),
+                }
+                let (_, response_part) = ingest_graphql_data(
+                    response_part,
+                    &parent_objects,
+                    shape_id,
+                    Deserializable::Json(&stale_data),
+                );
+                return (None, response_part);
+            }
+            (Ok(http_response), None) => http_response,
+            (Err(err), None) => {
                 response_part.insert_error_updates(&parent_objects, shape_id, [err]);
                 return (None, response_part);
             }
@@ -278,16 +326,19 @@ where
 
         if let Some(status) = status.filter(|s| s.is_success()) {
             let cache_ttl =
-                super::cache::calculate_cache_ttl(status, http_response.headers(), subgraph_default_cache_ttl);
+                super::cache::calculate_cache_ttl(status, http_response.headers(), subgraph_config.cache_ttl);
             if let Some(cache_ttl) = cache_ttl {
                 // We could probably put this call into the background at some point, but for
                 // simplicities sake I am not going to do that just now.
-                ctx.runtime()
-                    .entity_cache()
-                    .put(&cache_key, Cow::Borrowed(http_response.body().as_ref()), cache_ttl)
-                    .await
-                    .inspect_err(|err| tracing::warn!("Failed to write the cache key {cache_key}: {err}"))
-                    .ok();
+                put_entry(
+                    ctx.runtime().entity_cache(),
+                    subgraph_config,
+                    &cache_key,
+                    http_response.body().as_ref(),
+                    cache_tags,
+                    cache_ttl,
+                )
+                .await;
             }
         }
 
@@ -0,0 +1,149 @@
+use operation::{
+    ComplexityCost, Field, OperationContext, ResponseKey, Selection, SelectionSet, Variables, field_base_cost,
+    operation_base_cost,
+};
+use schema::{CompositeType, ObjectDefinitionId};
+
+use super::{ExecutedResponse, Response, ResponseData, ResponseObject, ResponseValue};
+
+impl Response {
+    /// Cost of the operation computed like the estimated one, but with the actual size of the
+    /// lists in the response rather than the assumed ones. A field resolved to null only costs
+    /// its own weight and arguments.
+    pub(crate) fn actual_cost(&self) -> Option<ComplexityCost> {
+        match self {
+            Response::Executed(resp) => Some(resp.actual_cost()),
+            _ => None,
+        }
+    }
+}
+
+impl ExecutedResponse {
+    fn actual_cost(&self) -> ComplexityCost {
+        let operation = &self.operation.cached.operation;
+        let base_cost = operation_base_cost(operation.attributes.ty);
+
+        let Some(data) = &self.data else {
+            return ComplexityCost(base_cost);
+        };
+
+        let ctx = OperationContext {
+            schema: &self.schema,
+            operation,
+        };
+        let walker = CostWalker {
+            data,
+            variables: &self.operation.variables,
+        };
+
+        ComplexityCost(base_cost + walker.object_cost(&[ctx.root_selection_set()], data.root_object()))
+    }
+}
+
+struct CostWalker<'a> {
+    data: &'a ResponseData,
+    variables: &'a Variables,
+}
+
+/// Fields sharing the same response key are merged into a single response field, so their
+/// selection sets are walked together.
+struct MergedField<'op> {
+    response_key: ResponseKey,
+    field: Field<'op>,
+    selection_sets: Vec<SelectionSet<'op>>,
+}
+
+impl CostWalker<'_> {
+    fn object_cost(&self, selection_sets: &[SelectionSet<'_>], object: &ResponseObject) -> usize {
+        let mut fields = Vec::<MergedField<'_>>::new();
+        for selection_set in selection_sets {
+            collect_fields(*selection_set, object.definition_id, &mut fields);
+        }
+
+        fields
+            .into_iter()
+            .map(|merged| {
+                let Some(value) = object
+                    .fields()
+                    .find(|field| field.key.query_position.is_some() && field.key.response_key == merged.response_key)
+                    .map(|field| &field.value)
+                else {
+                    // Skipped or not applicable to this object.
+                    return 0;
+                };
+
+                match merged.field {
+                    Field::Data(field) => {
+                        let base_cost = field_base_cost(field, self.variables);
+                        self.value_cost(base_cost, &merged.selection_sets, value)
+                    }
+                    Field::Typename(_) => 1,
+                }
+            })
+            .sum()
+    }
+
+    fn value_cost(&self, base_cost: usize, selection_sets: &[SelectionSet<'_>], value: &ResponseValue) -> usize {
+        match value {
+            ResponseValue::Object { id } => base_cost + self.object_cost(selection_sets, &self.data[*id]),
+            ResponseValue::List { id } => self.data[*id]
+                .iter()
+                .map(|item| self.value_cost(base_cost, selection_sets, item))
+                .sum(),
+            ResponseValue::IntList { id } => self.data[*id].len() * base_cost,
+            ResponseValue::FloatList { id } => self.data[*id].len() * base_cost,
+            ResponseValue::Inaccessible { id } => self.value_cost(base_cost, selection_sets, &self.data[*id]),
+            ResponseValue::Null
+            | ResponseValue::Boolean { .. }
+            | ResponseValue::Int { .. }
+            | ResponseValue::Float { .. }
+            | ResponseValue::String { .. }
+            | ResponseValue::StringId { .. }
+            | ResponseValue::Unexpected
+            | ResponseValue::I64 { .. }
+            | ResponseValue::U64 { .. }
+            | ResponseValue::Map { .. } => base_cost,
+        }
+    }
+}
+
+fn collect_fields<'op>(
+    selection_set: SelectionSet<'op>,
+    object_id: Option<ObjectDefinitionId>,
+    fields: &mut Vec<MergedField<'op>>,
+) {
+    for selection in selection_set {
+        match selection {
+            Selection::Field(field) => {
+                let response_key = field.response_key();
+                if let Some(merged) = fields.iter_mut().find(|merged| merged.response_key == response_key) {
+                    merged.selection_sets.push(field.selection_set());
+                } else {
+                    fields.push(MergedField {
+                        response_key,
+                        field,
+                        selection_sets: vec![field.selection_set()],
+                    });
+                }
+            }
+            Selection::FragmentSpread(spread) => {
+                let fragment = spread.fragment();
+                if applies_to(Some(fragment.type_condition()), object_id) {
+                    collect_fields(fragment.selection_set(), object_id, fields);
+                }
+            }
+            Selection::InlineFragment(fragment) => {
+                if applies_to(fragment.type_condition(), object_id) {
+                    collect_fields(fragment.selection_set(), object_id, fields);
+                }
+            }
+        }
+    }
+}
+
+fn applies_to(type_condition: Option<CompositeType<'_>>, object_id: Option<ObjectDefinitionId>) -> bool {
+    match (type_condition, object_id) {
+        (Some(type_condition), Some(object_id)) => type_condition.possible_type_ids().binary_search(&object_id).is_ok(),
+        _ => true,
+    }
+}
@@ -4,8 +4,9 @@ use serde::Serialize;
 use walker::Walk;
 
 use crate::{
+    execution::{PropagatedHeaders, RateLimitHeaders, ResponseCacheControl},
     mcp::McpResponseExtension,
-    prepare::{Executable, OperationPlanContext, PlanId, PreparedOperation},
+    prepare::{Executable, OperationPlanContext, PlanId, PreparedOperation, RequiredFieldSet},
     resolver::{
         ExtensionResolver, FederationEntityResolver, FieldResolverExtension, GraphqlResolver, LookupProxiedResolver,
         Resolver, SelectionSetExtensionResolver,
@@ -19,6 +20,12 @@ pub(crate) struct ResponseExtensions {
     pub grafbase: Option<GrafbaseResponseExtension>,
     #[serde(skip)]
     pub mcp: Option<McpResponseExtension>,
+    #[serde(skip)]
+    pub cache_control: Option<ResponseCacheControl>,
+    #[serde(skip)]
+    pub rate_limit: Option<RateLimitHeaders>,
+    #[serde(skip)]
+    pub propagated_headers: Option<PropagatedHeaders>,
 }
 
 impl ResponseExtensions {
@@ -35,6 +42,9 @@ impl ResponseExtensions {
         Self {
             grafbase,
             mcp: self.mcp.or(other.mcp),
+            cache_control: self.cache_control.or(other.cache_control),
+            rate_limit: self.rate_limit.or(other.rate_limit),
+            propagated_headers: self.propagated_headers.or(other.propagated_headers),
         }
     }
 }
@@ -46,6 +56,8 @@ pub(crate) struct GrafbaseResponseExtension {
     trace_id: Option<TraceId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     query_plan: Option<QueryPlan>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost: Option<OperationCost>,
 }
 
 impl GrafbaseResponseExtension {
@@ -53,6 +65,7 @@ impl GrafbaseResponseExtension {
         Self {
             trace_id: self.trace_id.or(other.trace_id),
             query_plan: self.query_plan.or(other.query_plan),
+            cost: self.cost.or(other.cost),
         }
     }
 }
@@ -63,6 +76,11 @@ impl GrafbaseResponseExtension {
         self
     }
 
+    pub fn with_cost(mut self, estimated: usize, actual: usize) -> Self {
+        self.cost = Some(OperationCost { estimated, actual });
+        self
+    }
+
     pub fn with_query_plan(mut self, schema: &Schema, prepared_operation: &PreparedOperation) -> Self {
         let mut nodes = Vec::with_capacity(prepared_operation.plan.plans.len());
         // at least one edge.
@@ -74,6 +92,12 @@ impl GrafbaseResponseExtension {
             plan: &prepared_operation.plan,
         };
 
+        let mut details = schema
+            .config
+            .response_extension
+            .include_query_plan_details
+            .then(|| Vec::with_capacity(prepared_operation.plan.plans.len()));
+
         for plan in ctx.plans() {
             nodes.push((ctx, &plan.resolver).into());
             for child in plan.children() {
@@ -81,9 +105,18 @@ impl GrafbaseResponseExtension {
                     edges.push((usize::from(plan.id), usize::from(child.id)))
                 }
             }
+            if let Some(details) = &mut details {
+                let mut required_fields = String::new();
+                write_required_fields(&mut required_fields, plan.required_fields());
+
+                details.push(QueryPlanNodeDetails {
+                    entity: plan.entity_definition().name().to_string(),
+                    required_fields,
+                });
+            }
         }
 
-        self.query_plan = Some(QueryPlan { nodes, edges });
+        self.query_plan = Some(QueryPlan { nodes, edges, details });
         self
     }
 }
@@ -99,12 +132,47 @@ where
     }
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OperationCost {
+    estimated: usize,
+    actual: usize,
+}
+
 #[derive(Debug, Serialize, id_derives::IndexedFields)]
 #[serde(rename_all = "camelCase")]
 struct QueryPlan {
     #[indexed_by(PlanId)]
     nodes: Vec<QueryPlanNode>,
     edges: Vec<(usize, usize)>,
+    /// In the same order as the nodes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<Vec<QueryPlanNodeDetails>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryPlanNodeDetails {
+    /// Root type for root fields, otherwise the entity resolved by the subgraph.
+    entity: String,
+    /// Retrieved by the parent nodes, entity keys and `@requires` fields.
+    required_fields: String,
+}
+
+fn write_required_fields(out: &mut String, fields: RequiredFieldSet<'_>) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(' ');
+        }
+        out.push_str(field.data_field().definition().name());
+
+        let subselection = field.subselection();
+        if !subselection.is_empty() {
+            out.push_str(" { ");
+            write_required_fields(out, subselection);
+            out.push_str(" }");
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
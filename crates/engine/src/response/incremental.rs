@@ -0,0 +1,646 @@
+//! Incremental delivery of @defer and @stream following the format of the incremental delivery
+//! RFC as implemented by graphql-js 17.0.0-alpha.2 and most clients today:
+//!
+//! ```json
+//! {"data": {..}, "hasNext": true}
+//! {"incremental": [{"data": {..}, "path": [..], "label": ".."}], "hasNext": true}
+//! {"incremental": [{"items": [..], "path": [.., 2]}], "hasNext": false}
+//! ```
+//!
+//! The operation is executed once in two phases, see `IncrementalPlan`. Payloads are projected
+//! from snapshots of the serialized response through the selection sets: the initial one
+//! excludes active deferred fragments and streamed items beyond the initial count. During the
+//! subsequent phase, a payload is sent whenever an executed plan completes some of them, so once
+//! all of their fields are present. Fragments with type conditions nested within them that don't
+//! match the object never appear complete and are only delivered with the last payload.
+use grafbase_telemetry::graphql::{GraphqlOperationAttributes, GraphqlResponseStatus};
+use operation::{ExecutableDirectiveId, InputValueContext, OperationContext, Selection, SelectionSet, Variables};
+use serde::Deserialize as _;
+use serde_json::{Map, Value};
+use walker::Walk as _;
+
+use super::{ErrorCodeCounter, Response, ResponseExtensions};
+
+pub(crate) struct IncrementalResponse {
+    pub(super) payload: Map<String, Value>,
+    pub(super) operation_attributes: GraphqlOperationAttributes,
+    pub(super) status: GraphqlResponseStatus,
+    pub(super) error_code_counter: ErrorCodeCounter,
+    pub(super) extensions: ResponseExtensions,
+}
+
+/// Splits the response of an operation with @defer and/or @stream into the initial and
+/// subsequent payloads.
+pub(crate) struct IncrementalPayloads<'a> {
+    operation_ctx: OperationContext<'a>,
+    variables: &'a Variables,
+    deferred: Vec<DeferredFragment<'a>>,
+    streamed: Vec<StreamedList<'a>>,
+    /// Number of errors of the response already assigned to a payload or pending.
+    delivered_errors_count: usize,
+    /// Errors within data that wasn't delivered yet.
+    pending_errors: Vec<Value>,
+}
+
+/// An active deferred fragment within the initial payload.
+struct DeferredFragment<'a> {
+    path: Vec<Value>,
+    label: Option<String>,
+    selection_set: SelectionSet<'a>,
+}
+
+/// An active streamed list within the initial payload with items beyond the initial count.
+struct StreamedList<'a> {
+    path: Vec<Value>,
+    label: Option<String>,
+    initial_count: usize,
+    selection_set: SelectionSet<'a>,
+}
+
+struct IncrementalItem {
+    path: Vec<Value>,
+    label: Option<String>,
+    data: IncrementalData,
+    errors: Vec<Value>,
+}
+
+enum IncrementalData {
+    Deferred(Map<String, Value>),
+    Streamed(Vec<Value>),
+}
+
+#[derive(Clone, Copy)]
+enum Projection {
+    /// Active deferred fragments and streamed items beyond the initial count are excluded.
+    Initial,
+    /// Nested active deferred fragments are excluded, lists are complete.
+    Deferred,
+    /// Everything is included.
+    Complete,
+}
+
+impl<'a> IncrementalPayloads<'a> {
+    pub(crate) fn new(operation_ctx: OperationContext<'a>, variables: &'a Variables) -> Self {
+        Self {
+            operation_ctx,
+            variables,
+            deferred: Vec::new(),
+            streamed: Vec::new(),
+            delivered_errors_count: 0,
+            pending_errors: Vec::new(),
+        }
+    }
+
+    /// Whether a subsequent payload is expected after the initial one.
+    pub(crate) fn has_next(&self) -> bool {
+        !self.deferred.is_empty() || !self.streamed.is_empty()
+    }
+
+    /// Builds the initial payload from the response of the initial phase of the execution.
+    pub(crate) fn initial(&mut self, response: &Response) -> Response {
+        let Response::Executed(executed) = response else {
+            unreachable!("The initial response is a snapshot of the execution");
+        };
+        let operation_attributes = executed.operation_attributes.clone();
+        let status = executed.graphql_status();
+        let mut error_code_counter = ErrorCodeCounter::default();
+        error_code_counter.add(response.error_code_counter());
+
+        let mut payload = match serde_json::to_value(response) {
+            Ok(Value::Object(payload)) => payload,
+            Ok(_) | Err(_) => {
+                tracing::error!("Failed to serialize the initial incremental payload");
+                Map::new()
+            }
+        };
+
+        if let Some(Value::Object(data)) = payload.get("data") {
+            let mut initial_data = Map::new();
+            let mut path = Vec::new();
+            self.project_selection_set(
+                Projection::Initial,
+                self.operation_ctx.root_selection_set(),
+                data,
+                &mut initial_data,
+                &mut path,
+            );
+            payload.insert("data".into(), Value::Object(initial_data));
+        }
+
+        if let Some(Value::Array(errors)) = payload.remove("errors") {
+            self.delivered_errors_count = errors.len();
+            let data = payload.get("data").unwrap_or(&Value::Null);
+            let (visible, hidden) = errors
+                .into_iter()
+                .partition::<Vec<_>, _>(|error| is_visible(data, error.get("path")));
+            self.pending_errors = hidden;
+            if !visible.is_empty() {
+                payload.insert("errors".into(), Value::Array(visible));
+            }
+        }
+
+        payload.insert("hasNext".into(), Value::Bool(self.has_next()));
+
+        Response::Incremental(IncrementalResponse {
+            payload,
+            operation_attributes,
+            status,
+            error_code_counter,
+            extensions: Default::default(),
+        })
+    }
+
+    /// Builds a subsequent payload from a snapshot of the response with the deferred fragments
+    /// and streamed items completed since the previous one, if any.
+    pub(crate) fn next(&mut self, response: &Response) -> Option<Response> {
+        if !self.has_next() {
+            return None;
+        }
+        self.subsequent(response, false)
+    }
+
+    /// Builds the last payload with all the remaining deferred fragments and streamed items from
+    /// the final response, unless everything was already delivered.
+    pub(crate) fn last(&mut self, response: Response) -> Option<Response> {
+        if !self.has_next() {
+            return None;
+        }
+        self.subsequent(&response, true).or(Some(response))
+    }
+
+    fn subsequent(&mut self, response: &Response, is_last: bool) -> Option<Response> {
+        let operation_attributes = response.operation_attributes().cloned()?;
+        let status = response.graphql_status();
+
+        let Ok(Value::Object(mut payload)) = serde_json::to_value(response) else {
+            tracing::error!("Failed to serialize the subsequent incremental payload");
+            return None;
+        };
+
+        let data = payload.remove("data").unwrap_or(Value::Null);
+        let mut items = Vec::new();
+
+        let mut i = 0;
+        while let Some(list) = self.streamed.get(i) {
+            let is_completed = match lookup(&data, &list.path) {
+                Some(Value::Array(values)) => {
+                    is_last
+                        || values
+                            .iter()
+                            .skip(list.initial_count)
+                            .all(|item| self.is_value_complete(Projection::Complete, list.selection_set, item))
+                }
+                // The list was nulled by an error.
+                _ => true,
+            };
+            if !is_completed {
+                i += 1;
+                continue;
+            }
+
+            let StreamedList {
+                path,
+                label,
+                initial_count,
+                selection_set,
+            } = self.streamed.remove(i);
+            let Some(Value::Array(list)) = lookup(&data, &path) else {
+                continue;
+            };
+            let streamed_items = list
+                .iter()
+                .skip(initial_count)
+                .map(|item| self.project_value(Projection::Complete, selection_set, item, &mut path.clone()))
+                .collect::<Vec<_>>();
+
+            let mut path = path;
+            path.push(Value::from(initial_count));
+            items.push(IncrementalItem {
+                path,
+                label,
+                data: IncrementalData::Streamed(streamed_items),
+                errors: Vec::new(),
+            });
+        }
+
+        // Nested deferred fragments are appended while iterating, becoming their own items.
+        let mut i = 0;
+        while let Some(fragment) = self.deferred.get(i) {
+            let is_completed = match lookup(&data, &fragment.path) {
+                Some(Value::Object(object)) => {
+                    is_last || self.is_selection_set_complete(Projection::Deferred, fragment.selection_set, object)
+                }
+                // The object was nulled by an error.
+                _ => true,
+            };
+            if !is_completed {
+                i += 1;
+                continue;
+            }
+
+            let DeferredFragment {
+                path,
+                label,
+                selection_set,
+            } = self.deferred.remove(i);
+            let Some(Value::Object(object)) = lookup(&data, &path) else {
+                continue;
+            };
+            let mut fragment_data = Map::new();
+            self.project_selection_set(
+                Projection::Deferred,
+                selection_set,
+                object,
+                &mut fragment_data,
+                &mut path.clone(),
+            );
+            // The type condition of the fragment doesn't match the object.
+            if fragment_data.is_empty() {
+                continue;
+            }
+            items.push(IncrementalItem {
+                path,
+                label,
+                data: IncrementalData::Deferred(fragment_data),
+                errors: Vec::new(),
+            });
+        }
+
+        // Nothing left to deliver after this payload.
+        let is_last = is_last || !self.has_next();
+        let mut error_code_counter = ErrorCodeCounter::default();
+        if is_last {
+            error_code_counter.add(response.error_code_counter());
+        }
+
+        let mut errors = std::mem::take(&mut self.pending_errors);
+        if let Some(Value::Array(response_errors)) = payload.remove("errors") {
+            let count = response_errors.len();
+            errors.extend(response_errors.into_iter().skip(self.delivered_errors_count));
+            self.delivered_errors_count = count;
+        }
+
+        // Errors within data that isn't delivered yet wait for the payload delivering it.
+        if !is_last {
+            let (current, pending) = errors
+                .into_iter()
+                .partition::<Vec<_>, _>(|error| match error.get("path") {
+                    Some(Value::Array(error_path)) => items.iter().any(|item| item.contains(error_path)),
+                    _ => false,
+                });
+            self.pending_errors = pending;
+            errors = current;
+
+            if items.is_empty() {
+                return None;
+            }
+        }
+        let remaining_errors = assign_errors(&mut items, errors);
+
+        let mut payload = Map::new();
+        if !items.is_empty() {
+            let incremental = items.into_iter().map(IncrementalItem::into_value).collect();
+            payload.insert("incremental".into(), Value::Array(incremental));
+        }
+        if !remaining_errors.is_empty() {
+            payload.insert("errors".into(), Value::Array(remaining_errors));
+        }
+        payload.insert("hasNext".into(), Value::Bool(!is_last));
+
+        Some(Response::Incremental(IncrementalResponse {
+            payload,
+            operation_attributes,
+            status,
+            error_code_counter,
+            extensions: Default::default(),
+        }))
+    }
+
+    /// Response delivered as a single payload, for example if the operation failed before its
+    /// execution.
+    pub(crate) fn complete(&self, response: Response) -> Response {
+        let Some(operation_attributes) = response.operation_attributes().cloned() else {
+            return response;
+        };
+        let mut error_code_counter = ErrorCodeCounter::default();
+        error_code_counter.add(response.error_code_counter());
+
+        let Ok(Value::Object(mut payload)) = serde_json::to_value(&response) else {
+            tracing::error!("Failed to serialize the incremental payload");
+            return response;
+        };
+        payload.insert("hasNext".into(), Value::Bool(false));
+
+        Response::Incremental(IncrementalResponse {
+            payload,
+            operation_attributes,
+            status: response.graphql_status(),
+            error_code_counter,
+            extensions: Default::default(),
+        })
+    }
+
+    fn input_value_ctx(&self) -> InputValueContext<'a> {
+        InputValueContext {
+            schema: self.operation_ctx.schema,
+            query_input_values: &self.operation_ctx.operation.query_input_values,
+            variables: self.variables,
+        }
+    }
+
+    /// Label of the active @defer directive if any.
+    fn active_defer(&self, directive_ids: &[ExecutableDirectiveId]) -> Option<Option<String>> {
+        directive_ids
+            .iter()
+            .find_map(|id| id.as_defer())
+            .filter(|directive| directive.is_active(self.input_value_ctx()))
+            .map(|directive| directive.walk(self.operation_ctx).label().map(str::to_string))
+    }
+
+    /// Initial count and label of the active @stream directive if any.
+    fn active_stream(&self, directive_ids: &[ExecutableDirectiveId]) -> Option<(usize, Option<String>)> {
+        directive_ids
+            .iter()
+            .find_map(|id| id.as_stream())
+            .filter(|directive| directive.is_active(self.input_value_ctx()))
+            .map(|directive| {
+                (
+                    directive.initial_count(self.input_value_ctx()),
+                    directive.walk(self.operation_ctx).label().map(str::to_string),
+                )
+            })
+    }
+
+    /// Copies the fields of the selection set from `source` into `target`, merging them with the
+    /// ones already present for fields selected multiple times.
+    fn project_selection_set(
+        &mut self,
+        projection: Projection,
+        selection_set: SelectionSet<'a>,
+        source: &Map<String, Value>,
+        target: &mut Map<String, Value>,
+        path: &mut Vec<Value>,
+    ) {
+        for selection in selection_set {
+            let (directive_ids, fragment_selection_set) = match selection {
+                Selection::Field(field) => {
+                    let key = field.response_key_str();
+                    let Some(value) = source.get(key) else {
+                        continue;
+                    };
+                    path.push(Value::String(key.to_string()));
+
+                    let target_value = target.entry(key.to_string()).or_insert(Value::Null);
+                    let stream = match projection {
+                        Projection::Initial => self.active_stream(field.directive_ids()),
+                        Projection::Deferred | Projection::Complete => None,
+                    };
+
+                    match (stream, value) {
+                        (Some((initial_count, label)), Value::Array(list)) => {
+                            let list = if list.len() > initial_count {
+                                self.streamed.push(StreamedList {
+                                    path: path.clone(),
+                                    label,
+                                    initial_count,
+                                    selection_set: field.selection_set(),
+                                });
+                                &list[..initial_count]
+                            } else {
+                                &list[..]
+                            };
+                            self.project_list(projection, field.selection_set(), list, target_value, path);
+                        }
+                        _ => self.project_value_into(projection, field.selection_set(), value, target_value, path),
+                    }
+
+                    path.pop();
+                    continue;
+                }
+                Selection::FragmentSpread(spread) => (
+                    spread.as_ref().directive_ids.as_slice(),
+                    spread.fragment().selection_set(),
+                ),
+                Selection::InlineFragment(fragment) => {
+                    (fragment.as_ref().directive_ids.as_slice(), fragment.selection_set())
+                }
+            };
+
+            match (projection, self.active_defer(directive_ids)) {
+                (Projection::Initial | Projection::Deferred, Some(label)) => {
+                    self.deferred.push(DeferredFragment {
+                        path: path.clone(),
+                        label,
+                        selection_set: fragment_selection_set,
+                    });
+                }
+                _ => self.project_selection_set(projection, fragment_selection_set, source, target, path),
+            }
+        }
+    }
+
+    /// Whether all the fields of the selection set are present in `source`, ignoring nested
+    /// active deferred fragments for `Projection::Deferred`. Fields are only added to the response
+    /// once the plan resolving them is executed.
+    fn is_selection_set_complete(
+        &self,
+        projection: Projection,
+        selection_set: SelectionSet<'a>,
+        source: &Map<String, Value>,
+    ) -> bool {
+        selection_set.into_iter().all(|selection| {
+            let (directive_ids, fragment_selection_set) = match selection {
+                Selection::Field(field) => {
+                    return self.is_skipped(field.directive_ids())
+                        || source
+                            .get(field.response_key_str())
+                            .is_some_and(|value| self.is_value_complete(projection, field.selection_set(), value));
+                }
+                Selection::FragmentSpread(spread) => (
+                    spread.as_ref().directive_ids.as_slice(),
+                    spread.fragment().selection_set(),
+                ),
+                Selection::InlineFragment(fragment) => {
+                    (fragment.as_ref().directive_ids.as_slice(), fragment.selection_set())
+                }
+            };
+
+            self.is_skipped(directive_ids)
+                || (matches!(projection, Projection::Deferred) && self.active_defer(directive_ids).is_some())
+                || self.is_selection_set_complete(projection, fragment_selection_set, source)
+        })
+    }
+
+    fn is_value_complete(&self, projection: Projection, selection_set: SelectionSet<'a>, value: &Value) -> bool {
+        if selection_set.is_empty() {
+            return true;
+        }
+
+        match value {
+            Value::Object(object) => self.is_selection_set_complete(projection, selection_set, object),
+            Value::Array(list) => list
+                .iter()
+                .all(|item| self.is_value_complete(projection, selection_set, item)),
+            _ => true,
+        }
+    }
+
+    /// Whether the selection is excluded by @skip or @include.
+    fn is_skipped(&self, directive_ids: &[ExecutableDirectiveId]) -> bool {
+        directive_ids.iter().any(|id| match id {
+            ExecutableDirectiveId::Include(directive) => {
+                !bool::deserialize(directive.condition.walk(self.input_value_ctx())).unwrap_or_default()
+            }
+            ExecutableDirectiveId::Skip(directive) => {
+                bool::deserialize(directive.condition.walk(self.input_value_ctx())).unwrap_or_default()
+            }
+            ExecutableDirectiveId::Defer(_) | ExecutableDirectiveId::Stream(_) => false,
+        })
+    }
+
+    fn project_value(
+        &mut self,
+        projection: Projection,
+        selection_set: SelectionSet<'a>,
+        value: &Value,
+        path: &mut Vec<Value>,
+    ) -> Value {
+        let mut target = Value::Null;
+        self.project_value_into(projection, selection_set, value, &mut target, path);
+        target
+    }
+
+    fn project_value_into(
+        &mut self,
+        projection: Projection,
+        selection_set: SelectionSet<'a>,
+        value: &Value,
+        target: &mut Value,
+        path: &mut Vec<Value>,
+    ) {
+        if selection_set.is_empty() {
+            *target = value.clone();
+            return;
+        }
+
+        match value {
+            Value::Object(object) => {
+                if !target.is_object() {
+                    *target = Value::Object(Map::new());
+                }
+                if let Value::Object(target) = target {
+                    self.project_selection_set(projection, selection_set, object, target, path);
+                }
+            }
+            Value::Array(list) => self.project_list(projection, selection_set, list, target, path),
+            value => *target = value.clone(),
+        }
+    }
+
+    fn project_list(
+        &mut self,
+        projection: Projection,
+        selection_set: SelectionSet<'a>,
+        list: &[Value],
+        target: &mut Value,
+        path: &mut Vec<Value>,
+    ) {
+        if !matches!(target, Value::Array(target_list) if target_list.len() == list.len()) {
+            *target = Value::Array(vec![Value::Null; list.len()]);
+        }
+        let Value::Array(target_list) = target else {
+            return;
+        };
+
+        for (i, (value, target)) in list.iter().zip(target_list.iter_mut()).enumerate() {
+            path.push(Value::from(i));
+            self.project_value_into(projection, selection_set, value, target, path);
+            path.pop();
+        }
+    }
+}
+
+impl IncrementalItem {
+    fn into_value(self) -> Value {
+        let mut item = Map::new();
+        match self.data {
+            IncrementalData::Deferred(data) => item.insert("data".into(), Value::Object(data)),
+            IncrementalData::Streamed(items) => item.insert("items".into(), Value::Array(items)),
+        };
+        item.insert("path".into(), Value::Array(self.path));
+        if let Some(label) = self.label {
+            item.insert("label".into(), Value::String(label));
+        }
+        if !self.errors.is_empty() {
+            item.insert("errors".into(), Value::Array(self.errors));
+        }
+        Value::Object(item)
+    }
+
+    fn contains(&self, error_path: &[Value]) -> bool {
+        if error_path.len() <= self.path.len() || !error_path.starts_with(&self.path) {
+            return false;
+        }
+        match (&self.data, &error_path[self.path.len()]) {
+            (IncrementalData::Deferred(data), Value::String(key)) => data.contains_key(key),
+            // The path of streamed items ends with the index of the first one.
+            (IncrementalData::Streamed(_), _) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Whether the value at the error path, or the null it propagated to, is part of the data.
+fn is_visible(data: &Value, error_path: Option<&Value>) -> bool {
+    let Some(Value::Array(error_path)) = error_path else {
+        return true;
+    };
+    let mut current = data;
+    for segment in error_path {
+        let next = match (current, segment) {
+            (Value::Object(object), Value::String(key)) => object.get(key),
+            (Value::Array(list), Value::Number(index)) => index
+                .as_u64()
+                .and_then(|index| usize::try_from(index).ok())
+                .and_then(|index| list.get(index)),
+            _ => return true,
+        };
+        match next {
+            Some(value) => current = value,
+            None => return false,
+        }
+    }
+    true
+}
+
+fn lookup<'v>(data: &'v Value, path: &[Value]) -> Option<&'v Value> {
+    path.iter().try_fold(data, |current, segment| match (current, segment) {
+        (Value::Object(object), Value::String(key)) => object.get(key),
+        (Value::Array(list), Value::Number(index)) => list.get(usize::try_from(index.as_u64()?).ok()?),
+        _ => None,
+    })
+}
+
+/// Errors are attached to the innermost item containing their path, and otherwise to the last
+/// one. Errors are only returned if there is no item at all.
+fn assign_errors(items: &mut [IncrementalItem], errors: Vec<Value>) -> Vec<Value> {
+    if items.is_empty() {
+        return errors;
+    }
+
+    for error in errors {
+        let item_ix = match error.get("path") {
+            Some(Value::Array(error_path)) => items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| item.contains(error_path))
+                .max_by_key(|(_, item)| item.path.len())
+                .map(|(ix, _)| ix),
+            _ => None,
+        };
+        let item_ix = item_ix.unwrap_or(items.len() - 1);
+        items[item_ix].errors.push(error);
+    }
+
+    Vec::new()
+}
@@ -1,6 +1,8 @@
+mod cost;
 mod data;
 mod error;
 mod extensions;
+mod incremental;
 mod object_set;
 mod path;
 mod read;
@@ -14,6 +16,7 @@ pub(crate) use error::*;
 pub(crate) use extensions::*;
 use gateway_config::ErrorCodeMapping;
 use grafbase_telemetry::graphql::{GraphqlExecutionTelemetry, GraphqlOperationAttributes, GraphqlResponseStatus};
+pub(crate) use incremental::*;
 pub(crate) use object_set::*;
 pub(crate) use path::*;
 pub(crate) use read::*;
@@ -37,6 +40,10 @@ pub(crate) enum Response {
     /// So `data` is present, even if null. That's considered to be a "partial response" and
     /// HTTP status code SHOULD be 2xx according to the GraphQL-over-HTTP spec for application/graphql-response+json
     Executed(ExecutedResponse),
+    /// Initial or subsequent payload of an incremental delivery with @defer or @stream.
+    Incremental(IncrementalResponse),
+    /// Response retrieved from the response cache.
+    Cached(CachedResponse),
 }
 
 pub(crate) struct ExecutedResponse {
@@ -65,6 +72,13 @@ impl ExecutedResponse {
     }
 }
 
+pub(crate) struct CachedResponse {
+    pub payload: serde_json::Map<String, serde_json::Value>,
+    pub operation_attributes: GraphqlOperationAttributes,
+    pub error_code_counter: ErrorCodeCounter,
+    pub extensions: ResponseExtensions,
+}
+
 pub(crate) struct RequestErrorResponse {
     error_code_mapping: ErrorCodeMapping,
     operation_attributes: Option<GraphqlOperationAttributes>,
@@ -97,6 +111,8 @@ impl Response {
             Self::Executed(resp) => {
                 resp.errors.len() * 80 + resp.data.as_ref().map(|data| data.size_hint()).unwrap_or(10)
             }
+            Self::Incremental(resp) => resp.payload.len() * 80,
+            Self::Cached(resp) => resp.payload.len() * 80,
         }
     }
 
@@ -173,6 +189,8 @@ impl Response {
             Self::RefusedRequest(resp) => &mut resp.extensions,
             Self::RequestError(resp) => &mut resp.extensions,
             Self::Executed(resp) => &mut resp.extensions,
+            Self::Incremental(resp) => &mut resp.extensions,
+            Self::Cached(resp) => &mut resp.extensions,
         }
     }
 
@@ -192,6 +210,8 @@ impl Response {
             Self::RefusedRequest(resp) => resp.operation_attributes.as_ref(),
             Self::RequestError(resp) => resp.operation_attributes.as_ref(),
             Self::Executed(resp) => Some(&resp.operation_attributes),
+            Self::Incremental(resp) => Some(&resp.operation_attributes),
+            Self::Cached(resp) => Some(&resp.operation_attributes),
         }
     }
 
@@ -206,6 +226,12 @@ impl Response {
             Self::Executed(resp) => {
                 resp.operation_attributes = operation_attributes;
             }
+            Self::Incremental(resp) => {
+                resp.operation_attributes = operation_attributes;
+            }
+            Self::Cached(resp) => {
+                resp.operation_attributes = operation_attributes;
+            }
         }
         self
     }
@@ -217,6 +243,9 @@ impl Response {
                 count: resp.errors.len() as u64,
             },
             Self::RefusedRequest(_) => GraphqlResponseStatus::RefusedRequest,
+            Self::Incremental(resp) => resp.status,
+            // Only successful responses are cached.
+            Self::Cached(_) => GraphqlResponseStatus::Success,
         }
     }
 
@@ -224,7 +253,7 @@ impl Response {
         match self {
             Response::RefusedRequest(resp) => &resp.errors,
             Response::RequestError(resp) => &resp.errors,
-            Response::Executed(_) => unreachable!(),
+            Response::Executed(_) | Response::Incremental(_) | Response::Cached(_) => unreachable!(),
         }
     }
 
@@ -233,6 +262,8 @@ impl Response {
             Response::RefusedRequest(resp) => &resp.error_code_counter,
             Response::RequestError(resp) => &resp.error_code_counter,
             Response::Executed(resp) => resp.errors.code_counter(),
+            Response::Incremental(resp) => &resp.error_code_counter,
+            Response::Cached(resp) => &resp.error_code_counter,
         }
     }
 }
@@ -4,7 +4,9 @@ mod errors;
 use operation::ResponseKeys;
 use serde::ser::SerializeMap;
 
-use crate::response::{ExecutedResponse, RefusedRequestResponse, RequestErrorResponse, Response};
+use crate::response::{
+    CachedResponse, ExecutedResponse, IncrementalResponse, RefusedRequestResponse, RequestErrorResponse, Response,
+};
 
 impl serde::Serialize for Response {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -100,6 +102,23 @@ impl serde::Serialize for Response {
                     map.serialize_entry("extensions", extensions)?;
                 }
 
+                map.end()
+            }
+            Response::Incremental(IncrementalResponse {
+                payload, extensions, ..
+            })
+            | Response::Cached(CachedResponse {
+                payload, extensions, ..
+            }) => {
+                let mut map = serializer.serialize_map(None)?;
+                for (key, value) in payload {
+                    map.serialize_entry(key, value)?;
+                }
+
+                if !extensions.is_empty() {
+                    map.serialize_entry("extensions", extensions)?;
+                }
+
                 map.end()
             }
         }
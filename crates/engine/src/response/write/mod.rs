@@ -9,10 +9,13 @@ use schema::{ObjectDefinitionId, Schema};
 use walker::Walk;
 
 use super::{
-    DataParts, ErrorPartBuilder, ErrorParts, ExecutedResponse, GraphqlError, Response, ResponseData, ResponseObject,
-    ResponseObjectId, ResponseObjectRef, ResponseObjectSet, ResponseValueId,
+    DataParts, ErrorPartBuilder, ErrorParts, ExecutedResponse, GraphqlError, Response, ResponseData,
+    ResponseExtensions, ResponseObject, ResponseObjectId, ResponseObjectRef, ResponseObjectSet, ResponseValueId,
+};
+use crate::{
+    execution::PropagatedHeaders,
+    prepare::{OperationPlanContext, PlanId, PreparedOperation, ResponseObjectSetId},
 };
-use crate::prepare::{OperationPlanContext, PreparedOperation, ResponseObjectSetId};
 pub(crate) use deserialize::*;
 pub(crate) use part::*;
 
@@ -24,6 +27,9 @@ pub(crate) struct ResponseBuilder<'ctx> {
     pub(super) data_parts: DataParts,
     pub(super) error_parts: ErrorParts,
     errors: ErrorPartBuilder<'ctx>,
+    /// Merged in plan order once the response is complete, so that the first and last merge
+    /// strategies don't depend on which subgraph answered first.
+    propagated_headers: Vec<(PlanId, PropagatedHeaders)>,
 }
 
 impl<'ctx> ResponseBuilder<'ctx> {
@@ -41,6 +47,7 @@ impl<'ctx> ResponseBuilder<'ctx> {
             data_parts,
             error_parts: ErrorParts::default(),
             errors: ErrorPartBuilder::new(operation),
+            propagated_headers: Vec::new(),
         }
     }
 
@@ -80,9 +87,12 @@ impl<'ctx> ResponseBuilder<'ctx> {
         })
     }
 
-    pub fn ingest(&mut self, part: ResponsePartBuilder<'ctx>) -> PartIngestionResult {
+    pub fn ingest(&mut self, plan_id: PlanId, part: ResponsePartBuilder<'ctx>) -> PartIngestionResult {
         self.data_parts.insert(part.data);
         self.error_parts.push(part.errors);
+        if !part.propagated_headers.is_empty() {
+            self.propagated_headers.push((plan_id, part.propagated_headers));
+        }
 
         if part.propagated_null_up_to_root {
             self.root = None;
@@ -132,9 +142,53 @@ impl<'ctx> ResponseBuilder<'ctx> {
         }
     }
 
+    /// Provides the response built so far while the execution continues, for the initial payload
+    /// of an incremental delivery. Errors pushed until now are kept in the final response.
+    pub fn with_snapshot<T>(
+        &mut self,
+        operation_attributes: GraphqlOperationAttributes,
+        f: impl FnOnce(&Response) -> T,
+    ) -> T {
+        let errors = std::mem::replace(&mut self.errors, ErrorPartBuilder::new(self.operation));
+        self.error_parts.push(errors);
+
+        let response = Response::Executed(ExecutedResponse {
+            schema: self.schema.clone(),
+            operation: self.operation.clone(),
+            operation_attributes,
+            data: self.root.map(|(root, _)| ResponseData {
+                root,
+                parts: std::mem::take(&mut self.data_parts),
+            }),
+            errors: std::mem::take(&mut self.error_parts),
+            extensions: Default::default(),
+        });
+
+        let output = f(&response);
+
+        let Response::Executed(ExecutedResponse { data, errors, .. }) = response else {
+            unreachable!()
+        };
+        if let Some(data) = data {
+            self.data_parts = data.parts;
+        }
+        self.error_parts = errors;
+
+        output
+    }
+
     pub fn build(mut self, operation_attributes: GraphqlOperationAttributes) -> Response {
         self.error_parts.push(self.errors);
 
+        self.propagated_headers.sort_by_key(|(plan_id, _)| *plan_id);
+        let propagated_headers =
+            self.propagated_headers
+                .into_iter()
+                .fold(PropagatedHeaders::default(), |mut merged, (_, headers)| {
+                    merged.extend(headers);
+                    merged
+                });
+
         Response::Executed(ExecutedResponse {
             schema: self.schema.clone(),
             operation: self.operation.clone(),
@@ -144,7 +198,10 @@ impl<'ctx> ResponseBuilder<'ctx> {
                 parts: self.data_parts,
             }),
             errors: self.error_parts,
-            extensions: Default::default(),
+            extensions: ResponseExtensions {
+                propagated_headers: Some(propagated_headers).filter(|headers| !headers.is_empty()),
+                ..Default::default()
+            },
         })
     }
 }
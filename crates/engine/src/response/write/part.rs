@@ -3,6 +3,7 @@ use schema::Schema;
 use walker::Walk as _;
 
 use crate::{
+    execution::PropagatedHeaders,
     prepare::{DefaultFieldShapeId, OnRootFieldsError, PreparedOperation, ResponseObjectSetId, RootFieldsShapeId},
     response::{
         DataPart, ErrorPartBuilder, GraphqlError, ResponseObjectField, ResponseObjectId, ResponseObjectRef,
@@ -22,6 +23,8 @@ pub(crate) struct ResponsePartBuilder<'ctx> {
     pub(super) propagated_null_at: Vec<ResponseValueId>,
     pub(super) object_updates: Vec<ObjectUpdate>,
     pub(super) object_sets: Vec<(ResponseObjectSetId, ResponseObjectSet)>,
+    /// Subgraph response headers propagated to the client response.
+    pub propagated_headers: PropagatedHeaders,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash, id_derives::Id)]
@@ -39,6 +42,7 @@ impl<'ctx> ResponsePartBuilder<'ctx> {
             propagated_null_up_to_root: false,
             propagated_null_at: Vec::new(),
             object_sets: Vec::new(),
+            propagated_headers: PropagatedHeaders::default(),
         }
     }
 
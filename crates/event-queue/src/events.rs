@@ -33,6 +33,7 @@ pub struct ExecutedOperation {
     pub status: GraphqlResponseStatus,
     pub operation_type: OperationType,
     pub complexity: Option<u64>,
+    pub actual_complexity: Option<u64>,
     pub has_deprecated_fields: bool,
 }
 
@@ -52,6 +53,7 @@ impl ExecutedOperation {
             status: GraphqlResponseStatus::Success,
             operation_type,
             complexity: None,
+            actual_complexity: None,
             has_deprecated_fields: false,
         }
     }
@@ -21,6 +21,7 @@ pub struct ExecutedOperationBuilder<'a> {
     pub(super) status: GraphqlResponseStatus,
     pub(crate) operation_type: OperationType,
     pub(super) complexity: Option<u64>,
+    pub(super) actual_complexity: Option<u64>,
     pub(super) has_deprecated_fields: bool,
 }
 
@@ -108,6 +109,20 @@ impl<'a> ExecutedOperationBuilder<'a> {
         self
     }
 
+    /// Sets the actual complexity cost of the operation.
+    ///
+    /// This should be called if the operation complexity was calculated, once the response
+    /// is known. Contrary to the estimated complexity, it relies on the actual size of the
+    /// returned lists.
+    ///
+    /// # Arguments
+    ///
+    /// * `complexity` - The actual complexity cost value
+    pub fn actual_complexity(&mut self, complexity: u64) -> &mut Self {
+        self.actual_complexity = Some(complexity);
+        self
+    }
+
     /// Sets whether the operation contains deprecated fields.
     ///
     /// This should be called if deprecated fields were used in the operation.
@@ -131,6 +146,7 @@ impl<'a> ExecutedOperationBuilder<'a> {
             status: self.status,
             operation_type: self.operation_type,
             complexity: self.complexity,
+            actual_complexity: self.actual_complexity,
             has_deprecated_fields: self.has_deprecated_fields,
         }
     }
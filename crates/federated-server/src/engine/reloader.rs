@@ -3,6 +3,7 @@ use std::{path::PathBuf, sync::Arc};
 use ::engine::CachedOperation;
 use engine::ContractAwareEngine;
 use extension_catalog::ExtensionCatalog;
+use grafbase_telemetry::{metrics::meter_from_global_provider, otel::opentelemetry::metrics::Counter};
 use tokio::{
     sync::{mpsc, watch},
     task::JoinHandle,
@@ -10,7 +11,11 @@ use tokio::{
 use wasi_component_loader::extension::GatewayWasmExtensions;
 
 use super::{EngineBuildContext, EngineRuntime};
-use crate::{events::UpdateEvent, graph::Graph, router::EngineWatcher};
+use crate::{
+    events::UpdateEvent,
+    graph::{Graph, GraphHealth, SchemaPublicKey, SchemaSignatureError},
+    router::EngineWatcher,
+};
 
 use super::AccessToken;
 
@@ -35,6 +40,13 @@ pub(crate) struct EngineReloaderConfig {
     pub access_token: Option<AccessToken>,
 
     pub gateway_extensions: GatewayWasmExtensions,
+
+    /// Whether the graphs come from a source which can sign them, in which case the schema
+    /// signature configuration applies
+    pub verify_graph_signatures: bool,
+
+    /// Updated with the outcome of each reload if the gateway loads the graph itself
+    pub graph_health: Option<GraphHealth>,
 }
 
 /// Handles graph and config updates by constructing a new engine
@@ -58,17 +70,34 @@ impl EngineReloader {
             hot_reload_config_path,
             access_token,
             gateway_extensions,
+            verify_graph_signatures,
+            graph_health,
         }: EngineReloaderConfig,
     ) -> crate::Result<Self> {
         let mut current_config = initial_config;
 
+        let mut verifier = GraphSignatureVerifier {
+            enabled: verify_graph_signatures,
+            public_key: None,
+            rejections: meter_from_global_provider()
+                .u64_counter("graph.signature.rejected")
+                .build(),
+        };
+
+        verifier.public_key = verifier.load_public_key(&current_config)?;
+
         tracing::debug!("Waiting for the initial graph...");
 
         let mut graph = loop {
             match update_receiver.recv().await {
-                Some(UpdateEvent::Graph(graph)) => break graph,
+                Some(UpdateEvent::Graph(graph)) => {
+                    // Without a valid initial graph there is nothing to serve.
+                    verifier.verify(&graph)?;
+                    break graph;
+                }
                 Some(UpdateEvent::Config(new_config)) => {
                     // Update config if we receive it before the initial graph
+                    verifier.public_key = verifier.load_public_key(&new_config)?;
                     current_config = *new_config;
                     continue;
                 }
@@ -95,20 +124,49 @@ impl EngineReloader {
         let engine = build_engine(initial_context, graph.clone(), vec![]).await?;
         let (engine_sender, engine_watcher) = watch::channel(engine);
 
+        if let Some(health) = &graph_health {
+            health.loaded();
+        }
+
         tokio::spawn(async move {
             let mut in_progress_reload: Option<JoinHandle<()>> = None;
 
             while let Some(update) = update_receiver.recv().await {
+                // A refused graph never replaces the current one, the running engine is kept.
+                match update {
+                    UpdateEvent::Graph(new_graph) => {
+                        if let Err(err) = verifier.verify(&new_graph) {
+                            report_load_failure(graph_health.as_ref(), &err);
+                            continue;
+                        }
+
+                        graph = new_graph;
+                    }
+                    UpdateEvent::Config(new_config) => {
+                        match verifier.load_public_key(&new_config) {
+                            Ok(public_key) => verifier.public_key = public_key,
+                            Err(err) => {
+                                tracing::error!(
+                                    "Could not load the schema signature public key, ignoring the configuration update: {err}"
+                                );
+                                continue;
+                            }
+                        }
+
+                        current_config = *new_config;
+
+                        if let Err(err) = verifier.verify(&graph) {
+                            report_load_failure(graph_health.as_ref(), &err);
+                            continue;
+                        }
+                    }
+                }
+
                 // Abort any in-progress reload
                 if let Some(reload) = in_progress_reload.take() {
                     reload.abort();
                 }
 
-                match update {
-                    UpdateEvent::Graph(new_graph) => graph = new_graph,
-                    UpdateEvent::Config(new_config) => current_config = *new_config,
-                }
-
                 in_progress_reload = Some(tokio::spawn({
                     let hot_reload_config_path = hot_reload_config_path.clone();
                     let access_token = access_token.clone();
@@ -117,6 +175,7 @@ impl EngineReloader {
                     let engine_sender = engine_sender.clone();
                     let logging_filter = logging_filter.clone();
                     let gateway_extensions = gateway_extensions.clone();
+                    let graph_health = graph_health.clone();
 
                     async move {
                         let operations_to_warm = extract_operations_to_warm(&current_config, &engine_sender);
@@ -135,9 +194,14 @@ impl EngineReloader {
                                 if let Err(err) = engine_sender.send(new_engine) {
                                     tracing::error!("Could not send engine: {err:?}");
                                 }
+
+                                if let Some(health) = &graph_health {
+                                    health.loaded();
+                                }
                             }
                             Err(err) => {
-                                tracing::error!("Could not build engine from latest graph: {err}")
+                                tracing::error!("Could not build engine from latest graph: {err}");
+                                report_load_failure(graph_health.as_ref(), &err);
                             }
                         }
                     }
@@ -155,6 +219,45 @@ impl EngineReloader {
     }
 }
 
+/// Refuses graphs without a valid signature if schema signatures are configured. The public key
+/// is only read when the configuration changes.
+struct GraphSignatureVerifier {
+    /// Graphs composed by the gateway itself are never signed.
+    enabled: bool,
+    public_key: Option<SchemaPublicKey>,
+    rejections: Counter<u64>,
+}
+
+impl GraphSignatureVerifier {
+    fn load_public_key(
+        &self,
+        config: &gateway_config::Config,
+    ) -> Result<Option<SchemaPublicKey>, SchemaSignatureError> {
+        match &config.schema_signature {
+            Some(signature_config) if self.enabled => SchemaPublicKey::from_config(signature_config).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn verify(&self, graph: &Graph) -> Result<(), SchemaSignatureError> {
+        let Some(public_key) = &self.public_key else {
+            return Ok(());
+        };
+
+        public_key.verify(graph.sdl()).inspect_err(|err| {
+            tracing::error!("Refused to load the federated schema: {err}");
+            self.rejections.add(1, &[]);
+        })
+    }
+}
+
+/// The previous engine keeps being served, but the health endpoint reports the failure.
+fn report_load_failure(graph_health: Option<&GraphHealth>, err: &dyn std::fmt::Display) {
+    if let Some(health) = graph_health {
+        health.load_failed(vec![err.to_string()]);
+    }
+}
+
 /// Helper function that builds a new engine instance.
 async fn build_engine(
     context: EngineBuildContext<'_>,
@@ -2,12 +2,15 @@ use std::{env, sync::Arc};
 
 use ::engine::{CachedOperation, Schema};
 use extension_catalog::ExtensionCatalog;
-use gateway_config::{EntityCachingRedisConfig, operation_caching::OperationCacheConfig};
+use gateway_config::{EntityCachingRedisConfig, ResponseCachingRedisConfig, operation_caching::OperationCacheConfig};
 use grafbase_telemetry::metrics::EngineMetrics;
 use hive_console_sdk::persisted_documents::PersistedDocumentsManager;
-use runtime::{entity_cache::EntityCache, trusted_documents_client::TrustedDocumentsEnforcementMode};
+use runtime::{
+    entity_cache::EntityCache, response_cache::ResponseCache, trusted_documents_client::TrustedDocumentsEnforcementMode,
+};
 use runtime_local::{
-    InMemoryEntityCache, InMemoryOperationCache, NativeFetcher, RedisEntityCache,
+    InMemoryEntityCache, InMemoryOperationCache, InMemoryResponseCache, NativeFetcher, RedisEntityCache,
+    RedisResponseCache,
     operation_cache::{RedisOperationCache, TieredOperationCache},
     rate_limiting::{in_memory::key_based::InMemoryRateLimiter, redis::RedisRateLimiter},
     redis::{RedisPoolFactory, RedisTlsConfig},
@@ -35,6 +38,8 @@ pub struct EngineRuntime {
     rate_limiter: runtime::rate_limiting::RateLimiter,
     entity_cache: Box<dyn EntityCache>,
     entity_cache_config: gateway_config::EntityCachingConfig,
+    response_cache: Box<dyn ResponseCache>,
+    response_cache_config: gateway_config::ResponseCachingConfig,
     pub(crate) operation_cache: TieredOperationCache<Arc<CachedOperation>>,
     operation_cache_config: OperationCacheConfig,
     redis_factory: Arc<tokio::sync::Mutex<RedisPoolFactory>>,
@@ -80,6 +85,7 @@ impl EngineRuntime {
 
         tracing::debug!("Building cache");
         let entity_cache = build_entity_cache(&ctx.gateway_config.entity_caching, &mut redis_factory)?;
+        let response_cache = build_response_cache(&ctx.gateway_config.response_caching, &mut redis_factory)?;
         let operation_cache = build_operation_cache(&ctx.gateway_config.operation_caching, &mut redis_factory)?;
 
         tracing::debug!("Building extensions");
@@ -144,6 +150,8 @@ impl EngineRuntime {
             rate_limiter,
             entity_cache,
             entity_cache_config: ctx.gateway_config.entity_caching.clone(),
+            response_cache,
+            response_cache_config: ctx.gateway_config.response_caching.clone(),
             operation_cache,
             operation_cache_config: ctx.gateway_config.operation_caching.clone(),
             redis_factory: Arc::new(tokio::sync::Mutex::new(redis_factory)),
@@ -182,6 +190,10 @@ impl engine::Runtime for EngineRuntime {
         self.entity_cache.as_ref()
     }
 
+    fn response_cache(&self) -> &dyn ResponseCache {
+        self.response_cache.as_ref()
+    }
+
     fn metrics(&self) -> &grafbase_telemetry::metrics::EngineMetrics {
         &self.metrics
     }
@@ -194,6 +206,8 @@ impl engine::Runtime for EngineRuntime {
         let mut redis_facttory = self.redis_factory.lock().await;
         let entity_cache = build_entity_cache(&self.entity_cache_config, &mut redis_facttory)
             .map_err(|err| format!("Failed to build entity cache: {err}"))?;
+        let response_cache = build_response_cache(&self.response_cache_config, &mut redis_facttory)
+            .map_err(|err| format!("Failed to build response cache: {err}"))?;
         let operation_cache = build_operation_cache(&self.operation_cache_config, &mut redis_facttory)
             .map_err(|err| format!("Failed to build operation cache: {err}"))?;
         Ok(EngineRuntime {
@@ -208,6 +222,8 @@ impl engine::Runtime for EngineRuntime {
             rate_limiter: self.rate_limiter.clone(),
             entity_cache,
             entity_cache_config: self.entity_cache_config.clone(),
+            response_cache,
+            response_cache_config: self.response_cache_config.clone(),
             operation_cache,
             operation_cache_config: self.operation_cache_config.clone(),
             redis_factory: self.redis_factory.clone(),
@@ -236,6 +252,31 @@ fn build_entity_cache(
     })
 }
 
+fn build_response_cache(
+    config: &gateway_config::ResponseCachingConfig,
+    redis_factory: &mut RedisPoolFactory,
+) -> Result<Box<dyn ResponseCache>, crate::Error> {
+    if !config.enabled {
+        return Ok(Box::new(()));
+    }
+
+    Ok(match config.storage {
+        gateway_config::ResponseCachingStorage::Memory => Box::new(InMemoryResponseCache::default()),
+        gateway_config::ResponseCachingStorage::Redis => {
+            let ResponseCachingRedisConfig { url, key_prefix, tls } = &config.redis;
+            let tls = tls.as_ref().map(|tls| RedisTlsConfig {
+                cert: tls.cert.as_deref(),
+                key: tls.key.as_deref(),
+                ca: tls.ca.as_deref(),
+            });
+            let pool = redis_factory
+                .pool(url.as_str(), tls)
+                .map_err(|e| crate::Error::InternalError(e.to_string()))?;
+            Box::new(RedisResponseCache::new(pool, key_prefix))
+        }
+    })
+}
+
 fn build_operation_cache(
     config: &OperationCacheConfig,
     redis_factory: &mut RedisPoolFactory,
@@ -19,6 +19,9 @@ pub enum Error {
     FetcherConfigError(String),
     #[error(transparent)]
     CreateExtensionCatalogError(#[from] crate::extensions::Error),
+    /// The initial graph or the public key was refused
+    #[error("refused to load the federated schema: {0}")]
+    SchemaSignature(#[from] crate::graph::SchemaSignatureError),
 }
 
 impl<T> From<watch::error::SendError<T>> for Error {
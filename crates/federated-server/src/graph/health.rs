@@ -0,0 +1,84 @@
+use std::sync::{Arc, Mutex};
+
+/// The health of a graph the gateway loads itself, where loading can fail after startup. Reported
+/// by the health endpoint.
+#[derive(Clone, Default)]
+pub struct GraphHealth {
+    inner: Arc<Mutex<GraphHealthInner>>,
+}
+
+#[derive(Default)]
+struct GraphHealthInner {
+    loaded: bool,
+    /// Errors of the latest composition, the previous graph keeps being loaded.
+    composition_errors: Vec<String>,
+    /// Errors of the latest engine reload, after composition succeeded.
+    load_errors: Vec<String>,
+}
+
+impl GraphHealth {
+    /// The latest composition succeeded, the outcome of loading the graph is reported separately.
+    pub(crate) fn composed(&self) {
+        self.lock().composition_errors.clear();
+    }
+
+    /// The latest composition failed. A previously loaded graph keeps being served.
+    pub(crate) fn composition_failed(&self, errors: Vec<String>) {
+        self.lock().composition_errors = errors;
+    }
+
+    /// The engine was built from the latest graph.
+    pub(crate) fn loaded(&self) {
+        let mut inner = self.lock();
+
+        inner.loaded = true;
+        inner.load_errors.clear();
+    }
+
+    /// The engine could not be built from the latest graph, or the graph was refused. A previously
+    /// loaded graph keeps being served.
+    pub(crate) fn load_failed(&self, errors: Vec<String>) {
+        self.lock().load_errors = errors;
+    }
+
+    /// Whether a graph was ever loaded, and the errors of the latest attempt.
+    pub(crate) fn status(&self) -> (bool, Vec<String>) {
+        let inner = self.lock();
+        let errors = inner
+            .composition_errors
+            .iter()
+            .chain(&inner.load_errors)
+            .cloned()
+            .collect();
+
+        (inner.loaded, errors)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, GraphHealthInner> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GraphHealth;
+
+    #[test]
+    fn composition_success_does_not_hide_load_errors() {
+        let health = GraphHealth::default();
+
+        health.composed();
+        assert_eq!(health.status(), (false, Vec::new()));
+
+        health.load_failed(vec!["invalid schema".to_owned()]);
+        health.composed();
+        assert_eq!(health.status(), (false, vec!["invalid schema".to_owned()]));
+
+        health.loaded();
+        health.composition_failed(vec!["missing subgraph".to_owned()]);
+        assert_eq!(health.status(), (true, vec!["missing subgraph".to_owned()]));
+
+        health.composed();
+        assert_eq!(health.status(), (true, Vec::new()));
+    }
+}
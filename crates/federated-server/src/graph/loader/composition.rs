@@ -0,0 +1,243 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use gateway_config::{Config, SubgraphConfig};
+use tokio::sync::{mpsc, watch};
+use tokio::time::MissedTickBehavior;
+
+use crate::{
+    events::UpdateEvent,
+    graph::{Graph, GraphHealth},
+};
+
+/// Composes the federated graph in the gateway, from the subgraphs in the configuration with a
+/// `schema_path` or an `introspection_url`.
+pub struct SubgraphComposer {
+    config: watch::Receiver<Config>,
+    poll_interval: Duration,
+    sender: mpsc::Sender<UpdateEvent>,
+    health: GraphHealth,
+    current_sdl: Option<String>,
+}
+
+impl SubgraphComposer {
+    pub fn new(
+        config: watch::Receiver<Config>,
+        poll_interval: Duration,
+        sender: mpsc::Sender<UpdateEvent>,
+        health: GraphHealth,
+    ) -> Self {
+        Self {
+            config,
+            poll_interval,
+            sender,
+            health,
+            current_sdl: None,
+        }
+    }
+
+    /// Composes the graph immediately, and after that on every interval with freshly loaded subgraph
+    /// schemas. The engine is only reloaded if composition succeeds and the graph changed, otherwise
+    /// the previous graph keeps being served.
+    pub async fn poll(&mut self) {
+        let mut interval = tokio::time::interval(self.poll_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+
+            // Subgraphs added or removed with a configuration hot reload are taken into account.
+            let subgraphs = self.config.borrow().subgraphs.clone();
+
+            let sdl = match compose(&subgraphs).await {
+                Ok(sdl) => sdl,
+                Err(errors) => {
+                    if self.current_sdl.is_some() {
+                        tracing::error!(
+                            "Failed to compose the graph, keeping the previous one:\n{}",
+                            errors.join("\n")
+                        );
+                    } else {
+                        tracing::error!("Failed to compose the graph:\n{}", errors.join("\n"));
+                    }
+
+                    self.health.composition_failed(errors);
+                    continue;
+                }
+            };
+
+            // Whether the graph is loaded is reported by the engine reloader.
+            self.health.composed();
+
+            if self.current_sdl.as_ref() == Some(&sdl) {
+                tracing::trace!("no updates to the composed graph");
+                continue;
+            }
+
+            tracing::info!("Composed a new graph");
+
+            self.current_sdl = Some(sdl.clone());
+
+            if self
+                .sender
+                .send(UpdateEvent::Graph(Graph::FromText { sdl }))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    }
+}
+
+/// Loads the subgraph schemas and composes them into a federated SDL, or returns the errors.
+async fn compose(subgraphs: &BTreeMap<String, SubgraphConfig>) -> Result<String, Vec<String>> {
+    let subgraphs = subgraphs
+        .iter()
+        .filter(|(_, subgraph)| subgraph.has_schema_override())
+        .collect::<Vec<_>>();
+
+    if subgraphs.is_empty() {
+        return Err(vec![
+            "No subgraph with a `schema_path` or an `introspection_url` in the configuration".to_owned(),
+        ]);
+    }
+
+    let schemas = futures_util::future::join_all(
+        subgraphs
+            .iter()
+            .map(|(name, subgraph)| async move { (*name, load_schema(name, subgraph).await) }),
+    )
+    .await;
+
+    let mut errors = Vec::new();
+    let mut composed_subgraphs = graphql_composition::Subgraphs::default();
+
+    for ((name, schema), (_, subgraph)) in schemas.into_iter().zip(&subgraphs) {
+        let url = subgraph.url.as_ref().map(|url| url.as_str());
+
+        match schema {
+            Ok(sdl) => {
+                if let Err(err) = composed_subgraphs.ingest_str(&sdl, name, url) {
+                    errors.push(format!("[{name}] Failed to parse the subgraph schema: {err}"));
+                }
+            }
+            Err(err) => errors.push(format!("[{name}] {err}")),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let result = graphql_composition::compose(&mut composed_subgraphs);
+
+    for warning in result.diagnostics().iter_warnings() {
+        tracing::warn!("Composition warning: {warning}");
+    }
+
+    let graph = result
+        .into_result()
+        .map_err(|diagnostics| diagnostics.iter_errors().map(ToOwned::to_owned).collect::<Vec<_>>())?;
+
+    graphql_composition::render_federated_sdl(&graph)
+        .map_err(|err| vec![format!("Failed to render the federated schema: {err}")])
+}
+
+async fn load_schema(name: &str, subgraph: &SubgraphConfig) -> Result<String, String> {
+    if let Some(path) = &subgraph.schema_path {
+        return tokio::fs::read_to_string(path)
+            .await
+            .map_err(|err| format!("Could not read the schema at {}: {err}", path.display()));
+    }
+
+    let Some(url) = &subgraph.introspection_url else {
+        return Err(format!("The subgraph {name} has no schema_path or introspection_url"));
+    };
+
+    let headers = subgraph
+        .introspection_headers
+        .iter()
+        .flatten()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect::<Vec<_>>();
+
+    grafbase_graphql_introspection::introspect(url.as_str(), &headers)
+        .await
+        .map_err(|err| format!("Could not introspect {url}: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subgraph(dir: &std::path::Path, name: &str, sdl: &str) -> (String, SubgraphConfig) {
+        let path = dir.join(format!("{name}.graphql"));
+        std::fs::write(&path, sdl).unwrap();
+
+        let subgraph = SubgraphConfig {
+            url: Some(format!("http://{name}.example.com/graphql").parse().unwrap()),
+            schema_path: Some(path),
+            ..Default::default()
+        };
+
+        (name.to_owned(), subgraph)
+    }
+
+    #[tokio::test]
+    async fn compose_subgraphs() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let subgraphs = BTreeMap::from([
+            subgraph(
+                dir.path(),
+                "accounts",
+                "type Query { me: User } type User @key(fields: \"id\") { id: ID! }",
+            ),
+            subgraph(
+                dir.path(),
+                "reviews",
+                "type User @key(fields: \"id\") { id: ID! reviews: [String!] }",
+            ),
+        ]);
+
+        let sdl = compose(&subgraphs).await.unwrap();
+
+        assert!(sdl.contains(r#"ACCOUNTS @join__graph(name: "accounts", url: "http://accounts.example.com/graphql")"#));
+        assert!(sdl.contains("reviews: [String!]"));
+    }
+
+    #[tokio::test]
+    async fn composition_errors() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut subgraphs = BTreeMap::from([
+            subgraph(
+                dir.path(),
+                "accounts",
+                "type Query { farm: Farm } type Farm @shareable { id: ID! }",
+            ),
+            subgraph(
+                dir.path(),
+                "reviews",
+                "type Query { farms: [Farm] } type Farm @shareable { id: ID! size: Float }",
+            ),
+        ]);
+
+        let errors = compose(&subgraphs).await.unwrap_err();
+        assert!(
+            errors.iter().any(|error| error.contains("missing the `size` field")),
+            "{errors:?}"
+        );
+
+        subgraphs.get_mut("reviews").unwrap().schema_path = Some(dir.path().join("missing.graphql"));
+
+        let errors = compose(&subgraphs).await.unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("[reviews] Could not read the schema at"));
+
+        assert_eq!(
+            compose(&BTreeMap::new()).await.unwrap_err(),
+            ["No subgraph with a `schema_path` or an `introspection_url` in the configuration"]
+        );
+    }
+}
@@ -1,17 +1,27 @@
+mod composition;
 mod hive_console;
 mod object_storage;
 mod schema_file;
+mod schema_url;
+mod snapshot;
 
 use std::{path::PathBuf, time::Duration};
 
+use gateway_config::Config;
 use graph_ref::GraphRef;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
+pub use composition::*;
 pub use hive_console::*;
 pub use object_storage::*;
 pub use schema_file::*;
+pub use schema_url::*;
 
-use crate::{AccessToken, events::UpdateEvent, graph::Graph};
+use crate::{
+    AccessToken,
+    events::UpdateEvent,
+    graph::{Graph, GraphHealth},
+};
 
 /// The method of running the gateway.
 pub enum GraphLoader {
@@ -20,6 +30,8 @@ pub enum GraphLoader {
         /// The access token for accessing the the API.
         access_token: AccessToken,
         graph_ref: GraphRef,
+        /// Where the last loaded graph is stored, to start from it if the API is unreachable.
+        snapshot_dir: Option<PathBuf>,
     },
     /// The schema is loaded from disk. No access to the Grafbase API.
     FromSchemaFile {
@@ -34,15 +46,42 @@ pub enum GraphLoader {
         key: Option<String>,
         poll_interval: Duration,
     },
+    /// The schema is composed by the gateway from the subgraphs in the configuration with a
+    /// `schema_path` or an `introspection_url`, and recomposed on every interval.
+    FromSubgraphs {
+        poll_interval: Duration,
+    },
+    /// The schema is fetched in regular intervals from an HTTP server or an S3-compatible storage.
+    FromUrl {
+        config: SchemaUrlConfig,
+    },
 }
 
 impl GraphLoader {
+    /// Whether the graphs are published by someone else and can be signed. Graphs composed by the
+    /// gateway or by `grafbase dev` never are.
+    pub(crate) fn loads_published_graphs(&self) -> bool {
+        match self {
+            GraphLoader::FromGraphRef { .. }
+            | GraphLoader::FromSchemaFile { .. }
+            | GraphLoader::FromHiveConsole { .. }
+            | GraphLoader::FromUrl { .. } => true,
+            GraphLoader::FromChannel { .. } | GraphLoader::FromSubgraphs { .. } => false,
+        }
+    }
+
     /// Starts a producer that sends graph updates to the provided channel.
     ///
     /// This can happen in two ways: if providing a graph SDL, we send a new graph immediately.
     /// Alternatively, if a graph ref and access token is provided, the function returns
     /// immediately, and runs a background process to fetch the graph definition from object storage
-    pub(crate) async fn start_producer(self, sender: mpsc::Sender<UpdateEvent>) -> crate::Result<()> {
+    ///
+    /// Returns the health of the graph for loaders which can fail after startup.
+    pub(crate) async fn start_producer(
+        self,
+        sender: mpsc::Sender<UpdateEvent>,
+        config: watch::Receiver<Config>,
+    ) -> crate::Result<Option<GraphHealth>> {
         #[cfg(feature = "lambda")]
         if matches!(self, GraphLoader::FromGraphRef { .. }) {
             return Err(crate::Error::InternalError(
@@ -54,14 +93,17 @@ impl GraphLoader {
             GraphLoader::FromGraphRef {
                 access_token,
                 graph_ref,
+                snapshot_dir,
             } => {
                 tokio::spawn(async move {
-                    ObjectStorageUpdater::new(graph_ref, access_token, sender)?.poll().await;
+                    ObjectStorageUpdater::new(graph_ref, access_token, sender, snapshot_dir)?
+                        .poll()
+                        .await;
 
                     Ok::<_, crate::Error>(())
                 });
 
-                Ok(())
+                Ok(None)
             }
             GraphLoader::FromSchemaFile { path } => {
                 let sdl = std::fs::read_to_string(&path).map_err(|err| {
@@ -77,7 +119,7 @@ impl GraphLoader {
                     SchemaFileGraphUpdater::new(path, sender).await.poll().await;
                 });
 
-                Ok(())
+                Ok(None)
             }
             GraphLoader::FromChannel { mut sdl_receiver } => {
                 tokio::spawn(async move {
@@ -88,7 +130,7 @@ impl GraphLoader {
                     }
                 });
 
-                Ok(())
+                Ok(None)
             }
             GraphLoader::FromHiveConsole {
                 endpoints,
@@ -103,7 +145,22 @@ impl GraphLoader {
                     Ok::<_, crate::Error>(())
                 });
 
-                Ok(())
+                Ok(None)
+            }
+            GraphLoader::FromUrl { config: url_config } => {
+                let mut updater = SchemaUrlGraphUpdater::new(url_config, sender)?;
+
+                tokio::spawn(async move { updater.poll().await });
+
+                Ok(None)
+            }
+            GraphLoader::FromSubgraphs { poll_interval } => {
+                let health = GraphHealth::default();
+                let mut composer = SubgraphComposer::new(config, poll_interval, sender, health.clone());
+
+                tokio::spawn(async move { composer.poll().await });
+
+                Ok(Some(health))
             }
         }
     }
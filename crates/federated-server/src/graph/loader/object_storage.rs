@@ -1,13 +1,18 @@
+use super::snapshot::GraphSnapshot;
 use crate::{AccessToken, events::UpdateEvent, graph::Graph};
 
 use grafbase_telemetry::{
     metrics::meter_from_global_provider,
-    otel::opentelemetry::{KeyValue, metrics::Histogram},
+    otel::opentelemetry::{
+        KeyValue,
+        metrics::{Gauge, Histogram},
+    },
 };
 use graph_ref::GraphRef;
 use http::{HeaderValue, StatusCode};
 use std::{
     borrow::Cow,
+    path::PathBuf,
     time::{Duration, SystemTime},
 };
 use tokio::sync::mpsc;
@@ -81,6 +86,10 @@ pub struct ObjectStorageUpdater {
     sender: mpsc::Sender<UpdateEvent>,
     current_id: Option<Ulid>,
     latencies: Histogram<u64>,
+    snapshot: Option<GraphSnapshot>,
+    /// Whether the running graph comes from the snapshot, without object storage having confirmed it's the latest.
+    stale: bool,
+    stale_gauge: Gauge<u64>,
 }
 
 pub(crate) fn object_storage_host() -> Cow<'static, str> {
@@ -98,6 +107,7 @@ impl ObjectStorageUpdater {
     /// * `graph_ref` - A reference to the graph to be updated.
     /// * `access_token` - The access token for authentication with the object storage service.
     /// * `sender` - The sender used to send a new instance of the gateway to the server.
+    /// * `snapshot_dir` - Where the last loaded graph is stored, used on startup if object storage cannot be reached.
     /// * `gateway_config` - Configuration settings for the gateway.
     /// * `hooks` - Hooks for custom behavior during operation execution.
     ///
@@ -108,6 +118,7 @@ impl ObjectStorageUpdater {
         graph_ref: GraphRef,
         access_token: AccessToken,
         sender: mpsc::Sender<UpdateEvent>,
+        snapshot_dir: Option<PathBuf>,
     ) -> crate::Result<Self> {
         let object_storage_client = reqwest::ClientBuilder::new()
             .timeout(OBJECT_STORAGE_TIMEOUT)
@@ -123,6 +134,8 @@ impl ObjectStorageUpdater {
             crate::Error::FetcherConfigError(format!("The configured object storage host is not a valid URL: {err}"))
         })?;
 
+        let snapshot = snapshot_dir.map(|dir| GraphSnapshot::new(&dir, &graph_ref));
+
         object_storage_url.set_path(&match graph_ref {
             GraphRef::LatestProductionVersion { graph_slug } => {
                 format!("/graphs/{graph_slug}")
@@ -144,15 +157,18 @@ impl ObjectStorageUpdater {
             }
         });
 
+        let meter = meter_from_global_provider();
+
         Ok(Self {
             object_storage_url,
             object_storage_client,
             access_token,
             sender,
             current_id: None,
-            latencies: meter_from_global_provider()
-                .u64_histogram("object_storage.request.duration")
-                .build(),
+            latencies: meter.u64_histogram("object_storage.request.duration").build(),
+            snapshot,
+            stale: false,
+            stale_gauge: meter.u64_gauge("object_storage.graph.stale").build(),
         })
     }
 
@@ -163,6 +179,9 @@ impl ObjectStorageUpdater {
     ///
     /// By having the gateway in a reference counter, we make sure the current requests
     /// are served before dropping.
+    ///
+    /// If the first fetch fails and a snapshot directory is configured, the gateway starts with
+    /// the last graph stored there and is reported as stale until object storage responds again.
     pub async fn poll(&mut self) {
         let mut interval = tokio::time::interval(TICK_INTERVAL);
 
@@ -201,6 +220,7 @@ impl ObjectStorageUpdater {
                     );
 
                     tracing::error!("Failed to update graph: {e}");
+                    self.load_snapshot_if_no_graph().await;
                     continue;
                 }
             };
@@ -215,6 +235,7 @@ impl ObjectStorageUpdater {
                 );
 
                 tracing::trace!("no updates to the graph");
+                self.set_stale(false);
                 continue;
             }
 
@@ -237,6 +258,8 @@ impl ObjectStorageUpdater {
                         tracing::error!("Failed to update graph: {e}");
                     }
                 }
+
+                self.load_snapshot_if_no_graph().await;
                 continue;
             }
 
@@ -252,6 +275,7 @@ impl ObjectStorageUpdater {
                     );
 
                     tracing::error!("Failed to update graph: {e}");
+                    self.load_snapshot_if_no_graph().await;
                     continue;
                 }
             };
@@ -269,6 +293,16 @@ impl ObjectStorageUpdater {
             );
 
             self.current_id = Some(version_id);
+            self.set_stale(false);
+
+            if let Some(snapshot) = &self.snapshot
+                && let Err(err) = snapshot.store(&response).await
+            {
+                tracing::warn!(
+                    "Could not store the graph snapshot at {}: {err}",
+                    snapshot.path().display()
+                );
+            }
 
             self.sender
                 .send(UpdateEvent::Graph(Graph::FromGraphRef {
@@ -281,6 +315,56 @@ impl ObjectStorageUpdater {
         }
     }
 
+    /// Starts the gateway from the snapshot if no graph could be loaded from object storage yet.
+    async fn load_snapshot_if_no_graph(&mut self) {
+        if self.current_id.is_some() {
+            return;
+        }
+
+        let Some(snapshot) = &self.snapshot else {
+            return;
+        };
+
+        let Some(response) = snapshot.load().await else {
+            return;
+        };
+
+        tracing::warn!(
+            "Could not fetch the graph from object storage, starting with the snapshot of version {} from {}. The graph may be stale until object storage is reachable again.",
+            response.version_id,
+            snapshot.path().display()
+        );
+
+        self.current_id = Some(response.version_id);
+        self.set_stale(true);
+
+        self.sender
+            .send(UpdateEvent::Graph(Graph::FromGraphRef {
+                branch_id: response.branch_id,
+                version_id: response.version_id,
+                sdl: response.sdl,
+            }))
+            .await
+            .expect("internal error: channel closed");
+    }
+
+    fn set_stale(&mut self, stale: bool) {
+        if self.snapshot.is_none() {
+            return;
+        }
+
+        if self.stale && !stale {
+            tracing::info!("Object storage is reachable again, the graph is up to date");
+        }
+
+        self.stale = stale;
+
+        self.stale_gauge.record(
+            stale as u64,
+            &[KeyValue::new("server.address", self.object_storage_url.to_string())],
+        );
+    }
+
     fn record_duration(
         &self,
         ObjectStorageFetchLatencyAttributes { kind, status_code }: ObjectStorageFetchLatencyAttributes,
@@ -0,0 +1,343 @@
+mod sigv4;
+
+use std::time::Duration;
+
+use http::{HeaderValue, StatusCode};
+use sha2::{Digest, Sha256};
+use tokio::{sync::mpsc, time::MissedTickBehavior};
+use url::Url;
+
+use crate::{events::UpdateEvent, graph::Graph};
+
+pub use sigv4::S3Credentials;
+
+/// How long we wait for a response from the storage.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long we wait until a connection is successfully opened.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The HTTP user-agent header we send to the storage.
+const USER_AGENT: &str = "grafbase-gateway";
+
+/// Where and how to fetch the federated SDL from an HTTP server or an S3-compatible storage.
+#[derive(Debug, Clone)]
+pub struct SchemaUrlConfig {
+    /// The URL of the federated SDL. For S3-compatible storage, the URL of the object.
+    pub url: Url,
+    /// Headers sent with every request, for example for authentication.
+    pub headers: Vec<(String, String)>,
+    /// If set, requests are signed with AWS Signature Version 4.
+    pub s3_credentials: Option<S3Credentials>,
+    /// The URL of the detached SHA-256 checksum of the SDL, in hexadecimal. The graph is only
+    /// updated if the fetched SDL matches it.
+    pub checksum_url: Option<Url>,
+    pub poll_interval: Duration,
+}
+
+/// Polls a URL for changes to the federated SDL, relying on the ETag of the response to only
+/// download the SDL when it changed.
+pub struct SchemaUrlGraphUpdater {
+    config: SchemaUrlConfig,
+    client: reqwest::Client,
+    sender: mpsc::Sender<UpdateEvent>,
+    etag: Option<HeaderValue>,
+    /// For servers which don't send an ETag.
+    current_sha256: Option<String>,
+}
+
+impl SchemaUrlGraphUpdater {
+    pub fn new(config: SchemaUrlConfig, sender: mpsc::Sender<UpdateEvent>) -> crate::Result<Self> {
+        let client = reqwest::ClientBuilder::new()
+            .timeout(REQUEST_TIMEOUT)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+            .map_err(|e| crate::Error::InternalError(e.to_string()))?;
+
+        Ok(Self {
+            config,
+            client,
+            sender,
+            etag: None,
+            current_sha256: None,
+        })
+    }
+
+    /// Fetches the SDL immediately, and after that on every interval. A new graph is only sent if
+    /// the SDL changed and passed the checksum verification.
+    pub async fn poll(&mut self) {
+        let mut interval = tokio::time::interval(self.config.poll_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        loop {
+            interval.tick().await;
+
+            let sdl = match self.next_graph().await {
+                Ok(Some(sdl)) => sdl,
+                Ok(None) => {
+                    tracing::trace!("no updates to the graph");
+                    continue;
+                }
+                Err(err) => {
+                    tracing::error!("Failed to update graph from {}: {err}", self.config.url);
+                    continue;
+                }
+            };
+
+            tracing::info!("Finished fetching new graph");
+
+            if self
+                .sender
+                .send(UpdateEvent::Graph(Graph::FromText { sdl }))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    }
+
+    /// The new SDL, or nothing if it didn't change since the last verified one.
+    async fn next_graph(&mut self) -> Result<Option<String>, String> {
+        let Some((sdl, etag)) = self.fetch_sdl().await? else {
+            return Ok(None);
+        };
+
+        let sha256 = hex::encode(Sha256::digest(sdl.as_bytes()));
+
+        self.verify_checksum(&sha256)
+            .await
+            .map_err(|err| format!("the graph was rejected, {err}"))?;
+
+        // The ETag is only kept once the SDL is verified, so that a rejected SDL is fetched again.
+        self.etag = etag;
+
+        if self.current_sha256.as_ref() == Some(&sha256) {
+            return Ok(None);
+        }
+
+        self.current_sha256 = Some(sha256);
+
+        Ok(Some(sdl))
+    }
+
+    /// The SDL and its ETag, or nothing if the SDL didn't change.
+    async fn fetch_sdl(&self) -> Result<Option<(String, Option<HeaderValue>)>, String> {
+        let mut request = self.request(&self.config.url);
+
+        if let Some(etag) = &self.etag {
+            request = request.header(http::header::IF_NONE_MATCH, etag.clone());
+        }
+
+        let response = request.send().await.map_err(|err| err.to_string())?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+
+        let response = response.error_for_status().map_err(|err| err.to_string())?;
+        let etag = response.headers().get(http::header::ETAG).cloned();
+        let sdl = response.text().await.map_err(|err| err.to_string())?;
+
+        Ok(Some((sdl, etag)))
+    }
+
+    async fn verify_checksum(&self, sha256: &str) -> Result<(), String> {
+        let Some(checksum_url) = &self.config.checksum_url else {
+            return Ok(());
+        };
+
+        let checksum = self
+            .request(checksum_url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| format!("could not fetch the checksum: {err}"))?
+            .text()
+            .await
+            .map_err(|err| format!("could not fetch the checksum: {err}"))?;
+
+        // Also accepts the `sha256sum` output, with the file name after the checksum.
+        let expected = checksum.split_whitespace().next().unwrap_or_default();
+
+        if !expected.eq_ignore_ascii_case(sha256) {
+            return Err(format!(
+                "the SHA-256 checksum {sha256} does not match the expected {expected}"
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn request(&self, url: &Url) -> reqwest::RequestBuilder {
+        let mut request = self.client.get(url.as_str());
+
+        for (name, value) in &self.config.headers {
+            request = request.header(name, value);
+        }
+
+        if let Some(credentials) = &self.config.s3_credentials {
+            for (name, value) in sigv4::sign_get(credentials, url, chrono::Utc::now()) {
+                request = request.header(name, value);
+            }
+        }
+
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{header, header_exists, method, path},
+    };
+
+    use super::*;
+
+    const SDL: &str = "type Query { hello: String }";
+
+    fn updater(server: &MockServer, checksum: bool) -> SchemaUrlGraphUpdater {
+        let config = SchemaUrlConfig {
+            url: format!("{}/schema.graphql", server.uri()).parse().unwrap(),
+            headers: Vec::new(),
+            s3_credentials: None,
+            checksum_url: checksum.then(|| format!("{}/schema.graphql.sha256", server.uri()).parse().unwrap()),
+            poll_interval: Duration::from_secs(10),
+        };
+
+        SchemaUrlGraphUpdater::new(config, mpsc::channel(1).0).unwrap()
+    }
+
+    async fn if_none_match_headers(server: &MockServer) -> Vec<Option<String>> {
+        server
+            .received_requests()
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|request| request.url.path() == "/schema.graphql")
+            .map(|request| {
+                request
+                    .headers
+                    .get(http::header::IF_NONE_MATCH)
+                    .map(|value| value.to_str().unwrap().to_owned())
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn not_modified_keeps_the_graph() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/schema.graphql"))
+            .and(header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/schema.graphql"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(SDL)
+                    .insert_header("etag", "\"v1\""),
+            )
+            .mount(&server)
+            .await;
+
+        let mut updater = updater(&server, false);
+
+        assert_eq!(updater.next_graph().await, Ok(Some(SDL.to_owned())));
+        assert_eq!(updater.next_graph().await, Ok(None));
+
+        assert_eq!(if_none_match_headers(&server).await, [None, Some("\"v1\"".to_owned())]);
+    }
+
+    #[tokio::test]
+    async fn checksum_mismatch_rejects_the_graph() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/schema.graphql"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(SDL)
+                    .insert_header("etag", "\"v1\""),
+            )
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/schema.graphql.sha256"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!("{}  schema.graphql\n", "0".repeat(64))))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+
+        let sha256 = hex::encode(Sha256::digest(SDL.as_bytes()));
+        Mock::given(method("GET"))
+            .and(path("/schema.graphql.sha256"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(sha256.to_uppercase()))
+            .mount(&server)
+            .await;
+
+        let mut updater = updater(&server, true);
+
+        let error = updater.next_graph().await.unwrap_err();
+        assert!(error.contains("does not match"), "{error}");
+
+        // The rejected SDL must be fetched again, without the ETag.
+        assert_eq!(updater.next_graph().await, Ok(Some(SDL.to_owned())));
+        assert_eq!(if_none_match_headers(&server).await, [None, None]);
+    }
+
+    #[tokio::test]
+    async fn missing_etag_compares_the_sdl() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/schema.graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SDL))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+
+        let new_sdl = "type Query { hello: String world: String }";
+        Mock::given(method("GET"))
+            .and(path("/schema.graphql"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(new_sdl))
+            .mount(&server)
+            .await;
+
+        // Nothing would match a conditional request.
+        Mock::given(method("GET"))
+            .and(header_exists("if-none-match"))
+            .respond_with(ResponseTemplate::new(500))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+
+        let mut updater = updater(&server, false);
+
+        assert_eq!(updater.next_graph().await, Ok(Some(SDL.to_owned())));
+        assert_eq!(updater.next_graph().await, Ok(None));
+        assert_eq!(updater.next_graph().await, Ok(Some(new_sdl.to_owned())));
+    }
+
+    #[tokio::test]
+    async fn error_status_is_reported() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/schema.graphql"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+
+        let error = updater(&server, false).next_graph().await.unwrap_err();
+        assert!(error.contains("403"), "{error}");
+    }
+}
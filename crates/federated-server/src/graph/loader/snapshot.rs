@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+
+use graph_ref::GraphRef;
+
+use super::ObjectStorageResponse;
+
+/// The last graph successfully loaded from object storage, kept on disk for the gateway to start
+/// even if object storage can't be reached.
+pub(crate) struct GraphSnapshot {
+    path: PathBuf,
+}
+
+impl GraphSnapshot {
+    /// One snapshot per graph ref, so that gateways of different graphs can share the directory.
+    pub(crate) fn new(dir: &Path, graph_ref: &GraphRef) -> Self {
+        let file_name = graph_ref
+            .to_string()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect::<String>();
+
+        Self {
+            path: dir.join(format!("{file_name}.json")),
+        }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) async fn load(&self) -> Option<ObjectStorageResponse> {
+        let content = match tokio::fs::read(&self.path).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(err) => {
+                tracing::error!("Could not read the graph snapshot at {}: {err}", self.path.display());
+                return None;
+            }
+        };
+
+        match serde_json::from_slice(&content) {
+            Ok(response) => Some(response),
+            Err(err) => {
+                tracing::error!("Invalid graph snapshot at {}: {err}", self.path.display());
+                None
+            }
+        }
+    }
+
+    /// Written to a temporary file first, a crash never leaves a partial snapshot behind.
+    pub(crate) async fn store(&self, response: &ObjectStorageResponse) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let content = serde_json::to_vec(response)?;
+        let tmp_path = self.path.with_extension(format!("json.{}.tmp", response.version_id));
+
+        tokio::fs::write(&tmp_path, content).await?;
+        tokio::fs::rename(&tmp_path, &self.path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ulid::Ulid;
+
+    use super::*;
+
+    fn response() -> ObjectStorageResponse {
+        ObjectStorageResponse {
+            account_id: Ulid::new(),
+            graph_id: Ulid::new(),
+            branch: "main".to_owned(),
+            branch_id: Ulid::new(),
+            sdl: "type Query { hello: String }".to_owned(),
+            version_id: Ulid::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn store_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let graph_ref: GraphRef = "my-graph@main".parse().unwrap();
+        let snapshot = GraphSnapshot::new(&dir.path().join("snapshots"), &graph_ref);
+
+        assert_eq!(snapshot.path().file_name().unwrap(), "my-graph_main.json");
+        assert!(snapshot.load().await.is_none());
+
+        let response = response();
+        snapshot.store(&response).await.unwrap();
+
+        let loaded = snapshot.load().await.unwrap();
+        assert_eq!(loaded.version_id, response.version_id);
+        assert_eq!(loaded.sdl, response.sdl);
+
+        let newer = self::response();
+        snapshot.store(&newer).await.unwrap();
+        assert_eq!(snapshot.load().await.unwrap().version_id, newer.version_id);
+
+        assert_eq!(std::fs::read_dir(dir.path().join("snapshots")).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn invalid_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let graph_ref: GraphRef = "my-graph".parse().unwrap();
+        let snapshot = GraphSnapshot::new(dir.path(), &graph_ref);
+
+        std::fs::write(snapshot.path(), "{").unwrap();
+
+        assert!(snapshot.load().await.is_none());
+    }
+}
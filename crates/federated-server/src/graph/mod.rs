@@ -1,8 +1,13 @@
+mod health;
 mod loader;
+mod signature;
 
 use ulid::Ulid;
 
+pub use health::GraphHealth;
 pub use loader::*;
+pub(crate) use signature::SchemaPublicKey;
+pub use signature::{SchemaSignatureError, sign_sdl};
 
 #[derive(Debug, Clone)]
 pub enum Graph {
@@ -0,0 +1,232 @@
+//! Signatures of federated schemas. The signature is appended to the SDL as a comment on its last
+//! line, so that it travels along with it through any storage:
+//!
+//! ```graphql
+//! type Query { hello: String }
+//! # grafbase-signature: ed25519:<base64 signature of everything before this line>
+//! ```
+
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use gateway_config::SchemaSignatureConfig;
+use p256::{
+    ecdsa::signature::{Signer as _, Verifier as _},
+    pkcs8::{DecodePrivateKey as _, DecodePublicKey as _},
+};
+
+const SIGNATURE_PREFIX: &str = "# grafbase-signature: ";
+
+const ED25519: &str = "ed25519";
+const ECDSA_P256: &str = "ecdsa-p256-sha256";
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchemaSignatureError {
+    #[error("the key is neither a PEM encoded Ed25519 nor ECDSA P-256 key")]
+    InvalidKey,
+    #[error("could not read the public key at {path}: {error}")]
+    ReadKey { path: String, error: std::io::Error },
+    #[error("the schema signature configuration has no public key")]
+    MissingKey,
+    #[error("the schema is not signed")]
+    MissingSignature,
+    #[error("the schema is signed with {found}, but the public key is {expected}")]
+    AlgorithmMismatch { found: String, expected: &'static str },
+    #[error("the schema signature is invalid")]
+    InvalidSignature,
+}
+
+/// The public key verifying the signatures of federated schemas.
+pub(crate) enum SchemaPublicKey {
+    Ed25519(ed25519_compact::PublicKey),
+    EcdsaP256(p256::ecdsa::VerifyingKey),
+}
+
+impl SchemaPublicKey {
+    /// Reads the configured public key, from the configuration itself or from its file.
+    pub(crate) fn from_config(config: &SchemaSignatureConfig) -> Result<Self, SchemaSignatureError> {
+        match (&config.public_key, &config.public_key_path) {
+            (Some(pem), _) => Self::from_pem(pem),
+            (None, Some(path)) => {
+                let pem = std::fs::read_to_string(path).map_err(|error| SchemaSignatureError::ReadKey {
+                    path: path.display().to_string(),
+                    error,
+                })?;
+
+                Self::from_pem(&pem)
+            }
+            (None, None) => Err(SchemaSignatureError::MissingKey),
+        }
+    }
+
+    fn from_pem(pem: &str) -> Result<Self, SchemaSignatureError> {
+        if let Ok(key) = ed25519_compact::PublicKey::from_pem(pem) {
+            return Ok(Self::Ed25519(key));
+        }
+
+        p256::ecdsa::VerifyingKey::from_public_key_pem(pem)
+            .map(Self::EcdsaP256)
+            .map_err(|_| SchemaSignatureError::InvalidKey)
+    }
+
+    fn algorithm(&self) -> &'static str {
+        match self {
+            SchemaPublicKey::Ed25519(_) => ED25519,
+            SchemaPublicKey::EcdsaP256(_) => ECDSA_P256,
+        }
+    }
+
+    /// Verifies the signature of a federated SDL.
+    pub(crate) fn verify(&self, sdl: &str) -> Result<(), SchemaSignatureError> {
+        let (message, Some(signature_line)) = split_signature(sdl) else {
+            return Err(SchemaSignatureError::MissingSignature);
+        };
+
+        let (algorithm, signature) = signature_line
+            .split_once(':')
+            .ok_or(SchemaSignatureError::InvalidSignature)?;
+
+        if algorithm != self.algorithm() {
+            return Err(SchemaSignatureError::AlgorithmMismatch {
+                found: algorithm.to_owned(),
+                expected: self.algorithm(),
+            });
+        }
+
+        let signature = STANDARD
+            .decode(signature.trim())
+            .map_err(|_| SchemaSignatureError::InvalidSignature)?;
+
+        let valid = match self {
+            SchemaPublicKey::Ed25519(key) => ed25519_compact::Signature::from_slice(&signature)
+                .is_ok_and(|signature| key.verify(message.as_bytes(), &signature).is_ok()),
+            SchemaPublicKey::EcdsaP256(key) => p256::ecdsa::Signature::from_slice(&signature)
+                .is_ok_and(|signature| key.verify(message.as_bytes(), &signature).is_ok()),
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(SchemaSignatureError::InvalidSignature)
+        }
+    }
+}
+
+/// Signs a federated SDL with a PEM encoded Ed25519 or ECDSA P-256 private key, replacing any
+/// previous signature.
+pub fn sign_sdl(sdl: &str, private_key_pem: &str) -> Result<String, SchemaSignatureError> {
+    let (sdl, _) = split_signature(sdl);
+
+    let mut signed = sdl.to_owned();
+    if !signed.ends_with('\n') {
+        signed.push('\n');
+    }
+
+    let (algorithm, signature) = if let Ok(key) = ed25519_compact::SecretKey::from_pem(private_key_pem) {
+        (ED25519, key.sign(signed.as_bytes(), None).to_vec())
+    } else if let Ok(key) = p256::ecdsa::SigningKey::from_pkcs8_pem(private_key_pem) {
+        let signature: p256::ecdsa::Signature = key.sign(signed.as_bytes());
+        (ECDSA_P256, signature.to_bytes().to_vec())
+    } else {
+        return Err(SchemaSignatureError::InvalidKey);
+    };
+
+    signed.push_str(SIGNATURE_PREFIX);
+    signed.push_str(algorithm);
+    signed.push(':');
+    signed.push_str(&STANDARD.encode(signature));
+    signed.push('\n');
+
+    Ok(signed)
+}
+
+/// The signed part of the SDL, and the signature line without its prefix if any.
+fn split_signature(sdl: &str) -> (&str, Option<&str>) {
+    let trimmed = sdl.trim_end_matches(['\n', '\r']);
+
+    let (message, last_line) = match trimmed.rfind('\n') {
+        Some(pos) => (&sdl[..=pos], &trimmed[pos + 1..]),
+        None => ("", trimmed),
+    };
+
+    match last_line.strip_prefix(SIGNATURE_PREFIX) {
+        Some(signature) => (message, Some(signature)),
+        None => (sdl, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use p256::pkcs8::{EncodePrivateKey as _, EncodePublicKey as _, LineEnding};
+
+    use super::*;
+
+    const SDL: &str = "type Query {\n  hello: String\n}\n";
+
+    fn verify_sdl_with_key(sdl: &str, public_key_pem: &str) -> Result<(), SchemaSignatureError> {
+        SchemaPublicKey::from_pem(public_key_pem)?.verify(sdl)
+    }
+
+    fn ed25519_keys(seed: u8) -> (String, String) {
+        let key_pair = ed25519_compact::KeyPair::from_seed(ed25519_compact::Seed::new([seed; 32]));
+        (key_pair.sk.to_pem(), key_pair.pk.to_pem())
+    }
+
+    fn p256_keys(seed: u8) -> (String, String) {
+        let key = p256::ecdsa::SigningKey::from_slice(&[seed; 32]).unwrap();
+
+        (
+            key.to_pkcs8_pem(LineEnding::LF).unwrap().to_string(),
+            key.verifying_key().to_public_key_pem(LineEnding::LF).unwrap(),
+        )
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        for (private_key, public_key) in [ed25519_keys(1), p256_keys(1)] {
+            let signed = sign_sdl(SDL, &private_key).unwrap();
+
+            assert!(signed.starts_with(SDL));
+            assert!(signed.lines().last().unwrap().starts_with(SIGNATURE_PREFIX));
+            verify_sdl_with_key(&signed, &public_key).unwrap();
+
+            // Signing again replaces the signature.
+            let resigned = sign_sdl(&signed, &private_key).unwrap();
+            assert_eq!(resigned.lines().count(), signed.lines().count());
+            verify_sdl_with_key(&resigned, &public_key).unwrap();
+        }
+    }
+
+    #[test]
+    fn unsigned_sdl_without_trailing_newline() {
+        let (private_key, public_key) = ed25519_keys(1);
+
+        let signed = sign_sdl(SDL.trim_end(), &private_key).unwrap();
+        verify_sdl_with_key(&signed, &public_key).unwrap();
+    }
+
+    #[test]
+    fn rejected_schemas() {
+        let (private_key, public_key) = ed25519_keys(1);
+        let signed = sign_sdl(SDL, &private_key).unwrap();
+
+        let err = verify_sdl_with_key(SDL, &public_key).unwrap_err();
+        assert!(matches!(err, SchemaSignatureError::MissingSignature), "{err}");
+
+        let tampered = signed.replace("hello", "goodbye");
+        let err = verify_sdl_with_key(&tampered, &public_key).unwrap_err();
+        assert!(matches!(err, SchemaSignatureError::InvalidSignature), "{err}");
+
+        let (_, other_public_key) = ed25519_keys(2);
+        let err = verify_sdl_with_key(&signed, &other_public_key).unwrap_err();
+        assert!(matches!(err, SchemaSignatureError::InvalidSignature), "{err}");
+
+        let (_, p256_public_key) = p256_keys(1);
+        let err = verify_sdl_with_key(&signed, &p256_public_key).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the schema is signed with ed25519, but the public key is ecdsa-p256-sha256"
+        );
+
+        let err = verify_sdl_with_key(&signed, "not a key").unwrap_err();
+        assert!(matches!(err, SchemaSignatureError::InvalidKey), "{err}");
+    }
+}
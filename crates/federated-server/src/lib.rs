@@ -7,7 +7,9 @@ mod hot_reload;
 
 pub use access_token::AccessToken;
 pub use error::Error;
-pub use graph::{GraphLoader, ObjectStorageResponse};
+pub use graph::{
+    GraphHealth, GraphLoader, ObjectStorageResponse, S3Credentials, SchemaSignatureError, SchemaUrlConfig, sign_sdl,
+};
 
 mod engine;
 mod error;
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, routing::post};
+use gateway_config::EntityCachingInvalidationEndpointConfig;
+use http::StatusCode;
+use runtime::entity_cache;
+
+use super::EngineWatcher;
+
+/// Body of the invalidation requests:
+///
+/// ```json
+/// { "entities": [{ "type": "Product", "key": { "upc": "1" } }, { "type": "Review" }] }
+/// ```
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InvalidationRequest {
+    entities: Vec<EntityInvalidation>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EntityInvalidation {
+    #[serde(rename = "type")]
+    type_name: String,
+    /// Without key, all the entities of the type are invalidated.
+    #[serde(default)]
+    key: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+struct InvalidationState<R: engine::Runtime> {
+    engine: EngineWatcher<R>,
+    // Hashed so that comparisons are done in constant time.
+    token: blake3::Hash,
+}
+
+/// Creates the router of the endpoint invalidating entity cache entries on demand.
+pub(super) fn router<R: engine::Runtime>(
+    engine: EngineWatcher<R>,
+    config: &EntityCachingInvalidationEndpointConfig,
+) -> crate::Result<axum::Router> {
+    let Some(token) = config.token.as_deref().filter(|token| !token.is_empty()) else {
+        return Err(crate::Error::InternalError(
+            "The entity cache invalidation endpoint requires a token".to_string(),
+        ));
+    };
+
+    let state = Arc::new(InvalidationState {
+        engine,
+        token: blake3::hash(token.as_bytes()),
+    });
+
+    Ok(axum::Router::new()
+        .route(&config.path, post(invalidate::<R>))
+        .with_state(state))
+}
+
+async fn invalidate<R: engine::Runtime>(
+    State(state): State<Arc<InvalidationState<R>>>,
+    headers: http::HeaderMap,
+    Json(request): Json<InvalidationRequest>,
+) -> (StatusCode, &'static str) {
+    let token = headers
+        .get(http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    if token.map(|token| blake3::hash(token.as_bytes())) != Some(state.token) {
+        return (StatusCode::UNAUTHORIZED, "Unauthorized");
+    }
+
+    let tags = request
+        .entities
+        .iter()
+        .map(|entity| match &entity.key {
+            Some(key) => entity_cache::entity_tag(&entity.type_name, key),
+            None => entity_cache::type_tag(&entity.type_name),
+        })
+        .collect::<Vec<_>>();
+
+    tracing::info!("Invalidating entity cache entries: {}", tags.join(", "));
+
+    let engine = state.engine.borrow().clone();
+    match engine.invalidate_entity_cache(&tags).await {
+        Ok(()) => (StatusCode::OK, "OK"),
+        Err(err) => {
+            tracing::error!("Failed to invalidate the entity cache: {err}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to invalidate the entity cache",
+            )
+        }
+    }
+}
@@ -5,38 +5,66 @@ use gateway_config::{HealthConfig, TlsConfig};
 use axum::{Json, Router, routing::get};
 use http::StatusCode;
 
+use crate::GraphHealth;
+
 #[derive(Debug, serde::Serialize)]
 #[serde(tag = "status", rename_all = "lowercase")]
 pub(crate) enum HealthState {
     /// Indicates that the server is healthy and operational.
     Healthy,
 
+    /// Indicates that the server serves a graph, but the latest one could not be loaded.
+    Degraded {
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        errors: Vec<String>,
+    },
+
     /// Indicates that the server is unhealthy and not operational.
-    #[expect(dead_code)] // I assume we'll use this sometime
-    Unhealthy,
+    Unhealthy {
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        errors: Vec<String>,
+    },
 }
 
 /// Handles health check requests and returns the current health status of the server.
 ///
 /// # Arguments
 ///
-/// - `State(state)`: The server state containing the gateway information.
+/// - `graph_health`: The health of the graph, if loaded by the gateway itself.
+/// - `expose_errors`: Whether to include the errors of the latest graph load, which may reveal
+///   details about the subgraphs. They're always logged.
 ///
 /// # Returns
 ///
 /// A tuple containing the HTTP status code and a JSON representation of the health status.
-pub(crate) async fn health() -> (StatusCode, Json<HealthState>) {
-    (StatusCode::OK, Json(HealthState::Healthy))
+pub(crate) async fn health(graph_health: Option<GraphHealth>, expose_errors: bool) -> (StatusCode, Json<HealthState>) {
+    let Some(graph_health) = graph_health else {
+        return (StatusCode::OK, Json(HealthState::Healthy));
+    };
+
+    let (loaded, mut errors) = graph_health.status();
+    let degraded = !errors.is_empty();
+
+    if !expose_errors {
+        errors.clear();
+    }
+
+    match (loaded, degraded) {
+        (false, _) => (StatusCode::SERVICE_UNAVAILABLE, Json(HealthState::Unhealthy { errors })),
+        (true, false) => (StatusCode::OK, Json(HealthState::Healthy)),
+        (true, true) => (StatusCode::OK, Json(HealthState::Degraded { errors })),
+    }
 }
 
-/// Binds the health check endpoint to the specified address and configuration.
+/// Binds the health check endpoint to the specified address and configuration. Only this
+/// dedicated listener reports the errors of the latest graph load.
 ///
 /// # Arguments
 ///
 /// - `addr`: The socket address to bind the server to.
 /// - `tls_config`: Optional TLS configuration for secure connections.
 /// - `health_config`: Configuration for health check settings.
-/// - `state`: The current state of the server.
+/// - `graph_health`: The health of the graph, if loaded by the gateway itself.
 ///
 /// # Returns
 ///
@@ -45,10 +73,13 @@ pub(super) async fn bind_health_endpoint(
     addr: SocketAddr,
     tls_config: Option<TlsConfig>,
     health_config: HealthConfig,
+    graph_health: Option<GraphHealth>,
 ) -> crate::Result<()> {
     let scheme = if tls_config.is_some() { "https" } else { "http" };
     let path = &health_config.path;
-    let app = Router::new().route(path, get(health)).into_make_service();
+    let app = Router::new()
+        .route(path, get(move || health(graph_health.clone(), true)))
+        .into_make_service();
 
     tracing::info!("Health check endpoint exposed at {scheme}://{addr}{path}");
 
@@ -1,6 +1,6 @@
-use std::{fmt::Display, future::Future, pin::Pin, sync::Arc};
+use std::{fmt::Display, future::Future, net::SocketAddr, pin::Pin, sync::Arc};
 
-use axum::body::Body;
+use axum::{body::Body, extract::ConnectInfo};
 use engine::{ErrorResponse, GraphqlError, RequestExtensions};
 use event_queue::ExecutedHttpRequest;
 use extension_catalog::ExtensionId;
@@ -136,11 +136,17 @@ where
 
             let response = match result {
                 Ok(token) => {
+                    let ip = parts
+                        .extensions
+                        .get::<ConnectInfo<SocketAddr>>()
+                        .map(|ConnectInfo(addr)| addr.ip());
+
                     parts.extensions.insert(RequestExtensions {
                         hooks_context: hooks_context.clone(),
                         event_queue: event_queue.clone(),
                         token,
                         contract_key: contract_key.or_else(|| layer.default_contract_key.clone()),
+                        ip,
                     });
 
                     next.call(Request::from_parts(parts, body)).await?
@@ -0,0 +1,67 @@
+use axum::{Router, routing::get};
+use gateway_config::{PrometheusExporterConfig, TlsConfig};
+use grafbase_telemetry::otel::metrics::prometheus::{self, PrometheusExporter};
+use http::{StatusCode, header};
+
+/// Handles Prometheus scrape requests, returning all the gateway metrics in the text exposition format.
+async fn metrics(exporter: &'static PrometheusExporter) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    match exporter.encode() {
+        Ok(body) => ([(header::CONTENT_TYPE, prometheus::CONTENT_TYPE)], body).into_response(),
+        Err(err) => {
+            tracing::error!("Failed to collect metrics: {err}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Binds the Prometheus metrics endpoint to its dedicated listener.
+///
+/// # Arguments
+///
+/// - `tls_config`: Optional TLS configuration for secure connections.
+/// - `prometheus_config`: Configuration for the metrics endpoint.
+/// - `exporter`: The exporter collecting the gateway metrics.
+///
+/// # Returns
+///
+/// The future serving the endpoint once the address is bound, so that binding failures are
+/// reported at startup. Errors while serving are logged.
+pub(super) async fn bind_metrics_endpoint(
+    tls_config: Option<TlsConfig>,
+    prometheus_config: &PrometheusExporterConfig,
+    exporter: &'static PrometheusExporter,
+) -> crate::Result<impl Future<Output = ()> + Send + 'static> {
+    let addr = prometheus_config.listen;
+    let scheme = if tls_config.is_some() { "https" } else { "http" };
+    let path = &prometheus_config.path;
+    let app = Router::new()
+        .route(path, get(move || metrics(exporter)))
+        .into_make_service();
+
+    let listener = std::net::TcpListener::bind(addr).map_err(crate::Error::Server)?;
+    listener.set_nonblocking(true).map_err(crate::Error::Server)?;
+
+    let rustls_config = match tls_config {
+        Some(tls) => Some(
+            axum_server::tls_rustls::RustlsConfig::from_pem_file(&tls.certificate, &tls.key)
+                .await
+                .map_err(crate::Error::CertificateError)?,
+        ),
+        None => None,
+    };
+
+    tracing::info!("Prometheus metrics endpoint exposed at {scheme}://{addr}{path}");
+
+    Ok(async move {
+        let result = match rustls_config {
+            Some(rustls_config) => axum_server::from_tcp_rustls(listener, rustls_config).serve(app).await,
+            None => axum_server::from_tcp(listener).serve(app).await,
+        };
+
+        if let Err(err) = result {
+            tracing::error!("Prometheus metrics endpoint failed: {err}");
+        }
+    })
+}
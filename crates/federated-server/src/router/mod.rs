@@ -1,6 +1,8 @@
+mod entity_cache;
 mod graphql;
 mod health;
 pub(crate) mod layers;
+mod metrics;
 mod public_metadata;
 mod state;
 
@@ -24,6 +26,7 @@ use crate::router::{
 };
 
 use super::ServerRuntime;
+use crate::GraphHealth;
 
 pub struct RouterConfig<R, SR, E>
 where
@@ -37,6 +40,8 @@ where
     pub server_runtime: SR,
     pub extensions: E,
     pub listen_address: Option<SocketAddr>,
+    /// Reported by the health endpoint when the gateway loads the graph itself.
+    pub graph_health: Option<GraphHealth>,
 }
 
 pub type EngineWatcher<R> = watch::Receiver<Arc<ContractAwareEngine<R>>>;
@@ -49,6 +54,7 @@ pub async fn create<R, SR, E>(
         server_runtime,
         extensions,
         listen_address,
+        graph_health,
     }: RouterConfig<R, SR, E>,
 ) -> crate::Result<(axum::Router, Option<CancellationToken>)>
 where
@@ -171,14 +177,52 @@ where
         _ => None,
     };
 
+    //
+    // == /admin/entity-cache/invalidate ==
+    //
+    let invalidation_endpoint = &config.entity_caching.invalidation.endpoint;
+    if invalidation_endpoint.enabled {
+        let entity_cache_router = entity_cache::router(engine.clone(), invalidation_endpoint)?;
+        router = router.merge(
+            entity_cache_router.layer(
+                common_layers
+                    .clone()
+                    .layer(telemetry.clone().with_route(&invalidation_endpoint.path)),
+            ),
+        );
+    }
+
     //
     // == /health ==
     //
     if config.health.enabled {
         if let Some(listen) = config.health.listen {
-            tokio::spawn(health::bind_health_endpoint(listen, config.tls.clone(), config.health));
+            tokio::spawn(health::bind_health_endpoint(
+                listen,
+                config.tls.clone(),
+                config.health,
+                graph_health,
+            ));
         } else {
-            router = router.route(&config.health.path, get(health::health));
+            // The public listener doesn't expose the graph load errors, they're only logged.
+            router = router.route(
+                &config.health.path,
+                get(move || health::health(graph_health.clone(), false)),
+            );
+        }
+    }
+
+    //
+    // == /metrics ==
+    //
+    if let Some(prometheus_config) = config.telemetry.metrics_prometheus_config() {
+        match grafbase_telemetry::otel::metrics::prometheus::global_exporter() {
+            Some(exporter) => {
+                // Metrics are never served on the gateway listener, which may be publicly exposed.
+                let serve = metrics::bind_metrics_endpoint(config.tls.clone(), prometheus_config, exporter).await?;
+                tokio::spawn(serve);
+            }
+            None => tracing::warn!("The Prometheus exporter is enabled, but metrics are not collected"),
         }
     }
 
@@ -74,8 +74,12 @@ pub async fn serve(
     // Create the central channel for all update events
     let (update_sender, update_receiver) = mpsc::channel::<UpdateEvent>(16);
 
+    let verify_graph_signatures = graph_loader.loads_published_graphs();
+
     // Start the graph producer
-    graph_loader.start_producer(update_sender.clone()).await?;
+    let graph_health = graph_loader
+        .start_producer(update_sender.clone(), config_receiver.clone())
+        .await?;
 
     // Bridge config updates to the central channel if hot reload is enabled
     if config_hot_reload {
@@ -99,6 +103,8 @@ pub async fn serve(
         hot_reload_config_path: config_hot_reload.then_some(config_path).flatten(),
         access_token: grafbase_access_token,
         gateway_extensions: gateway_extensions.clone(),
+        verify_graph_signatures,
+        graph_health: graph_health.clone(),
     })
     .await?;
 
@@ -115,6 +121,7 @@ pub async fn serve(
         server_runtime: server_runtime.clone(),
         extensions: gateway_extensions,
         listen_address: Some(listen_address),
+        graph_health,
     };
 
     // Generate all routes for the HTTP server.
@@ -162,7 +169,7 @@ async fn bind(
     server_runtime: impl ServerRuntime,
     mcp_url: Option<String>,
 ) -> crate::Result<()> {
-    let app = router.into_make_service();
+    let app = router.into_make_service_with_connect_info::<SocketAddr>();
 
     let handle = axum_server::Handle::new();
 
@@ -1,9 +1,13 @@
+use std::time::Duration;
+
 #[derive(Debug, Default, serde::Deserialize, Clone, PartialEq)]
 #[serde(default, deny_unknown_fields)]
 pub struct ComplexityControlConfig {
     pub mode: Option<ComplexityControlMode>,
     pub limit: Option<usize>,
     pub list_size: Option<usize>,
+    /// Total cost a single client may spend per time window.
+    pub budget: Option<CostBudgetConfig>,
 }
 
 #[derive(Debug, serde::Deserialize, Clone, PartialEq)]
@@ -12,3 +16,36 @@ pub enum ComplexityControlMode {
     Measure,
     Enforce,
 }
+
+#[derive(Debug, serde::Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CostBudgetConfig {
+    /// The cost a client may spend within the duration.
+    pub limit: usize,
+    /// The time window of the budget.
+    #[serde(deserialize_with = "duration_str::deserialize_duration")]
+    pub duration: Duration,
+    /// How clients are identified.
+    pub key: CostBudgetKey,
+    /// What to do with requests which don't provide the key.
+    pub missing_key: CostBudgetMissingKey,
+}
+
+#[derive(Debug, serde::Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum CostBudgetKey {
+    /// Name of the request header identifying the client.
+    Header(String),
+    /// JWT claim identifying the client. Nested claims can be accessed with a dot-separated path,
+    /// such as `org.id`.
+    JwtClaim(String),
+}
+
+#[derive(Debug, serde::Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CostBudgetMissingKey {
+    /// The request isn't subject to any budget.
+    Allow,
+    /// The request is rejected.
+    Reject,
+}
@@ -1,4 +1,4 @@
-use std::{path::PathBuf, time::Duration};
+use std::{collections::BTreeMap, path::PathBuf, time::Duration};
 
 const DEFAULT_ENTITY_CACHE_TTL: Duration = Duration::from_secs(60);
 
@@ -10,6 +10,14 @@ pub struct SubgraphEntityCachingConfig {
     /// The ttl to store cache entries with. Defaults to global entity cache TTL value
     #[serde(deserialize_with = "duration_str::deserialize_option_duration")]
     pub ttl: Option<Duration>,
+    /// How long expired entries are still served while being refreshed in the background.
+    /// Defaults to the global entity cache value.
+    #[serde(deserialize_with = "duration_str::deserialize_option_duration")]
+    pub stale_while_revalidate: Option<Duration>,
+    /// How long expired entries are still served when the subgraph request fails or times out.
+    /// Defaults to the global entity cache value.
+    #[serde(deserialize_with = "duration_str::deserialize_option_duration")]
+    pub stale_if_error: Option<Duration>,
 }
 
 #[derive(Debug, serde::Deserialize, Clone, PartialEq)]
@@ -22,6 +30,20 @@ pub struct EntityCachingConfig {
     /// The ttl to store cache entries with.  Defaults to 60s
     #[serde(deserialize_with = "duration_str::deserialize_duration")]
     pub ttl: Duration,
+
+    /// How long expired entries are still served while being refreshed in the background.
+    /// Disabled by default.
+    #[serde(deserialize_with = "duration_str::deserialize_option_duration")]
+    pub stale_while_revalidate: Option<Duration>,
+
+    /// How long expired entries are still served when the subgraph request fails or times out.
+    /// Disabled by default.
+    #[serde(deserialize_with = "duration_str::deserialize_option_duration")]
+    pub stale_if_error: Option<Duration>,
+
+    /// Removal of cache entries before they expire. Cached root field responses are only tagged
+    /// with the types they return, so invalidating a single entity doesn't remove them.
+    pub invalidation: EntityCachingInvalidationConfig,
 }
 
 impl Default for EntityCachingConfig {
@@ -31,10 +53,56 @@ impl Default for EntityCachingConfig {
             storage: Default::default(),
             redis: Default::default(),
             ttl: DEFAULT_ENTITY_CACHE_TTL,
+            stale_while_revalidate: None,
+            stale_if_error: None,
+            invalidation: Default::default(),
         }
     }
 }
 
+#[derive(Debug, Default, serde::Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EntityCachingInvalidationConfig {
+    /// HTTP endpoint to invalidate entities on demand.
+    pub endpoint: EntityCachingInvalidationEndpointConfig,
+    /// Mutations invalidating entities once executed.
+    pub mutations: Vec<EntityCachingMutationInvalidation>,
+}
+
+#[derive(Debug, serde::Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EntityCachingInvalidationEndpointConfig {
+    pub enabled: bool,
+    pub path: String,
+    /// Bearer token the requests must provide in the authorization header. Required if the
+    /// endpoint is enabled.
+    pub token: Option<String>,
+}
+
+impl Default for EntityCachingInvalidationEndpointConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "/admin/entity-cache/invalidate".to_string(),
+            token: None,
+        }
+    }
+}
+
+#[derive(Debug, serde::Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EntityCachingMutationInvalidation {
+    /// Name of the mutation field.
+    pub field: String,
+    /// Entity type to invalidate.
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// Maps the entity key fields to the mutation arguments, such as `{ id = "input.id" }`.
+    /// When present only the entity with this key is invalidated, otherwise all entities of the type are.
+    #[serde(default)]
+    pub key: Option<BTreeMap<String, String>>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum EntityCachingStorage {
@@ -43,6 +111,7 @@ pub enum EntityCachingStorage {
     Redis,
 }
 
+/// Requires Redis 7.0 or later, Redis Cluster is supported.
 #[derive(Debug, Clone, PartialEq, serde::Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct EntityCachingRedisConfig {
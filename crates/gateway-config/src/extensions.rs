@@ -1,4 +1,7 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 use semver::VersionReq;
 use serde::{Deserialize, Deserializer};
@@ -56,9 +59,26 @@ pub struct StructuredExtensionConfig {
     pub stderr: Option<bool>,
     pub environment_variables: Option<bool>,
     pub max_pool_size: Option<usize>,
+    /// Redis connection pools of the extension, by the name it connects with.
+    pub redis: BTreeMap<String, ExtensionRedisConfig>,
     pub config: Option<toml::Value>,
 }
 
+#[derive(PartialEq, Default, serde::Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExtensionRedisConfig {
+    /// TLS configuration for `rediss://` URLs.
+    pub tls: Option<ExtensionRedisTlsConfig>,
+}
+
+#[derive(PartialEq, Default, serde::Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ExtensionRedisTlsConfig {
+    pub cert: Option<PathBuf>,
+    pub key: Option<PathBuf>,
+    pub ca: Option<PathBuf>,
+}
+
 impl Default for StructuredExtensionConfig {
     fn default() -> Self {
         Self {
@@ -69,6 +89,7 @@ impl Default for StructuredExtensionConfig {
             stderr: None,
             environment_variables: None,
             max_pool_size: None,
+            redis: BTreeMap::new(),
             config: None,
         }
     }
@@ -124,6 +145,13 @@ impl ExtensionConfig {
         }
     }
 
+    pub fn redis(&self) -> Option<&BTreeMap<String, ExtensionRedisConfig>> {
+        match self {
+            ExtensionConfig::Version(_) => None,
+            ExtensionConfig::Structured(config) => Some(&config.redis),
+        }
+    }
+
     pub fn config(&self) -> Option<&toml::Value> {
         match self {
             ExtensionConfig::Version(_) => None,
@@ -167,4 +195,43 @@ mod tests {
 
         toml::from_str::<StructuredExtensionConfig>(toml).unwrap();
     }
+
+    #[test]
+    fn redis_pools() {
+        let toml = r#"
+            version = "1.0"
+
+            [redis.sessions.tls]
+            cert = "/certs/client.crt"
+            key = "/certs/client.key"
+            ca = "/certs/ca.crt"
+
+            [redis.cache]
+        "#;
+
+        let config = toml::from_str::<StructuredExtensionConfig>(toml).unwrap();
+
+        insta::assert_debug_snapshot!(config.redis, @r#"
+        {
+            "cache": ExtensionRedisConfig {
+                tls: None,
+            },
+            "sessions": ExtensionRedisConfig {
+                tls: Some(
+                    ExtensionRedisTlsConfig {
+                        cert: Some(
+                            "/certs/client.crt",
+                        ),
+                        key: Some(
+                            "/certs/client.key",
+                        ),
+                        ca: Some(
+                            "/certs/ca.crt",
+                        ),
+                    },
+                ),
+            },
+        }
+        "#);
+    }
 }
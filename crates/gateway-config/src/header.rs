@@ -89,10 +89,20 @@ pub struct HeaderForward {
 pub struct HeaderInsert {
     /// The name of the header.
     pub name: AsciiString,
-    /// The value of the header.
+    /// The value of the header. It can be a template rendered for each request, e.g.
+    /// `{{ jwt.sub }}`, with the following variables:
+    /// - `jwt`: the claims of the authentication token,
+    /// - `client`: the `ip`, `name` and `version` of the client,
+    /// - `operation`: the `name` and `type` of the operation, only available in subgraph rules,
+    /// - `headers`: the client request headers with lowercase names, e.g. `{{ headers.x-foo }}`.
+    ///
+    /// The header isn't inserted if the template renders to nothing.
     pub value: AsciiString,
 }
 
+/// Variables of the templated header values, kept as is when expanding environment variables.
+pub(crate) const HEADER_TEMPLATE_SCOPES: &[&str] = &["jwt", "client", "operation", "headers"];
+
 /// Header removal rules
 #[derive(Deserialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
@@ -101,3 +111,44 @@ pub struct HeaderRemove {
     #[serde(flatten)]
     pub name: NameOrPattern,
 }
+
+/// Defines a rule applied to the headers of the subgraph responses, shaping the response
+/// headers sent back to the client. Subgraph response headers are never cached, so responses
+/// served from the entity or response cache don't get any.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "rule")]
+pub enum ResponseHeaderRule {
+    /// Propagate the header from the subgraph response to the client response.
+    #[serde(rename = "propagate")]
+    Propagate(HeaderPropagate),
+}
+
+/// Header propagation rules.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HeaderPropagate {
+    /// Name or pattern of the header to be propagated.
+    #[serde(flatten)]
+    pub name: NameOrPattern,
+    /// Use this name instead of the original when propagating.
+    pub rename: Option<AsciiString>,
+    /// How to merge the header when multiple subgraph responses contain it.
+    #[serde(default)]
+    pub merge: HeaderMergeStrategy,
+}
+
+/// Merge strategy of a propagated header returned by multiple subgraphs.
+#[derive(serde::Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HeaderMergeStrategy {
+    /// Keep the value of the first subgraph response.
+    First,
+    /// Keep the value of the last subgraph response.
+    #[default]
+    Last,
+    /// Keep the values of all subgraph responses.
+    Append,
+    /// Merge `Cache-Control` values, keeping the most restrictive directives and the lowest
+    /// `max-age`.
+    MinMaxAge,
+}
@@ -17,6 +17,8 @@ mod mcp;
 pub mod message_signatures;
 pub mod operation_caching;
 pub mod rate_limit;
+pub mod response_caching;
+mod schema_signature;
 mod size_ext;
 mod subscription_protocol;
 pub mod telemetry;
@@ -37,6 +39,7 @@ use std::{
 pub use self::{
     log_level::*,
     mcp::{McpTransport, ModelControlProtocolConfig},
+    schema_signature::SchemaSignatureConfig,
     subscription_protocol::SubscriptionProtocol,
     trusted_documents::*,
     websockets_config::WebsocketsConfig,
@@ -51,6 +54,7 @@ pub use health::*;
 pub use hooks::*;
 pub use message_signatures::MessageSignaturesConfig;
 pub use rate_limit::*;
+pub use response_caching::*;
 use size::Size;
 pub use telemetry::*;
 pub use traffic_shaping::*;
@@ -102,26 +106,31 @@ impl Loader {
             value: &'a mut toml::Value,
         ) -> Result<(), String> {
             match value {
-                toml::Value::String(s) => match serde_dynamic_string::DynamicString::<String>::from_str(s) {
-                    Ok(out) => *s = out.into_inner(),
-                    Err(err) => {
-                        use std::fmt::Write;
-                        let mut p = String::new();
-                        for segment in path {
-                            match segment {
-                                Ok(s) => {
-                                    p.push_str(s);
-                                    p.push('.');
+                toml::Value::String(s) => {
+                    match serde_dynamic_string::DynamicString::<String>::from_str_with_passthrough(
+                        s,
+                        header::HEADER_TEMPLATE_SCOPES,
+                    ) {
+                        Ok(out) => *s = out.into_inner(),
+                        Err(err) => {
+                            use std::fmt::Write;
+                            let mut p = String::new();
+                            for segment in path {
+                                match segment {
+                                    Ok(s) => {
+                                        p.push_str(s);
+                                        p.push('.');
+                                    }
+                                    Err(i) => write!(p, "[{i}]").unwrap(),
                                 }
-                                Err(i) => write!(p, "[{i}]").unwrap(),
                             }
+                            if p.ends_with('.') {
+                                p.pop();
+                            }
+                            return Err(format!("At {p}, failed substituing environment variable: {err}",));
                         }
-                        if p.ends_with('.') {
-                            p.pop();
-                        }
-                        return Err(format!("At {p}, failed substituing environment variable: {err}",));
                     }
-                },
+                }
                 toml::Value::Array(values) => {
                     for (i, value) in values.iter_mut().enumerate() {
                         path.push(Err(i));
@@ -150,6 +159,8 @@ impl Loader {
         let mut config =
             Config::deserialize(raw_config).map_err(|err| format!("Failed to parse configuration: {err}"))?;
 
+        rate_limit::validate_burst(&config)?;
+
         config.path = Some(if path.is_relative() {
             let cdir = match self.current_dir.as_ref() {
                 Some(cdir) => cdir.clone(),
@@ -205,6 +216,8 @@ pub struct Config {
     pub authentication: AuthenticationConfig,
     /// Header bypass configuration
     pub headers: Vec<HeaderRule>,
+    /// Subgraph response headers propagated to the client
+    pub response_headers: Vec<ResponseHeaderRule>,
     /// Subgraph configuration
     pub subgraphs: BTreeMap<String, SubgraphConfig>,
     /// Hooks configuration
@@ -215,6 +228,8 @@ pub struct Config {
     pub health: HealthConfig,
     /// Global configuration for entity caching
     pub entity_caching: EntityCachingConfig,
+    /// Global configuration for whole response caching
+    pub response_caching: ResponseCachingConfig,
     /// Configuration for complexity control
     pub complexity_control: ComplexityControlConfig,
     /// Automatic persisted queries' configuration
@@ -226,6 +241,9 @@ pub struct Config {
     /// Model Control Protocol configuration
     pub mcp: Option<ModelControlProtocolConfig>,
     pub wasm: Option<WasmConfig>,
+    /// If set, federated schemas loaded from a file, object storage, Hive or the Grafbase API
+    /// without a valid signature are refused
+    pub schema_signature: Option<SchemaSignatureConfig>,
 }
 
 impl Config {
@@ -273,6 +291,13 @@ impl Config {
             *dir = parent.join(&dir);
         }
 
+        if let Some(signature) = &mut self.schema_signature
+            && let Some(path) = &mut signature.public_key_path
+            && path.is_relative()
+        {
+            *path = parent.join(&path);
+        }
+
         Some(self)
     }
 }
@@ -295,10 +320,12 @@ impl Default for Config {
             trusted_documents: Default::default(),
             authentication: Default::default(),
             headers: Default::default(),
+            response_headers: Default::default(),
             subgraphs: Default::default(),
             hooks: Default::default(),
             health: Default::default(),
             entity_caching: Default::default(),
+            response_caching: Default::default(),
             complexity_control: Default::default(),
             apq: Default::default(),
             operation_caching: Default::default(),
@@ -306,6 +333,7 @@ impl Default for Config {
             extensions: Default::default(),
             mcp: Default::default(),
             wasm: Default::default(),
+            schema_signature: Default::default(),
         }
     }
 }
@@ -407,6 +435,8 @@ pub struct SubgraphConfig {
     pub url: Option<Url>,
     /// Header bypass configuration
     pub headers: Vec<HeaderRule>,
+    /// Response headers propagated from this subgraph to the client
+    pub response_headers: Vec<ResponseHeaderRule>,
     /// The URL to use for GraphQL websocket calls.
     pub websocket_url: Option<Url>,
     /// Rate limiting configuration specifically for this Subgraph
@@ -415,16 +445,24 @@ pub struct SubgraphConfig {
     #[serde(deserialize_with = "duration_str::deserialize_duration")]
     pub timeout: Duration,
     pub retry: Option<RetryConfig>,
+    /// Request hedging for the queries sent to this subgraph.
+    pub hedging: Option<HedgingConfig>,
+    /// Subgraph specific circuit breaker, overriding the one defined in the traffic shaping
+    /// configuration.
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Subgraph specific concurrency limit, overriding the one defined in the traffic shaping
+    /// configuration.
+    pub concurrency_limit: Option<ConcurrencyLimitConfig>,
     /// Subgraph specific entity caching config  this overrides the global config if there
     /// is any
     pub entity_caching: Option<SubgraphEntityCachingConfig>,
     /// Subgraph specific message signatures config
     pub message_signatures: Option<MessageSignaturesConfig>,
-    /// The path of an SDL schema file for the subgraph (dev only).
+    /// The path of an SDL schema file for the subgraph (dev, or gateway composition only).
     pub schema_path: Option<PathBuf>,
-    /// A URL from which to retreive the subgraph SDL (dev only).
+    /// A URL from which to retreive the subgraph SDL (dev, or gateway composition only).
     pub introspection_url: Option<Url>,
-    /// Header configuration for subgraph introspection (dev only).
+    /// Header configuration for subgraph introspection (dev, or gateway composition only).
     pub introspection_headers: Option<BTreeMap<String, String>>,
     /// The protocol used for subscriptions
     pub subscription_protocol: Option<SubscriptionProtocol>,
@@ -443,10 +481,14 @@ impl Default for SubgraphConfig {
         Self {
             url: Default::default(),
             headers: Default::default(),
+            response_headers: Default::default(),
             websocket_url: Default::default(),
             rate_limit: Default::default(),
             timeout: DEFAULT_SUBGRAPH_TIMEOUT,
             retry: Default::default(),
+            hedging: Default::default(),
+            circuit_breaker: Default::default(),
+            concurrency_limit: Default::default(),
             entity_caching: Default::default(),
             message_signatures: Default::default(),
             schema_path: Default::default(),
@@ -474,6 +516,30 @@ pub struct RetryConfig {
     pub retry_mutations: bool,
 }
 
+#[derive(Debug, serde::Deserialize, Clone, Copy, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HedgingConfig {
+    /// Should we hedge requests or not.
+    pub enabled: bool,
+    /// How long to wait for the first request before sending a second identical one. Also used
+    /// with `percentile` until enough latencies have been observed.
+    #[serde(deserialize_with = "duration_str::deserialize_duration")]
+    pub delay: Duration,
+    /// Sends the second request once the first one is slower than this percentile of the recent
+    /// subgraph latencies, between 0 and 100.
+    pub percentile: Option<f64>,
+}
+
+impl Default for HedgingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            delay: Duration::from_millis(100),
+            percentile: None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, serde::Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct GraphConfig {
@@ -1530,6 +1596,70 @@ mod tests {
         })
     }
 
+    #[test]
+    fn schema_signature() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config.toml");
+        std::fs::write(
+            &path,
+            indoc! {r#"
+                [schema_signature]
+                public_key_path = "keys/schema.pub.pem"
+            "#},
+        )
+        .unwrap();
+
+        let config = Config::load(&path).unwrap().unwrap();
+
+        assert_eq!(
+            config.schema_signature.unwrap().public_key_path.unwrap(),
+            tmp.path().join("keys/schema.pub.pem")
+        );
+    }
+
+    #[test]
+    fn header_insert_template() {
+        temp_env::with_var("USER_PREFIX", Some("user"), || {
+            let input = indoc! {r#"
+                [[headers]]
+                rule = "insert"
+                name = "x-user-id"
+                value = "{{ env.USER_PREFIX }}:{{ jwt.sub }}"
+
+                [[subgraphs.products.headers]]
+                rule = "insert"
+                name = "x-operation"
+                value = "{{ operation.name }} from {{ client.ip }}"
+            "#};
+
+            let tmp = tempfile::tempdir().unwrap();
+            let path = tmp.path().join("config.toml");
+            std::fs::write(&path, input).unwrap();
+            let config = Config::load(&path).unwrap().unwrap();
+
+            insta::assert_debug_snapshot!((&config.headers, &config.subgraphs["products"].headers), @r#"
+            (
+                [
+                    Insert(
+                        HeaderInsert {
+                            name: "x-user-id",
+                            value: "user:{{ jwt.sub }}",
+                        },
+                    ),
+                ],
+                [
+                    Insert(
+                        HeaderInsert {
+                            name: "x-operation",
+                            value: "{{ operation.name }} from {{ client.ip }}",
+                        },
+                    ),
+                ],
+            )
+            "#);
+        })
+    }
+
     #[test]
     fn header_insert_invalid_name() {
         let input = indoc! {r#"
@@ -1612,6 +1742,91 @@ mod tests {
         "#);
     }
 
+    #[test]
+    fn response_header_propagate() {
+        let input = indoc! {r#"
+            [[response_headers]]
+            rule = "propagate"
+            name = "set-cookie"
+            merge = "append"
+
+            [[response_headers]]
+            rule = "propagate"
+            pattern = "^x-ratelimit-"
+
+            [[subgraphs.products.response_headers]]
+            rule = "propagate"
+            name = "cache-control"
+            merge = "min_max_age"
+            rename = "x-products-cache-control"
+        "#};
+
+        let result: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&result.response_headers, @r#"
+        [
+            Propagate(
+                HeaderPropagate {
+                    name: Name(
+                        "set-cookie",
+                    ),
+                    rename: None,
+                    merge: Append,
+                },
+            ),
+            Propagate(
+                HeaderPropagate {
+                    name: Pattern(
+                        NamePattern(
+                            Regex(
+                                "^x-ratelimit-",
+                            ),
+                        ),
+                    ),
+                    rename: None,
+                    merge: Last,
+                },
+            ),
+        ]
+        "#);
+
+        insta::assert_debug_snapshot!(&result.subgraphs["products"].response_headers, @r#"
+        [
+            Propagate(
+                HeaderPropagate {
+                    name: Name(
+                        "cache-control",
+                    ),
+                    rename: Some(
+                        "x-products-cache-control",
+                    ),
+                    merge: MinMaxAge,
+                },
+            ),
+        ]
+        "#);
+    }
+
+    #[test]
+    fn response_header_invalid_merge() {
+        let input = indoc! {r#"
+            [[response_headers]]
+            rule = "propagate"
+            name = "set-cookie"
+            merge = "concat"
+        "#};
+
+        let error = toml::from_str::<Config>(input).unwrap_err();
+
+        insta::assert_snapshot!(&error.to_string(), @r#"
+        TOML parse error at line 1, column 1
+          |
+        1 | [[response_headers]]
+          | ^^^^^^^^^^^^^^^^^^^^
+        unknown variant `concat`, expected one of `first`, `last`, `append`, `min_max_age`
+        "#);
+    }
+
     #[test]
     fn subgraph_header_forward_static() {
         let input = indoc! {r#"
@@ -1637,10 +1852,14 @@ mod tests {
                         },
                     ),
                 ],
+                response_headers: [],
                 websocket_url: None,
                 rate_limit: None,
                 timeout: 30s,
                 retry: None,
+                hedging: None,
+                circuit_breaker: None,
+                concurrency_limit: None,
                 entity_caching: None,
                 message_signatures: None,
                 schema_path: None,
@@ -1755,8 +1974,12 @@ mod tests {
                     GraphRateLimit {
                         limit: 1000,
                         duration: 10s,
+                        burst: None,
                     },
                 ),
+                rules: [],
+                algorithm: SlidingWindow,
+                headers: false,
                 storage: Memory,
                 redis: RateLimitRedisConfig {
                     url: Url {
@@ -1797,6 +2020,9 @@ mod tests {
         Some(
             RateLimitConfig {
                 global: None,
+                rules: [],
+                algorithm: SlidingWindow,
+                headers: false,
                 storage: Redis,
                 redis: RateLimitRedisConfig {
                     url: Url {
@@ -1840,6 +2066,9 @@ mod tests {
         Some(
             RateLimitConfig {
                 global: None,
+                rules: [],
+                algorithm: SlidingWindow,
+                headers: false,
                 storage: Redis,
                 redis: RateLimitRedisConfig {
                     url: Url {
@@ -1885,6 +2114,9 @@ mod tests {
         Some(
             RateLimitConfig {
                 global: None,
+                rules: [],
+                algorithm: SlidingWindow,
+                headers: false,
                 storage: Redis,
                 redis: RateLimitRedisConfig {
                     url: Url {
@@ -1929,6 +2161,9 @@ mod tests {
         Some(
             RateLimitConfig {
                 global: None,
+                rules: [],
+                algorithm: SlidingWindow,
+                headers: false,
                 storage: Redis,
                 redis: RateLimitRedisConfig {
                     url: Url {
@@ -1984,6 +2219,9 @@ mod tests {
         Some(
             RateLimitConfig {
                 global: None,
+                rules: [],
+                algorithm: SlidingWindow,
+                headers: false,
                 storage: Redis,
                 redis: RateLimitRedisConfig {
                     url: Url {
@@ -2039,6 +2277,7 @@ mod tests {
             GraphRateLimit {
                 limit: 1000,
                 duration: 10s,
+                burst: None,
             },
         )
         "###);
@@ -2057,6 +2296,161 @@ mod tests {
         insta::assert_debug_snapshot!(&error.to_string(), @r###""TOML parse error at line 3, column 12\n  |\n3 | duration = \"0s\"\n  |            ^^^^\nrate limit duration cannot be 0\n""###);
     }
 
+    #[test]
+    fn rate_limiting_rules() {
+        let input = indoc! {r#"
+            [[gateway.rate_limit.rules]]
+            name = "tenant"
+            key = { header = "x-tenant-id" }
+            limit = 100
+            duration = "1m"
+
+            [[gateway.rate_limit.rules]]
+            name = "user"
+            key = { jwt_claim = "sub" }
+            limit = 10
+            duration = "1s"
+
+            [[gateway.rate_limit.rules]]
+            name = "ip"
+            key = "ip"
+            limit = 50
+            duration = "10s"
+
+            [[gateway.rate_limit.rules]]
+            name = "operation"
+            key = "operation_name"
+            limit = 1000
+            duration = "1m"
+        "#};
+
+        let config = toml::from_str::<Config>(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.gateway.rate_limit.unwrap().rules, @r#"
+        [
+            RateLimitRule {
+                name: "tenant",
+                key: Header(
+                    "x-tenant-id",
+                ),
+                limit: 100,
+                duration: 60s,
+                burst: None,
+            },
+            RateLimitRule {
+                name: "user",
+                key: JwtClaim(
+                    "sub",
+                ),
+                limit: 10,
+                duration: 1s,
+                burst: None,
+            },
+            RateLimitRule {
+                name: "ip",
+                key: Ip,
+                limit: 50,
+                duration: 10s,
+                burst: None,
+            },
+            RateLimitRule {
+                name: "operation",
+                key: OperationName,
+                limit: 1000,
+                duration: 60s,
+                burst: None,
+            },
+        ]
+        "#);
+    }
+
+    #[test]
+    fn rate_limiting_rules_validation() {
+        let input = indoc! {r#"
+            [[gateway.rate_limit.rules]]
+            name = "tenant"
+            key = { header = "x-tenant-id" }
+            limit = 100
+            duration = "1m"
+
+            [[gateway.rate_limit.rules]]
+            name = "tenant"
+            key = "ip"
+            limit = 50
+            duration = "10s"
+        "#};
+
+        let error = toml::from_str::<Config>(input).unwrap_err();
+        assert_eq!(error.message(), "duplicate rate limit rule name: tenant");
+
+        let input = indoc! {r#"
+            [[gateway.rate_limit.rules]]
+            name = "tenant"
+            key = { header = "x tenant" }
+            limit = 100
+            duration = "1m"
+        "#};
+
+        let error = toml::from_str::<Config>(input).unwrap_err();
+        assert_eq!(error.message(), "invalid header name: x tenant");
+    }
+
+    #[test]
+    fn rate_limiting_algorithm() {
+        let input = indoc! {r#"
+            [gateway.rate_limit]
+            algorithm = "token_bucket"
+            headers = true
+
+            [gateway.rate_limit.global]
+            limit = 100
+            duration = "1s"
+            burst = 200
+        "#};
+
+        let config = toml::from_str::<Config>(input).unwrap();
+        let rate_limit = config.gateway.rate_limit.unwrap();
+
+        insta::assert_debug_snapshot!((rate_limit.algorithm, rate_limit.headers, rate_limit.global), @r"
+        (
+            TokenBucket,
+            true,
+            Some(
+                GraphRateLimit {
+                    limit: 100,
+                    duration: 1s,
+                    burst: Some(
+                        200,
+                    ),
+                },
+            ),
+        )
+        ");
+    }
+
+    #[test]
+    fn rate_limiting_burst_requires_algorithm() {
+        let input = indoc! {r#"
+            [gateway.rate_limit]
+            algorithm = "sliding_window"
+
+            [subgraphs.products.rate_limit]
+            limit = 100
+            duration = "1s"
+            burst = 200
+        "#};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config.toml");
+        std::fs::write(&path, input).unwrap();
+        let err = Config::load(&path).unwrap_err();
+
+        insta::assert_snapshot!(err, @"At subgraphs.products.rate_limit.burst, bursts require the token_bucket or gcra rate limit algorithm");
+
+        std::fs::write(&path, input.replace("sliding_window", "gcra")).unwrap();
+        assert!(Config::load(&path).is_ok());
+    }
+
     #[test]
     fn subgraph_global_retry() {
         let input = indoc! {r#"
@@ -2091,6 +2485,7 @@ mod tests {
             "products": SubgraphConfig {
                 url: None,
                 headers: [],
+                response_headers: [],
                 websocket_url: None,
                 rate_limit: None,
                 timeout: 30s,
@@ -2103,6 +2498,9 @@ mod tests {
                         retry_mutations: false,
                     },
                 ),
+                hedging: None,
+                circuit_breaker: None,
+                concurrency_limit: None,
                 entity_caching: None,
                 message_signatures: None,
                 schema_path: None,
@@ -2287,6 +2685,350 @@ mod tests {
         assert_eq!(500, config.operation_caching.limit);
     }
 
+    #[test]
+    fn response_caching_defaults() {
+        let config: Config = toml::from_str("").unwrap();
+
+        assert!(!config.response_caching.enabled);
+        assert_eq!(Duration::from_secs(60), config.response_caching.ttl);
+        assert_eq!(ResponseCachingStorage::Memory, config.response_caching.storage);
+    }
+
+    #[test]
+    fn response_caching_settings() {
+        let input = indoc! {r#"
+            [response_caching]
+            enabled = true
+            ttl = "30s"
+            storage = "redis"
+
+            [response_caching.redis]
+            url = "redis://cache:6379"
+
+            [response_caching.vary]
+            headers = ["x-tenant"]
+            jwt_claims = ["sub", "org.id"]
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.response_caching, @r#"
+        ResponseCachingConfig {
+            enabled: true,
+            storage: Redis,
+            redis: ResponseCachingRedisConfig {
+                url: Url {
+                    scheme: "redis",
+                    cannot_be_a_base: false,
+                    username: "",
+                    password: None,
+                    host: Some(
+                        Domain(
+                            "cache",
+                        ),
+                    ),
+                    port: Some(
+                        6379,
+                    ),
+                    path: "",
+                    query: None,
+                    fragment: None,
+                },
+                key_prefix: "grafbase-response-cache",
+                tls: None,
+            },
+            vary: ResponseCachingVaryConfig {
+                headers: [
+                    "x-tenant",
+                ],
+                jwt_claims: [
+                    "sub",
+                    "org.id",
+                ],
+            },
+            ttl: 30s,
+        }
+        "#);
+    }
+
+    #[test]
+    fn complexity_control_budget() {
+        let input = indoc! {r#"
+            [complexity_control]
+            mode = "enforce"
+            limit = 100
+
+            [complexity_control.budget]
+            limit = 10000
+            duration = "1m"
+            key = { jwt_claim = "org.id" }
+            missing_key = "reject"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.complexity_control, @r#"
+        ComplexityControlConfig {
+            mode: Some(
+                Enforce,
+            ),
+            limit: Some(
+                100,
+            ),
+            list_size: None,
+            budget: Some(
+                CostBudgetConfig {
+                    limit: 10000,
+                    duration: 60s,
+                    key: JwtClaim(
+                        "org.id",
+                    ),
+                    missing_key: Reject,
+                },
+            ),
+        }
+        "#);
+
+        let input = indoc! {r#"
+            [complexity_control.budget]
+            limit = 10000
+            duration = "1m"
+            key = { cookie = "client" }
+            missing_key = "allow"
+        "#};
+
+        let error = toml::from_str::<Config>(input).unwrap_err();
+
+        insta::assert_snapshot!(error.to_string(), @r#"
+        TOML parse error at line 4, column 9
+          |
+        4 | key = { cookie = "client" }
+          |         ^^^^^^
+        unknown variant `cookie`, expected `header` or `jwt_claim`
+        "#);
+    }
+
+    #[test]
+    fn entity_caching_invalidation() {
+        let input = indoc! {r#"
+            [entity_caching]
+            enabled = true
+
+            [entity_caching.invalidation.endpoint]
+            enabled = true
+            token = "secret"
+
+            [[entity_caching.invalidation.mutations]]
+            field = "updateProduct"
+            type = "Product"
+            key = { upc = "input.upc" }
+
+            [[entity_caching.invalidation.mutations]]
+            field = "importProducts"
+            type = "Product"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.entity_caching.invalidation, @r#"
+        EntityCachingInvalidationConfig {
+            endpoint: EntityCachingInvalidationEndpointConfig {
+                enabled: true,
+                path: "/admin/entity-cache/invalidate",
+                token: Some(
+                    "secret",
+                ),
+            },
+            mutations: [
+                EntityCachingMutationInvalidation {
+                    field: "updateProduct",
+                    type_name: "Product",
+                    key: Some(
+                        {
+                            "upc": "input.upc",
+                        },
+                    ),
+                },
+                EntityCachingMutationInvalidation {
+                    field: "importProducts",
+                    type_name: "Product",
+                    key: None,
+                },
+            ],
+        }
+        "#);
+    }
+
+    #[test]
+    fn entity_caching_stale_windows() {
+        let input = indoc! {r#"
+            [entity_caching]
+            enabled = true
+            ttl = "30s"
+            stale_while_revalidate = "10s"
+            stale_if_error = "1h"
+
+            [subgraphs.products.entity_caching]
+            stale_if_error = "5m"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        assert_eq!(
+            Some(Duration::from_secs(10)),
+            config.entity_caching.stale_while_revalidate
+        );
+        assert_eq!(Some(Duration::from_secs(3600)), config.entity_caching.stale_if_error);
+
+        insta::assert_debug_snapshot!(&config.subgraphs["products"].entity_caching, @r#"
+        Some(
+            SubgraphEntityCachingConfig {
+                enabled: None,
+                ttl: None,
+                stale_while_revalidate: None,
+                stale_if_error: Some(
+                    300s,
+                ),
+            },
+        )
+        "#);
+    }
+
+    #[test]
+    fn circuit_breaker() {
+        let input = indoc! {r#"
+            [traffic_shaping.circuit_breaker]
+            enabled = true
+            error_rate = 0.5
+            window = "30s"
+
+            [subgraphs.products.circuit_breaker]
+            enabled = true
+            consecutive_failures = 3
+            cooldown = "5s"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.traffic_shaping.circuit_breaker, @r#"
+        CircuitBreakerConfig {
+            enabled: true,
+            consecutive_failures: None,
+            error_rate: Some(
+                0.5,
+            ),
+            minimum_requests: 20,
+            window: 30s,
+            cooldown: 30s,
+            half_open_requests: 1,
+        }
+        "#);
+
+        insta::assert_debug_snapshot!(&config.subgraphs["products"].circuit_breaker, @r#"
+        Some(
+            CircuitBreakerConfig {
+                enabled: true,
+                consecutive_failures: Some(
+                    3,
+                ),
+                error_rate: None,
+                minimum_requests: 20,
+                window: 10s,
+                cooldown: 5s,
+                half_open_requests: 1,
+            },
+        )
+        "#);
+    }
+
+    #[test]
+    fn hedging() {
+        let input = indoc! {r#"
+            [subgraphs.products.hedging]
+            enabled = true
+            percentile = 95
+            delay = "50ms"
+
+            [subgraphs.reviews.hedging]
+            enabled = true
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.subgraphs["products"].hedging, @r#"
+        Some(
+            HedgingConfig {
+                enabled: true,
+                delay: 50ms,
+                percentile: Some(
+                    95.0,
+                ),
+            },
+        )
+        "#);
+
+        insta::assert_debug_snapshot!(&config.subgraphs["reviews"].hedging, @r#"
+        Some(
+            HedgingConfig {
+                enabled: true,
+                delay: 100ms,
+                percentile: None,
+            },
+        )
+        "#);
+    }
+
+    #[test]
+    fn concurrency_limit() {
+        let input = indoc! {r#"
+            [traffic_shaping.concurrency_limit]
+            enabled = true
+            max_in_flight = 50
+            queue_size = 100
+            queue_timeout = "500ms"
+
+            [subgraphs.products.concurrency_limit]
+            enabled = true
+            mode = "aimd"
+            max_limit = 200
+            latency_threshold = "200ms"
+        "#};
+
+        let config: Config = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(&config.traffic_shaping.concurrency_limit, @r#"
+        ConcurrencyLimitConfig {
+            enabled: true,
+            max_in_flight: 50,
+            queue_size: 100,
+            queue_timeout: 500ms,
+            mode: Fixed,
+            min_limit: 1,
+            max_limit: 1000,
+            latency_threshold: 1s,
+            backoff_ratio: 0.9,
+            smoothing: 0.2,
+        }
+        "#);
+
+        insta::assert_debug_snapshot!(&config.subgraphs["products"].concurrency_limit, @r#"
+        Some(
+            ConcurrencyLimitConfig {
+                enabled: true,
+                max_in_flight: 100,
+                queue_size: 0,
+                queue_timeout: 1s,
+                mode: Aimd,
+                min_limit: 1,
+                max_limit: 200,
+                latency_threshold: 200ms,
+                backoff_ratio: 0.9,
+                smoothing: 0.2,
+            },
+        )
+        "#);
+    }
+
     #[test]
     fn extension_only_version() {
         let input = indoc! {r#"
@@ -2366,6 +3108,7 @@ mod tests {
                     max_pool_size: Some(
                         1000,
                     ),
+                    redis: {},
                     config: None,
                 },
             ),
@@ -2457,6 +3200,7 @@ mod tests {
                     stderr: None,
                     environment_variables: None,
                     max_pool_size: None,
+                    redis: {},
                     config: Some(
                         Table(
                             {
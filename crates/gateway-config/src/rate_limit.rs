@@ -4,12 +4,18 @@ use serde::de::Error;
 use std::path::PathBuf;
 use std::time::Duration;
 
+use crate::Config;
+
 #[derive(Debug, Clone, Copy, serde::Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct GraphRateLimit {
     pub limit: usize,
     #[serde(deserialize_with = "deserialize_duration_internal")]
     pub duration: Duration,
+    /// Number of requests which can be made at once with the token bucket and GCRA algorithms,
+    /// reported as the `RateLimit-Limit` header. Defaults to the limit for the former and to a
+    /// single request for the latter. Not supported by the sliding window algorithm.
+    pub burst: Option<usize>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize)]
@@ -17,11 +23,71 @@ pub struct GraphRateLimit {
 pub struct RateLimitConfig {
     pub global: Option<GraphRateLimit>,
     #[serde(default)]
+    pub algorithm: RateLimitAlgorithm,
+    /// Adds the `RateLimit-Limit`, `RateLimit-Remaining`, `RateLimit-Reset` and `Retry-After`
+    /// headers to the responses of non-streaming requests.
+    #[serde(default)]
+    pub headers: bool,
+    /// Limits applied per value of a request attribute, such as a header or a JWT claim.
+    #[serde(default, deserialize_with = "deserialize_rules")]
+    pub rules: Vec<RateLimitRule>,
+    #[serde(default)]
     pub storage: RateLimitStorage,
     #[serde(default)]
     pub redis: RateLimitRedisConfig,
 }
 
+/// A rate limit counted separately for each distinct value of its key. Requests without a
+/// value for the key are not limited by the rule.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitRule {
+    pub name: String,
+    pub key: RateLimitRuleKey,
+    pub limit: usize,
+    #[serde(deserialize_with = "deserialize_duration_internal")]
+    pub duration: Duration,
+    pub burst: Option<usize>,
+}
+
+impl RateLimitRule {
+    pub fn as_graph_rate_limit(&self) -> GraphRateLimit {
+        GraphRateLimit {
+            limit: self.limit,
+            duration: self.duration,
+            burst: self.burst,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum RateLimitRuleKey {
+    /// Value of the given request header.
+    Header(#[serde(deserialize_with = "deserialize_header_name")] String),
+    /// Value of the JWT claim at the given path, with nested claims separated by dots.
+    JwtClaim(String),
+    /// IP address of the client.
+    Ip,
+    /// Name of the GraphQL operation.
+    OperationName,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitAlgorithm {
+    /// Approximates a sliding window by adding the requests of the current window to the
+    /// remaining share of the previous one. In memory, limits are enforced as a rate per second.
+    #[default]
+    SlidingWindow,
+    /// Tokens are refilled continuously at the configured rate, allowing bursts up to the bucket
+    /// capacity.
+    TokenBucket,
+    /// Generic cell rate algorithm, spacing requests evenly like a sliding log would without
+    /// keeping track of every request.
+    Gcra,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum RateLimitStorage {
@@ -86,3 +152,69 @@ where
 
     Ok(duration)
 }
+
+fn deserialize_rules<'de, D>(data: D) -> Result<Vec<RateLimitRule>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let rules: Vec<RateLimitRule> = serde::Deserialize::deserialize(data)?;
+
+    // Rule names are part of the counter keys, rules sharing a name would share their counters.
+    for (i, rule) in rules.iter().enumerate() {
+        if rules[..i].iter().any(|other| other.name == rule.name) {
+            return Err(Error::custom(format!("duplicate rate limit rule name: {}", rule.name)));
+        }
+    }
+
+    Ok(rules)
+}
+
+fn deserialize_header_name<'de, D>(data: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let name: String = serde::Deserialize::deserialize(data)?;
+
+    if http::HeaderName::from_bytes(name.as_bytes()).is_err() {
+        return Err(Error::custom(format!("invalid header name: {name}")));
+    }
+
+    Ok(name)
+}
+
+/// Bursts are only supported by the token bucket and GCRA algorithms. The algorithm is shared by
+/// the gateway and subgraph limits, so this can only be checked once the whole configuration is
+/// known.
+pub(crate) fn validate_burst(config: &Config) -> Result<(), String> {
+    let rate_limit = config.gateway.rate_limit.as_ref();
+
+    if rate_limit.map(|rate_limit| rate_limit.algorithm).unwrap_or_default() != RateLimitAlgorithm::SlidingWindow {
+        return Ok(());
+    }
+
+    let global = rate_limit
+        .and_then(|rate_limit| rate_limit.global)
+        .filter(|limit| limit.burst.is_some())
+        .map(|_| String::from("gateway.rate_limit.global.burst"));
+
+    let rules = rate_limit
+        .map(|rate_limit| rate_limit.rules.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| rule.burst.is_some())
+        .map(|(i, _)| format!("gateway.rate_limit.rules[{i}].burst"));
+
+    let subgraphs = config
+        .subgraphs
+        .iter()
+        .filter(|(_, subgraph)| subgraph.rate_limit.is_some_and(|limit| limit.burst.is_some()))
+        .map(|(name, _)| format!("subgraphs.{name}.rate_limit.burst"));
+
+    match global.into_iter().chain(rules).chain(subgraphs).next() {
+        Some(path) => Err(format!(
+            "At {path}, bursts require the token_bucket or gcra rate limit algorithm"
+        )),
+        None => Ok(()),
+    }
+}
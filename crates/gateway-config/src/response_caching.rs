@@ -0,0 +1,74 @@
+use std::{path::PathBuf, time::Duration};
+
+const DEFAULT_RESPONSE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, serde::Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResponseCachingConfig {
+    pub enabled: bool,
+    pub storage: ResponseCachingStorage,
+    pub redis: ResponseCachingRedisConfig,
+    /// Request properties which are part of the cache key in addition to the operation and its variables.
+    pub vary: ResponseCachingVaryConfig,
+
+    /// The max-age of a response when the schema doesn't provide any `@cacheControl` hint
+    /// for the queried fields. Defaults to 60s
+    #[serde(deserialize_with = "duration_str::deserialize_duration")]
+    pub ttl: Duration,
+}
+
+impl Default for ResponseCachingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            storage: Default::default(),
+            redis: Default::default(),
+            vary: Default::default(),
+            ttl: DEFAULT_RESPONSE_CACHE_TTL,
+        }
+    }
+}
+
+#[derive(Debug, Default, serde::Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResponseCachingVaryConfig {
+    /// Names of the request headers to include in the cache key.
+    pub headers: Vec<String>,
+    /// Names of the JWT claims to include in the cache key. Nested claims can be accessed with
+    /// a dot-separated path, such as `org.id`.
+    pub jwt_claims: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseCachingStorage {
+    #[default]
+    Memory,
+    Redis,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResponseCachingRedisConfig {
+    pub url: url::Url,
+    pub key_prefix: String,
+    pub tls: Option<ResponseCachingRedisTlsConfig>,
+}
+
+impl Default for ResponseCachingRedisConfig {
+    fn default() -> Self {
+        Self {
+            url: url::Url::parse("redis://localhost:6379").expect("must be correct"),
+            key_prefix: String::from("grafbase-response-cache"),
+            tls: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseCachingRedisTlsConfig {
+    pub cert: Option<PathBuf>,
+    pub key: Option<PathBuf>,
+    pub ca: Option<PathBuf>,
+}
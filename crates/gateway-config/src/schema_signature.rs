@@ -0,0 +1,11 @@
+use std::path::PathBuf;
+
+/// Verification of the signature of federated schemas before they're loaded by the gateway.
+#[derive(Debug, Default, serde::Deserialize, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SchemaSignatureConfig {
+    /// Path to the PEM encoded public key, Ed25519 or ECDSA P-256.
+    pub public_key_path: Option<PathBuf>,
+    /// The PEM encoded public key, instead of a path.
+    pub public_key: Option<String>,
+}
@@ -1,9 +1,18 @@
-use super::OpenTelemetryExportersConfig;
+use super::{OtlpExporterConfig, PrometheusExporterConfig, StdoutExporterConfig};
 
 /// Logs configuration
 #[derive(Debug, Default, Clone, PartialEq, serde::Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct MetricsConfig {
     /// Exporters configurations
-    pub exporters: OpenTelemetryExportersConfig,
+    pub exporters: MetricsExportersConfig,
+}
+
+/// Metrics exporters, on top of the OpenTelemetry ones metrics can be scraped by Prometheus.
+#[derive(Debug, Clone, PartialEq, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsExportersConfig {
+    pub stdout: Option<StdoutExporterConfig>,
+    pub otlp: Option<OtlpExporterConfig>,
+    pub prometheus: Option<PrometheusExporterConfig>,
 }
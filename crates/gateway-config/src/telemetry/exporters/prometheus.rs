@@ -0,0 +1,24 @@
+use std::{borrow::Cow, net::SocketAddr};
+
+/// Prometheus exporter configuration, serving the metrics in the text exposition format.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrometheusExporterConfig {
+    /// Enable or disable the exporter
+    pub enabled: bool,
+    /// The address of the dedicated listener serving the metrics endpoint. Metrics are never
+    /// exposed on the gateway listen address.
+    pub listen: SocketAddr,
+    /// The path of the metrics endpoint.
+    pub path: Cow<'static, str>,
+}
+
+impl Default for PrometheusExporterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            listen: SocketAddr::from(([127, 0, 0, 1], 9464)),
+            path: Cow::Borrowed("/metrics"),
+        }
+    }
+}
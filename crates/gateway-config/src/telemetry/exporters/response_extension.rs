@@ -10,6 +10,12 @@ pub struct ResponseExtensionExporterConfig {
     pub trace_id: bool,
     /// Whether queryPlan is exposed in the grafbase response extension. Defaults to true.
     pub query_plan: bool,
+    /// Whether the queryPlan details the entity and the required fields of each step, as shown by
+    /// the query plan explorer of `grafbase dev`. Defaults to false.
+    pub query_plan_details: bool,
+    /// Whether the estimated and actual cost of the operation are exposed in the grafbase response
+    /// extension when complexity control is enabled. Defaults to true.
+    pub cost: bool,
     /// Defines under which conditions the grafbase response extension will be added.
     /// Defaults to a simple header rule, the presence of `x-grafbase-telemetry` is enough.
     pub access_control: Vec<AccessControl>,
@@ -20,6 +26,8 @@ impl Default for ResponseExtensionExporterConfig {
         Self {
             trace_id: true,
             query_plan: true,
+            query_plan_details: false,
+            cost: true,
             access_control: vec![AccessControl::Header(HeaderAccessControl {
                 name: AsciiString::from_str("x-grafbase-telemetry").unwrap(),
                 value: None,
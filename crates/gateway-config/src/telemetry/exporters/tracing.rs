@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use super::OpenTelemetryExportersConfig;
 
 use serde::de::Error as DeserializeError;
@@ -5,6 +7,7 @@ use serde::{Deserialize, Deserializer};
 
 pub const DEFAULT_SAMPLING: f64 = 0.15;
 pub const DEFAULT_COLLECT_VALUE: usize = 128;
+pub const DEFAULT_MAX_BUFFERED_TRACES: usize = 10_000;
 
 /// Tracing configuration
 #[derive(Debug, Clone, PartialEq, serde::Deserialize)]
@@ -16,6 +19,12 @@ pub struct TracingConfig {
     pub sampling: f64,
     /// Allow clients to specify sampling rate. Enable only if you are not exposing the gateway directly to clients. Default: false.
     pub parent_based_sampler: bool,
+    /// Sampling ratios for specific operations, clients or subgraphs. The first matching rule
+    /// overrides the `sampling` value.
+    pub sampling_rules: Vec<SamplingRuleConfig>,
+    /// Buffers the spans of each trace until the request finished, always keeping the traces of
+    /// failed or slow requests.
+    pub tail_sampling: TailSamplingConfig,
     /// Collection configuration
     pub collect: TracingCollectConfig,
     /// Exporters configurations
@@ -32,6 +41,58 @@ impl Default for TracingConfig {
             exporters: Default::default(),
             propagation: Default::default(),
             parent_based_sampler: false,
+            sampling_rules: Vec::new(),
+            tail_sampling: Default::default(),
+        }
+    }
+}
+
+impl TracingConfig {
+    /// Whether the sampling decision is taken once the request has finished, rather than when its
+    /// first span starts.
+    pub fn is_sampled_after_request(&self) -> bool {
+        !self.sampling_rules.is_empty() || self.tail_sampling.enabled
+    }
+}
+
+/// A sampling ratio for the traces matching all the given conditions.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SamplingRuleConfig {
+    /// The name of the GraphQL operation.
+    pub operation_name: Option<String>,
+    /// The client name, sent in the `x-grafbase-client-name` header.
+    pub client_name: Option<String>,
+    /// The name of a subgraph the request went to.
+    pub subgraph: Option<String>,
+    /// The sampler between 0.0 and 1.0.
+    #[serde(deserialize_with = "deserialize_sampling")]
+    pub sampling: f64,
+}
+
+/// Tail sampling configuration
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TailSamplingConfig {
+    /// Enable or disable tail sampling. Default: false.
+    pub enabled: bool,
+    /// Always export the traces of requests with errors, including subgraph errors. Default: true.
+    pub errors: bool,
+    /// Always export the traces of requests taking longer than this.
+    #[serde(deserialize_with = "duration_str::deserialize_option_duration")]
+    pub latency_threshold: Option<Duration>,
+    /// The maximum number of traces kept in memory while waiting for their request to finish.
+    /// The default is 10000.
+    pub max_buffered_traces: usize,
+}
+
+impl Default for TailSamplingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            errors: true,
+            latency_threshold: None,
+            max_buffered_traces: DEFAULT_MAX_BUFFERED_TRACES,
         }
     }
 }
@@ -83,6 +144,12 @@ pub struct PropagationConfig {
     pub baggage: bool,
     /// Enable AWS X-Ray propagation through the `x-amzn-trace-id` header. This is the standard trace parent propagation mechanism for AWS X-Ray. https://docs.aws.amazon.com/xray/latest/devguide/xray-concepts.html#xray-concepts-tracingheader
     pub aws_xray: bool,
+    /// Enable Zipkin B3 propagation through the single `b3` header. https://github.com/openzipkin/b3-propagation
+    pub b3: bool,
+    /// Enable Zipkin B3 propagation through the `X-B3-TraceId`, `X-B3-SpanId`, `X-B3-Sampled` and `X-B3-Flags` headers.
+    pub b3_multi: bool,
+    /// Enable Jaeger propagation through the `uber-trace-id` header. https://www.jaegertracing.io/docs/1.52/client-libraries/#propagation-format
+    pub jaeger: bool,
 }
 
 fn deserialize_sampling<'de, D>(deserializer: D) -> Result<f64, D::Error>
@@ -82,6 +82,13 @@ impl TelemetryConfig {
         if cfg.is_enabled() { Some(cfg) } else { None }
     }
 
+    pub fn metrics_prometheus_config(&self) -> Option<&PrometheusExporterConfig> {
+        self.metrics
+            .as_ref()
+            .and_then(|c| c.exporters.prometheus.as_ref())
+            .filter(|c| c.enabled)
+    }
+
     pub fn logs_stdout_config(&self) -> Option<&StdoutExporterConfig> {
         match self.logs.as_ref().and_then(|c| c.exporters.stdout.as_ref()) {
             Some(config) if config.enabled => Some(config),
@@ -168,6 +175,83 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn sampling_rules() {
+        let input = indoc! {r#"
+            sampling = 0.1
+
+            [[sampling_rules]]
+            operation_name = "Checkout"
+            sampling = 1.0
+
+            [[sampling_rules]]
+            client_name = "ios"
+            subgraph = "payments"
+            sampling = 0.5
+        "#};
+
+        let config: TracingConfig = toml::from_str(input).unwrap();
+
+        assert!(config.is_sampled_after_request());
+        insta::assert_debug_snapshot!(config.sampling_rules, @r#"
+        [
+            SamplingRuleConfig {
+                operation_name: Some(
+                    "Checkout",
+                ),
+                client_name: None,
+                subgraph: None,
+                sampling: 1.0,
+            },
+            SamplingRuleConfig {
+                operation_name: None,
+                client_name: Some(
+                    "ios",
+                ),
+                subgraph: Some(
+                    "payments",
+                ),
+                sampling: 0.5,
+            },
+        ]
+        "#);
+    }
+
+    #[test]
+    fn sampling_rule_invalid() {
+        let input = indoc! {r#"
+            [[sampling_rules]]
+            operation_name = "Checkout"
+        "#};
+
+        let error = toml::from_str::<TracingConfig>(input).unwrap_err();
+
+        assert!(error.to_string().contains("missing field `sampling`"));
+    }
+
+    #[test]
+    fn tail_sampling() {
+        let input = indoc! {r#"
+            [tail_sampling]
+            enabled = true
+            latency_threshold = "500ms"
+        "#};
+
+        let config: TracingConfig = toml::from_str(input).unwrap();
+
+        assert!(config.is_sampled_after_request());
+        insta::assert_debug_snapshot!(config.tail_sampling, @r#"
+        TailSamplingConfig {
+            enabled: true,
+            errors: true,
+            latency_threshold: Some(
+                500ms,
+            ),
+            max_buffered_traces: 10000,
+        }
+        "#);
+    }
+
     #[test]
     fn custom_collect() {
         // prepare
@@ -1952,16 +2036,95 @@ mod tests {
                 tracing: TracingConfig {
                     sampling: 0.15,
                     parent_based_sampler: true,
+                    sampling_rules: Vec::new(),
+                    tail_sampling: Default::default(),
                     collect: Default::default(),
                     exporters: Default::default(),
                     propagation: exporters::PropagationConfig {
                         trace_context: true,
                         baggage: true,
-                        aws_xray: false
+                        aws_xray: false,
+                        b3: false,
+                        b3_multi: false,
+                        jaeger: false,
                     },
                 },
             },
             config
         );
     }
+
+    #[test]
+    fn b3_and_jaeger_propagation() {
+        let input = indoc! {r#"
+            [tracing.propagation]
+            b3 = true
+            b3_multi = true
+            jaeger = true
+        "#};
+
+        let config: TelemetryConfig = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(config.tracing.propagation, @r#"
+        PropagationConfig {
+            trace_context: false,
+            baggage: false,
+            aws_xray: false,
+            b3: true,
+            b3_multi: true,
+            jaeger: true,
+        }
+        "#);
+    }
+
+    #[test]
+    fn metrics_prometheus_defaults() {
+        let input = indoc! {r#"
+            [metrics.exporters.prometheus]
+        "#};
+
+        let config: TelemetryConfig = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(config.metrics_prometheus_config(), @r#"
+        Some(
+            PrometheusExporterConfig {
+                enabled: true,
+                listen: 127.0.0.1:9464,
+                path: "/metrics",
+            },
+        )
+        "#);
+    }
+
+    #[test]
+    fn metrics_prometheus_separate_listen() {
+        let input = indoc! {r#"
+            [metrics.exporters.prometheus]
+            listen = "0.0.0.0:9464"
+            path = "/custom-metrics"
+        "#};
+
+        let config: TelemetryConfig = toml::from_str(input).unwrap();
+
+        insta::assert_debug_snapshot!(config.metrics_prometheus_config(), @r#"
+        Some(
+            PrometheusExporterConfig {
+                enabled: true,
+                listen: 0.0.0.0:9464,
+                path: "/custom-metrics",
+            },
+        )
+        "#);
+    }
+
+    #[test]
+    fn metrics_prometheus_not_enabled() {
+        let input = indoc! {r#"
+            [metrics.exporters.prometheus]
+            enabled = false
+        "#};
+
+        let config: TelemetryConfig = toml::from_str(input).unwrap();
+        assert_eq!(None, config.metrics_prometheus_config());
+    }
 }
@@ -1,13 +1,118 @@
+use std::time::Duration;
+
 #[derive(Clone, Debug, serde::Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct TrafficShapingConfig {
     pub inflight_deduplication: bool,
+    /// Circuit breaker applied to each subgraph individually.
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Limit of in-flight requests applied to each subgraph individually.
+    pub concurrency_limit: ConcurrencyLimitConfig,
 }
 
 impl Default for TrafficShapingConfig {
     fn default() -> Self {
         Self {
             inflight_deduplication: true,
+            circuit_breaker: Default::default(),
+            concurrency_limit: Default::default(),
         }
     }
 }
+
+#[derive(Clone, Debug, serde::Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CircuitBreakerConfig {
+    pub enabled: bool,
+    /// Opens the circuit after this many consecutive failed requests. If neither this nor
+    /// `error_rate` is defined, the circuit opens after 5 consecutive failures.
+    pub consecutive_failures: Option<u32>,
+    /// Opens the circuit when the ratio of failed requests within the `window` reaches this value,
+    /// between 0 and 1.
+    pub error_rate: Option<f64>,
+    /// Minimum number of requests within the `window` before the error rate is considered.
+    pub minimum_requests: u32,
+    /// Sliding window over which the error rate is computed.
+    #[serde(deserialize_with = "duration_str::deserialize_duration")]
+    pub window: Duration,
+    /// How long the circuit stays open before probing the subgraph again.
+    #[serde(deserialize_with = "duration_str::deserialize_duration")]
+    pub cooldown: Duration,
+    /// Number of probe requests allowed through once the cooldown has elapsed. The first one
+    /// decides whether the circuit closes again or re-opens.
+    pub half_open_requests: u32,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            consecutive_failures: None,
+            error_rate: None,
+            minimum_requests: 20,
+            window: Duration::from_secs(10),
+            cooldown: Duration::from_secs(30),
+            half_open_requests: 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConcurrencyLimitConfig {
+    pub enabled: bool,
+    /// Maximum number of in-flight requests to the subgraph. With an adaptive `mode`, this is the
+    /// initial limit.
+    pub max_in_flight: u32,
+    /// Number of requests allowed to wait for a slot once the limit is reached. Further requests
+    /// are rejected immediately.
+    pub queue_size: u32,
+    /// How long a request may wait in the queue before being rejected.
+    #[serde(deserialize_with = "duration_str::deserialize_duration")]
+    pub queue_timeout: Duration,
+    /// How the limit evolves over time.
+    pub mode: ConcurrencyLimitMode,
+    /// Lower bound of the adaptive limit.
+    pub min_limit: u32,
+    /// Upper bound of the adaptive limit.
+    pub max_limit: u32,
+    /// With the `aimd` mode, requests slower than this are treated like failures and decrease the
+    /// limit.
+    #[serde(deserialize_with = "duration_str::deserialize_duration")]
+    pub latency_threshold: Duration,
+    /// With the `aimd` mode, factor applied to the limit on failure, between 0 and 1.
+    pub backoff_ratio: f64,
+    /// With the `gradient` mode, weight of each new limit estimation, between 0 and 1.
+    pub smoothing: f64,
+}
+
+impl Default for ConcurrencyLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_in_flight: 100,
+            queue_size: 0,
+            queue_timeout: Duration::from_secs(1),
+            mode: ConcurrencyLimitMode::Fixed,
+            min_limit: 1,
+            max_limit: 1000,
+            latency_threshold: Duration::from_secs(1),
+            backoff_ratio: 0.9,
+            smoothing: 0.2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, serde::Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcurrencyLimitMode {
+    /// The limit is always `max_in_flight`.
+    #[default]
+    Fixed,
+    /// Additive increase, multiplicative decrease: the limit grows by one while requests succeed
+    /// at full capacity and is reduced by the `backoff_ratio` on failures or slow responses.
+    Aimd,
+    /// The limit follows the ratio between the long-term average latency and the latest one,
+    /// shrinking as the subgraph slows down.
+    Gradient,
+}
@@ -23,10 +23,10 @@ fn report_error(error: Error) {
 }
 
 fn report_diagnostic(message: String, severity: Severity) {
-    let label = match severity {
-        Severity::Warning => "Warning",
-    };
-    println!("{}", format!("⚠️ [{label}]: {message}").bright_yellow());
+    match severity {
+        Severity::Warning => println!("{}", format!("⚠️ [Warning]: {message}").bright_yellow()),
+        Severity::Error => println!("{}", format!("❌ [Error]: {message}").bright_red()),
+    }
 }
 
 fn report_success() {
@@ -0,0 +1,294 @@
+//! # Redis client
+//!
+//! A Redis client backed by a connection pool of the gateway, shared by all the instances of the
+//! extension. As the data is shared by all the gateway replicas pointing to the same Redis, it is
+//! a good fit for sessions, allowlists or deduplication keys.
+//!
+//! ## Quick Start
+//!
+//! ```rust,no_run
+//! # use std::time::Duration;
+//! # use grafbase_sdk::{SdkError, host_io::redis::{Command, Pipeline, Pool}};
+//! # fn main() -> Result<(), SdkError> {
+//! let pool = Pool::connect("sessions", "redis://localhost:6379")?;
+//!
+//! pool.set("session:1", b"alice", Some(Duration::from_secs(3600)))?;
+//! let session = pool.get("session:1")?;
+//!
+//! // Any command can be executed, in a pipeline if needed.
+//! let pipeline = Pipeline::new()
+//!     .atomic()
+//!     .command(Command::new("INCR").arg("requests"))
+//!     .command(Command::new("EXPIRE").arg("requests").arg("60"));
+//!
+//! let results = pool.pipeline(&pipeline)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! ## TLS
+//!
+//! TLS is enabled with a `rediss://` URL. Client certificates and certificate authorities are
+//! configured by the gateway operator for each pool name:
+//!
+//! ```toml
+//! [extensions.my-extension.redis.sessions.tls]
+//! cert = "/path/to/client.crt"
+//! key = "/path/to/client.key"
+//! ca = "/path/to/ca.crt"
+//! ```
+
+use std::time::Duration;
+
+use crate::{
+    SdkError,
+    types::{Error, Response, SubscriptionItem},
+    wit,
+};
+
+/// A Redis connection pool.
+///
+/// Pools are shared by name between the instances of the extension, so it can be created once
+/// per extension instance.
+pub struct Pool(wit::RedisPool);
+
+impl Pool {
+    /// Returns the pool with the given name, creating it on first use with the Redis URL. The
+    /// TLS configuration of the pool is read from the gateway configuration. Fails if the pool
+    /// was created with a different URL.
+    pub fn connect(name: &str, url: &str) -> Result<Self, SdkError> {
+        let pool = wit::RedisPool::connect(name, url)?;
+        Ok(Self(pool))
+    }
+
+    /// Executes a command and returns its value.
+    pub fn execute(&self, command: &Command) -> Result<Value, SdkError> {
+        let values = self.0.execute(&command.0)?;
+        let mut values = values.into_iter();
+
+        Value::unflatten(&mut values)
+    }
+
+    /// Executes the commands of the pipeline in a single round trip, and returns one value per
+    /// command.
+    pub fn pipeline(&self, pipeline: &Pipeline) -> Result<Vec<Value>, SdkError> {
+        let values = self.0.pipeline(&pipeline.commands, pipeline.atomic)?;
+        let mut values = values.into_iter().peekable();
+
+        let mut results = Vec::with_capacity(pipeline.commands.len());
+
+        while values.peek().is_some() {
+            results.push(Value::unflatten(&mut values)?);
+        }
+
+        Ok(results)
+    }
+
+    /// Subscribes to channels and channel patterns, such as `news.*`. The subscription uses a
+    /// dedicated connection, which is closed when the subscription is dropped.
+    pub fn subscribe(&self, channels: &[&str], patterns: &[&str]) -> Result<Subscription, SdkError> {
+        let channels = channels.iter().map(|channel| channel.to_string()).collect::<Vec<_>>();
+        let patterns = patterns.iter().map(|pattern| pattern.to_string()).collect::<Vec<_>>();
+
+        let subscriber = self.0.subscribe(&channels, &patterns)?;
+
+        Ok(Subscription(subscriber))
+    }
+
+    /// Returns the value of a key, or `None` if the key doesn't exist.
+    pub fn get(&self, key: &str) -> Result<Option<Vec<u8>>, SdkError> {
+        match self.execute(&Command::new("GET").arg(key))? {
+            Value::Nil => Ok(None),
+            value => value.into_bytes().map(Some),
+        }
+    }
+
+    /// Sets the value of a key, expiring after the given time to live if any.
+    pub fn set(&self, key: &str, value: &[u8], ttl: Option<Duration>) -> Result<(), SdkError> {
+        let mut command = Command::new("SET").arg(key).arg(value);
+
+        if let Some(ttl) = ttl {
+            command = command.arg("PX").arg(ttl.as_millis().to_string());
+        }
+
+        self.execute(&command)?;
+
+        Ok(())
+    }
+
+    /// Deletes a key, and returns whether it existed.
+    pub fn delete(&self, key: &str) -> Result<bool, SdkError> {
+        let deleted = self.execute(&Command::new("DEL").arg(key))?.as_int()?;
+        Ok(deleted > 0)
+    }
+
+    /// Publishes a message to a channel, and returns the number of subscribers who received it.
+    pub fn publish(&self, channel: &str, payload: &[u8]) -> Result<u64, SdkError> {
+        let receivers = self
+            .execute(&Command::new("PUBLISH").arg(channel).arg(payload))?
+            .as_int()?;
+        Ok(receivers as u64)
+    }
+}
+
+/// A Redis command with its arguments.
+pub struct Command(wit::RedisCommand);
+
+impl Command {
+    /// Creates a command without arguments, for example `GET` or `HSET`.
+    pub fn new(name: &str) -> Self {
+        Self(wit::RedisCommand {
+            name: name.to_owned(),
+            args: Vec::new(),
+        })
+    }
+
+    /// Appends an argument to the command.
+    pub fn arg(mut self, arg: impl AsRef<[u8]>) -> Self {
+        self.0.args.push(arg.as_ref().to_vec());
+        self
+    }
+}
+
+/// Commands sent to Redis in a single round trip.
+#[derive(Default)]
+pub struct Pipeline {
+    commands: Vec<wit::RedisCommand>,
+    atomic: bool,
+}
+
+impl Pipeline {
+    /// Creates an empty pipeline.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wraps the commands in a MULTI/EXEC transaction.
+    pub fn atomic(mut self) -> Self {
+        self.atomic = true;
+        self
+    }
+
+    /// Appends a command to the pipeline.
+    pub fn command(mut self, command: Command) -> Self {
+        self.commands.push(command.0);
+        self
+    }
+}
+
+/// A value returned by Redis.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// The absence of a value, for example for a missing key.
+    Nil,
+    /// An integer.
+    Int(i64),
+    /// A binary-safe string.
+    Data(Vec<u8>),
+    /// A status or verbatim string.
+    Status(String),
+    /// The OK status.
+    Okay,
+    /// A floating point number.
+    Double(f64),
+    /// A boolean.
+    Boolean(bool),
+    /// An array or a set.
+    Array(Vec<Value>),
+    /// A map of keys and values.
+    Map(Vec<(Value, Value)>),
+}
+
+impl Value {
+    /// Returns the integer, parsing strings such as the values of counters.
+    pub fn as_int(&self) -> Result<i64, SdkError> {
+        match self {
+            Value::Int(int) => Ok(*int),
+            Value::Data(data) => std::str::from_utf8(data)
+                .ok()
+                .and_then(|data| data.parse().ok())
+                .ok_or_else(|| SdkError::from("Redis value is not an integer")),
+            Value::Status(status) => status
+                .parse()
+                .map_err(|_| SdkError::from("Redis value is not an integer")),
+            _ => Err(SdkError::from("Redis value is not an integer")),
+        }
+    }
+
+    /// Returns the bytes of a string.
+    pub fn into_bytes(self) -> Result<Vec<u8>, SdkError> {
+        match self {
+            Value::Data(data) => Ok(data),
+            Value::Status(status) => Ok(status.into_bytes()),
+            _ => Err(SdkError::from("Redis value is not a string")),
+        }
+    }
+
+    /// Rebuilds a value flattened by the host, WIT doesn't support recursive types.
+    fn unflatten(values: &mut impl Iterator<Item = wit::RedisValue>) -> Result<Value, SdkError> {
+        let Some(value) = values.next() else {
+            return Err(SdkError::from("Incomplete Redis value"));
+        };
+
+        let value = match value {
+            wit::RedisValue::Nil => Value::Nil,
+            wit::RedisValue::Int(int) => Value::Int(int),
+            wit::RedisValue::BulkString(data) => Value::Data(data),
+            wit::RedisValue::SimpleString(status) => Value::Status(status),
+            wit::RedisValue::Okay => Value::Okay,
+            wit::RedisValue::Double(double) => Value::Double(double),
+            wit::RedisValue::Boolean(boolean) => Value::Boolean(boolean),
+            wit::RedisValue::Array(len) => {
+                Value::Array((0..len).map(|_| Value::unflatten(values)).collect::<Result<_, _>>()?)
+            }
+            wit::RedisValue::Map(len) => Value::Map(
+                (0..len)
+                    .map(|_| Ok((Value::unflatten(values)?, Value::unflatten(values)?)))
+                    .collect::<Result<_, SdkError>>()?,
+            ),
+        };
+
+        Ok(value)
+    }
+}
+
+/// A subscription to Redis channels.
+pub struct Subscription(wit::RedisSubscriber);
+
+impl Subscription {
+    /// Waits for the next message. Returns `None` if the connection was closed.
+    pub fn next(&self) -> Result<Option<Message>, SdkError> {
+        let message = self.0.next()?;
+        Ok(message.map(Message))
+    }
+}
+
+impl crate::Subscription for Subscription {
+    fn next(&mut self) -> Result<Option<SubscriptionItem>, Error> {
+        match Subscription::next(self) {
+            Ok(Some(message)) => Ok(Some(Response::json(message.0.payload).into())),
+            Ok(None) => Ok(None),
+            Err(err) => Err(format!("Error receiving Redis message: {err}").into()),
+        }
+    }
+}
+
+/// A message published to a channel.
+pub struct Message(wit::RedisMessage);
+
+impl Message {
+    /// The channel the message was published to.
+    pub fn channel(&self) -> &str {
+        &self.0.channel
+    }
+
+    /// The pattern that matched the channel, for pattern subscriptions.
+    pub fn pattern(&self) -> Option<&str> {
+        self.0.pattern.as_deref()
+    }
+
+    /// The raw payload of the message.
+    pub fn payload(&self) -> &[u8] {
+        &self.0.payload
+    }
+}
@@ -8,7 +8,7 @@ mod resolver_types;
 
 wit_bindgen::generate!({
     skip: ["register-extension"],
-    path: "./wit/since_0_23_0/",
+    path: "./wit/since_0_24_0/",
     world: "sdk",
     with: {
         "grafbase:sdk/resolver-types": resolver_types,
@@ -43,6 +43,7 @@ pub(crate) use grafbase::sdk::kafka_client::*;
 pub(crate) use grafbase::sdk::logger::*;
 pub use grafbase::sdk::nats_client::*;
 pub use grafbase::sdk::postgres::*;
+pub(crate) use grafbase::sdk::redis::*;
 pub(crate) use grafbase::sdk::schema::*;
 pub(crate) use grafbase::sdk::token::Token;
 pub(crate) use resolver_types::{ArgumentsId, Data, Field, FieldId, Response, SelectionSet, SubscriptionItem};
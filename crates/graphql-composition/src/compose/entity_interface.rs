@@ -166,17 +166,23 @@ pub(crate) fn merge_entity_interface_definitions<'a>(
         let object = ctx.subgraphs.at(object_id);
         match object.id.keys(ctx.subgraphs).next() {
             Some(key) if key.selection_set == expected_key.fields() => (),
-            Some(_) => ctx.diagnostics.push_fatal(format!(
-                "[{}] The object type `{}` implements the entity interface `{}` but does not have the same key. The key must match exactly.",
-                &ctx[ctx.subgraphs.at(object.subgraph_id).name],
-                &ctx[object.name],
-                ctx.subgraphs[first.name],
-            )),
-            None => ctx.diagnostics.push_fatal(format!(
-                "[{}] The object type `{}` is annotated with @interfaceObject but missing a key.",
-                &ctx[ctx.subgraphs.at(object.subgraph_id).name],
-                &ctx[object.name],
-            )),
+            Some(_) => {
+                ctx.diagnostics.push_fatal(format!(
+                    "[{}] The object type `{}` implements the entity interface `{}` but does not have the same key. The key must match exactly.",
+                    &ctx[ctx.subgraphs.at(object.subgraph_id).name],
+                    &ctx[object.name],
+                    ctx.subgraphs[first.name],
+                )).at(ctx.subgraphs.definition_location(object.id));
+            }
+            None => {
+                ctx.diagnostics
+                    .push_fatal(format!(
+                        "[{}] The object type `{}` is annotated with @interfaceObject but missing a key.",
+                        &ctx[ctx.subgraphs.at(object.subgraph_id).name],
+                        &ctx[object.name],
+                    ))
+                    .at(ctx.subgraphs.definition_location(object.id));
+            }
         }
 
         let object_name = ctx.insert_string(object.name);
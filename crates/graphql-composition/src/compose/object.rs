@@ -138,7 +138,7 @@ fn compose_field_argument_defaults<'a>(
                 argument_name = ctx.subgraphs[argument.name],
                 first_subgraph = ctx.subgraphs[first_subgraph.name],
                 second_subgraph = ctx.subgraphs[second_subgraph.name],
-            ))
+            ));
             }
         }
     }
@@ -295,22 +295,26 @@ fn ingest_join_field_directives(
 
         if directives.requires(ctx.subgraphs).is_some() && is_external {
             let parent_definition = ctx.subgraphs.at(field.parent_definition_id);
-            ctx.diagnostics.push_fatal(format!(
-                "field `{}` on `{}` declared as `@external` in subgraph `{}` cannot have a `@requires`.",
-                ctx.subgraphs[field_name],
-                ctx.subgraphs[parent_definition.name],
-                ctx.subgraphs[ctx.subgraphs.at(parent_definition.subgraph_id).name],
-            ));
+            ctx.diagnostics
+                .push_fatal(format!(
+                    "field `{}` on `{}` declared as `@external` in subgraph `{}` cannot have a `@requires`.",
+                    ctx.subgraphs[field_name],
+                    ctx.subgraphs[parent_definition.name],
+                    ctx.subgraphs[ctx.subgraphs.at(parent_definition.subgraph_id).name],
+                ))
+                .at(ctx.subgraphs.field_location(field.path()));
         }
 
         if directives.provides(ctx.subgraphs).is_some() && is_external {
             let parent_definition = ctx.subgraphs.at(field.parent_definition_id);
-            ctx.diagnostics.push_fatal(format!(
-                "field `{}` on `{}` declared as `@external` in subgraph `{}` cannot have a `@provides`.",
-                ctx.subgraphs[field_name],
-                ctx.subgraphs[parent_definition.name],
-                ctx.subgraphs[ctx.subgraphs.at(parent_definition.subgraph_id).name],
-            ));
+            ctx.diagnostics
+                .push_fatal(format!(
+                    "field `{}` on `{}` declared as `@external` in subgraph `{}` cannot have a `@provides`.",
+                    ctx.subgraphs[field_name],
+                    ctx.subgraphs[parent_definition.name],
+                    ctx.subgraphs[ctx.subgraphs.at(parent_definition.subgraph_id).name],
+                ))
+                .at(ctx.subgraphs.field_location(field.path()));
         }
 
         out.push(ir::Directive::JoinField(directive));
@@ -333,12 +337,14 @@ pub(crate) fn validate_shareable_object_fields_match(definitions: &[DefinitionVi
     for definition in definitions {
         for field in all_fields.difference(&inaccessible_fields) {
             if definition.id.field_by_name(ctx.subgraphs, *field).is_none() {
-                ctx.diagnostics.push_fatal(format!(
-                    "[{}] The shareable object `{}` is missing the `{}` field defined in other subgraphs.",
-                    ctx.subgraphs[ctx.subgraphs.at(ctx.subgraphs.at(definition.id).subgraph_id).name],
-                    ctx.subgraphs[definition.name],
-                    ctx.subgraphs[*field],
-                ));
+                ctx.diagnostics
+                    .push_fatal(format!(
+                        "[{}] The shareable object `{}` is missing the `{}` field defined in other subgraphs.",
+                        ctx.subgraphs[ctx.subgraphs.at(ctx.subgraphs.at(definition.id).subgraph_id).name],
+                        ctx.subgraphs[definition.name],
+                        ctx.subgraphs[*field],
+                    ))
+                    .at(ctx.subgraphs.definition_location(definition.id));
             }
         }
     }
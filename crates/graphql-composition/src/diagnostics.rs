@@ -1,5 +1,6 @@
 //! Composition warnings and errors.
 
+use crate::VecExt as _;
 use std::fmt;
 
 /// Warnings and errors produced by composition.
@@ -52,52 +53,67 @@ impl Diagnostics {
         source_schema_name: &str,
         message: impl fmt::Display,
         error_code: CompositeSchemasSourceSchemaValidationErrorCode,
-    ) {
-        self.0.push(Diagnostic {
+    ) -> &mut Diagnostic {
+        self.push(Diagnostic {
             message: format!("[{source_schema_name}] {message}"),
             severity: error_code.severity(),
             error_code: Some(error_code.into()),
-        });
+            subgraph: Some(source_schema_name.to_owned()),
+            span: None,
+        })
     }
 
     pub(crate) fn push_composite_schemas_pre_merge_validation_error(
         &mut self,
         message: String,
         error_code: CompositeSchemasPreMergeValidationErrorCode,
-    ) {
-        self.0.push(Diagnostic {
+    ) -> &mut Diagnostic {
+        self.push(Diagnostic {
             message,
             severity: error_code.severity(),
             error_code: Some(error_code.into()),
-        });
+            subgraph: None,
+            span: None,
+        })
     }
 
     pub(crate) fn push_composite_schemas_post_merge_validation_error(
         &mut self,
         message: String,
         error_code: CompositeSchemasPostMergeValidationErrorCode,
-    ) {
-        self.0.push(Diagnostic {
+    ) -> &mut Diagnostic {
+        self.push(Diagnostic {
             message,
             severity: error_code.severity(),
             error_code: Some(error_code.into()),
-        });
+            subgraph: None,
+            span: None,
+        })
     }
 
-    pub(crate) fn push_fatal(&mut self, message: String) {
-        self.0.push(Diagnostic {
+    pub(crate) fn push_fatal(&mut self, message: String) -> &mut Diagnostic {
+        self.push(Diagnostic {
             message,
             severity: Severity::Error,
             error_code: None,
-        });
+            subgraph: None,
+            span: None,
+        })
     }
 
-    pub(crate) fn push_warning(&mut self, message: String) {
-        self.0.push(Diagnostic {
+    pub(crate) fn push_warning(&mut self, message: String) -> &mut Diagnostic {
+        self.push(Diagnostic {
             message,
             severity: Severity::Warning,
             error_code: None,
-        });
+            subgraph: None,
+            span: None,
+        })
+    }
+
+    fn push(&mut self, diagnostic: Diagnostic) -> &mut Diagnostic {
+        let idx = self.0.push_return_idx(diagnostic);
+        &mut self.0[idx]
     }
 }
 
@@ -107,6 +123,8 @@ pub struct Diagnostic {
     message: String,
     severity: Severity,
     error_code: Option<CompositeSchemasErrorCode>,
+    subgraph: Option<String>,
+    span: Option<SourceSpan>,
 }
 
 impl Diagnostic {
@@ -124,6 +142,61 @@ impl Diagnostic {
     pub fn composite_schemas_error_code(&self) -> Option<CompositeSchemasErrorCode> {
         self.error_code
     }
+
+    /// The name of the subgraph the diagnostic originates from, when it can be attributed to a
+    /// single subgraph.
+    pub fn subgraph(&self) -> Option<&str> {
+        self.subgraph.as_deref()
+    }
+
+    /// The location of the diagnostic in the SDL of [Diagnostic::subgraph()]. Only available when
+    /// the subgraph was ingested along with its source, see [Subgraphs::ingest_with_source()].
+    ///
+    /// [Subgraphs::ingest_with_source()]: crate::Subgraphs::ingest_with_source
+    pub fn span(&self) -> Option<SourceSpan> {
+        self.span
+    }
+
+    pub(crate) fn at(&mut self, location: SubgraphLocation<'_>) -> &mut Self {
+        self.subgraph = Some(location.subgraph.to_owned());
+        self.span = location.span.or(self.span);
+        self
+    }
+
+    pub(crate) fn with_subgraph(&mut self, subgraph: String) -> &mut Self {
+        self.subgraph = Some(subgraph);
+        self
+    }
+
+    pub(crate) fn with_span(&mut self, span: Option<SourceSpan>) -> &mut Self {
+        self.span = span;
+        self
+    }
+}
+
+/// A position in a subgraph SDL. Lines and columns start at 1, columns are counted in characters.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    /// The line number, starting at 1.
+    pub line: usize,
+    /// The column number, starting at 1.
+    pub column: usize,
+}
+
+/// A range in a subgraph SDL. The end is exclusive.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceSpan {
+    /// Where the range starts.
+    pub start: Location,
+    /// Where the range ends.
+    pub end: Location,
+}
+
+/// Where a diagnostic points to: a subgraph, and a span in its SDL if known.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SubgraphLocation<'a> {
+    pub(crate) subgraph: &'a str,
+    pub(crate) span: Option<SourceSpan>,
 }
 
 /// The severity of a [Diagnostic].
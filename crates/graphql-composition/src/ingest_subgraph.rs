@@ -6,11 +6,13 @@ mod directive_definitions;
 mod directives;
 mod enums;
 mod fields;
+mod line_index;
 mod nested_key_fields;
 mod schema_definitions;
 
 use self::{
-    directive_definitions::*, directives::*, nested_key_fields::ingest_nested_key_fields, schema_definitions::*,
+    directive_definitions::*, directives::*, line_index::LineIndex, nested_key_fields::ingest_nested_key_fields,
+    schema_definitions::*,
 };
 use crate::{
     Subgraphs,
@@ -29,10 +31,19 @@ struct Context<'a> {
     subgraph_id: SubgraphId,
     subgraphs: &'a mut Subgraphs,
     root_type_matcher: RootTypeMatcher<'a>,
+    /// Only present when the subgraph was ingested with its source.
+    line_index: Option<LineIndex<'a>>,
+}
+
+impl Context<'_> {
+    fn span(&self, span: cynic_parser::Span) -> Option<crate::diagnostics::SourceSpan> {
+        self.line_index.as_ref().map(|line_index| line_index.span(span))
+    }
 }
 
 pub(crate) fn ingest_subgraph(
     document: &ast::TypeSystemDocument,
+    source: Option<&str>,
     name: &str,
     url: Option<&str>,
     subgraphs: &mut Subgraphs,
@@ -44,6 +55,7 @@ pub(crate) fn ingest_subgraph(
         subgraph_id,
         subgraphs,
         root_type_matcher: Default::default(),
+        line_index: source.map(LineIndex::new),
     };
 
     ingest_directive_definitions(&mut ctx);
@@ -89,7 +101,8 @@ fn ingest_top_level_definitions(ctx: &mut Context<'_>) {
                                 ctx.subgraphs.set_subscription_type(subgraph_id, definition_id);
                             }
                             RootTypeMatch::NotRootButHasDefaultRootName => {
-                                ctx.subgraphs.push_ingestion_diagnostic(subgraph_id, format!("The {type_name} type has the default name for a root but is itself not a root. This is not valid in a federation context."));
+                                let span = ctx.span(type_definition.span());
+                                ctx.subgraphs.push_ingestion_diagnostic(subgraph_id, format!("The {type_name} type has the default name for a root but is itself not a root. This is not valid in a federation context.")).with_span(span);
                             }
                             RootTypeMatch::NotRoot => (),
                         }
@@ -126,6 +139,10 @@ fn ingest_top_level_definitions(ctx: &mut Context<'_>) {
                     }
                 };
 
+                if let Some(span) = ctx.span(type_definition.span()) {
+                    ctx.subgraphs.set_definition_span(definition_id, span);
+                }
+
                 let directive_site_id = ctx.subgraphs.at(definition_id).directives;
                 directives::ingest_directives(ctx, directive_site_id, type_definition.directives(), |_| {
                     type_name.to_owned()
@@ -148,29 +165,35 @@ fn ingest_definition_bodies(ctx: &mut Context<'_>) {
     });
 
     for definition in type_definitions {
+        let span = ctx.span(definition.span());
+
         match definition {
             ast::TypeDefinition::Union(_) if definition.name() == ENTITY_UNION_NAME => continue,
             ast::TypeDefinition::Union(union) => {
                 let Some(union_id) = ctx.subgraphs.definition_by_name(definition.name(), subgraph_id) else {
-                    ctx.subgraphs.push_ingestion_diagnostic(
-                        subgraph_id,
-                        format!(
-                            "Union type `{}` is used but not defined in the subgraph.",
-                            definition.name()
-                        ),
-                    );
+                    ctx.subgraphs
+                        .push_ingestion_diagnostic(
+                            subgraph_id,
+                            format!(
+                                "Union type `{}` is used but not defined in the subgraph.",
+                                definition.name()
+                            ),
+                        )
+                        .with_span(span);
                     continue;
                 };
 
                 for member in union.members() {
                     let Some(member_id) = ctx.subgraphs.definition_by_name(member.name(), subgraph_id) else {
-                        ctx.subgraphs.push_ingestion_diagnostic(
-                            subgraph_id,
-                            format!(
-                                "Union member `{}` is used but not defined in the subgraph.",
-                                member.name()
-                            ),
-                        );
+                        ctx.subgraphs
+                            .push_ingestion_diagnostic(
+                                subgraph_id,
+                                format!(
+                                    "Union member `{}` is used but not defined in the subgraph.",
+                                    member.name()
+                                ),
+                            )
+                            .with_span(span);
                         continue;
                     };
                     ctx.subgraphs.push_union_member(union_id, member_id);
@@ -178,26 +201,30 @@ fn ingest_definition_bodies(ctx: &mut Context<'_>) {
             }
             ast::TypeDefinition::InputObject(input_object) => {
                 let Some(definition_id) = ctx.subgraphs.definition_by_name(definition.name(), subgraph_id) else {
-                    ctx.subgraphs.push_ingestion_diagnostic(
-                        subgraph_id,
-                        format!(
-                            "Input object type `{}` is used but not defined in the subgraph.",
-                            definition.name()
-                        ),
-                    );
+                    ctx.subgraphs
+                        .push_ingestion_diagnostic(
+                            subgraph_id,
+                            format!(
+                                "Input object type `{}` is used but not defined in the subgraph.",
+                                definition.name()
+                            ),
+                        )
+                        .with_span(span);
                     continue;
                 };
                 fields::ingest_input_fields(ctx, definition_id, input_object.fields());
             }
             ast::TypeDefinition::Interface(interface) => {
                 let Some(definition_id) = ctx.subgraphs.definition_by_name(interface.name(), subgraph_id) else {
-                    ctx.subgraphs.push_ingestion_diagnostic(
-                        subgraph_id,
-                        format!(
-                            "Interface type `{}` is used but not defined in the subgraph.",
-                            interface.name()
-                        ),
-                    );
+                    ctx.subgraphs
+                        .push_ingestion_diagnostic(
+                            subgraph_id,
+                            format!(
+                                "Interface type `{}` is used but not defined in the subgraph.",
+                                interface.name()
+                            ),
+                        )
+                        .with_span(span);
                     continue;
                 };
 
@@ -205,15 +232,17 @@ fn ingest_definition_bodies(ctx: &mut Context<'_>) {
                     let Some(implemented_interface) =
                         ctx.subgraphs.definition_by_name(implemented_interface, subgraph_id)
                     else {
-                        ctx.subgraphs.push_ingestion_diagnostic(
-                            subgraph_id,
-                            format!(
-                                "Interface `{}` implements `{}`, but `{}` is not defined in the subgraph.",
-                                interface.name(),
-                                implemented_interface,
-                                implemented_interface
-                            ),
-                        );
+                        ctx.subgraphs
+                            .push_ingestion_diagnostic(
+                                subgraph_id,
+                                format!(
+                                    "Interface `{}` implements `{}`, but `{}` is not defined in the subgraph.",
+                                    interface.name(),
+                                    implemented_interface,
+                                    implemented_interface
+                                ),
+                            )
+                            .with_span(span);
                         continue;
                     };
 
@@ -227,13 +256,15 @@ fn ingest_definition_bodies(ctx: &mut Context<'_>) {
             ast::TypeDefinition::Object(_) if definition.name() == SERVICE_TYPE_NAME => continue,
             ast::TypeDefinition::Object(object_type) => {
                 let Some(definition_id) = ctx.subgraphs.definition_by_name(definition.name(), subgraph_id) else {
-                    ctx.subgraphs.push_ingestion_diagnostic(
-                        subgraph_id,
-                        format!(
-                            "Object type `{}` is used but not defined in the subgraph.",
-                            definition.name()
-                        ),
-                    );
+                    ctx.subgraphs
+                        .push_ingestion_diagnostic(
+                            subgraph_id,
+                            format!(
+                                "Object type `{}` is used but not defined in the subgraph.",
+                                definition.name()
+                            ),
+                        )
+                        .with_span(span);
                     continue;
                 };
 
@@ -241,15 +272,17 @@ fn ingest_definition_bodies(ctx: &mut Context<'_>) {
                     let Some(implemented_interface) =
                         ctx.subgraphs.definition_by_name(implemented_interface, subgraph_id)
                     else {
-                        ctx.subgraphs.push_ingestion_diagnostic(
-                            subgraph_id,
-                            format!(
-                                "Object type `{}` implements `{}`, but `{}` is not defined in the subgraph.",
-                                object_type.name(),
-                                implemented_interface,
-                                implemented_interface
-                            ),
-                        );
+                        ctx.subgraphs
+                            .push_ingestion_diagnostic(
+                                subgraph_id,
+                                format!(
+                                    "Object type `{}` implements `{}`, but `{}` is not defined in the subgraph.",
+                                    object_type.name(),
+                                    implemented_interface,
+                                    implemented_interface
+                                ),
+                            )
+                            .with_span(span);
                         continue;
                     };
 
@@ -262,13 +295,15 @@ fn ingest_definition_bodies(ctx: &mut Context<'_>) {
             }
             ast::TypeDefinition::Enum(enum_definition) => {
                 let Some(enum_id) = ctx.subgraphs.definition_by_name(definition.name(), subgraph_id) else {
-                    ctx.subgraphs.push_ingestion_diagnostic(
-                        subgraph_id,
-                        format!(
-                            "Union type `{}` is used but not defined in the subgraph.",
-                            definition.name()
-                        ),
-                    );
+                    ctx.subgraphs
+                        .push_ingestion_diagnostic(
+                            subgraph_id,
+                            format!(
+                                "Union type `{}` is used but not defined in the subgraph.",
+                                definition.name()
+                            ),
+                        )
+                        .with_span(span);
                     continue;
                 };
 
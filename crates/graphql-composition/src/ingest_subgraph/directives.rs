@@ -17,6 +17,7 @@ pub(super) fn ingest_directives(
 ) {
     for directive in directives_node {
         let (directive_name_id, match_result) = match_directive_name(ctx, directive.name());
+        let span = ctx.span(directive.name_span());
 
         let is_composed_directive = ctx.subgraphs.is_composed_directive(ctx.subgraph_id, directive_name_id);
 
@@ -77,10 +78,12 @@ pub(super) fn ingest_directives(
                 }
                 Err(error) => {
                     let location = location(ctx.subgraphs);
-                    ctx.subgraphs.push_ingestion_diagnostic(
-                        ctx.subgraph_id,
-                        format!("Error validating the @cost directive at {location}: {error}"),
-                    );
+                    ctx.subgraphs
+                        .push_ingestion_diagnostic(
+                            ctx.subgraph_id,
+                            format!("Error validating the @cost directive at {location}: {error}"),
+                        )
+                        .with_span(span);
                 }
             },
             DirectiveNameMatch::ListSize => match directive.deserialize::<ListSizeDirective>() {
@@ -89,10 +92,12 @@ pub(super) fn ingest_directives(
                 }
                 Err(error) => {
                     let location = location(ctx.subgraphs);
-                    ctx.subgraphs.push_ingestion_diagnostic(
-                        ctx.subgraph_id,
-                        format!("Error validating the @listSize directive at {location}: {error}"),
-                    );
+                    ctx.subgraphs
+                        .push_ingestion_diagnostic(
+                            ctx.subgraph_id,
+                            format!("Error validating the @listSize directive at {location}: {error}"),
+                        )
+                        .with_span(span);
                 }
             },
             DirectiveNameMatch::Authenticated => {
@@ -102,10 +107,12 @@ pub(super) fn ingest_directives(
                 Ok(directive) => ctx.subgraphs.insert_deprecated(directive_site_id, directive.reason),
                 Err(err) => {
                     let location = location(ctx.subgraphs);
-                    ctx.subgraphs.push_ingestion_diagnostic(
-                        ctx.subgraph_id,
-                        format!("Error validating the @deprecated directive at {location}: {err}",),
-                    );
+                    ctx.subgraphs
+                        .push_ingestion_diagnostic(
+                            ctx.subgraph_id,
+                            format!("Error validating the @deprecated directive at {location}: {err}",),
+                        )
+                        .with_span(span);
                 }
             },
             DirectiveNameMatch::External => {
@@ -169,7 +176,8 @@ pub(super) fn ingest_directives(
                 };
                 if let Err(err) = ctx.subgraphs.insert_provides(directive_site_id, fields_arg) {
                     ctx.subgraphs
-                        .push_ingestion_diagnostic(ctx.subgraph_id, err.to_string());
+                        .push_ingestion_diagnostic(ctx.subgraph_id, err.to_string())
+                        .with_span(span);
                 }
             }
             DirectiveNameMatch::Requires => {
@@ -181,7 +189,8 @@ pub(super) fn ingest_directives(
 
                 if let Err(err) = ctx.subgraphs.insert_requires(directive_site_id, fields_arg) {
                     ctx.subgraphs
-                        .push_ingestion_diagnostic(ctx.subgraph_id, err.to_string());
+                        .push_ingestion_diagnostic(ctx.subgraph_id, err.to_string())
+                        .with_span(span);
                 };
             }
             DirectiveNameMatch::RequiresScopes => {
@@ -234,7 +243,8 @@ pub(super) fn ingest_directives(
                     Ok(directive) => directive,
                     Err(err) => {
                         ctx.subgraphs
-                            .push_ingestion_diagnostic(ctx.subgraph_id, err.to_string());
+                            .push_ingestion_diagnostic(ctx.subgraph_id, err.to_string())
+                            .with_span(span);
                         continue;
                     }
                 };
@@ -254,7 +264,8 @@ pub(super) fn ingest_directives(
                     Ok(directive) => directive,
                     Err(err) => {
                         ctx.subgraphs
-                            .push_ingestion_diagnostic(ctx.subgraph_id, err.to_string());
+                            .push_ingestion_diagnostic(ctx.subgraph_id, err.to_string())
+                            .with_span(span);
                         continue;
                     }
                 };
@@ -278,10 +289,12 @@ pub(super) fn ingest_directives(
                 let location = location(ctx.subgraphs);
                 let directive_name = ctx.subgraphs.at(directive_name_id);
 
-                ctx.subgraphs.push_ingestion_warning(
-                    ctx.subgraph_id,
-                    format!("Unknown directive `@{}` at `{}`", directive_name.as_ref(), location,),
-                );
+                ctx.subgraphs
+                    .push_ingestion_warning(
+                        ctx.subgraph_id,
+                        format!("Unknown directive `@{}` at `{}`", directive_name.as_ref(), location,),
+                    )
+                    .with_span(span);
             }
 
             DirectiveNameMatch::ComposeDirective
@@ -305,11 +318,12 @@ pub(super) fn ingest_keys(
 
         if let DirectiveNameMatch::Key | DirectiveNameMatch::KeyFromCompositeSchemas = match_result {
             if directive.argument("field").is_some() {
+                let span = ctx.span(directive.name_span());
                 let definition_name = &ctx.subgraphs[ctx.subgraphs[definition_id].name];
                 ctx.subgraphs.push_ingestion_warning(
                     ctx.subgraph_id,
                     format!(r#"Wrong argument: "field:" argument instead of "fields:" in @key directive on `{definition_name}`"#,),
-                );
+                ).with_span(span);
             }
 
             let fields_arg = directive.argument("fields").and_then(|v| v.value().as_str());
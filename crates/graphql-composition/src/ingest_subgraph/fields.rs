@@ -35,6 +35,11 @@ pub(super) fn ingest_input_fields(
             description,
             input_field_default_value: default,
         });
+
+        if let Some(span) = ctx.span(field.span()) {
+            ctx.subgraphs
+                .set_field_span(subgraphs::FieldPath(parent_definition_id, name), span);
+        }
     }
 }
 
@@ -108,6 +113,11 @@ pub(super) fn ingest_fields(
             input_field_default_value: None,
         });
 
+        if let Some(span) = ctx.span(field.span()) {
+            ctx.subgraphs
+                .set_field_span(subgraphs::FieldPath(definition_id, field_name_id), span);
+        }
+
         directives::ingest_directives(ctx, directives, field.directives(), |subgraphs| {
             let definition = subgraphs.at(definition_id);
             format!("{}.{}", subgraphs[definition.name], field_name)
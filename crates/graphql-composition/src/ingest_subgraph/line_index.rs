@@ -0,0 +1,65 @@
+use crate::diagnostics::{Location, SourceSpan};
+
+/// Converts the byte offsets of the parser into lines and columns.
+pub(super) struct LineIndex<'a> {
+    source: &'a str,
+    /// The byte offset of the first character of each line.
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub(super) fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+
+        LineIndex { source, line_starts }
+    }
+
+    pub(super) fn span(&self, span: cynic_parser::Span) -> SourceSpan {
+        SourceSpan {
+            start: self.location(span.start),
+            end: self.location(span.end),
+        }
+    }
+
+    fn location(&self, offset: usize) -> Location {
+        let offset = offset.min(self.source.len());
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let line_start = self.line_starts[line];
+
+        let column = self
+            .source
+            .get(line_start..offset)
+            .map(|line| line.chars().count())
+            .unwrap_or(offset - line_start);
+
+        Location {
+            line: line + 1,
+            column: column + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locations() {
+        let source = "type Query {\n  café: String\n  id: ID!\n}\n";
+        let index = LineIndex::new(source);
+
+        let id = source.find("id").unwrap();
+        let span = index.span(cynic_parser::Span { start: id, end: id + 2 });
+
+        assert_eq!(span.start, Location { line: 3, column: 3 });
+        assert_eq!(span.end, Location { line: 3, column: 5 });
+
+        let string = source.find("String").unwrap();
+        assert_eq!(index.location(string), Location { line: 2, column: 9 });
+
+        assert_eq!(index.location(0), Location { line: 1, column: 1 });
+        assert_eq!(index.location(source.len()), Location { line: 5, column: 1 });
+    }
+}
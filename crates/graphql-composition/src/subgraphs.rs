@@ -7,6 +7,7 @@ mod fields;
 mod ids;
 mod keys;
 mod linked_schemas;
+mod locations;
 mod strings;
 mod top;
 mod unions;
@@ -17,7 +18,7 @@ pub(crate) use self::{
     linked_schemas::*, strings::StringId, top::*, view::View,
 };
 
-use crate::VecExt;
+use crate::{VecExt, diagnostics::Diagnostic};
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 /// A set of subgraphs to be composed.
@@ -34,6 +35,8 @@ pub struct Subgraphs {
 
     ingestion_diagnostics: crate::Diagnostics,
 
+    locations: locations::Locations,
+
     extensions: Vec<ExtensionRecord>,
 
     // Secondary indexes.
@@ -63,6 +66,7 @@ impl Default for Subgraphs {
             keys: Default::default(),
             unions: Default::default(),
             ingestion_diagnostics: Default::default(),
+            locations: Default::default(),
             definition_names: Default::default(),
             linked_schemas: Default::default(),
             extensions: Vec::new(),
@@ -97,17 +101,30 @@ impl std::fmt::Display for IngestError {
 impl Subgraphs {
     /// Add a subgraph to compose.
     pub fn ingest(&mut self, subgraph_schema: &cynic_parser::TypeSystemDocument, name: &str, url: Option<&str>) {
-        crate::ingest_subgraph::ingest_subgraph(subgraph_schema, name, url, self);
+        crate::ingest_subgraph::ingest_subgraph(subgraph_schema, None, name, url, self);
+    }
+
+    /// Add a subgraph to compose, along with the SDL it was parsed from. Diagnostics about the
+    /// subgraph will carry their [span](crate::diagnostics::Diagnostic::span) in that SDL.
+    pub fn ingest_with_source(
+        &mut self,
+        subgraph_schema: &cynic_parser::TypeSystemDocument,
+        source: &str,
+        name: &str,
+        url: Option<&str>,
+    ) {
+        crate::ingest_subgraph::ingest_subgraph(subgraph_schema, Some(source), name, url, self);
     }
 
     /// Add a subgraph to compose.
     pub fn ingest_str(&mut self, subgraph_schema: &str, name: &str, url: Option<&str>) -> Result<(), IngestError> {
+        let subgraph_schema_source = subgraph_schema;
         let subgraph_schema =
             cynic_parser::parse_type_system_document(subgraph_schema).map_err(|error| IngestError {
                 report: error.to_report(subgraph_schema).to_string(),
                 error,
             })?;
-        crate::ingest_subgraph::ingest_subgraph(&subgraph_schema, name, url, self);
+        self.ingest_with_source(&subgraph_schema, subgraph_schema_source, name, url);
         Ok(())
     }
 
@@ -156,14 +173,20 @@ impl Subgraphs {
         compose_fn(&buf)
     }
 
-    pub(crate) fn push_ingestion_diagnostic(&mut self, subgraph: SubgraphId, message: String) {
+    pub(crate) fn push_ingestion_diagnostic(&mut self, subgraph: SubgraphId, message: String) -> &mut Diagnostic {
+        let subgraph_name = self[self.at(subgraph).name].to_string();
+
         self.ingestion_diagnostics
-            .push_fatal(format!("[{}]: {message}", self[self.at(subgraph).name]));
+            .push_fatal(format!("[{subgraph_name}]: {message}"))
+            .with_subgraph(subgraph_name)
     }
 
-    pub(crate) fn push_ingestion_warning(&mut self, subgraph: SubgraphId, message: String) {
+    pub(crate) fn push_ingestion_warning(&mut self, subgraph: SubgraphId, message: String) -> &mut Diagnostic {
+        let subgraph_name = self[self.at(subgraph).name].to_string();
+
         self.ingestion_diagnostics
-            .push_warning(format!("[{}]: {message}", self[self.at(subgraph).name]));
+            .push_warning(format!("[{subgraph_name}]: {message}"))
+            .with_subgraph(subgraph_name)
     }
 
     /// Iterates all builtin scalars.
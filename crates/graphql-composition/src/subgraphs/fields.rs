@@ -87,6 +87,10 @@ impl Subgraphs {
 }
 
 impl FieldTuple {
+    pub(crate) fn path(&self) -> FieldPath {
+        FieldPath(self.parent_definition_id, self.name)
+    }
+
     /// ```graphql,ignore
     /// type Query {
     ///   findManyUser(filters: FindManyUserFilter?, searchQuery: String?): [User!]!
@@ -0,0 +1,48 @@
+use super::*;
+use crate::diagnostics::{SourceSpan, SubgraphLocation};
+
+/// The spans of definitions and fields in the SDL of their subgraph. They are only known for
+/// subgraphs ingested along with their source.
+#[derive(Default)]
+pub(crate) struct Locations {
+    definitions: HashMap<DefinitionId, SourceSpan>,
+    // Field ids are not stable during ingestion, see [Fields].
+    fields: HashMap<FieldPath, SourceSpan>,
+}
+
+impl Subgraphs {
+    /// Keeps the first span, which is the definition itself and not one of its extensions, if the
+    /// definition comes first.
+    pub(crate) fn set_definition_span(&mut self, definition_id: DefinitionId, span: SourceSpan) {
+        self.locations.definitions.entry(definition_id).or_insert(span);
+    }
+
+    pub(crate) fn set_field_span(&mut self, field: FieldPath, span: SourceSpan) {
+        self.locations.fields.entry(field).or_insert(span);
+    }
+
+    pub(crate) fn subgraph_location(&self, subgraph_id: SubgraphId) -> SubgraphLocation<'_> {
+        SubgraphLocation {
+            subgraph: &self[self.at(subgraph_id).name],
+            span: None,
+        }
+    }
+
+    pub(crate) fn definition_location(&self, definition_id: DefinitionId) -> SubgraphLocation<'_> {
+        SubgraphLocation {
+            span: self.locations.definitions.get(&definition_id).copied(),
+            ..self.subgraph_location(self.at(definition_id).subgraph_id)
+        }
+    }
+
+    /// Falls back to the span of the parent definition.
+    pub(crate) fn field_location(&self, field: FieldPath) -> SubgraphLocation<'_> {
+        let FieldPath(parent_definition_id, _) = field;
+        let parent_location = self.definition_location(parent_definition_id);
+
+        SubgraphLocation {
+            span: self.locations.fields.get(&field).copied().or(parent_location.span),
+            ..parent_location
+        }
+    }
+}
@@ -15,11 +15,13 @@ pub(crate) fn query_root_type_inaccessible(ctx: &mut ValidateContext<'_>) {
         }
 
         let subgraph_name = &ctx.subgraphs[subgraph.name];
-        ctx.diagnostics.push_composite_schemas_source_schema_validation_error(
-            subgraph_name,
-            format_args!("The query root type cannot be inaccessible"),
-            CompositeSchemasSourceSchemaValidationErrorCode::QueryRootTypeInaccessible,
-        );
+        ctx.diagnostics
+            .push_composite_schemas_source_schema_validation_error(
+                subgraph_name,
+                format_args!("The query root type cannot be inaccessible"),
+                CompositeSchemasSourceSchemaValidationErrorCode::QueryRootTypeInaccessible,
+            )
+            .at(ctx.subgraphs.definition_location(query_root));
     }
 }
 
@@ -40,11 +42,13 @@ pub(crate) fn lookup_returns_non_nullable_type(ctx: &mut ValidateContext<'_>, fi
             "The \"{parent_definition_name}.{field_name}\" lookup field is required, but fields annotated with @lookup should be nullable.",
         );
 
-        ctx.diagnostics.push_composite_schemas_source_schema_validation_error(
-            source_schema_name,
-            message,
-            CompositeSchemasSourceSchemaValidationErrorCode::LookupReturnsNonNullableType,
-        );
+        ctx.diagnostics
+            .push_composite_schemas_source_schema_validation_error(
+                source_schema_name,
+                message,
+                CompositeSchemasSourceSchemaValidationErrorCode::LookupReturnsNonNullableType,
+            )
+            .at(ctx.subgraphs.field_location(field.path()));
     }
 }
 
@@ -69,5 +73,6 @@ pub(crate) fn override_from_self(ctx: &mut ValidateContext<'_>, field: subgraphs
             field_name = ctx.subgraphs[field.name]
         ),
         CompositeSchemasSourceSchemaValidationErrorCode::OverrideFromSelf,
-    );
+    )
+    .at(ctx.subgraphs.field_location(field.path()));
 }
@@ -160,27 +160,30 @@ fn validate_field_selection(
 ) {
     if &ctx[selection.field] == "__typename" {
         if !selection.arguments.is_empty() {
-            return ctx.diagnostics.push_fatal(format!(
+            ctx.diagnostics.push_fatal(format!(
                 "[{subgraph_name}] Error in @{directive_name} on {directive_path}: the __typename field does not accept arguments.",
                 directive_path = directive_path(),
             ));
+            return;
         }
         if !selection.subselection.is_empty() {
-            return ctx.diagnostics.push_fatal(format!(
+            ctx.diagnostics.push_fatal(format!(
                 "Error in @{directive_name} on {directive_path}: the __typename field does not accept subselections.",
                 directive_path = directive_path(),
             ));
+            return;
         }
         return;
     }
     // The selected field must exist.
     let Some(field) = on_definition.id.field_by_name(ctx.subgraphs, selection.field) else {
-        return ctx.diagnostics.push_fatal(format!(
+        ctx.diagnostics.push_fatal(format!(
             "[{subgraph_name}] Error in @{directive_name} at {directive_path}: the {field_in_selection} field does not exist on {definition_name}",
             field_in_selection = ctx.subgraphs[selection.field],
             directive_path = directive_path(),
             definition_name = ctx.subgraphs[on_definition.name]
         ));
+        return;
     };
 
     for required_argument in field
@@ -201,23 +204,25 @@ fn validate_field_selection(
     // The arguments must exist on the field.
     for (argument_name, argument_value) in &selection.arguments {
         let Some(argument) = field.argument_by_name(ctx.subgraphs, *argument_name) else {
-            return ctx.diagnostics.push_fatal(format!(
+            ctx.diagnostics.push_fatal(format!(
                 "[{subgraph_name}] Error in @{directive_name} on {directive_path}: the {field_in_selection}.{argument_name} argument does not exist on {definition_name}",
                 argument_name = ctx.subgraphs[*argument_name],
                 field_in_selection = ctx.subgraphs[field.name],
                 definition_name = ctx.subgraphs[on_definition.name],
                 directive_path = directive_path(),
             ));
+            return;
         };
 
         if !argument_type_matches(ctx, on_definition.subgraph_id, &argument.r#type, argument_value) {
-            return ctx.diagnostics.push_fatal(format!(
+            ctx.diagnostics.push_fatal(format!(
                 "[{subgraph_name}] Error in @{directive_name} on {directive_path}: the {field_in_selection}.{argument_name} argument does not not match the expected type ({expected_type})",
                 argument_name = ctx.subgraphs[*argument_name],
                 field_in_selection = ctx.subgraphs[field.name],
                 expected_type = argument.r#type.display(ctx.subgraphs),
                 directive_path = directive_path(),
             ));
+            return;
         }
     }
 
@@ -20,3 +20,36 @@ fn subgraph_names_that_differ_only_by_case_are_not_allowed() {
         "Found two subgraphs named \"Valid\". Subgraph names are case insensitive."
     );
 }
+
+#[test]
+fn diagnostics_point_to_their_subgraph_and_span() {
+    let mut subgraphs = graphql_composition::Subgraphs::default();
+
+    let schema = "type Query {\n  a: A\n}\n\nunion U = A | B\n\ntype A {\n  id: ID\n}\n";
+
+    subgraphs
+        .ingest_str(schema, "with-source", Some("http://example.com/with-source"))
+        .unwrap();
+
+    let document = cynic_parser::parse_type_system_document(schema).unwrap();
+    subgraphs.ingest(&document, "without-source", Some("http://example.com/without-source"));
+
+    let result = graphql_composition::compose(&mut subgraphs);
+
+    let diagnostics = result
+        .diagnostics()
+        .iter()
+        .filter(|diagnostic| diagnostic.message().contains("Union member `B`"))
+        .collect::<Vec<_>>();
+
+    assert_eq!(diagnostics.len(), 2);
+
+    assert_eq!(diagnostics[0].subgraph(), Some("with-source"));
+    assert_eq!(
+        diagnostics[0].span().map(|span| span.start),
+        Some(graphql_composition::diagnostics::Location { line: 5, column: 1 })
+    );
+
+    assert_eq!(diagnostics[1].subgraph(), Some("without-source"));
+    assert_eq!(diagnostics[1].span(), None);
+}
@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use crate::Severity;
+
+/// A lint rule, which can be turned off or have its severity changed in [LintConfig].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+    /// Types and enums in `PascalCase`, fields, arguments and directives in `camelCase`, enum
+    /// values in `SCREAMING_SNAKE_CASE`.
+    NamingConvention,
+    /// Redundant prefixes and suffixes, e.g. `TypeUser` or `getUser` on `Query`.
+    ForbiddenAffixes,
+    /// `@deprecated` without a `reason` argument.
+    DeprecationReason,
+    /// Type definitions without a description.
+    DescriptionRequired,
+    /// Type definitions that are neither root types nor referenced anywhere in the schema.
+    NoUnusedTypes,
+    /// Boolean fields not starting with one of [BOOLEAN_FIELD_PREFIXES].
+    BooleanFieldPrefix,
+    /// Input objects not ending with `Input`.
+    InputObjectNaming,
+}
+
+/// The prefixes expected on Boolean fields with [Rule::BooleanFieldPrefix].
+pub const BOOLEAN_FIELD_PREFIXES: [&str; 7] = ["is", "has", "can", "should", "was", "will", "did"];
+
+impl Rule {
+    pub const ALL: [Rule; 7] = [
+        Rule::NamingConvention,
+        Rule::ForbiddenAffixes,
+        Rule::DeprecationReason,
+        Rule::DescriptionRequired,
+        Rule::NoUnusedTypes,
+        Rule::BooleanFieldPrefix,
+        Rule::InputObjectNaming,
+    ];
+
+    /// The name of the rule in configuration files and `# lint-disable` comments.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rule::NamingConvention => "naming-convention",
+            Rule::ForbiddenAffixes => "forbidden-affixes",
+            Rule::DeprecationReason => "deprecation-reason",
+            Rule::DescriptionRequired => "description-required",
+            Rule::NoUnusedTypes => "no-unused-types",
+            Rule::BooleanFieldPrefix => "boolean-field-prefix",
+            Rule::InputObjectNaming => "input-object-naming",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Rule> {
+        Rule::ALL.into_iter().find(|rule| rule.as_str() == name)
+    }
+
+    /// The rules checked before rules were configurable are enabled by default, the others must
+    /// be enabled explicitly.
+    pub fn default_level(&self) -> RuleLevel {
+        match self {
+            Rule::NamingConvention | Rule::ForbiddenAffixes | Rule::DeprecationReason => RuleLevel::Warning,
+            Rule::DescriptionRequired | Rule::NoUnusedTypes | Rule::BooleanFieldPrefix | Rule::InputObjectNaming => {
+                RuleLevel::Off
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    Off,
+    Warning,
+    Error,
+}
+
+/// The linter configuration, usually deserialized from a file such as:
+///
+/// ```toml
+/// [rules]
+/// naming-convention = "error"
+/// description-required = "warning"
+/// forbidden-affixes = "off"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LintConfig {
+    pub rules: BTreeMap<Rule, RuleLevel>,
+}
+
+impl LintConfig {
+    /// The severity of the diagnostics of a rule, `None` if the rule is turned off.
+    pub fn severity(&self, rule: Rule) -> Option<Severity> {
+        match self.rules.get(&rule).copied().unwrap_or_else(|| rule.default_level()) {
+            RuleLevel::Off => None,
+            RuleLevel::Warning => Some(Severity::Warning),
+            RuleLevel::Error => Some(Severity::Error),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use std::ops::Range;
+
+use crate::Diagnostic;
+
+/// A replacement of a part of the schema, fixing a diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Edit {
+    /// The byte range being replaced.
+    pub span: Range<usize>,
+    pub replacement: String,
+}
+
+/// Applies the fixes of all the diagnostics to the schema they were produced from. Edits
+/// overlapping with a previous one are skipped, running the linter again will report them.
+pub fn apply_fixes(schema: &str, diagnostics: &[Diagnostic]) -> String {
+    let mut edits = diagnostics
+        .iter()
+        .flat_map(|diagnostic| &diagnostic.fixes)
+        .collect::<Vec<_>>();
+
+    edits.sort_by_key(|edit| (edit.span.start, edit.span.end));
+    edits.dedup();
+
+    let mut fixed = String::with_capacity(schema.len());
+    let mut offset = 0;
+
+    for edit in edits {
+        if edit.span.start < offset {
+            continue;
+        }
+
+        fixed.push_str(&schema[offset..edit.span.start]);
+        fixed.push_str(&edit.replacement);
+        offset = edit.span.end;
+    }
+
+    fixed.push_str(&schema[offset..]);
+    fixed
+}
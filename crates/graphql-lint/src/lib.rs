@@ -1,12 +1,27 @@
+mod config;
+mod fix;
+mod suppressions;
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
 use cynic_parser::TypeSystemDocument;
+use cynic_parser::common::WrappingType;
 use cynic_parser::type_system::{
     Definition, Directive, DirectiveDefinition, EnumDefinition, EnumValueDefinition, FieldDefinition,
     InputObjectDefinition, InputValueDefinition, InterfaceDefinition, ObjectDefinition, ScalarDefinition,
-    TypeDefinition, UnionDefinition,
+    SchemaDefinition, TypeDefinition, UnionDefinition,
 };
 use heck::{ToLowerCamelCase, ToPascalCase, ToShoutySnakeCase};
 use thiserror::Error;
 
+pub use config::{BOOLEAN_FIELD_PREFIXES, LintConfig, Rule, RuleLevel};
+pub use fix::{Edit, apply_fixes};
+use suppressions::Suppressions;
+
+const BUILTIN_SCALARS: [&str; 5] = ["String", "Int", "Float", "Boolean", "ID"];
+const DEFAULT_ROOT_TYPES: [&str; 3] = ["Query", "Mutation", "Subscription"];
+
 enum CaseMatch<'a> {
     Correct,
     Incorrect { current: &'a str, fix: String },
@@ -18,8 +33,21 @@ enum Case {
     Camel,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Severity {
     Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub rule: Rule,
+    pub severity: Severity,
+    pub message: String,
+    /// The byte range of the offending name, or of the whole definition.
+    pub span: Range<usize>,
+    /// The edits fixing the diagnostic, empty if it can't be fixed automatically.
+    pub fixes: Vec<Edit>,
 }
 
 #[derive(Error, Debug)]
@@ -28,30 +56,61 @@ pub enum LinterError {
     Parse(String),
 }
 
+/// Lints a schema with the default configuration.
 pub fn lint(schema: &str) -> Result<Vec<(String, Severity)>, LinterError> {
+    Ok(lint_with_config(schema, &LintConfig::default())?
+        .into_iter()
+        .map(|diagnostic| (diagnostic.message, diagnostic.severity))
+        .collect())
+}
+
+pub fn lint_with_config(schema: &str, config: &LintConfig) -> Result<Vec<Diagnostic>, LinterError> {
     let parsed_schema =
         cynic_parser::parse_type_system_document(schema).map_err(|error| LinterError::Parse(error.to_string()))?;
-    Ok(SchemaLinter::new().lint(&parsed_schema))
+    Ok(SchemaLinter::new(schema, config).lint(&parsed_schema))
 }
 
-struct SchemaLinter {
-    diagnostics: Vec<(String, Severity)>,
+struct SchemaLinter<'a> {
+    schema: &'a str,
+    config: &'a LintConfig,
+    suppressions: Suppressions,
+    /// The exact location of every type name used in the schema, to rename them with their
+    /// definition.
+    type_references: HashMap<&'a str, Vec<Range<usize>>>,
+    /// Types referenced without location, by `implements`.
+    used_types: HashSet<&'a str>,
+    directive_usages: HashMap<&'a str, Vec<Range<usize>>>,
+    root_types: Vec<&'a str>,
+    diagnostics: Vec<Diagnostic>,
 }
 
-impl<'a> SchemaLinter {
-    pub fn new() -> Self {
+impl<'a> SchemaLinter<'a> {
+    pub fn new(schema: &'a str, config: &'a LintConfig) -> Self {
         Self {
+            schema,
+            config,
+            suppressions: Suppressions::new(schema),
+            type_references: HashMap::new(),
+            used_types: HashSet::new(),
+            directive_usages: HashMap::new(),
+            root_types: Vec::new(),
             diagnostics: Vec::new(),
         }
     }
 
-    pub fn lint(mut self, schema: &'a TypeSystemDocument) -> Vec<(String, Severity)> {
+    pub fn lint(mut self, schema: &'a TypeSystemDocument) -> Vec<Diagnostic> {
+        self.collect_references(schema);
+
         schema.definitions().for_each(|definition| match definition {
             Definition::Schema(_) => {}
             Definition::SchemaExtension(_) => {}
             // TODO: we can optimize this by not rechecking spelling for extensions.
             // We'll also need to do this to avoid duplicate warnings if extending a type with an incorrect name
             Definition::TypeExtension(r#type) | Definition::Type(r#type) => {
+                if let Definition::Type(_) = definition {
+                    self.visit_type_definition(r#type);
+                }
+
                 match r#type {
                     TypeDefinition::Scalar(scalar) => {
                         self.visit_scalar(scalar);
@@ -60,7 +119,7 @@ impl<'a> SchemaLinter {
                             .for_each(|directive| self.visit_directive_usage(r#type, directive));
                     }
                     TypeDefinition::Object(object) => {
-                        self.visit_object(object);
+                        self.visit_object(r#type, object);
                         object
                             .directives()
                             .for_each(|directive| self.visit_directive_usage(r#type, directive));
@@ -75,7 +134,7 @@ impl<'a> SchemaLinter {
                         });
                     }
                     TypeDefinition::Interface(interface) => {
-                        self.visit_interface(interface);
+                        self.visit_interface(r#type, interface);
                         interface
                             .directives()
                             .for_each(|directive| self.visit_directive_usage(r#type, directive));
@@ -90,13 +149,13 @@ impl<'a> SchemaLinter {
                         });
                     }
                     TypeDefinition::Union(union) => {
-                        self.visit_union(union);
+                        self.visit_union(r#type, union);
                         union
                             .directives()
                             .for_each(|directive| self.visit_directive_usage(r#type, directive))
                     }
                     TypeDefinition::Enum(r#enum) => {
-                        self.visit_enum(r#enum);
+                        self.visit_enum(r#type, r#enum);
                         r#enum
                             .directives()
                             .for_each(|directive| self.visit_directive_usage(r#type, directive));
@@ -108,7 +167,7 @@ impl<'a> SchemaLinter {
                         });
                     }
                     TypeDefinition::InputObject(input_object) => {
-                        self.visit_input_object(input_object);
+                        self.visit_input_object(r#type, input_object);
                         input_object
                             .directives()
                             .for_each(|directive| self.visit_directive_usage(r#type, directive));
@@ -132,7 +191,181 @@ impl<'a> SchemaLinter {
         self.diagnostics
     }
 
-    fn case_check(current: &'a str, case: Case) -> CaseMatch<'a> {
+    /// Finds the root types, and where types and directives are used, before linting
+    /// definitions.
+    fn collect_references(&mut self, schema: &'a TypeSystemDocument) {
+        for definition in schema.definitions() {
+            match definition {
+                Definition::Schema(schema_definition) | Definition::SchemaExtension(schema_definition) => {
+                    self.collect_root_types(schema_definition);
+                    self.collect_directive_usages(schema_definition.directives());
+                }
+                Definition::Type(r#type) | Definition::TypeExtension(r#type) => {
+                    self.collect_directive_usages(r#type.directives());
+
+                    match r#type {
+                        TypeDefinition::Scalar(_) => {}
+                        TypeDefinition::Object(object) => {
+                            self.used_types.extend(object.implements_interfaces());
+                            object.fields().for_each(|field| self.collect_field_references(field));
+                        }
+                        TypeDefinition::Interface(interface) => {
+                            self.used_types.extend(interface.implements_interfaces());
+                            interface
+                                .fields()
+                                .for_each(|field| self.collect_field_references(field));
+                        }
+                        TypeDefinition::Union(union) => {
+                            for member in union.members() {
+                                self.collect_type_reference(member.name(), member.span().start..member.span().end);
+                            }
+                        }
+                        TypeDefinition::Enum(r#enum) => {
+                            for value in r#enum.values() {
+                                self.collect_directive_usages(value.directives());
+                            }
+                        }
+                        TypeDefinition::InputObject(input_object) => {
+                            input_object
+                                .fields()
+                                .for_each(|input_value| self.collect_input_value_references(input_value));
+                        }
+                    }
+                }
+                Definition::Directive(directive) => {
+                    directive
+                        .arguments()
+                        .for_each(|argument| self.collect_input_value_references(argument));
+                }
+            }
+        }
+
+        if self.root_types.is_empty() {
+            self.root_types.extend(DEFAULT_ROOT_TYPES);
+        }
+    }
+
+    fn collect_root_types(&mut self, schema_definition: SchemaDefinition<'a>) {
+        let root_types = [
+            schema_definition.query_type(),
+            schema_definition.mutation_type(),
+            schema_definition.subscription_type(),
+        ];
+
+        for root_type in root_types.into_iter().flatten() {
+            let span = root_type.named_type_span();
+
+            self.root_types.push(root_type.named_type());
+            self.collect_type_reference(root_type.named_type(), span.start..span.end);
+        }
+    }
+
+    fn collect_field_references(&mut self, field: FieldDefinition<'a>) {
+        let span = field.ty().span();
+        self.collect_type_reference(field.ty().name(), span.start..span.end);
+        self.collect_directive_usages(field.directives());
+
+        field
+            .arguments()
+            .for_each(|argument| self.collect_input_value_references(argument));
+    }
+
+    fn collect_input_value_references(&mut self, input_value: InputValueDefinition<'a>) {
+        let span = input_value.ty().span();
+        self.collect_type_reference(input_value.ty().name(), span.start..span.end);
+        self.collect_directive_usages(input_value.directives());
+    }
+
+    fn collect_type_reference(&mut self, name: &'a str, region: Range<usize>) {
+        self.used_types.insert(name);
+
+        if let Some(span) = self.find_name(region, name) {
+            self.type_references.entry(name).or_default().push(span);
+        }
+    }
+
+    fn collect_directive_usages(&mut self, directives: impl Iterator<Item = Directive<'a>>) {
+        for directive in directives {
+            let span = directive.name_span();
+
+            if let Some(span) = self.find_name(span.start..span.end, directive.name()) {
+                self.directive_usages.entry(directive.name()).or_default().push(span);
+            }
+        }
+    }
+
+    /// The exact location of a name in a region of the schema. Descriptions must be excluded
+    /// from the region as they could contain the name.
+    fn find_name(&self, region: Range<usize>, name: &str) -> Option<Range<usize>> {
+        let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let haystack = self.schema.get(region.clone())?;
+
+        haystack
+            .match_indices(name)
+            .map(|(offset, _)| region.start + offset)
+            .find(|&start| {
+                let before = self.schema[..start].chars().next_back();
+                let after = self.schema[start + name.len()..].chars().next();
+
+                !before.is_some_and(is_name_char) && !after.is_some_and(is_name_char)
+            })
+            .map(|start| start..start + name.len())
+    }
+
+    /// The location of the name of a definition, skipping its description.
+    fn definition_name_span(&self, span: cynic_parser::Span, description_end: Option<usize>, name: &str) -> NameSpan {
+        let start = description_end.unwrap_or(span.start).max(span.start);
+
+        match self.find_name(start..span.end, name) {
+            Some(name) => NameSpan::Exact(name),
+            None => NameSpan::Definition(span.start..span.end),
+        }
+    }
+
+    fn push(&mut self, rule: Rule, span: Range<usize>, message: String, fixes: Vec<Edit>) {
+        let Some(severity) = self.config.severity(rule) else {
+            return;
+        };
+
+        if self.suppressions.is_disabled(rule, span.start) {
+            return;
+        }
+
+        self.diagnostics.push(Diagnostic {
+            rule,
+            severity,
+            message,
+            span,
+            fixes,
+        });
+    }
+
+    /// Pushes a naming convention diagnostic, fixed by renaming the definition and the given
+    /// references to it.
+    fn push_rename(&mut self, name: NameSpan, fix: &str, references: &[Range<usize>], message: String) {
+        let (span, fixes) = match name {
+            NameSpan::Exact(span) => {
+                let fixes = std::iter::once(span.clone())
+                    .chain(references.iter().cloned())
+                    .map(|span| Edit {
+                        span,
+                        replacement: fix.to_owned(),
+                    })
+                    .collect();
+
+                (span, fixes)
+            }
+            NameSpan::Definition(span) => (span, Vec::new()),
+        };
+
+        self.push(Rule::NamingConvention, span, message, fixes);
+    }
+
+    fn type_references(&self, name: &str) -> Vec<Range<usize>> {
+        self.type_references.get(name).cloned().unwrap_or_default()
+    }
+
+    fn case_check(current: &str, case: Case) -> CaseMatch<'_> {
         use regex::RegexSet;
         use std::sync::LazyLock;
 
@@ -162,39 +395,63 @@ impl<'a> SchemaLinter {
         argument: InputValueDefinition<'_>,
     ) {
         if let CaseMatch::Incorrect { current, fix } = Self::case_check(argument.name(), Case::Camel) {
-            self.diagnostics.push((
+            let name = self.definition_name_span(
+                argument.span(),
+                argument.description().map(|description| description.span().end),
+                current,
+            );
+
+            self.push_rename(
+                name,
+                &fix,
+                &[],
                 format!(
                     "argument '{current}' on field '{}' on {} '{}' should be renamed to '{fix}'",
                     field.name(),
                     Self::type_definition_display(parent_type),
                     parent_type.name()
                 ),
-                Severity::Warning,
-            ));
+            );
         }
     }
 
     pub fn visit_directive_argument(&mut self, directive: DirectiveDefinition<'_>, argument: InputValueDefinition<'_>) {
         if let CaseMatch::Incorrect { current, fix } = Self::case_check(argument.name(), Case::Camel) {
-            self.diagnostics.push((
+            let name = self.definition_name_span(
+                argument.span(),
+                argument.description().map(|description| description.span().end),
+                current,
+            );
+
+            self.push_rename(
+                name,
+                &fix,
+                &[],
                 format!(
                     "argument '{current}' on directive '{}' should be renamed to '{fix}'",
                     directive.name()
                 ),
-                Severity::Warning,
-            ));
+            );
         }
     }
 
     pub fn visit_input_value(&mut self, parent: TypeDefinition<'_>, value: InputValueDefinition<'_>) {
         if let CaseMatch::Incorrect { current, fix } = Self::case_check(value.name(), Case::Camel) {
-            self.diagnostics.push((
+            let name = self.definition_name_span(
+                value.span(),
+                value.description().map(|description| description.span().end),
+                current,
+            );
+
+            self.push_rename(
+                name,
+                &fix,
+                &[],
                 format!(
                     "input value '{current}' on input '{}' should be renamed to '{fix}'",
                     parent.name()
                 ),
-                Severity::Warning,
-            ));
+            );
         }
     }
 
@@ -209,6 +466,36 @@ impl<'a> SchemaLinter {
         }
     }
 
+    /// Rules applying to all type definitions, but not to their extensions.
+    pub fn visit_type_definition(&mut self, r#type: TypeDefinition<'_>) {
+        let type_name = r#type.name();
+        let span = r#type.span().start..r#type.span().end;
+        let display = Self::type_definition_display(r#type);
+
+        if r#type.description().is_none() && !type_name.starts_with("__") {
+            self.push(
+                Rule::DescriptionRequired,
+                span.clone(),
+                format!("{display} '{type_name}' should have a description"),
+                Vec::new(),
+            );
+        }
+
+        let is_used = self.root_types.iter().any(|root| *root == type_name)
+            || self.used_types.contains(type_name)
+            || BUILTIN_SCALARS.contains(&type_name)
+            || type_name.starts_with("__");
+
+        if !is_used {
+            self.push(
+                Rule::NoUnusedTypes,
+                span,
+                format!("{display} '{type_name}' is never used"),
+                Vec::new(),
+            );
+        }
+    }
+
     pub fn visit_field(&mut self, parent: TypeDefinition<'_>, field: FieldDefinition<'_>) {
         let field_name = field.name();
 
@@ -217,63 +504,116 @@ impl<'a> SchemaLinter {
             return;
         }
 
+        let span = field.span().start..field.span().end;
+
         if let CaseMatch::Incorrect { current, fix } = Self::case_check(field_name, Case::Camel) {
-            self.diagnostics.push((
+            let name = self.definition_name_span(
+                field.span(),
+                field.description().map(|description| description.span().end),
+                current,
+            );
+
+            self.push_rename(
+                name,
+                &fix,
+                &[],
                 format!(
                     "field '{current}' on {} '{}' should be renamed to '{fix}'",
                     Self::type_definition_display(parent),
                     parent.name()
                 ),
-                Severity::Warning,
-            ));
+            );
         }
+
+        let is_boolean = field.ty().name() == "Boolean"
+            && !field
+                .ty()
+                .wrappers()
+                .any(|wrapper| matches!(wrapper, WrappingType::List));
+
+        if is_boolean
+            && !BOOLEAN_FIELD_PREFIXES.iter().any(|prefix| {
+                field_name
+                    .strip_prefix(prefix)
+                    .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_uppercase()))
+            })
+        {
+            self.push(
+                Rule::BooleanFieldPrefix,
+                span.clone(),
+                format!(
+                    "boolean field '{field_name}' on {} '{}' should start with one of: {}",
+                    Self::type_definition_display(parent),
+                    parent.name(),
+                    BOOLEAN_FIELD_PREFIXES
+                        .iter()
+                        .map(|prefix| format!("'{prefix}'"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                Vec::new(),
+            );
+        }
+
         match parent.name() {
             "Query" => {
                 for prefix in ["query", "get", "list"] {
                     if field_name.starts_with(prefix) {
-                        self.diagnostics.push((
+                        self.push(
+                            Rule::ForbiddenAffixes,
+                            span.clone(),
                             format!("field '{field_name}' on type 'Query' has a forbidden prefix: '{prefix}'"),
-                            Severity::Warning,
-                        ));
+                            Vec::new(),
+                        );
                         break;
                     }
                 }
                 if field_name.ends_with("Query") {
-                    self.diagnostics.push((
+                    self.push(
+                        Rule::ForbiddenAffixes,
+                        span,
                         format!("field '{field_name}' on type 'Query' has a forbidden suffix: 'Query'"),
-                        Severity::Warning,
-                    ));
+                        Vec::new(),
+                    );
                 }
             }
             "Mutation" => {
                 for prefix in ["mutation", "put", "post", "patch"] {
                     if field_name.starts_with(prefix) {
-                        self.diagnostics.push((
+                        self.push(
+                            Rule::ForbiddenAffixes,
+                            span.clone(),
                             format!("field '{field_name}' on type 'Mutation' has a forbidden prefix: '{prefix}'"),
-                            Severity::Warning,
-                        ));
+                            Vec::new(),
+                        );
                         break;
                     }
                 }
                 if field_name.ends_with("Mutation") {
-                    self.diagnostics.push((
+                    self.push(
+                        Rule::ForbiddenAffixes,
+                        span,
                         format!("field '{field_name}' on type 'Mutation' has a forbidden suffix: 'Mutation'"),
-                        Severity::Warning,
-                    ));
+                        Vec::new(),
+                    );
                 }
             }
             "Subscription" => {
                 if field_name.starts_with("subscription") {
-                    self.diagnostics.push((
+                    self.push(
+                        Rule::ForbiddenAffixes,
+                        span.clone(),
                         format!("field '{field_name}' on type 'Subscription' has a forbidden prefix: 'subscription'"),
-                        Severity::Warning,
-                    ));
+                        Vec::new(),
+                    );
                 }
                 if field_name.ends_with("Subscription") {
-                    self.diagnostics.push((
+                    self.push(
+                        Rule::ForbiddenAffixes,
+                        span,
                         format!("field '{field_name}' on type 'Subscription' has a forbidden suffix: 'Subscription'"),
-                        Severity::Warning,
-                    ));
+                        Vec::new(),
+                    );
                 }
             }
             _ => {}
@@ -282,43 +622,55 @@ impl<'a> SchemaLinter {
 
     pub fn visit_directive(&mut self, directive: DirectiveDefinition<'_>) {
         if let CaseMatch::Incorrect { current, fix } = Self::case_check(directive.name(), Case::Camel) {
-            self.diagnostics.push((
+            let name = self.definition_name_span(
+                directive.span(),
+                directive.description().map(|description| description.span().end),
+                current,
+            );
+            let usages = self.directive_usages.get(current).cloned().unwrap_or_default();
+
+            self.push_rename(
+                name,
+                &fix,
+                &usages,
                 format!("directive '{current}' should be renamed to '{fix}'"),
-                Severity::Warning,
-            ));
+            );
         }
     }
 
-    pub fn visit_directive_usage(&mut self, parent: TypeDefinition<'_>, directive: Directive<'_>) {
+    fn push_missing_deprecation_reason(&mut self, directive: Directive<'_>, message: String) {
         if directive.name() == "deprecated" && !directive.arguments().any(|argument| argument.name() == "reason") {
-            self.diagnostics.push((
-                format!(
-                    "usage of directive 'deprecated' on {} '{}' does not populate the 'reason' argument",
-                    Self::type_definition_display(parent),
-                    parent.name()
-                ),
-                Severity::Warning,
-            ));
+            let span = directive.name_span();
+            self.push(Rule::DeprecationReason, span.start..span.end, message, Vec::new());
         }
     }
 
+    pub fn visit_directive_usage(&mut self, parent: TypeDefinition<'_>, directive: Directive<'_>) {
+        self.push_missing_deprecation_reason(
+            directive,
+            format!(
+                "usage of directive 'deprecated' on {} '{}' does not populate the 'reason' argument",
+                Self::type_definition_display(parent),
+                parent.name()
+            ),
+        );
+    }
+
     pub fn visit_directive_usage_field(
         &mut self,
         parent_type: TypeDefinition<'_>,
         parent_field: FieldDefinition<'_>,
         directive: Directive<'_>,
     ) {
-        if directive.name() == "deprecated" && !directive.arguments().any(|argument| argument.name() == "reason") {
-            self.diagnostics.push((
-                format!(
-                    "usage of directive 'deprecated' on field '{}' on {} '{}' does not populate the 'reason' argument",
-                    parent_field.name(),
-                    Self::type_definition_display(parent_type),
-                    parent_type.name()
-                ),
-                Severity::Warning,
-            ));
-        }
+        self.push_missing_deprecation_reason(
+            directive,
+            format!(
+                "usage of directive 'deprecated' on field '{}' on {} '{}' does not populate the 'reason' argument",
+                parent_field.name(),
+                Self::type_definition_display(parent_type),
+                parent_type.name()
+            ),
+        );
     }
 
     pub fn visit_directive_usage_input_value(
@@ -327,16 +679,14 @@ impl<'a> SchemaLinter {
         parent_input_value: InputValueDefinition<'_>,
         directive: Directive<'_>,
     ) {
-        if directive.name() == "deprecated" && !directive.arguments().any(|argument| argument.name() == "reason") {
-            self.diagnostics.push((
-                format!(
-                    "usage of directive 'deprecated' on input value '{}' on input '{}' does not populate the 'reason' argument",
-                    parent_input_value.name(),
-                    parent_input.name()
-                ),
-                Severity::Warning,
-            ));
-        }
+        self.push_missing_deprecation_reason(
+            directive,
+            format!(
+                "usage of directive 'deprecated' on input value '{}' on input '{}' does not populate the 'reason' argument",
+                parent_input_value.name(),
+                parent_input.name()
+            ),
+        );
     }
 
     pub fn visit_directive_usage_enum_value(
@@ -345,96 +695,156 @@ impl<'a> SchemaLinter {
         parent_value: EnumValueDefinition<'_>,
         directive: Directive<'_>,
     ) {
-        if directive.name() == "deprecated" && !directive.arguments().any(|argument| argument.name() == "reason") {
-            self.diagnostics.push((
-                format!(
-                    "usage of directive 'deprecated' on enum value '{}' on enum '{}' does not populate the 'reason' argument",
-                    parent_value.value(),
-                    parent_enum.name()
-                ),
-                Severity::Warning,
-            ));
-        }
+        self.push_missing_deprecation_reason(
+            directive,
+            format!(
+                "usage of directive 'deprecated' on enum value '{}' on enum '{}' does not populate the 'reason' argument",
+                parent_value.value(),
+                parent_enum.name()
+            ),
+        );
     }
 
-    pub fn visit_input_object(&mut self, _input_object: InputObjectDefinition<'_>) {}
+    pub fn visit_input_object(&mut self, r#type: TypeDefinition<'_>, input_object: InputObjectDefinition<'_>) {
+        let input_name = input_object.name();
+
+        if !input_name.ends_with("Input") {
+            let name = self.definition_name_span(
+                r#type.span(),
+                r#type.description().map(|description| description.span().end),
+                input_name,
+            );
+
+            let span = match name {
+                NameSpan::Exact(span) | NameSpan::Definition(span) => span,
+            };
+
+            self.push(
+                Rule::InputObjectNaming,
+                span,
+                format!("input '{input_name}' should have the 'Input' suffix"),
+                Vec::new(),
+            );
+        }
+    }
 
-    pub fn visit_union(&mut self, union: UnionDefinition<'_>) {
+    pub fn visit_union(&mut self, r#type: TypeDefinition<'_>, union: UnionDefinition<'_>) {
         let union_name = union.name();
+        let span = r#type.span().start..r#type.span().end;
+
         if union_name.starts_with("Union") {
-            self.diagnostics.push((
+            self.push(
+                Rule::ForbiddenAffixes,
+                span.clone(),
                 format!("union '{union_name}' has a forbidden prefix: 'Union'"),
-                Severity::Warning,
-            ));
+                Vec::new(),
+            );
         }
         if union_name.ends_with("Union") {
-            self.diagnostics.push((
+            self.push(
+                Rule::ForbiddenAffixes,
+                span,
                 format!("union '{union_name}' has a forbidden suffix: 'Union'"),
-                Severity::Warning,
-            ));
+                Vec::new(),
+            );
         }
     }
 
     pub fn visit_scalar(&mut self, _scalar: ScalarDefinition<'_>) {}
 
-    pub fn visit_interface(&mut self, object: InterfaceDefinition<'_>) {
+    pub fn visit_interface(&mut self, r#type: TypeDefinition<'_>, object: InterfaceDefinition<'_>) {
         let interface_name = object.name();
+        let span = r#type.span().start..r#type.span().end;
+
         if interface_name.starts_with("Interface") {
-            self.diagnostics.push((
+            self.push(
+                Rule::ForbiddenAffixes,
+                span.clone(),
                 format!("interface '{interface_name}' has a forbidden prefix: 'Interface'"),
-                Severity::Warning,
-            ));
+                Vec::new(),
+            );
         }
         if interface_name.ends_with("Interface") {
-            self.diagnostics.push((
+            self.push(
+                Rule::ForbiddenAffixes,
+                span,
                 format!("interface '{interface_name}' has a forbidden suffix: 'Interface'"),
-                Severity::Warning,
-            ));
+                Vec::new(),
+            );
         }
     }
 
-    pub fn visit_object(&mut self, object: ObjectDefinition<'_>) {
+    pub fn visit_object(&mut self, r#type: TypeDefinition<'_>, object: ObjectDefinition<'_>) {
         let object_name = object.name();
+        let span = r#type.span().start..r#type.span().end;
 
         if let CaseMatch::Incorrect { current, fix } = Self::case_check(object_name, Case::Pascal) {
-            self.diagnostics.push((
+            let name = self.definition_name_span(
+                r#type.span(),
+                r#type.description().map(|description| description.span().end),
+                current,
+            );
+            let references = self.type_references(current);
+
+            self.push_rename(
+                name,
+                &fix,
+                &references,
                 format!("type '{current}' should be renamed to '{fix}'"),
-                Severity::Warning,
-            ));
+            );
         }
         if object_name.starts_with("Type") {
-            self.diagnostics.push((
+            self.push(
+                Rule::ForbiddenAffixes,
+                span.clone(),
                 format!("type '{object_name}' has a forbidden prefix: 'Type'"),
-                Severity::Warning,
-            ));
+                Vec::new(),
+            );
         }
         if object_name.ends_with("Type") {
-            self.diagnostics.push((
+            self.push(
+                Rule::ForbiddenAffixes,
+                span,
                 format!("type '{object_name}' has a forbidden suffix: 'Type'"),
-                Severity::Warning,
-            ));
+                Vec::new(),
+            );
         }
     }
 
-    pub fn visit_enum(&mut self, r#enum: EnumDefinition<'_>) {
+    pub fn visit_enum(&mut self, r#type: TypeDefinition<'_>, r#enum: EnumDefinition<'_>) {
         let enum_name = r#enum.name();
+        let span = r#type.span().start..r#type.span().end;
+
         if let CaseMatch::Incorrect { current, fix } = Self::case_check(enum_name, Case::Pascal) {
-            self.diagnostics.push((
+            let name = self.definition_name_span(
+                r#type.span(),
+                r#type.description().map(|description| description.span().end),
+                current,
+            );
+            let references = self.type_references(current);
+
+            self.push_rename(
+                name,
+                &fix,
+                &references,
                 format!("enum '{current}' should be renamed to '{fix}'"),
-                Severity::Warning,
-            ));
+            );
         }
         if enum_name.starts_with("Enum") {
-            self.diagnostics.push((
+            self.push(
+                Rule::ForbiddenAffixes,
+                span.clone(),
                 format!("enum '{enum_name}' has a forbidden prefix: 'Enum'"),
-                Severity::Warning,
-            ));
+                Vec::new(),
+            );
         }
         if enum_name.ends_with("Enum") {
-            self.diagnostics.push((
+            self.push(
+                Rule::ForbiddenAffixes,
+                span,
                 format!("enum '{enum_name}' has a forbidden suffix: 'Enum'"),
-                Severity::Warning,
-            ));
+                Vec::new(),
+            );
         }
     }
 
@@ -443,14 +853,30 @@ impl<'a> SchemaLinter {
 
         let name = enum_value.value();
         if let CaseMatch::Incorrect { current, fix } = Self::case_check(name, Case::ShoutySnake) {
-            self.diagnostics.push((
+            // Enum values used as default values are not renamed.
+            let name = self.definition_name_span(
+                enum_value.span(),
+                enum_value.description().map(|description| description.span().end),
+                current,
+            );
+
+            self.push_rename(
+                name,
+                &fix,
+                &[],
                 format!("value '{current}' on enum '{enum_name}' should be renamed to '{fix}'"),
-                Severity::Warning,
-            ));
+            );
         }
     }
 }
 
+enum NameSpan {
+    Exact(Range<usize>),
+    /// The name could not be found, the diagnostic points to the whole definition and can't be
+    /// fixed.
+    Definition(Range<usize>),
+}
+
 #[test]
 fn linter() {
     let schema = r#"
@@ -657,3 +1083,293 @@ fn linter() {
 
     assert!(diagnostics.is_empty());
 }
+
+#[test]
+fn configured_rules() {
+    let config = LintConfig {
+        rules: [
+            (Rule::NamingConvention, RuleLevel::Error),
+            (Rule::ForbiddenAffixes, RuleLevel::Off),
+            (Rule::DescriptionRequired, RuleLevel::Warning),
+            (Rule::NoUnusedTypes, RuleLevel::Warning),
+            (Rule::BooleanFieldPrefix, RuleLevel::Warning),
+            (Rule::InputObjectNaming, RuleLevel::Warning),
+        ]
+        .into_iter()
+        .collect(),
+    };
+
+    let schema = r#"
+        "The root"
+        type Query {
+          getUser(filter: UserFilter): User
+          active: Boolean
+          isAdmin: Boolean
+          Name: String
+        }
+
+        type User {
+          id: ID!
+        }
+
+        input UserFilter {
+          id: ID
+        }
+
+        type Orphan {
+          id: ID!
+        }
+    "#;
+
+    let diagnostics = lint_with_config(schema, &config)
+        .unwrap()
+        .into_iter()
+        .map(|diagnostic| (diagnostic.rule, diagnostic.severity, diagnostic.message))
+        .collect::<Vec<_>>();
+
+    let expected = [
+        (
+            Rule::BooleanFieldPrefix,
+            Severity::Warning,
+            "boolean field 'active' on type 'Query' should start with one of: 'is', 'has', 'can', 'should', 'was', 'will', 'did'",
+        ),
+        (
+            Rule::NamingConvention,
+            Severity::Error,
+            "field 'Name' on type 'Query' should be renamed to 'name'",
+        ),
+        (
+            Rule::DescriptionRequired,
+            Severity::Warning,
+            "type 'User' should have a description",
+        ),
+        (
+            Rule::DescriptionRequired,
+            Severity::Warning,
+            "input 'UserFilter' should have a description",
+        ),
+        (
+            Rule::InputObjectNaming,
+            Severity::Warning,
+            "input 'UserFilter' should have the 'Input' suffix",
+        ),
+        (
+            Rule::DescriptionRequired,
+            Severity::Warning,
+            "type 'Orphan' should have a description",
+        ),
+        (Rule::NoUnusedTypes, Severity::Warning, "type 'Orphan' is never used"),
+    ]
+    .map(|(rule, severity, message)| (rule, severity, message.to_owned()));
+
+    assert_eq!(diagnostics, expected);
+}
+
+#[test]
+fn lint_disable_comments() {
+    let schema = r##"
+        type Query {
+          Hello: String # lint-disable
+          # lint-disable naming-convention
+          World: String
+          # lint-disable forbidden-affixes
+          Other: String
+          "# lint-disable"
+          Last: String
+        }
+    "##;
+
+    let messages = lint(schema)
+        .unwrap()
+        .into_iter()
+        .map(|(message, _)| message)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        messages,
+        [
+            "field 'Other' on type 'Query' should be renamed to 'other'",
+            "field 'Last' on type 'Query' should be renamed to 'last'",
+        ]
+    );
+
+    let schema = r#"
+        # lint-disable-file
+        type query {
+          Hello: String
+        }
+    "#;
+
+    assert!(lint(schema).unwrap().is_empty());
+}
+
+#[test]
+fn lint_disable_comments_skip_strings() {
+    let schema = r####"
+        """
+        # lint-disable-file
+        """
+        type Query {
+          "An \" escaped quote # lint-disable-file naming-convention "
+          First: String
+          """
+          Escaped \""" # lint-disable-file
+          """
+          Second: String
+          Third(arg: String = "#"): String # lint-disable
+          Fourth(arg: String = "a \" # lint-disable"): String
+          # lint-disable naming-convention, forbidden-affixes
+          Fifth: String
+          # lint-disabled
+          Sixth: String
+        }
+    "####;
+
+    let messages = lint(schema)
+        .unwrap()
+        .into_iter()
+        .map(|(message, _)| message)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        messages,
+        [
+            "field 'First' on type 'Query' should be renamed to 'first'",
+            "field 'Second' on type 'Query' should be renamed to 'second'",
+            "field 'Fourth' on type 'Query' should be renamed to 'fourth'",
+            "field 'Sixth' on type 'Query' should be renamed to 'sixth'",
+        ]
+    );
+}
+
+#[test]
+fn fix_case_violations() {
+    let schema = r#"
+        type Query {
+          user_by_id(USER_ID: ID!): user
+        }
+
+        "A user"
+        type user {
+          id: ID!
+          status: user_status @Cached
+        }
+
+        extend type user {
+          Name: String
+        }
+
+        enum user_status {
+          active
+        }
+
+        directive @Cached on FIELD_DEFINITION
+    "#;
+
+    let diagnostics = lint_with_config(schema, &LintConfig::default()).unwrap();
+
+    let expected = r#"
+        type Query {
+          userById(userId: ID!): User
+        }
+
+        "A user"
+        type User {
+          id: ID!
+          status: UserStatus @cached
+        }
+
+        extend type User {
+          name: String
+        }
+
+        enum UserStatus {
+          ACTIVE
+        }
+
+        directive @cached on FIELD_DEFINITION
+    "#;
+
+    let fixed = apply_fixes(schema, &diagnostics);
+    assert_eq!(fixed, expected);
+
+    let diagnostics = lint_with_config(&fixed, &LintConfig::default()).unwrap();
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn fix_renames_references() {
+    let schema = r#"
+        schema {
+          query: query_root
+        }
+
+        type query_root {
+          search(filter: SearchFilterInput @Tag, kinds: [item_kind!]!): [SearchResult!]!
+          product: product
+        }
+
+        union SearchResult = product | user_account
+
+        "A product, sold to a user_account"
+        type product {
+          id: ID!
+        }
+
+        type user_account {
+          id: ID!
+        }
+
+        input SearchFilterInput {
+          kind: item_kind @Tag
+          nested: [[item_kind]]
+        }
+
+        enum item_kind {
+          BOOK @Tag
+        }
+
+        directive @Tag on ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION | ENUM_VALUE
+    "#;
+
+    let diagnostics = lint_with_config(schema, &LintConfig::default()).unwrap();
+
+    let expected = r#"
+        schema {
+          query: QueryRoot
+        }
+
+        type QueryRoot {
+          search(filter: SearchFilterInput @tag, kinds: [ItemKind!]!): [SearchResult!]!
+          product: Product
+        }
+
+        union SearchResult = Product | UserAccount
+
+        "A product, sold to a user_account"
+        type Product {
+          id: ID!
+        }
+
+        type UserAccount {
+          id: ID!
+        }
+
+        input SearchFilterInput {
+          kind: ItemKind @tag
+          nested: [[ItemKind]]
+        }
+
+        enum ItemKind {
+          BOOK @tag
+        }
+
+        directive @tag on ARGUMENT_DEFINITION | INPUT_FIELD_DEFINITION | ENUM_VALUE
+    "#;
+
+    let fixed = apply_fixes(schema, &diagnostics);
+    assert_eq!(fixed, expected);
+
+    let diagnostics = lint_with_config(&fixed, &LintConfig::default()).unwrap();
+    assert!(diagnostics.is_empty());
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use crate::Rule;
+
+const DISABLE_FILE: &str = "lint-disable-file";
+const DISABLE: &str = "lint-disable";
+
+/// Rules turned off with comments in the schema:
+///
+/// - `# lint-disable` at the end of a line disables all rules on that line.
+/// - `# lint-disable` on its own line disables all rules on the next line.
+/// - `# lint-disable-file` disables all rules in the whole schema.
+///
+/// All of them can be restricted to some rules, e.g. `# lint-disable naming-convention, no-unused-types`.
+#[derive(Debug, Default)]
+pub(crate) struct Suppressions {
+    file: Option<Scope>,
+    lines: HashMap<usize, Scope>,
+    line_starts: Vec<usize>,
+}
+
+#[derive(Debug)]
+enum Scope {
+    All,
+    Rules(Vec<Rule>),
+}
+
+impl Scope {
+    fn parse(rules: &str) -> Scope {
+        let rules = rules
+            .split([',', ' '])
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+            .collect::<Vec<_>>();
+
+        if rules.is_empty() {
+            Scope::All
+        } else {
+            Scope::Rules(rules.into_iter().filter_map(Rule::from_name).collect())
+        }
+    }
+
+    fn contains(&self, rule: Rule) -> bool {
+        match self {
+            Scope::All => true,
+            Scope::Rules(rules) => rules.contains(&rule),
+        }
+    }
+}
+
+impl Suppressions {
+    pub(crate) fn new(schema: &str) -> Self {
+        let mut suppressions = Suppressions {
+            line_starts: std::iter::once(0)
+                .chain(schema.match_indices('\n').map(|(offset, _)| offset + 1))
+                .collect(),
+            ..Default::default()
+        };
+
+        for comment in comments(schema) {
+            let text = comment.text.trim();
+
+            if let Some(rules) = text.strip_prefix(DISABLE_FILE) {
+                suppressions.file = Some(Scope::parse(rules));
+            } else if let Some(rules) = text.strip_prefix(DISABLE)
+                && (rules.is_empty() || rules.starts_with(' '))
+            {
+                let line = suppressions.line(comment.offset);
+                let line = if comment.standalone { line + 1 } else { line };
+
+                suppressions.lines.insert(line, Scope::parse(rules));
+            }
+        }
+
+        suppressions
+    }
+
+    pub(crate) fn is_disabled(&self, rule: Rule, offset: usize) -> bool {
+        self.file.as_ref().is_some_and(|scope| scope.contains(rule))
+            || self
+                .lines
+                .get(&self.line(offset))
+                .is_some_and(|scope| scope.contains(rule))
+    }
+
+    fn line(&self, offset: usize) -> usize {
+        self.line_starts
+            .partition_point(|start| *start <= offset)
+            .saturating_sub(1)
+    }
+}
+
+struct Comment<'a> {
+    /// The text after the `#`.
+    text: &'a str,
+    offset: usize,
+    /// Whether the comment is alone on its line.
+    standalone: bool,
+}
+
+/// The comments of a GraphQL document, skipping `#` in strings.
+fn comments(schema: &str) -> Vec<Comment<'_>> {
+    let mut comments = Vec::new();
+    let mut offset = 0;
+    let mut line_start = 0;
+
+    while let Some(rest) = schema.get(offset..)
+        && let Some(c) = rest.chars().next()
+    {
+        if rest.starts_with("\"\"\"") {
+            let end = rest[3..]
+                .match_indices("\"\"\"")
+                .find(|(i, _)| !rest[3..3 + i].ends_with('\\'))
+                .map(|(i, _)| i + 6)
+                .unwrap_or(rest.len());
+            offset += end;
+            line_start = schema[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+            continue;
+        }
+
+        match c {
+            '"' => {
+                let mut escaped = false;
+                let end = rest[1..]
+                    .char_indices()
+                    .find(|&(_, c)| {
+                        let end = !escaped && (c == '"' || c == '\n');
+                        escaped = !escaped && c == '\\';
+                        end
+                    })
+                    // An unterminated string stops at the end of the line.
+                    .map(|(i, c)| if c == '\n' { i + 1 } else { i + 2 })
+                    .unwrap_or(rest.len());
+                offset += end;
+            }
+            '#' => {
+                let len = rest.find('\n').unwrap_or(rest.len());
+
+                comments.push(Comment {
+                    text: &rest[1..len],
+                    offset,
+                    standalone: schema[line_start..offset].trim().is_empty(),
+                });
+
+                offset += len;
+            }
+            '\n' => {
+                offset += 1;
+                line_start = offset;
+            }
+            c => offset += c.len_utf8(),
+        }
+    }
+
+    comments
+}
@@ -0,0 +1,66 @@
+use grafbase_sdk::{
+    ResolverExtension,
+    host_io::redis::{Command, Pipeline, Pool},
+    types::{
+        AuthorizedOperationContext, Configuration, Error, ResolvedField, Response, SubgraphHeaders, SubgraphSchema,
+        Variables,
+    },
+};
+
+#[derive(ResolverExtension)]
+struct Redis24 {
+    config: Config,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    url: String,
+}
+
+#[derive(serde::Deserialize)]
+struct KeyArgs {
+    key: String,
+}
+
+impl ResolverExtension for Redis24 {
+    fn new(_subgraph_schemas: Vec<SubgraphSchema>, config: Configuration) -> Result<Self, Error> {
+        let config: Config = config.deserialize()?;
+        Ok(Self { config })
+    }
+
+    fn resolve(
+        &mut self,
+        _ctx: &AuthorizedOperationContext,
+        prepared: &[u8],
+        _headers: SubgraphHeaders,
+        _variables: Variables,
+    ) -> Result<Response, Error> {
+        let field = ResolvedField::try_from(prepared)?;
+        let KeyArgs { key } = field.directive().arguments()?;
+
+        // The pool is created by the first call and shared with all the instances afterwards.
+        let pool = Pool::connect("default", &self.config.url)?;
+
+        match field.directive().name() {
+            "redisIncr" => {
+                let pipeline = Pipeline::new()
+                    .atomic()
+                    .command(Command::new("INCR").arg(&key))
+                    .command(Command::new("EXPIRE").arg(&key).arg("60"));
+
+                let results = pool.pipeline(&pipeline)?;
+
+                Ok(Response::data(results[0].as_int()?))
+            }
+            "redisGet" => {
+                let value = pool
+                    .get(&key)?
+                    .map(|value| String::from_utf8_lossy(&value).into_owned());
+
+                Ok(Response::data(value))
+            }
+            _ => unimplemented!(),
+        }
+    }
+}
@@ -12,6 +12,7 @@ use runtime::fetch::{FetchError, FetchRequest, FetchResult, dynamic::DynFetcher}
 pub struct MockFetch {
     responses: Arc<Mutex<HashMap<String, crossbeam_queue::SegQueue<Vec<u8>>>>>,
     requests: Arc<crossbeam_queue::SegQueue<(String, ReceivedRequest)>>,
+    response_headers: Arc<Mutex<HashMap<String, http::HeaderMap>>>,
 }
 
 impl MockFetch {
@@ -26,6 +27,21 @@ impl MockFetch {
         self
     }
 
+    /// Headers added to every response sent by the given host.
+    #[must_use]
+    pub fn with_response_headers<'a>(self, host: &str, headers: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut headers_by_host = self.response_headers.lock().unwrap();
+        let map = headers_by_host.entry(host.to_string()).or_default();
+        for (name, value) in headers {
+            map.append(
+                http::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                http::HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        drop(headers_by_host);
+        self
+    }
+
     pub fn drain_received_requests(&self) -> impl Iterator<Item = (String, ReceivedRequest)> + '_ {
         std::iter::from_fn(|| self.requests.pop())
     }
@@ -52,7 +68,13 @@ impl DynFetcher for MockFetch {
             .unwrap()
             .get(host)
             .and_then(|responses| responses.pop())
-            .map(|bytes| http::Response::builder().body(bytes.into()).unwrap())
+            .map(|bytes| {
+                let mut response = http::Response::builder().body(bytes.into()).unwrap();
+                if let Some(headers) = self.response_headers.lock().unwrap().get(host) {
+                    response.headers_mut().extend(headers.clone());
+                }
+                response
+            })
             .ok_or(FetchError::from("No more responses"));
 
         (result, None)
@@ -248,6 +248,7 @@ impl GatewayBuilder {
             extension_catalog,
             extensions: engine.no_contract.runtime.gateway_extensions.clone(),
             listen_address: None,
+            graph_health: None,
         };
 
         let (router, _) = federated_server::router::create(router_config).await.unwrap();
@@ -7,9 +7,11 @@ use engine::{CachedOperation, Schema};
 use extension_catalog::ExtensionCatalog;
 use gateway_config::Config;
 use grafbase_telemetry::metrics::{self, EngineMetrics};
-use runtime::{entity_cache::EntityCache, fetch::dynamic::DynamicFetcher, trusted_documents_client};
+use runtime::{
+    entity_cache::EntityCache, fetch::dynamic::DynamicFetcher, response_cache::ResponseCache, trusted_documents_client,
+};
 use runtime_local::{
-    InMemoryEntityCache, NativeFetcher,
+    InMemoryEntityCache, InMemoryResponseCache, NativeFetcher,
     operation_cache::{InMemoryOperationCache, RedisOperationCache, TieredOperationCache},
     rate_limiting::in_memory::key_based::InMemoryRateLimiter,
     redis::{RedisPoolFactory, RedisTlsConfig},
@@ -27,6 +29,7 @@ pub struct TestRuntime {
     pub metrics: EngineMetrics,
     pub rate_limiter: runtime::rate_limiting::RateLimiter,
     pub entity_cache: InMemoryEntityCache,
+    pub response_cache: InMemoryResponseCache,
     pub engine_extensions: EngineTestExtensions,
     pub gateway_extensions: GatewayTestExtensions,
 }
@@ -62,6 +65,7 @@ impl TestRuntimeBuilder {
             metrics: EngineMetrics::build(&metrics::meter_from_global_provider(), None),
             rate_limiter: InMemoryRateLimiter::runtime_with_watcher(rx),
             entity_cache: InMemoryEntityCache::default(),
+            response_cache: InMemoryResponseCache::default(),
             operation_cache: build_operation_cache(&config.operation_caching)?,
             operation_cache_config: config.operation_caching.clone(),
             engine_extensions,
@@ -86,6 +90,7 @@ impl TestRuntime {
             metrics: EngineMetrics::build(&metrics::meter_from_global_provider(), None),
             rate_limiter: InMemoryRateLimiter::runtime_with_watcher(rx),
             entity_cache: InMemoryEntityCache::default(),
+            response_cache: InMemoryResponseCache::default(),
             engine_extensions: EngineTestExtensions::default(),
             gateway_extensions: GatewayTestExtensions::default(),
         }
@@ -121,6 +126,10 @@ impl engine::Runtime for TestRuntime {
         &self.entity_cache
     }
 
+    fn response_cache(&self) -> &dyn ResponseCache {
+        &self.response_cache
+    }
+
     fn metrics(&self) -> &EngineMetrics {
         &self.metrics
     }
@@ -141,6 +150,7 @@ impl engine::Runtime for TestRuntime {
                 .map_err(|err| format!("Failed to adjust extensions for contract: {err}"))?,
             rate_limiter: self.rate_limiter.clone(),
             entity_cache: InMemoryEntityCache::default(),
+            response_cache: InMemoryResponseCache::default(),
             operation_cache: build_operation_cache(&self.operation_cache_config)
                 .map_err(|err| format!("Failed to build operation cache for contract: {err}"))?,
             operation_cache_config: self.operation_cache_config.clone(),
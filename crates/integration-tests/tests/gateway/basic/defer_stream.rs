@@ -0,0 +1,430 @@
+//! Tests of incremental delivery with @defer and @stream.
+
+use graphql_mocks::{FakeGithubSchema, FederatedProductsSchema, FederatedReviewsSchema};
+use integration_tests::{gateway::Gateway, runtime};
+
+#[test]
+fn defer_fragment() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FakeGithubSchema::default())
+            .build()
+            .await;
+
+        let response = engine
+            .post(
+                r#"
+                query {
+                    serverVersion
+                    ... @defer(label: "prs") {
+                        allBotPullRequests { title }
+                    }
+                }
+                "#,
+            )
+            .into_multipart_stream()
+            .await
+            .collect()
+            .await;
+
+        insta::assert_json_snapshot!(response.messages, @r#"
+        [
+          {
+            "data": {
+              "serverVersion": "1"
+            },
+            "hasNext": true
+          },
+          {
+            "incremental": [
+              {
+                "data": {
+                  "allBotPullRequests": [
+                    {
+                      "title": "Creating the thing"
+                    },
+                    {
+                      "title": "Some bot PR"
+                    }
+                  ]
+                },
+                "path": [],
+                "label": "prs"
+              }
+            ],
+            "hasNext": false
+          }
+        ]
+        "#);
+    })
+}
+
+#[test]
+fn deferred_entity_plan_is_delivered_in_its_own_payload() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FederatedProductsSchema::default())
+            .with_subgraph(FederatedReviewsSchema::default())
+            .build()
+            .await;
+
+        let response = engine
+            .post(
+                r#"
+                query {
+                    product(upc: "top-2") {
+                        name
+                        ... @defer(label: "price") { price }
+                        ... @defer(label: "reviews") {
+                            reviews { body }
+                        }
+                    }
+                }
+                "#,
+            )
+            .into_multipart_stream()
+            .await
+            .collect()
+            .await;
+
+        // The price is retrieved with the name and sent right after the initial payload, the
+        // reviews once the entity plan on the reviews subgraph is executed.
+        insta::assert_json_snapshot!(response.messages, @r#"
+        [
+          {
+            "data": {
+              "product": {
+                "name": "Fedora"
+              }
+            },
+            "hasNext": true
+          },
+          {
+            "incremental": [
+              {
+                "data": {
+                  "price": 22
+                },
+                "path": [
+                  "product"
+                ],
+                "label": "price"
+              }
+            ],
+            "hasNext": true
+          },
+          {
+            "incremental": [
+              {
+                "data": {
+                  "reviews": [
+                    {
+                      "body": "Fedoras are one of the most fashionable hats around and can look great with a variety of outfits."
+                    }
+                  ]
+                },
+                "path": [
+                  "product"
+                ],
+                "label": "reviews"
+              }
+            ],
+            "hasNext": false
+          }
+        ]
+        "#);
+
+        assert_eq!(
+            engine.drain_graphql_requests_sent_to::<FederatedReviewsSchema>().len(),
+            1
+        );
+    })
+}
+
+#[test]
+fn defer_disabled_with_if_argument() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FakeGithubSchema::default())
+            .build()
+            .await;
+
+        let response = engine
+            .post(
+                r#"
+                query($defer: Boolean!) {
+                    serverVersion
+                    ... @defer(if: $defer) {
+                        allBotPullRequests { title }
+                    }
+                }
+                "#,
+            )
+            .variables(serde_json::json!({"defer": false}))
+            .into_sse_stream()
+            .await
+            .collect()
+            .await;
+
+        insta::assert_json_snapshot!(response.messages, @r#"
+        [
+          {
+            "data": {
+              "serverVersion": "1",
+              "allBotPullRequests": [
+                {
+                  "title": "Creating the thing"
+                },
+                {
+                  "title": "Some bot PR"
+                }
+              ]
+            }
+          }
+        ]
+        "#);
+    })
+}
+
+#[test]
+fn defer_is_ignored_without_streaming_response() {
+    let response = runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FakeGithubSchema::default())
+            .build()
+            .await;
+
+        engine
+            .post(
+                r#"
+                query {
+                    serverVersion
+                    ... @defer {
+                        allBotPullRequests { title }
+                    }
+                }
+                "#,
+            )
+            .await
+    });
+
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "data": {
+        "serverVersion": "1",
+        "allBotPullRequests": [
+          {
+            "title": "Creating the thing"
+          },
+          {
+            "title": "Some bot PR"
+          }
+        ]
+      }
+    }
+    "#);
+}
+
+#[test]
+fn stream_list() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FakeGithubSchema::default())
+            .build()
+            .await;
+
+        let response = engine
+            .post(r#"query { allBotPullRequests @stream(initialCount: 1, label: "bots") { title } }"#)
+            .into_multipart_stream()
+            .await
+            .collect()
+            .await;
+
+        insta::assert_json_snapshot!(response.messages, @r#"
+        [
+          {
+            "data": {
+              "allBotPullRequests": [
+                {
+                  "title": "Creating the thing"
+                }
+              ]
+            },
+            "hasNext": true
+          },
+          {
+            "incremental": [
+              {
+                "items": [
+                  {
+                    "title": "Some bot PR"
+                  }
+                ],
+                "path": [
+                  "allBotPullRequests",
+                  1
+                ],
+                "label": "bots"
+              }
+            ],
+            "hasNext": false
+          }
+        ]
+        "#);
+    })
+}
+
+#[test]
+fn nested_defer_in_fragments() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FakeGithubSchema::default())
+            .build()
+            .await;
+
+        let response = engine
+            .post(
+                r#"
+                query {
+                    serverVersion
+                    ...Bots
+                }
+
+                fragment Bots on Query {
+                    ... on Query {
+                        ... @defer(label: "bots") {
+                            allBotPullRequests {
+                                title
+                                ... @defer(label: "checks") { checks }
+                            }
+                        }
+                    }
+                }
+                "#,
+            )
+            .into_multipart_stream()
+            .await
+            .collect()
+            .await;
+
+        insta::assert_json_snapshot!(response.messages, @r#"
+        [
+          {
+            "data": {
+              "serverVersion": "1"
+            },
+            "hasNext": true
+          },
+          {
+            "incremental": [
+              {
+                "data": {
+                  "allBotPullRequests": [
+                    {
+                      "title": "Creating the thing"
+                    },
+                    {
+                      "title": "Some bot PR"
+                    }
+                  ]
+                },
+                "path": [],
+                "label": "bots"
+              },
+              {
+                "data": {
+                  "checks": [
+                    "Success!"
+                  ]
+                },
+                "path": [
+                  "allBotPullRequests",
+                  0
+                ],
+                "label": "checks"
+              },
+              {
+                "data": {
+                  "checks": [
+                    "Success!"
+                  ]
+                },
+                "path": [
+                  "allBotPullRequests",
+                  1
+                ],
+                "label": "checks"
+              }
+            ],
+            "hasNext": false
+          }
+        ]
+        "#);
+    })
+}
+
+#[test]
+fn stream_disabled_with_if_argument() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FakeGithubSchema::default())
+            .build()
+            .await;
+
+        let response = engine
+            .post(r#"query($stream: Boolean!) { allBotPullRequests @stream(if: $stream, initialCount: 1) { title } }"#)
+            .variables(serde_json::json!({"stream": false}))
+            .into_multipart_stream()
+            .await
+            .collect()
+            .await;
+
+        insta::assert_json_snapshot!(response.messages, @r#"
+        [
+          {
+            "data": {
+              "allBotPullRequests": [
+                {
+                  "title": "Creating the thing"
+                },
+                {
+                  "title": "Some bot PR"
+                }
+              ]
+            }
+          }
+        ]
+        "#);
+    })
+}
+
+#[test]
+fn defer_on_field_is_rejected() {
+    let response = runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FakeGithubSchema::default())
+            .build()
+            .await;
+
+        engine.post("query { serverVersion @defer }").await
+    });
+
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "errors": [
+        {
+          "message": "Directive '@defer' can only be used on fragment spreads and inline fragments.",
+          "locations": [
+            {
+              "line": 1,
+              "column": 24
+            }
+          ],
+          "extensions": {
+            "code": "OPERATION_VALIDATION_ERROR"
+          }
+        }
+      ]
+    }
+    "#);
+}
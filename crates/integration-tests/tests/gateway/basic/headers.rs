@@ -1213,3 +1213,52 @@ fn header_regex_remove_should_remove_duplicates() {
     }
     "#);
 }
+
+#[test]
+fn header_insert_with_templated_value() {
+    let response = runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(EchoSchema::default())
+            .with_toml_config(
+                r#"
+                    [[headers]]
+                    rule = "insert"
+                    name = "x-user"
+                    value = "user-{{ headers.x-source }}"
+
+                    [[headers]]
+                    rule = "insert"
+                    name = "x-missing"
+                    value = "{{ operation.name }}"
+
+                    [[subgraphs.echo.headers]]
+                    rule = "insert"
+                    name = "x-operation"
+                    value = "{{ operation.type }} {{ operation.name }}"
+                "#,
+            )
+            .build()
+            .await;
+
+        engine
+            .post(
+                r#"query Greet {
+                    user: header(name: "x-user")
+                    operation: header(name: "x-operation")
+                    missing: header(name: "x-missing")
+                }"#,
+            )
+            .header("x-source", "boom")
+            .await
+    });
+
+    insta::assert_json_snapshot!(response, @r#"
+    {
+      "data": {
+        "user": "user-boom",
+        "operation": "query Greet",
+        "missing": null
+      }
+    }
+    "#);
+}
@@ -4,6 +4,7 @@
 //! that our engine supports all the things a normal GraphQL server should.
 
 mod collisions;
+mod defer_stream;
 mod empty_config;
 mod enums;
 mod error_code_mapping;
@@ -432,6 +432,257 @@ fn test_argument_complexity() {
     });
 }
 
+const PRODUCTS_SDL: &str = r#"
+extend schema
+    @link(url: "resolver", import: ["@resolve"])
+
+type Query {
+    products: [Product!]! @resolve
+}
+
+type Product {
+    name: String @cost(weight: 2)
+}
+"#;
+
+fn products() -> serde_json::Value {
+    json!([{"name": "a"}, {"name": "b"}])
+}
+
+#[test]
+fn actual_cost_is_reported_in_response_extension() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph_sdl("x", PRODUCTS_SDL)
+            .with_extension(ResolverExt::json(products()))
+            .with_toml_config(
+                r#"
+                [complexity_control]
+                mode = "measure"
+                list_size = 10
+
+                [telemetry.exporters.response_extension]
+                trace_id = false
+                query_plan = false
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine
+            .post("query { products { name } }")
+            .header("x-grafbase-telemetry", "yes")
+            .await;
+
+        insta::assert_json_snapshot!(response, @r#"
+        {
+          "data": {
+            "products": [
+              {
+                "name": "a"
+              },
+              {
+                "name": "b"
+              }
+            ]
+          },
+          "extensions": {
+            "grafbase": {
+              "cost": {
+                "estimated": 30,
+                "actual": 6
+              }
+            }
+          }
+        }
+        "#);
+    });
+}
+
+#[test]
+fn actual_cost_is_not_reported_when_disabled() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph_sdl("x", PRODUCTS_SDL)
+            .with_extension(ResolverExt::json(products()))
+            .with_toml_config(
+                r#"
+                [complexity_control]
+                mode = "measure"
+                list_size = 10
+
+                [telemetry.exporters.response_extension]
+                trace_id = false
+                query_plan = false
+                cost = false
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine
+            .post("query { products { name } }")
+            .header("x-grafbase-telemetry", "yes")
+            .await;
+
+        similar_asserts::assert_serde_eq!(
+            response.body,
+            json!({"data": {"products": [{"name": "a"}, {"name": "b"}]}, "extensions": {"grafbase": {}}})
+        );
+    });
+}
+
+#[test]
+fn cost_budget_per_client() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph_sdl("x", PRODUCTS_SDL)
+            .with_extension(ResolverExt::json(products()))
+            .with_toml_config(
+                r#"
+                [complexity_control]
+                mode = "enforce"
+                limit = 100
+                list_size = 10
+
+                [complexity_control.budget]
+                limit = 10
+                duration = "1h"
+                key = { header = "x-client-id" }
+                missing_key = "allow"
+                "#,
+            )
+            .build()
+            .await;
+
+        // Each request costs 6, the budget is only checked before execution.
+        for _ in 0..2 {
+            let response = engine
+                .post("query { products { name } }")
+                .header("x-client-id", "partner")
+                .await;
+            assert_eq!(response.status, http::StatusCode::OK);
+        }
+
+        let response = engine
+            .post("query { products { name } }")
+            .header("x-client-id", "partner")
+            .await;
+        assert_eq!(response.status, http::StatusCode::TOO_MANY_REQUESTS);
+        insta::assert_json_snapshot!(response.body, @r#"
+        {
+          "errors": [
+            {
+              "message": "Cost budget exceeded",
+              "extensions": {
+                "code": "RATE_LIMITED"
+              }
+            }
+          ]
+        }
+        "#);
+
+        let response = engine
+            .post("query { products { name } }")
+            .header("x-client-id", "other")
+            .await;
+        assert_eq!(response.status, http::StatusCode::OK);
+
+        // Requests without the header aren't subject to the budget.
+        let response = engine.post("query { products { name } }").await;
+        assert_eq!(response.status, http::StatusCode::OK);
+    });
+}
+
+#[test]
+fn cost_budget_rejects_requests_without_key() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph_sdl("x", PRODUCTS_SDL)
+            .with_extension(ResolverExt::json(products()))
+            .with_toml_config(
+                r#"
+                [complexity_control]
+                mode = "enforce"
+                limit = 100
+                list_size = 10
+
+                [complexity_control.budget]
+                limit = 10
+                duration = "1h"
+                key = { header = "x-client-id" }
+                missing_key = "reject"
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.post("query { products { name } }").await;
+        assert_eq!(response.status, http::StatusCode::BAD_REQUEST);
+        insta::assert_json_snapshot!(response.body, @r#"
+        {
+          "errors": [
+            {
+              "message": "The request doesn't identify the client of the cost budget",
+              "extensions": {
+                "code": "BAD_REQUEST"
+              }
+            }
+          ]
+        }
+        "#);
+
+        let response = engine
+            .post("query { products { name } }")
+            .header("x-client-id", "partner")
+            .await;
+        assert_eq!(response.status, http::StatusCode::OK);
+    });
+}
+
+#[test]
+fn cost_budget_is_spent_by_cached_responses() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph_sdl("x", PRODUCTS_SDL)
+            .with_extension(ResolverExt::json(products()))
+            .with_toml_config(
+                r#"
+                [complexity_control]
+                mode = "enforce"
+                limit = 100
+                list_size = 10
+
+                [complexity_control.budget]
+                limit = 10
+                duration = "1h"
+                key = { header = "x-client-id" }
+                missing_key = "allow"
+
+                [response_caching]
+                enabled = true
+                "#,
+            )
+            .build()
+            .await;
+
+        // The second response comes from the cache, but costs as much as the first one.
+        for _ in 0..2 {
+            let response = engine
+                .post("query { products { name } }")
+                .header("x-client-id", "partner")
+                .await;
+            assert_eq!(response.status, http::StatusCode::OK);
+        }
+
+        let response = engine
+            .post("query { products { name } }")
+            .header("x-client-id", "partner")
+            .await;
+        assert_eq!(response.status, http::StatusCode::TOO_MANY_REQUESTS);
+    });
+}
+
 pub struct ComplexitySchema;
 
 impl Subgraph for ComplexitySchema {
@@ -4,7 +4,9 @@ use graphql_mocks::{ErrorSchema, FederatedInventorySchema, FederatedProductsSche
 use integration_tests::{gateway::Gateway, runtime};
 use serde_json::json;
 
+mod invalidation;
 mod redis;
+mod stale;
 mod subgraph_cache_control;
 
 #[test]
@@ -0,0 +1,330 @@
+use graphql_mocks::dynamic::{DynamicSchema, EntityResolverContext, ServerError};
+use integration_tests::{
+    gateway::{Gateway, GatewayBuilder},
+    runtime,
+};
+use serde_json::json;
+
+const QUERY: &str = "{ products { upc name } }";
+
+fn gateway() -> GatewayBuilder {
+    Gateway::builder()
+        .with_subgraph(
+            DynamicSchema::builder(
+                r#"
+                extend schema
+                    @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+
+                type Query {
+                    products: [Product!]!
+                }
+
+                type Mutation {
+                    updateProduct(upc: String!): Boolean
+                    deleteProduct(upc: String!): Boolean
+                }
+
+                type Product @key(fields: "upc") {
+                    upc: String!
+                }
+                "#,
+            )
+            .with_resolver("Query", "products", json!([{"upc": "1"}, {"upc": "2"}]))
+            .with_resolver("Mutation", "updateProduct", json!(true))
+            .with_resolver("Mutation", "deleteProduct", ServerError::new("Product not found", None))
+            .into_subgraph("x"),
+        )
+        .with_subgraph(
+            DynamicSchema::builder(
+                r#"
+                extend schema
+                    @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@key"])
+
+                type Product @key(fields: "upc") {
+                    upc: String!
+                    name: String!
+                }
+                "#,
+            )
+            .with_entity_resolver("Product", |ctx: EntityResolverContext<'_>| {
+                let upc = ctx.representation["upc"].as_str().unwrap();
+                Some(json!({"upc": upc, "name": format!("Product {upc}")}))
+            })
+            .into_subgraph("y"),
+        )
+}
+
+/// Keys of the entities requested to the subgraph y, for each request.
+fn requested_entities(engine: &Gateway) -> Vec<Vec<String>> {
+    engine
+        .drain_graphql_requests_sent_to_by_name("y")
+        .into_iter()
+        .map(|request| {
+            let variables = serde_json::to_value(&request.variables).unwrap();
+            variables["var0"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|representation| representation["upc"].as_str().unwrap().to_string())
+                .collect()
+        })
+        .collect()
+}
+
+async fn invalidate(engine: &Gateway, token: &str, body: serde_json::Value) -> http::StatusCode {
+    engine
+        .raw_execute(
+            http::Request::post("/admin/entity-cache/invalidate")
+                .header(http::header::AUTHORIZATION, format!("Bearer {token}"))
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(serde_json::to_vec(&body).unwrap())
+                .unwrap(),
+        )
+        .await
+        .status()
+}
+
+#[test]
+fn invalidate_single_entity_through_endpoint() {
+    runtime().block_on(async move {
+        let engine = gateway()
+            .with_toml_config(
+                r#"
+                [entity_caching]
+                enabled = true
+
+                [entity_caching.invalidation.endpoint]
+                enabled = true
+                token = "secret"
+                "#,
+            )
+            .build()
+            .await;
+
+        engine.post(QUERY).await;
+        engine.post(QUERY).await;
+        assert_eq!(requested_entities(&engine), vec![vec!["1", "2"]]);
+
+        let status = invalidate(
+            &engine,
+            "secret",
+            json!({"entities": [{"type": "Product", "key": {"upc": "2"}}]}),
+        )
+        .await;
+        assert_eq!(status, http::StatusCode::OK);
+
+        let response = engine.post(QUERY).await;
+        assert_eq!(requested_entities(&engine), vec![vec!["2"]]);
+
+        insta::assert_json_snapshot!(response, @r#"
+        {
+          "data": {
+            "products": [
+              {
+                "upc": "1",
+                "name": "Product 1"
+              },
+              {
+                "upc": "2",
+                "name": "Product 2"
+              }
+            ]
+          }
+        }
+        "#);
+    })
+}
+
+#[test]
+fn single_entity_invalidation_keeps_root_field_responses() {
+    runtime().block_on(async move {
+        let engine = gateway()
+            .with_toml_config(
+                r#"
+                [entity_caching]
+                enabled = true
+
+                [entity_caching.invalidation.endpoint]
+                enabled = true
+                token = "secret"
+                "#,
+            )
+            .build()
+            .await;
+
+        engine.post(QUERY).await;
+
+        let status = invalidate(
+            &engine,
+            "secret",
+            json!({"entities": [{"type": "Product", "key": {"upc": "2"}}]}),
+        )
+        .await;
+        assert_eq!(status, http::StatusCode::OK);
+
+        engine.post(QUERY).await;
+        assert_eq!(requested_entities(&engine), vec![vec!["1", "2"], vec!["2"]]);
+        // Root field responses are only tagged with the type they return, not with the entities
+        // they contain. Removing a product from the list requires invalidating the type.
+        assert_eq!(engine.drain_graphql_requests_sent_to_by_name("x").len(), 1);
+    })
+}
+
+#[test]
+fn invalidate_entity_type_through_endpoint() {
+    runtime().block_on(async move {
+        let engine = gateway()
+            .with_toml_config(
+                r#"
+                [entity_caching]
+                enabled = true
+
+                [entity_caching.invalidation.endpoint]
+                enabled = true
+                token = "secret"
+                "#,
+            )
+            .build()
+            .await;
+
+        engine.post(QUERY).await;
+        assert_eq!(requested_entities(&engine), vec![vec!["1", "2"]]);
+
+        let status = invalidate(&engine, "secret", json!({"entities": [{"type": "Product"}]})).await;
+        assert_eq!(status, http::StatusCode::OK);
+
+        engine.post(QUERY).await;
+        assert_eq!(requested_entities(&engine), vec![vec!["1", "2"]]);
+        // The root field response returning products was invalidated as well.
+        assert_eq!(engine.drain_graphql_requests_sent_to_by_name("x").len(), 2);
+    })
+}
+
+#[test]
+fn invalidation_endpoint_requires_token() {
+    runtime().block_on(async move {
+        let engine = gateway()
+            .with_toml_config(
+                r#"
+                [entity_caching]
+                enabled = true
+
+                [entity_caching.invalidation.endpoint]
+                enabled = true
+                token = "secret"
+                "#,
+            )
+            .build()
+            .await;
+
+        engine.post(QUERY).await;
+
+        let status = invalidate(&engine, "wrong", json!({"entities": [{"type": "Product"}]})).await;
+        assert_eq!(status, http::StatusCode::UNAUTHORIZED);
+
+        engine.post(QUERY).await;
+        assert_eq!(requested_entities(&engine), vec![vec!["1", "2"]]);
+    })
+}
+
+#[test]
+fn mutation_invalidates_entity() {
+    runtime().block_on(async move {
+        let engine = gateway()
+            .with_toml_config(
+                r#"
+                [entity_caching]
+                enabled = true
+
+                [[entity_caching.invalidation.mutations]]
+                field = "updateProduct"
+                type = "Product"
+                key = { upc = "upc" }
+                "#,
+            )
+            .build()
+            .await;
+
+        engine.post(QUERY).await;
+        assert_eq!(requested_entities(&engine), vec![vec!["1", "2"]]);
+
+        let response = engine.post(r#"mutation { updateProduct(upc: "1") }"#).await;
+        assert_eq!(response.into_data(), json!({"updateProduct": true}));
+
+        engine.post(QUERY).await;
+        assert_eq!(requested_entities(&engine), vec![vec!["1"]]);
+    })
+}
+
+#[test]
+fn failed_mutation_does_not_invalidate_entity() {
+    runtime().block_on(async move {
+        let engine = gateway()
+            .with_toml_config(
+                r#"
+                [entity_caching]
+                enabled = true
+
+                [[entity_caching.invalidation.mutations]]
+                field = "updateProduct"
+                type = "Product"
+                key = { upc = "upc" }
+
+                [[entity_caching.invalidation.mutations]]
+                field = "deleteProduct"
+                type = "Product"
+                key = { upc = "upc" }
+                "#,
+            )
+            .build()
+            .await;
+
+        engine.post(QUERY).await;
+        assert_eq!(requested_entities(&engine), vec![vec!["1", "2"]]);
+
+        let response = engine
+            .post(r#"mutation { deleteProduct(upc: "1") updateProduct(upc: "2") }"#)
+            .await;
+        assert_eq!(
+            response.into_data(),
+            json!({"deleteProduct": null, "updateProduct": true})
+        );
+
+        engine.post(QUERY).await;
+        assert_eq!(requested_entities(&engine), vec![vec!["2"]]);
+    })
+}
+
+#[test]
+fn streamed_mutation_invalidates_entity() {
+    runtime().block_on(async move {
+        let engine = gateway()
+            .with_toml_config(
+                r#"
+                [entity_caching]
+                enabled = true
+
+                [[entity_caching.invalidation.mutations]]
+                field = "updateProduct"
+                type = "Product"
+                key = { upc = "upc" }
+                "#,
+            )
+            .build()
+            .await;
+
+        engine.post(QUERY).await;
+        assert_eq!(requested_entities(&engine), vec![vec!["1", "2"]]);
+
+        let response = engine
+            .post(r#"mutation { updateProduct(upc: "1") }"#)
+            .into_sse_stream()
+            .await
+            .collect()
+            .await;
+        assert_eq!(response.messages, vec![json!({"data": {"updateProduct": true}})]);
+
+        engine.post(QUERY).await;
+        assert_eq!(requested_entities(&engine), vec![vec!["1"]]);
+    })
+}
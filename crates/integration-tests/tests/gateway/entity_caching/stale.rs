@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use graphql_mocks::{FederatedInventorySchema, FederatedProductsSchema, FederatedReviewsSchema};
+use integration_tests::{gateway::Gateway, runtime};
+
+const QUERY: &str = r"query { topProducts { upc name price } }";
+
+#[test]
+fn stale_entries_are_served_while_revalidating() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FederatedProductsSchema::default())
+            .with_toml_config(
+                r#"
+                [entity_caching]
+                enabled = true
+
+                [subgraphs.products.entity_caching]
+                ttl = "1s"
+                stale_while_revalidate = "60s"
+                "#,
+            )
+            .build()
+            .await;
+
+        let first_response = engine.post(QUERY).await.into_data();
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        // Served from the stale entry, while the background refresh updates it.
+        let second_response = engine.post(QUERY).await.into_data();
+        assert_eq!(first_response, second_response);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(
+            engine.drain_graphql_requests_sent_to::<FederatedProductsSchema>().len(),
+            2
+        );
+
+        // The entry was refreshed, so no subgraph request is needed.
+        let third_response = engine.post(QUERY).await.into_data();
+        assert_eq!(first_response, third_response);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(
+            engine.drain_graphql_requests_sent_to::<FederatedProductsSchema>().len(),
+            0
+        );
+    });
+}
+
+#[test]
+fn stale_entries_are_served_on_subgraph_error() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FederatedProductsSchema::default())
+            .with_toml_config(
+                r#"
+                [entity_caching]
+                enabled = true
+
+                [subgraphs.products.entity_caching]
+                ttl = "1s"
+                stale_if_error = "60s"
+                "#,
+            )
+            .build()
+            .await;
+
+        let first_response = engine.post(QUERY).await;
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        engine
+            .subgraph::<FederatedProductsSchema>()
+            .force_next_response(http::StatusCode::INTERNAL_SERVER_ERROR);
+
+        let second_response = engine.post(QUERY).await;
+        assert!(second_response.errors().is_empty());
+        assert_eq!(first_response.into_data(), second_response.into_data());
+
+        assert_eq!(
+            engine.drain_graphql_requests_sent_to::<FederatedProductsSchema>().len(),
+            2
+        );
+    });
+}
+
+#[test]
+fn expired_entries_are_not_served_on_subgraph_error_without_stale_if_error() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FederatedProductsSchema::default())
+            .with_toml_config(
+                r#"
+                [entity_caching]
+                enabled = true
+
+                [subgraphs.products.entity_caching]
+                ttl = "1s"
+                "#,
+            )
+            .build()
+            .await;
+
+        engine.post(QUERY).await;
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        engine
+            .subgraph::<FederatedProductsSchema>()
+            .force_next_response(http::StatusCode::INTERNAL_SERVER_ERROR);
+
+        let response = engine.post(QUERY).await;
+        assert!(!response.errors().is_empty());
+    });
+}
+
+#[test]
+fn stale_entities_are_served_on_subgraph_error() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FederatedProductsSchema::default())
+            .with_subgraph(FederatedReviewsSchema::default())
+            .with_subgraph(FederatedInventorySchema::default())
+            .with_toml_config(
+                r#"
+                [entity_caching]
+                enabled = true
+                ttl = "1s"
+                stale_if_error = "60s"
+                "#,
+            )
+            .build()
+            .await;
+
+        const QUERY: &str = "{ topProducts { upc reviews { id body } } }";
+
+        let first_response = engine.post(QUERY).await;
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        engine
+            .subgraph::<FederatedReviewsSchema>()
+            .force_next_response(http::StatusCode::INTERNAL_SERVER_ERROR);
+
+        let second_response = engine.post(QUERY).await;
+        assert!(second_response.errors().is_empty());
+        assert_eq!(first_response.into_data(), second_response.into_data());
+
+        assert_eq!(
+            engine.drain_graphql_requests_sent_to::<FederatedReviewsSchema>().len(),
+            2
+        );
+    });
+}
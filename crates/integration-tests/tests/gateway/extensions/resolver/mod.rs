@@ -1,6 +1,7 @@
 mod nested;
 mod sdk17;
 mod sdk21;
+mod sdk24;
 mod subscription;
 
 use std::sync::Arc;
@@ -0,0 +1,119 @@
+use integration_tests::{gateway::Gateway, runtime};
+use rand::Rng;
+
+// Requires the Redis container of the gateway docker-compose.yml.
+
+fn random_key_prefix() -> String {
+    // Keys are shared with the other tests using the same Redis.
+    rand::thread_rng()
+        .sample_iter(&rand::distributions::Alphanumeric)
+        .take(6)
+        .map(char::from)
+        .collect()
+}
+
+#[test]
+fn commands_and_pipelines() {
+    let key_prefix = random_key_prefix();
+
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_toml_config(
+                r#"
+                [extensions.redis-24.config]
+                url = "redis://localhost:6379"
+                "#,
+            )
+            .with_subgraph_sdl(
+                "a",
+                format!(
+                    r#"
+                    extend schema
+                        @link(url: "redis-24", import: ["@redisIncr", "@redisGet"])
+
+                    type Query {{
+                        counter: Int @redisIncr(key: "test-{key_prefix}-counter")
+                        value: String @redisGet(key: "test-{key_prefix}-counter")
+                        missing: String @redisGet(key: "test-{key_prefix}-missing")
+                    }}
+                    "#
+                ),
+            )
+            .with_extension("redis-24")
+            .build()
+            .await;
+
+        let response = engine.post("query { counter }").await;
+        insta::assert_json_snapshot!(response, @r#"
+        {
+          "data": {
+            "counter": 1
+          }
+        }
+        "#);
+
+        let response = engine.post("query { counter }").await;
+        insta::assert_json_snapshot!(response, @r#"
+        {
+          "data": {
+            "counter": 2
+          }
+        }
+        "#);
+
+        let response = engine.post("query { value missing }").await;
+        insta::assert_json_snapshot!(response, @r#"
+        {
+          "data": {
+            "value": "2",
+            "missing": null
+          }
+        }
+        "#);
+    })
+}
+
+#[test]
+fn tls_is_configured_by_the_gateway() {
+    let key_prefix = random_key_prefix();
+
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_toml_config(
+                r#"
+                [extensions.redis-24.config]
+                url = "rediss://localhost:6379"
+
+                [extensions.redis-24.redis.default.tls]
+                ca = "/does/not/exist/ca.crt"
+                "#,
+            )
+            .with_subgraph_sdl(
+                "a",
+                format!(
+                    r#"
+                    extend schema
+                        @link(url: "redis-24", import: ["@redisGet"])
+
+                    type Query {{
+                        value: String @redisGet(key: "test-{key_prefix}-value")
+                    }}
+                    "#
+                ),
+            )
+            .with_extension("redis-24")
+            .build()
+            .await;
+
+        let response = engine.post("query { value }").await;
+        let errors = response.errors();
+
+        assert_eq!(errors.len(), 1, "{response}");
+        assert!(
+            errors[0]["message"]
+                .as_str()
+                .is_some_and(|message| message.contains("loading the Redis CA certificate")),
+            "{response}"
+        );
+    })
+}
@@ -14,8 +14,11 @@ mod issues;
 mod mcp;
 mod message_signing;
 mod mtls;
+mod response_caching;
 mod response_extensions;
+mod response_headers;
 mod router;
+mod subgraph_hedging;
 mod subgraph_retries;
 mod subgraphs;
 mod subscriptions;
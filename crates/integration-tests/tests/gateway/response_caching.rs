@@ -0,0 +1,231 @@
+use graphql_mocks::{FederatedProductsSchema, dynamic::DynamicSchema};
+use integration_tests::{gateway::Gateway, runtime};
+use serde_json::json;
+
+const CONFIG: &str = r#"
+    [response_caching]
+    enabled = true
+"#;
+
+#[test]
+fn identical_queries_are_served_from_the_cache() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FederatedProductsSchema::default())
+            .with_toml_config(CONFIG)
+            .build()
+            .await;
+
+        const QUERY: &str = r"query { topProducts { upc name price } }";
+
+        let first_response = engine.post(QUERY).await;
+        let second_response = engine.post(QUERY).await;
+
+        assert_eq!(
+            first_response
+                .headers
+                .get("cache-control")
+                .and_then(|h| h.to_str().ok()),
+            Some("public, max-age=60")
+        );
+        assert!(first_response.headers.get("vary").is_none());
+        assert!(first_response.headers.get("age").is_none());
+        assert!(second_response.headers.get("age").is_some());
+
+        assert_eq!(first_response.into_data(), second_response.into_data());
+
+        assert_eq!(
+            engine.drain_graphql_requests_sent_to::<FederatedProductsSchema>().len(),
+            1
+        );
+    });
+}
+
+#[test]
+fn variables_are_part_of_the_cache_key() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FederatedProductsSchema::default())
+            .with_toml_config(CONFIG)
+            .build()
+            .await;
+
+        const QUERY: &str = r"query($upc: String!) { product(upc: $upc) { name } }";
+
+        let first = engine.post(QUERY).variables(json!({"upc": "top-1"})).await;
+        let second = engine.post(QUERY).variables(json!({"upc": "top-2"})).await;
+        let third = engine.post(QUERY).variables(json!({"upc": "top-1"})).await;
+
+        assert_eq!(first.into_data(), json!({"product": {"name": "Trilby"}}));
+        assert_eq!(second.into_data(), json!({"product": {"name": "Fedora"}}));
+        assert_eq!(third.into_data(), json!({"product": {"name": "Trilby"}}));
+
+        assert_eq!(
+            engine.drain_graphql_requests_sent_to::<FederatedProductsSchema>().len(),
+            2
+        );
+    });
+}
+
+#[test]
+fn vary_headers_are_part_of_the_cache_key() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FederatedProductsSchema::default())
+            .with_toml_config(
+                r#"
+                [response_caching]
+                enabled = true
+
+                [response_caching.vary]
+                headers = ["x-tenant"]
+                "#,
+            )
+            .build()
+            .await;
+
+        const QUERY: &str = r"query { topProducts { upc } }";
+
+        let first = engine.post(QUERY).header("x-tenant", "a").await;
+        engine.post(QUERY).header("x-tenant", "b").await;
+        engine.post(QUERY).header("x-tenant", "a").await;
+
+        assert_eq!(
+            first.headers.get("cache-control").and_then(|h| h.to_str().ok()),
+            Some("private, max-age=60")
+        );
+        assert_eq!(
+            first.headers.get("vary").and_then(|h| h.to_str().ok()),
+            Some("x-tenant")
+        );
+
+        assert_eq!(
+            engine.drain_graphql_requests_sent_to::<FederatedProductsSchema>().len(),
+            2
+        );
+    });
+}
+
+#[test]
+fn responses_varying_on_jwt_claims_are_private() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FederatedProductsSchema::default())
+            .with_toml_config(
+                r#"
+                [response_caching]
+                enabled = true
+
+                [response_caching.vary]
+                jwt_claims = ["sub"]
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.post(r"query { topProducts { upc } }").await;
+
+        assert_eq!(
+            response.headers.get("cache-control").and_then(|h| h.to_str().ok()),
+            Some("private, max-age=60")
+        );
+        assert!(response.headers.get("vary").is_none());
+    });
+}
+
+#[test]
+fn mutations_are_never_cached() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(
+                DynamicSchema::builder(
+                    r#"
+                    type Query {
+                        counter: Int!
+                    }
+
+                    type Mutation {
+                        increment: Int!
+                    }
+                    "#,
+                )
+                .with_resolver("Query", "counter", json!(0))
+                .with_resolver("Mutation", "increment", json!(1))
+                .into_subgraph("x"),
+            )
+            .with_toml_config(CONFIG)
+            .build()
+            .await;
+
+        let first = engine.post("mutation { increment }").await;
+        engine.post("mutation { increment }").await;
+
+        assert!(first.headers.get("cache-control").is_none());
+        assert_eq!(engine.drain_graphql_requests_sent_to_by_name("x").len(), 2);
+    });
+}
+
+#[test]
+fn smallest_cache_control_hint_defines_the_max_age() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(
+                DynamicSchema::builder(
+                    r#"
+                    extend schema
+                        @link(url: "https://specs.apollo.dev/federation/v2.3", import: ["@composeDirective"])
+                        @link(url: "https://example.com/cache", import: ["@cacheControl"])
+                        @composeDirective(name: "@cacheControl")
+
+                    directive @cacheControl(maxAge: Int) on OBJECT | INTERFACE | UNION | FIELD_DEFINITION
+
+                    type Query {
+                        user: User @cacheControl(maxAge: 120)
+                        news: [String!]! @cacheControl(maxAge: 30)
+                        uncached: String @cacheControl(maxAge: 0)
+                        settings: Settings
+                    }
+
+                    type Settings {
+                        theme: String!
+                    }
+
+                    type User @cacheControl(maxAge: 300) {
+                        name: String!
+                    }
+                    "#,
+                )
+                .with_resolver("Query", "user", json!({"name": "Alice"}))
+                .with_resolver("Query", "news", json!(["Hello"]))
+                .with_resolver("Query", "uncached", json!("now"))
+                .with_resolver("Query", "settings", json!({"theme": "dark"}))
+                .into_subgraph("x"),
+            )
+            .with_toml_config(CONFIG)
+            .build()
+            .await;
+
+        let response = engine.post("query { user { name } }").await;
+        assert_eq!(
+            response.headers.get("cache-control").and_then(|h| h.to_str().ok()),
+            Some("public, max-age=120")
+        );
+
+        let response = engine.post("query { user { name } news }").await;
+        assert_eq!(
+            response.headers.get("cache-control").and_then(|h| h.to_str().ok()),
+            Some("public, max-age=30")
+        );
+
+        // Fields without any hint use the default max-age, except leaf fields which inherit it
+        // from their parent.
+        let response = engine.post("query { user { name } settings { theme } }").await;
+        assert_eq!(
+            response.headers.get("cache-control").and_then(|h| h.to_str().ok()),
+            Some("public, max-age=60")
+        );
+
+        let response = engine.post("query { news uncached }").await;
+        assert!(response.headers.get("cache-control").is_none());
+    });
+}
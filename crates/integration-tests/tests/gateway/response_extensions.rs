@@ -3,7 +3,8 @@ use graphql_mocks::{
     FederatedReviewsSchema, FederatedShippingSchema,
     dynamic::{DynamicSchema, ServerError},
 };
-use integration_tests::{gateway::Gateway, runtime};
+use integration_tests::{fetch::MockFetch, gateway::Gateway, runtime};
+use serde_json::json;
 
 #[test]
 fn grafbase_extension_on_successful_request() {
@@ -575,3 +576,94 @@ fn complex_query_plan() {
         "#);
     })
 }
+
+#[test]
+fn query_plan_details() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_federated_sdl(
+                r#"
+                enum join__Graph {
+                  A @join__graph(name: "a", url: "https://a/graphql")
+                  B @join__graph(name: "b", url: "https://b/graphql")
+                }
+
+                type Query {
+                  user: User @join__field(graph: A)
+                }
+
+                type User @join__type(graph: A, key: "id") @join__type(graph: B, key: "id") {
+                  id: ID!
+                  name: String @join__field(graph: B)
+                }
+                "#,
+            )
+            .with_mock_fetcher(
+                MockFetch::default()
+                    .with_responses("a", vec![json!({"data": {"user": {"id": "1"}}})])
+                    .with_responses("b", vec![json!({"data": {"_entities": [{"name": "Alice"}]}})]),
+            )
+            .with_toml_config(
+                r#"
+                [telemetry.exporters.response_extension]
+                trace_id = false
+                query_plan_details = true
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine
+            .post("query { user { name } }")
+            .header("x-grafbase-telemetry", "")
+            .await;
+
+        insta::assert_json_snapshot!(response, @r#"
+        {
+          "data": {
+            "user": {
+              "name": "Alice"
+            }
+          },
+          "extensions": {
+            "grafbase": {
+              "queryPlan": {
+                "nodes": [
+                  {
+                    "__typename": "GraphqlResolver",
+                    "subgraphName": "a",
+                    "request": {
+                      "query": "query { user { id } }"
+                    }
+                  },
+                  {
+                    "__typename": "GraphqlResolver",
+                    "subgraphName": "b",
+                    "request": {
+                      "query": "query($var0: [_Any!]!) { _entities(representations: $var0) { ... on User { name } } }"
+                    }
+                  }
+                ],
+                "edges": [
+                  [
+                    0,
+                    1
+                  ]
+                ],
+                "details": [
+                  {
+                    "entity": "Query",
+                    "requiredFields": ""
+                  },
+                  {
+                    "entity": "User",
+                    "requiredFields": "id"
+                  }
+                ]
+              }
+            }
+          }
+        }
+        "#);
+    })
+}
@@ -0,0 +1,236 @@
+use integration_tests::{fetch::MockFetch, gateway::Gateway, runtime};
+use serde_json::json;
+
+const SDL: &str = r###"
+    enum join__Graph {
+      A @join__graph(name: "a", url: "https://a/graphql")
+      B @join__graph(name: "b", url: "https://b/graphql")
+    }
+
+    type Query {
+      a: String @join__field(graph: A)
+      b: String @join__field(graph: B)
+    }
+"###;
+
+fn fetcher() -> MockFetch {
+    MockFetch::default()
+        .with_responses("a", vec![json!({"data": {"a": "a"}})])
+        .with_responses("b", vec![json!({"data": {"b": "b"}})])
+        .with_response_headers(
+            "a",
+            [
+                ("set-cookie", "a=1"),
+                ("x-ratelimit-remaining", "10"),
+                ("cache-control", "public, max-age=60"),
+                ("x-trace-id", "trace-a"),
+            ],
+        )
+        .with_response_headers(
+            "b",
+            [
+                ("set-cookie", "b=2"),
+                ("x-ratelimit-remaining", "5"),
+                ("cache-control", "private, max-age=30"),
+                ("x-trace-id", "trace-b"),
+            ],
+        )
+}
+
+fn values<'a>(headers: &'a http::HeaderMap, name: &str) -> Vec<&'a str> {
+    let mut values = headers
+        .get_all(name)
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect::<Vec<_>>();
+    values.sort_unstable();
+    values
+}
+
+#[test]
+fn subgraph_response_headers_are_not_propagated_by_default() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(fetcher())
+            .build()
+            .await;
+
+        let response = engine.post("query { a b }").await;
+        assert!(response.errors().is_empty());
+
+        assert!(response.headers.get("set-cookie").is_none());
+        assert!(response.headers.get("x-trace-id").is_none());
+    })
+}
+
+#[test]
+fn subgraph_response_headers_are_propagated_and_merged() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(fetcher())
+            .with_toml_config(
+                r#"
+                [[response_headers]]
+                rule = "propagate"
+                name = "set-cookie"
+                merge = "append"
+
+                [[response_headers]]
+                rule = "propagate"
+                pattern = "^x-ratelimit-"
+                merge = "append"
+
+                [[response_headers]]
+                rule = "propagate"
+                name = "cache-control"
+                merge = "min_max_age"
+
+                [[subgraphs.b.response_headers]]
+                rule = "propagate"
+                name = "x-trace-id"
+                rename = "x-b-trace-id"
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.post("query { a b }").await;
+
+        insta::assert_json_snapshot!(response, @r#"
+        {
+          "data": {
+            "a": "a",
+            "b": "b"
+          }
+        }
+        "#);
+
+        assert_eq!(values(&response.headers, "set-cookie"), ["a=1", "b=2"]);
+        assert_eq!(values(&response.headers, "x-ratelimit-remaining"), ["10", "5"]);
+        assert_eq!(values(&response.headers, "cache-control"), ["private, max-age=30"]);
+        assert_eq!(values(&response.headers, "x-b-trace-id"), ["trace-b"]);
+        assert!(response.headers.get("x-trace-id").is_none());
+    })
+}
+
+#[test]
+fn first_and_last_follow_the_plan_order() {
+    runtime().block_on(async move {
+        // The entity plan depends on the root one, so it's planned after it.
+        let engine = Gateway::builder()
+            .with_federated_sdl(
+                r###"
+                enum join__Graph {
+                  A @join__graph(name: "a", url: "https://a/graphql")
+                  B @join__graph(name: "b", url: "https://b/graphql")
+                }
+
+                type Query @join__type(graph: A) {
+                  product: Product @join__field(graph: A)
+                }
+
+                type Product @join__type(graph: A, key: "id") @join__type(graph: B, key: "id") {
+                  id: ID!
+                  name: String @join__field(graph: B)
+                }
+                "###,
+            )
+            .with_mock_fetcher(
+                MockFetch::default()
+                    .with_responses("a", vec![json!({"data": {"product": {"id": "1"}}})])
+                    .with_responses("b", vec![json!({"data": {"_entities": [{"name": "Fedora"}]}})])
+                    .with_response_headers("a", [("x-first", "a"), ("x-last", "a")])
+                    .with_response_headers("b", [("x-first", "b"), ("x-last", "b")]),
+            )
+            .with_toml_config(
+                r#"
+                [[response_headers]]
+                rule = "propagate"
+                name = "x-first"
+                merge = "first"
+
+                [[response_headers]]
+                rule = "propagate"
+                name = "x-last"
+                merge = "last"
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.post("query { product { name } }").await;
+        assert!(response.errors().is_empty());
+
+        assert_eq!(values(&response.headers, "x-first"), ["a"]);
+        assert_eq!(values(&response.headers, "x-last"), ["b"]);
+    })
+}
+
+const CACHED_FETCHER_RULES: &str = r#"
+    [[response_headers]]
+    rule = "propagate"
+    name = "set-cookie"
+    merge = "append"
+"#;
+
+/// A single response per subgraph, later requests must be served from the cache.
+fn cached_fetcher() -> MockFetch {
+    MockFetch::default()
+        .with_responses("a", vec![json!({"data": {"a": "a"}})])
+        .with_response_headers("a", [("set-cookie", "a=1"), ("cache-control", "public, max-age=60")])
+}
+
+#[test]
+fn subgraph_response_headers_are_not_cached_by_the_entity_cache() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(cached_fetcher())
+            .with_toml_config(format!(
+                r#"
+                [entity_caching]
+                enabled = true
+                {CACHED_FETCHER_RULES}
+                "#
+            ))
+            .build()
+            .await;
+
+        let alice = engine.post("query { a }").header("x-client-id", "alice").await;
+        let bob = engine.post("query { a }").header("x-client-id", "bob").await;
+
+        assert_eq!(values(&alice.headers, "set-cookie"), ["a=1"]);
+        assert_eq!(alice.into_data(), json!({"a": "a"}));
+        assert!(bob.headers.get("set-cookie").is_none());
+        assert_eq!(bob.into_data(), json!({"a": "a"}));
+    })
+}
+
+#[test]
+fn subgraph_response_headers_are_not_cached_by_the_response_cache() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_federated_sdl(SDL)
+            .with_mock_fetcher(cached_fetcher())
+            .with_toml_config(format!(
+                r#"
+                [response_caching]
+                enabled = true
+                {CACHED_FETCHER_RULES}
+                "#
+            ))
+            .build()
+            .await;
+
+        let alice = engine.post("query { a }").header("x-client-id", "alice").await;
+        let bob = engine.post("query { a }").header("x-client-id", "bob").await;
+
+        assert_eq!(values(&alice.headers, "set-cookie"), ["a=1"]);
+        assert_eq!(alice.into_data(), json!({"a": "a"}));
+        assert!(bob.headers.get("age").is_some());
+        assert!(bob.headers.get("set-cookie").is_none());
+        assert_eq!(bob.into_data(), json!({"a": "a"}));
+    })
+}
@@ -0,0 +1,95 @@
+use graphql_mocks::SlowSchema;
+use integration_tests::{gateway::Gateway, runtime};
+
+const CONFIG: &str = r#"
+    [subgraphs.slow.retry]
+    enabled = true
+
+    [subgraphs.slow.hedging]
+    enabled = true
+    delay = "50ms"
+"#;
+
+#[test]
+fn slow_queries_are_hedged() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(SlowSchema::default())
+            .with_toml_config(CONFIG)
+            .build()
+            .await;
+
+        let response = engine.post("query { delay(ms: 300) }").await;
+
+        insta::assert_json_snapshot!(response, @r#"
+        {
+          "data": {
+            "delay": 300
+          }
+        }
+        "#);
+
+        assert_eq!(engine.drain_graphql_requests_sent_to::<SlowSchema>().len(), 2);
+    })
+}
+
+#[test]
+fn fast_queries_are_not_hedged() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(SlowSchema::default())
+            .with_toml_config(CONFIG)
+            .build()
+            .await;
+
+        let response = engine.post("query { delay(ms: 1) }").await;
+        assert!(response.errors().is_empty());
+
+        assert_eq!(engine.drain_graphql_requests_sent_to::<SlowSchema>().len(), 1);
+    })
+}
+
+#[test]
+fn mutations_are_never_hedged() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(SlowSchema::default())
+            .with_toml_config(CONFIG)
+            .build()
+            .await;
+
+        let response = engine.post("mutation { delay(ms: 300) }").await;
+
+        insta::assert_json_snapshot!(response, @r#"
+        {
+          "data": {
+            "delay": 300
+          }
+        }
+        "#);
+
+        assert_eq!(engine.drain_graphql_requests_sent_to::<SlowSchema>().len(), 1);
+    })
+}
+
+#[test]
+fn hedging_requires_a_retry_budget() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(SlowSchema::default())
+            .with_toml_config(
+                r#"
+                [subgraphs.slow.hedging]
+                enabled = true
+                delay = "50ms"
+                "#,
+            )
+            .build()
+            .await;
+
+        let response = engine.post("query { delay(ms: 300) }").await;
+        assert!(response.errors().is_empty());
+
+        assert_eq!(engine.drain_graphql_requests_sent_to::<SlowSchema>().len(), 1);
+    })
+}
@@ -3,6 +3,9 @@ use futures::stream::FuturesUnordered;
 use graphql_mocks::SlowSchema;
 use integration_tests::{gateway::Gateway, runtime};
 
+mod circuit_breaker;
+mod concurrency_limit;
+
 #[test]
 fn inflight_deduplication_enabled() {
     runtime().block_on(async move {
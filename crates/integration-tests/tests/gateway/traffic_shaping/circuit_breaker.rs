@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use graphql_mocks::FederatedProductsSchema;
+use integration_tests::{
+    gateway::{Gateway, GraphqlResponse},
+    runtime,
+};
+
+const QUERY: &str = "query { topProducts { upc } }";
+
+fn error_code(response: &GraphqlResponse) -> Option<String> {
+    response
+        .errors()
+        .first()
+        .and_then(|error| error["extensions"]["code"].as_str())
+        .map(str::to_string)
+}
+
+#[test]
+fn circuit_breaker_opens_after_consecutive_failures() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FederatedProductsSchema::default())
+            .with_toml_config(
+                r#"
+                [traffic_shaping.circuit_breaker]
+                enabled = true
+                consecutive_failures = 2
+                cooldown = "1s"
+                "#,
+            )
+            .build()
+            .await;
+
+        for _ in 0..2 {
+            engine
+                .subgraph::<FederatedProductsSchema>()
+                .force_next_response(http::StatusCode::INTERNAL_SERVER_ERROR);
+            let response = engine.post(QUERY).await;
+            assert_eq!(error_code(&response).as_deref(), Some("SUBGRAPH_REQUEST_ERROR"));
+        }
+        assert_eq!(
+            engine.drain_graphql_requests_sent_to::<FederatedProductsSchema>().len(),
+            2
+        );
+
+        // The circuit is open, so the request fails without reaching the subgraph.
+        let response = engine.post(QUERY).await;
+        insta::assert_json_snapshot!(response, @r#"
+        {
+          "data": null,
+          "errors": [
+            {
+              "message": "Request to subgraph 'products' was not sent, its circuit breaker is open.",
+              "locations": [
+                {
+                  "line": 1,
+                  "column": 9
+                }
+              ],
+              "path": [
+                "topProducts"
+              ],
+              "extensions": {
+                "code": "SUBGRAPH_CIRCUIT_BREAKER_OPEN"
+              }
+            }
+          ]
+        }
+        "#);
+        assert_eq!(
+            engine.drain_graphql_requests_sent_to::<FederatedProductsSchema>().len(),
+            0
+        );
+
+        // After the cooldown a probe goes through and closes the circuit.
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        let response = engine.post(QUERY).await;
+        assert!(response.errors().is_empty());
+        let response = engine.post(QUERY).await;
+        assert!(response.errors().is_empty());
+        assert_eq!(
+            engine.drain_graphql_requests_sent_to::<FederatedProductsSchema>().len(),
+            2
+        );
+    })
+}
+
+#[test]
+fn circuit_breaker_reopens_if_probe_fails() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FederatedProductsSchema::default())
+            .with_toml_config(
+                r#"
+                [traffic_shaping.circuit_breaker]
+                enabled = true
+                consecutive_failures = 1
+                cooldown = "1s"
+                "#,
+            )
+            .build()
+            .await;
+
+        engine
+            .subgraph::<FederatedProductsSchema>()
+            .force_next_response(http::StatusCode::INTERNAL_SERVER_ERROR);
+        engine.post(QUERY).await;
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        // The probe fails, re-opening the circuit.
+        engine
+            .subgraph::<FederatedProductsSchema>()
+            .force_next_response(http::StatusCode::INTERNAL_SERVER_ERROR);
+        let response = engine.post(QUERY).await;
+        assert_eq!(error_code(&response).as_deref(), Some("SUBGRAPH_REQUEST_ERROR"));
+
+        let response = engine.post(QUERY).await;
+        assert_eq!(error_code(&response).as_deref(), Some("SUBGRAPH_CIRCUIT_BREAKER_OPEN"));
+
+        assert_eq!(
+            engine.drain_graphql_requests_sent_to::<FederatedProductsSchema>().len(),
+            2
+        );
+    })
+}
+
+#[test]
+fn circuit_breaker_can_be_disabled_per_subgraph() {
+    runtime().block_on(async move {
+        let engine = Gateway::builder()
+            .with_subgraph(FederatedProductsSchema::default())
+            .with_toml_config(
+                r#"
+                [traffic_shaping.circuit_breaker]
+                enabled = true
+                consecutive_failures = 1
+
+                [subgraphs.products.circuit_breaker]
+                enabled = false
+                "#,
+            )
+            .build()
+            .await;
+
+        engine
+            .subgraph::<FederatedProductsSchema>()
+            .force_next_response(http::StatusCode::INTERNAL_SERVER_ERROR);
+        engine.post(QUERY).await;
+
+        let response = engine.post(QUERY).await;
+        assert!(response.errors().is_empty());
+    })
+}
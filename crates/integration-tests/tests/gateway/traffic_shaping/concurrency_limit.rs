@@ -0,0 +1,125 @@
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use graphql_mocks::SlowSchema;
+use integration_tests::{
+    gateway::{Gateway, GraphqlResponse},
+    runtime,
+};
+
+async fn concurrent_requests(gateway: &Gateway, delays: &[u32]) -> Vec<GraphqlResponse> {
+    delays
+        .iter()
+        .map(|ms| async move { gateway.post(format!("query {{ delay(ms: {ms}) }}")).await })
+        .collect::<FuturesUnordered<_>>()
+        .collect::<Vec<_>>()
+        .await
+}
+
+fn failed_requests(responses: &[GraphqlResponse]) -> usize {
+    responses
+        .iter()
+        .filter(|response| !response.errors().is_empty())
+        .count()
+}
+
+#[test]
+fn requests_above_the_limit_are_rejected_without_queue() {
+    runtime().block_on(async move {
+        let gateway = Gateway::builder()
+            .with_subgraph(SlowSchema::default())
+            .with_toml_config(
+                r#"
+                [traffic_shaping.concurrency_limit]
+                enabled = true
+                max_in_flight = 1
+                "#,
+            )
+            .build()
+            .await;
+
+        let responses = concurrent_requests(&gateway, &[200, 201]).await;
+        assert_eq!(failed_requests(&responses), 1);
+
+        let error = responses
+            .iter()
+            .find_map(|response| response.errors().first().cloned())
+            .unwrap();
+        assert_eq!(error["extensions"]["code"], "SUBGRAPH_REQUEST_ERROR");
+        assert_eq!(gateway.drain_graphql_requests_sent_to::<SlowSchema>().len(), 1);
+
+        // The slot is released once the request completes.
+        let response = gateway.post("query { delay(ms: 1) }").await;
+        assert!(response.errors().is_empty());
+    })
+}
+
+#[test]
+fn requests_above_the_limit_are_queued() {
+    runtime().block_on(async move {
+        let gateway = Gateway::builder()
+            .with_subgraph(SlowSchema::default())
+            .with_toml_config(
+                r#"
+                [traffic_shaping.concurrency_limit]
+                enabled = true
+                max_in_flight = 1
+                queue_size = 5
+                queue_timeout = "5s"
+                "#,
+            )
+            .build()
+            .await;
+
+        let responses = concurrent_requests(&gateway, &[100, 101, 102]).await;
+        assert_eq!(failed_requests(&responses), 0);
+        assert_eq!(gateway.drain_graphql_requests_sent_to::<SlowSchema>().len(), 3);
+    })
+}
+
+#[test]
+fn queued_requests_time_out() {
+    runtime().block_on(async move {
+        let gateway = Gateway::builder()
+            .with_subgraph(SlowSchema::default())
+            .with_toml_config(
+                r#"
+                [traffic_shaping.concurrency_limit]
+                enabled = true
+                max_in_flight = 1
+                queue_size = 5
+                queue_timeout = "50ms"
+                "#,
+            )
+            .build()
+            .await;
+
+        let responses = concurrent_requests(&gateway, &[500, 501]).await;
+        assert_eq!(failed_requests(&responses), 1);
+        assert_eq!(gateway.drain_graphql_requests_sent_to::<SlowSchema>().len(), 1);
+    })
+}
+
+#[test]
+fn subgraph_specific_limit_overrides_global_one() {
+    runtime().block_on(async move {
+        let gateway = Gateway::builder()
+            .with_subgraph(SlowSchema::default())
+            .with_toml_config(
+                r#"
+                [traffic_shaping.concurrency_limit]
+                enabled = true
+                max_in_flight = 1
+
+                [subgraphs.slow.concurrency_limit]
+                enabled = true
+                max_in_flight = 10
+                mode = "aimd"
+                "#,
+            )
+            .build()
+            .await;
+
+        let responses = concurrent_requests(&gateway, &[100, 101, 102]).await;
+        assert_eq!(failed_requests(&responses), 0);
+    })
+}
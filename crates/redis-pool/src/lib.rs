@@ -0,0 +1,91 @@
+//! Pools of multiplexed Redis connections, used by the gateway for its caches and rate limiting,
+//! and by extensions through the Redis host IO.
+
+mod manager;
+
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+    time::Duration,
+};
+
+use anyhow::Context;
+use redis::ClientTlsConfig;
+
+pub use manager::Manager;
+
+pub type Pool = deadpool::managed::Pool<Manager>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RedisTlsConfig<'a> {
+    pub cert: Option<&'a Path>,
+    pub key: Option<&'a Path>,
+    pub ca: Option<&'a Path>,
+}
+
+/// Creates a pool for the given URL, with the certificates of the TLS configuration if any.
+pub fn new_pool(url: &str, tls_config: Option<RedisTlsConfig<'_>>) -> anyhow::Result<Pool> {
+    let tls_config = match tls_config {
+        Some(tls) => {
+            let client_tls = match tls.cert.zip(tls.key) {
+                Some((cert, key)) => {
+                    let mut client_cert = Vec::new();
+
+                    File::open(cert)
+                        .and_then(|file| BufReader::new(file).read_to_end(&mut client_cert))
+                        .context("loading the Redis client certificate")?;
+
+                    let mut client_key = Vec::new();
+
+                    File::open(key)
+                        .and_then(|file| BufReader::new(file).read_to_end(&mut client_key))
+                        .context("loading the Redis client key")?;
+
+                    Some(ClientTlsConfig {
+                        client_cert,
+                        client_key,
+                    })
+                }
+                None => None,
+            };
+
+            let root_cert = match tls.ca {
+                Some(path) => {
+                    let mut ca = Vec::new();
+
+                    File::open(path)
+                        .and_then(|file| BufReader::new(file).read_to_end(&mut ca))
+                        .context("loading the Redis CA certificate")?;
+
+                    Some(ca)
+                }
+                None => None,
+            };
+
+            Some(manager::TlsConfig { client_tls, root_cert })
+        }
+        None => None,
+    };
+
+    let manager = match Manager::new(url, tls_config) {
+        Ok(manager) => manager,
+        Err(e) => {
+            tracing::error!("error creating a Redis pool: {e}");
+            return Err(e.into());
+        }
+    };
+
+    match Pool::builder(manager)
+        .wait_timeout(Some(Duration::from_secs(5)))
+        .create_timeout(Some(Duration::from_secs(10)))
+        .runtime(deadpool::Runtime::Tokio1)
+        .build()
+    {
+        Ok(pool) => Ok(pool),
+        Err(e) => {
+            tracing::error!("error creating a Redis pool: {e}");
+            Err(e.into())
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use deadpool::managed::{self, Metrics};
-use redis::{Client, ClientTlsConfig, RedisError, RedisResult, TlsCertificates, aio::MultiplexedConnection};
+use redis::{
+    Client, ClientTlsConfig, RedisError, RedisResult, TlsCertificates,
+    aio::{MultiplexedConnection, PubSub},
+};
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[derive(Debug)]
@@ -8,13 +11,13 @@ pub struct Manager {
     ping_number: AtomicUsize,
 }
 
-pub(super) struct TlsConfig {
+pub(crate) struct TlsConfig {
     pub client_tls: Option<ClientTlsConfig>,
     pub root_cert: Option<Vec<u8>>,
 }
 
 impl Manager {
-    pub(super) fn new(url: &str, tls: Option<TlsConfig>) -> RedisResult<Self> {
+    pub(crate) fn new(url: &str, tls: Option<TlsConfig>) -> RedisResult<Self> {
         let client = match tls {
             Some(config) => Client::build_with_tls(
                 url,
@@ -31,6 +34,12 @@ impl Manager {
             ping_number: AtomicUsize::new(0),
         })
     }
+
+    /// Opens a dedicated connection for subscriptions, which cannot go through the multiplexed
+    /// connections of the pool.
+    pub async fn pubsub(&self) -> RedisResult<PubSub> {
+        self.client.get_async_pubsub().await
+    }
 }
 
 impl managed::Manager for Manager {
@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 use bytes::Bytes;
 use futures_util::{FutureExt, future::BoxFuture};
@@ -11,6 +11,7 @@ pub struct InMemoryEntityCache {
 #[derive(Clone)]
 struct CacheValue {
     data: Bytes,
+    tags: Arc<[String]>,
     expires_at: Instant,
 }
 
@@ -38,17 +39,36 @@ impl InMemoryEntityCache {
         &self,
         name: &str,
         bytes: std::borrow::Cow<'_, [u8]>,
+        tags: &[String],
         expiration_ttl: std::time::Duration,
     ) -> anyhow::Result<()> {
         self.inner.insert(
             name.to_string(),
             CacheValue {
                 data: bytes.into_owned().into(),
+                tags: tags.into(),
                 expires_at: Instant::now() + expiration_ttl,
             },
         );
         Ok(())
     }
+
+    async fn invalidate(&self, tags: &[String]) -> anyhow::Result<()> {
+        // Invalidations are rare and the cache is bounded, so we don't bother maintaining an index
+        // of the tags. Keys are collected first as we can't modify the cache while iterating over it.
+        let keys = self
+            .inner
+            .iter()
+            .filter(|entry| entry.value().tags.iter().any(|tag| tags.contains(tag)))
+            .map(|entry| entry.key().clone())
+            .collect::<Vec<_>>();
+
+        for key in keys {
+            self.inner.invalidate(&key);
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for InMemoryEntityCache {
@@ -88,9 +108,15 @@ impl runtime::entity_cache::EntityCache for InMemoryEntityCache {
         &'a self,
         name: &'a str,
         bytes: std::borrow::Cow<'a, [u8]>,
+        tags: &'a [String],
         expiration_ttl: std::time::Duration,
     ) -> BoxFuture<'a, anyhow::Result<()>> {
         let cache_span = tracing::info_span!("entity cache put");
-        Box::pin(self.put(name, bytes, expiration_ttl).instrument(cache_span))
+        Box::pin(self.put(name, bytes, tags, expiration_ttl).instrument(cache_span))
+    }
+
+    fn invalidate<'a>(&'a self, tags: &'a [String]) -> BoxFuture<'a, anyhow::Result<()>> {
+        let cache_span = tracing::info_span!("entity cache invalidate");
+        Box::pin(self.invalidate(tags).instrument(cache_span))
     }
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use bytes::Bytes;
 use deadpool::managed::Object;
 use futures_util::{FutureExt, future::BoxFuture};
@@ -6,6 +8,9 @@ use tracing::{Instrument, field::Empty};
 
 use crate::redis::{Manager, Pool};
 
+/// Entity cache stored in Redis 7.0 or later, tags rely on the `NX` and `GT` options of
+/// `PEXPIRE`. Each tag is a set of entry keys, removed by the client in batches of keys sharing
+/// the same hash slot to work with Redis Cluster.
 pub struct RedisEntityCache {
     pool: Pool,
     key_prefix: String,
@@ -28,17 +33,81 @@ impl RedisEntityCache {
         &self,
         name: &str,
         bytes: std::borrow::Cow<'_, [u8]>,
+        tags: &[String],
         expiration_ttl: std::time::Duration,
     ) -> anyhow::Result<()> {
         let mut connection = self.connection().await?;
+        let key = self.key(name);
         let options = SetOptions::default().with_expiration(self.expiry_time(expiration_ttl));
-        Ok(connection.set_options(self.key(name), bytes.as_ref(), options).await?)
+
+        if tags.is_empty() {
+            return Ok(connection.set_options(key, bytes.as_ref(), options).await?);
+        }
+
+        // Not a transaction as the keys may be in different hash slots on Redis Cluster. Tags are
+        // written first, so a stored entry is always tagged.
+        let mut pipe = redis::pipe();
+
+        // Each tag is a set of the keys to remove on invalidation. The set must live at least as
+        // long as the longest lived entry within it, so the expiry is only ever extended.
+        let ttl_ms = expiration_ttl.as_millis() as u64;
+        for tag in tags {
+            let tag_key = self.tag_key(tag);
+            pipe.sadd(&tag_key, &key).ignore();
+            pipe.cmd("PEXPIRE").arg(&tag_key).arg(ttl_ms).arg("NX").ignore();
+            pipe.cmd("PEXPIRE").arg(&tag_key).arg(ttl_ms).arg("GT").ignore();
+        }
+
+        pipe.set_options(&key, bytes.as_ref(), options).ignore();
+
+        Ok(pipe.query_async::<()>(&mut *connection).await?)
+    }
+
+    async fn invalidate(&self, tags: &[String]) -> anyhow::Result<()> {
+        let mut connection = self.connection().await?;
+
+        // Each tag set is read and deleted in its own transaction, so an entry added concurrently
+        // to the tag goes to a new set instead of being left behind. On Redis Cluster, commands
+        // and transactions fail if their keys aren't all in the same hash slot.
+        let mut pipe = redis::pipe();
+        for tag in tags {
+            let tag_key = self.tag_key(tag);
+            pipe.cmd("MULTI").ignore();
+            pipe.smembers(&tag_key).ignore();
+            pipe.del(&tag_key).ignore();
+            pipe.cmd("EXEC");
+        }
+        let transactions: Vec<(Vec<String>, u64)> = pipe.query_async(&mut *connection).await?;
+
+        let mut keys_by_slot = BTreeMap::<u16, Vec<String>>::new();
+        for (keys, _) in transactions {
+            for key in keys {
+                keys_by_slot.entry(cluster_slot(key.as_bytes())).or_default().push(key);
+            }
+        }
+
+        if keys_by_slot.is_empty() {
+            return Ok(());
+        }
+
+        let mut pipe = redis::pipe();
+        for keys in keys_by_slot.values() {
+            for chunk in keys.chunks(1000) {
+                pipe.del(chunk).ignore();
+            }
+        }
+
+        Ok(pipe.query_async::<()>(&mut *connection).await?)
     }
 
     fn key(&self, name: &str) -> String {
         format!("{}-{name}", self.key_prefix)
     }
 
+    fn tag_key(&self, tag: &str) -> String {
+        format!("{}-tag-{tag}", self.key_prefix)
+    }
+
     fn expiry_time(&self, duration: std::time::Duration) -> redis::SetExpiry {
         if duration.as_secs() > 60 {
             redis::SetExpiry::PX(duration.as_millis() as u64)
@@ -89,9 +158,62 @@ impl runtime::entity_cache::EntityCache for RedisEntityCache {
         &'a self,
         name: &'a str,
         bytes: std::borrow::Cow<'a, [u8]>,
+        tags: &'a [String],
         expiration_ttl: std::time::Duration,
     ) -> BoxFuture<'a, anyhow::Result<()>> {
         let cache_span = tracing::info_span!("entity cache put");
-        Box::pin(self.put(name, bytes, expiration_ttl).instrument(cache_span))
+        Box::pin(self.put(name, bytes, tags, expiration_ttl).instrument(cache_span))
+    }
+
+    fn invalidate<'a>(&'a self, tags: &'a [String]) -> BoxFuture<'a, anyhow::Result<()>> {
+        let cache_span = tracing::info_span!("entity cache invalidate");
+        Box::pin(self.invalidate(tags).instrument(cache_span))
+    }
+}
+
+/// Hash slot of a key in a Redis Cluster: the CRC16 (XMODEM) of its hash tag, the part between
+/// the first `{` and the next `}` if not empty, or of the whole key otherwise.
+fn cluster_slot(key: &[u8]) -> u16 {
+    let key = key
+        .iter()
+        .position(|&byte| byte == b'{')
+        .and_then(|start| {
+            let tag = &key[start + 1..];
+            let end = tag.iter().position(|&byte| byte == b'}')?;
+            (end > 0).then(|| &tag[..end])
+        })
+        .unwrap_or(key);
+
+    let mut crc: u16 = 0;
+    for &byte in key {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+
+    crc % 16384
+}
+
+#[cfg(test)]
+mod tests {
+    use super::cluster_slot;
+
+    #[test]
+    fn cluster_slots() {
+        // Values from the Redis Cluster specification and `CLUSTER KEYSLOT`.
+        assert_eq!(cluster_slot(b"123456789"), 0x31C3);
+        assert_eq!(cluster_slot(b"somekey"), 11058);
+        assert_eq!(cluster_slot(b"foo"), 12182);
+        assert_eq!(cluster_slot(b"{foo}.bar"), 12182);
+        assert_eq!(
+            cluster_slot(b"{user1000}.following"),
+            cluster_slot(b"{user1000}.followers")
+        );
+        assert_ne!(cluster_slot(b"{}foo"), cluster_slot(b"foo"));
     }
 }
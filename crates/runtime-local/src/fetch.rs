@@ -1,3 +1,4 @@
+mod concurrency_limit;
 mod signing;
 mod traffic_shaping;
 
@@ -20,7 +21,7 @@ use reqwest::{Certificate, Identity, RequestBuilder};
 use reqwest_eventsource::RequestBuilderExt;
 use runtime::fetch::{FetchError, FetchRequest, FetchResult, Fetcher, WebsocketRequest};
 
-use crate::fetch::traffic_shaping::TrafficShaping;
+use crate::fetch::{concurrency_limit::ConcurrencyLimits, traffic_shaping::TrafficShaping};
 
 const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(5);
 const ENABLE_HICKORY_DNS: bool = true;
@@ -30,6 +31,7 @@ pub struct NativeFetcherInner {
     signer: signing::RequestSigner,
     dedicated_clients: FxHashMap<GraphqlSubgraphId, reqwest::Client>,
     traffic_shaping: traffic_shaping::TrafficShaping,
+    concurrency_limits: ConcurrencyLimits,
 }
 
 #[derive(Clone)]
@@ -51,12 +53,18 @@ impl NativeFetcher {
             .collect::<RapidHashMap<_, _>>();
         let signer = signing::RequestSigner::new(config, &name_to_id)?;
         let dedicated_clients = generate_dedicated_http_clients(config, &name_to_id)?;
+        let concurrency_limits = ConcurrencyLimits::new(
+            config,
+            &name_to_id,
+            &grafbase_telemetry::metrics::meter_from_global_provider(),
+        );
 
         Ok(NativeFetcher(Arc::new(NativeFetcherInner {
             client: client_builder().build()?,
             signer,
             dedicated_clients,
             traffic_shaping: TrafficShaping::new(&config.traffic_shaping),
+            concurrency_limits,
         })))
     }
 }
@@ -156,7 +164,11 @@ impl Fetcher for NativeFetcher {
     ) -> (FetchResult<http::Response<Bytes>>, Option<SubgraphResponseBuilder>) {
         let FetchResponse { result, info } = self
             .traffic_shaping
-            .deduplicate(request, |request| async move { self.execute(request).await })
+            .deduplicate(request, |request| async move {
+                self.concurrency_limits
+                    .run(request.subgraph_id, self.execute(request))
+                    .await
+            })
             .await;
         (result, info)
     }
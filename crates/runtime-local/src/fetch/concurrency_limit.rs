@@ -0,0 +1,371 @@
+use std::{
+    collections::VecDeque,
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use engine_schema::GraphqlSubgraphId;
+use fxhash::FxHashMap;
+use gateway_config::{ConcurrencyLimitConfig, ConcurrencyLimitMode, Config};
+use grafbase_telemetry::otel::opentelemetry::{
+    KeyValue,
+    metrics::{Gauge, Meter},
+};
+use rapidhash::fast::RapidHashMap;
+use runtime::fetch::FetchError;
+use tokio::sync::oneshot;
+
+use crate::fetch::FetchResponse;
+
+/// Weight of each latency sample in the long-term average used by the gradient mode.
+const LONG_TERM_LATENCY_WEIGHT: f64 = 0.05;
+
+pub struct ConcurrencyLimits {
+    by_subgraph: FxHashMap<GraphqlSubgraphId, ConcurrencyLimiter>,
+}
+
+impl ConcurrencyLimits {
+    pub fn new(config: &Config, name_to_id: &RapidHashMap<&str, GraphqlSubgraphId>, meter: &Meter) -> Self {
+        let metrics = Metrics {
+            limit: meter.u64_gauge("graphql.subgraph.concurrency_limit.limit").build(),
+            queue_depth: meter
+                .u64_gauge("graphql.subgraph.concurrency_limit.queue_depth")
+                .build(),
+        };
+
+        let by_subgraph = name_to_id
+            .iter()
+            .filter_map(|(name, id)| {
+                let config = config
+                    .subgraphs
+                    .get(*name)
+                    .and_then(|subgraph| subgraph.concurrency_limit.as_ref())
+                    .unwrap_or(&config.traffic_shaping.concurrency_limit);
+
+                if !config.enabled {
+                    return None;
+                }
+
+                Some((*id, ConcurrencyLimiter::new(name, config, metrics.clone())))
+            })
+            .collect();
+
+        Self { by_subgraph }
+    }
+
+    /// Executes the request once a slot is available for the subgraph, or rejects it if the queue
+    /// is full or the queue timeout is reached.
+    pub async fn run<F>(&self, subgraph_id: GraphqlSubgraphId, f: F) -> FetchResponse
+    where
+        F: Future<Output = FetchResponse>,
+    {
+        let Some(limiter) = self.by_subgraph.get(&subgraph_id) else {
+            return f.await;
+        };
+
+        let permit = match limiter.acquire().await {
+            Ok(permit) => permit,
+            Err(error) => {
+                return FetchResponse {
+                    result: Err(error),
+                    info: None,
+                };
+            }
+        };
+
+        let response = f.await;
+
+        let success = response
+            .result
+            .as_ref()
+            .is_ok_and(|response| !response.status().is_server_error());
+        permit.complete(success);
+
+        response
+    }
+}
+
+#[derive(Clone)]
+struct Metrics {
+    limit: Gauge<u64>,
+    queue_depth: Gauge<u64>,
+}
+
+/// Limits the number of in-flight requests to a subgraph. Requests above the limit wait in a
+/// bounded queue and are given the slots of completed requests in order.
+///
+/// With an adaptive mode, the limit is adjusted after each request from its latency and outcome.
+struct ConcurrencyLimiter {
+    subgraph_name: String,
+    config: ConcurrencyLimitConfig,
+    metrics: Metrics,
+    attributes: [KeyValue; 1],
+    state: Mutex<State>,
+}
+
+struct State {
+    limit: f64,
+    in_flight: u32,
+    queue: VecDeque<oneshot::Sender<()>>,
+    long_term_latency: Option<f64>,
+}
+
+impl State {
+    fn has_free_slot(&self) -> bool {
+        (self.in_flight as f64) < self.limit.floor().max(1.0)
+    }
+}
+
+impl ConcurrencyLimiter {
+    fn new(subgraph_name: &str, config: &ConcurrencyLimitConfig, metrics: Metrics) -> Self {
+        let mut config = config.clone();
+        config.min_limit = config.min_limit.max(1);
+        config.max_limit = config.max_limit.max(config.min_limit);
+
+        let limit = match config.mode {
+            ConcurrencyLimitMode::Fixed => config.max_in_flight.max(1),
+            ConcurrencyLimitMode::Aimd | ConcurrencyLimitMode::Gradient => {
+                config.max_in_flight.clamp(config.min_limit, config.max_limit)
+            }
+        };
+
+        let limiter = Self {
+            subgraph_name: subgraph_name.to_string(),
+            config,
+            metrics,
+            attributes: [KeyValue::new("graphql.subgraph.name", subgraph_name.to_string())],
+            state: Mutex::new(State {
+                limit: limit as f64,
+                in_flight: 0,
+                queue: VecDeque::new(),
+                long_term_latency: None,
+            }),
+        };
+        limiter.metrics.limit.record(limit as u64, &limiter.attributes);
+        limiter.metrics.queue_depth.record(0, &limiter.attributes);
+
+        limiter
+    }
+
+    async fn acquire(&self) -> Result<Permit<'_>, FetchError> {
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+
+            if state.has_free_slot() {
+                state.in_flight += 1;
+                return Ok(Permit::new(self));
+            }
+
+            // Drop the requests which were cancelled or timed out while waiting.
+            state.queue.retain(|sender| !sender.is_closed());
+            if state.queue.len() >= self.config.queue_size as usize {
+                return Err(FetchError::ConcurrencyLimitRejected(format!(
+                    "Request to subgraph '{}' was rejected, its concurrency limit is reached",
+                    self.subgraph_name
+                )));
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            state.queue.push_back(sender);
+            self.record_queue_depth(&state);
+
+            receiver
+        };
+
+        let mut waiter = Waiter {
+            limiter: self,
+            receiver,
+        };
+
+        match tokio::time::timeout(self.config.queue_timeout, &mut waiter.receiver).await {
+            Ok(Ok(())) => return Ok(Permit::new(self)),
+            Ok(Err(_)) => (),
+            Err(_) => {
+                // A slot may have been handed over right as we timed out.
+                waiter.receiver.close();
+                if waiter.receiver.try_recv().is_ok() {
+                    return Ok(Permit::new(self));
+                }
+            }
+        }
+        drop(waiter);
+
+        let mut state = self.state.lock().unwrap();
+        state.queue.retain(|sender| !sender.is_closed());
+        self.record_queue_depth(&state);
+
+        Err(FetchError::ConcurrencyLimitRejected(format!(
+            "Request to subgraph '{}' timed out waiting for its concurrency limit",
+            self.subgraph_name
+        )))
+    }
+
+    fn release(&self, sample: Option<Sample>) {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(sample) = sample {
+            let previous_limit = state.limit.floor();
+            self.update_limit(&mut state, sample);
+            if state.limit.floor() != previous_limit {
+                self.metrics.limit.record(state.limit.floor() as u64, &self.attributes);
+            }
+        }
+
+        state.in_flight -= 1;
+        while state.has_free_slot() {
+            let Some(sender) = state.queue.pop_front() else {
+                break;
+            };
+            // Fails if the waiting request was cancelled or timed out. If it is cancelled after,
+            // its `Waiter` gives the slot back.
+            if sender.send(()).is_ok() {
+                state.in_flight += 1;
+            }
+        }
+
+        self.record_queue_depth(&state);
+    }
+
+    fn update_limit(&self, state: &mut State, Sample { latency, success }: Sample) {
+        let config = &self.config;
+        let limit = state.limit;
+        // Growing the limit only makes sense if it's actually used.
+        let is_saturated = (state.in_flight as f64) * 2.0 >= limit;
+
+        let new_limit = match config.mode {
+            ConcurrencyLimitMode::Fixed => return,
+            ConcurrencyLimitMode::Aimd => {
+                if !success || latency > config.latency_threshold {
+                    limit * config.backoff_ratio
+                } else if is_saturated {
+                    limit + 1.0
+                } else {
+                    return;
+                }
+            }
+            ConcurrencyLimitMode::Gradient => {
+                let latency = latency.as_secs_f64();
+                let long_term_latency = match state.long_term_latency {
+                    Some(average) => average + (latency - average) * LONG_TERM_LATENCY_WEIGHT,
+                    None => latency,
+                };
+                state.long_term_latency = Some(long_term_latency);
+
+                if success && !is_saturated {
+                    return;
+                }
+
+                let gradient = if success && latency > 0.0 {
+                    (long_term_latency / latency).clamp(0.5, 1.0)
+                } else {
+                    0.5
+                };
+                // Leaves some room for the limit to grow when the latency is stable.
+                let estimate = limit * gradient + limit.sqrt();
+                limit * (1.0 - config.smoothing) + estimate * config.smoothing
+            }
+        };
+
+        state.limit = new_limit.clamp(config.min_limit as f64, config.max_limit as f64);
+    }
+
+    fn record_queue_depth(&self, state: &State) {
+        self.metrics
+            .queue_depth
+            .record(state.queue.len() as u64, &self.attributes);
+    }
+}
+
+struct Sample {
+    latency: Duration,
+    success: bool,
+}
+
+/// Request waiting in the queue for a slot. If it's cancelled after a slot was handed over to it,
+/// the slot is released on drop.
+struct Waiter<'a> {
+    limiter: &'a ConcurrencyLimiter,
+    receiver: oneshot::Receiver<()>,
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        self.receiver.close();
+        if self.receiver.try_recv().is_ok() {
+            self.limiter.release(None);
+        }
+    }
+}
+
+/// Slot of an in-flight request, released on drop.
+struct Permit<'a> {
+    limiter: &'a ConcurrencyLimiter,
+    start: Instant,
+    sample: Option<Sample>,
+}
+
+impl<'a> Permit<'a> {
+    fn new(limiter: &'a ConcurrencyLimiter) -> Self {
+        Self {
+            limiter,
+            start: Instant::now(),
+            sample: None,
+        }
+    }
+
+    /// Releases the slot, taking the request outcome into account for the adaptive limit.
+    fn complete(mut self, success: bool) {
+        self.sample = Some(Sample {
+            latency: self.start.elapsed(),
+            success,
+        });
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.limiter.release(self.sample.take());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::FutureExt as _;
+    use grafbase_telemetry::otel::opentelemetry::global;
+
+    use super::*;
+
+    fn limiter(max_in_flight: u32) -> ConcurrencyLimiter {
+        let meter = global::meter("concurrency_limit_test");
+        let metrics = Metrics {
+            limit: meter.u64_gauge("limit").build(),
+            queue_depth: meter.u64_gauge("queue_depth").build(),
+        };
+        let config = ConcurrencyLimitConfig {
+            enabled: true,
+            max_in_flight,
+            queue_size: 1,
+            ..Default::default()
+        };
+
+        ConcurrencyLimiter::new("products", &config, metrics)
+    }
+
+    #[tokio::test]
+    async fn slot_handed_over_to_a_dropped_request_is_released() {
+        let limiter = limiter(1);
+
+        let permit = limiter.acquire().await.unwrap();
+        let mut queued = Box::pin(limiter.acquire());
+        assert!(futures_util::poll!(&mut queued).is_pending());
+
+        // The slot is handed over to the queued request, which is dropped before being polled.
+        drop(permit);
+        drop(queued);
+        assert_eq!(limiter.state.lock().unwrap().in_flight, 0);
+
+        let permit = limiter.acquire().now_or_never().expect("a slot should be free");
+        assert!(permit.is_ok());
+    }
+}
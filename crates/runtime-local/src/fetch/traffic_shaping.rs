@@ -29,7 +29,7 @@ impl TrafficShaping {
     where
         F: Future<Output = FetchResponse> + Send,
     {
-        if !self.config.inflight_deduplication || request.is_mutation {
+        if !self.config.inflight_deduplication || request.is_mutation || request.hedge {
             return f(request).await;
         }
         let key = Key(Arc::new(RequestKey::from(&request)));
@@ -188,6 +188,7 @@ mod tests {
             subgraph_id: subgraph_id.into(),
             method,
             is_mutation: false,
+            hedge: false,
             headers: {
                 let mut map = http::HeaderMap::new();
                 for (name, value) in &headers {
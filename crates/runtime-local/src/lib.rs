@@ -4,12 +4,16 @@ pub mod operation_cache;
 pub mod rate_limiting;
 #[cfg(feature = "redis")]
 pub mod redis;
+mod response_cache;
 
 pub use entity_cache::memory::InMemoryEntityCache;
 #[cfg(feature = "redis")]
 pub use entity_cache::redis::RedisEntityCache;
 pub use fetch::NativeFetcher;
 pub use operation_cache::InMemoryOperationCache;
+pub use response_cache::memory::InMemoryResponseCache;
+#[cfg(feature = "redis")]
+pub use response_cache::redis::RedisResponseCache;
 
 pub struct ExecutionContext {
     pub request_id: String,
@@ -1,3 +1,4 @@
 pub mod in_memory;
 #[cfg(feature = "redis")]
 pub mod redis;
+mod rules;
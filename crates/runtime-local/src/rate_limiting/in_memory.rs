@@ -1 +1,4 @@
+mod cost_budget;
 pub mod key_based;
+mod limiter;
+mod rules;
@@ -0,0 +1,114 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
+
+use gateway_config::{Config, CostBudgetConfig};
+use runtime::rate_limiting::Error;
+
+/// Clients are only pruned once there are this many of them.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Cost spent by each client within the configured window. Like the Redis rate limiter, it uses
+/// an averaging fixed window: the spent cost is the one of the current window added with the
+/// remaining percentage of the previous one.
+#[derive(Default)]
+pub(super) struct CostBudgets {
+    config: Option<CostBudgetConfig>,
+    windows: HashMap<String, Window>,
+}
+
+struct Window {
+    bucket: u64,
+    previous: u64,
+    current: u64,
+}
+
+impl CostBudgets {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            config: budget_config(config),
+            windows: HashMap::new(),
+        }
+    }
+
+    pub fn update(&mut self, config: &Config) {
+        let config = budget_config(config);
+
+        if config != self.config {
+            self.config = config;
+            self.windows.clear();
+        }
+    }
+
+    pub fn check(&self, client: &str) -> Result<(), Error> {
+        let Some(config) = &self.config else { return Ok(()) };
+        let Some(window) = self.windows.get(client) else {
+            return Ok(());
+        };
+
+        let (bucket, bucket_percentage) = current_bucket(config.duration);
+        let (previous, current) = if window.bucket == bucket {
+            (window.previous, window.current)
+        } else if window.bucket + 1 == bucket {
+            (window.current, 0)
+        } else {
+            (0, 0)
+        };
+
+        let spent = previous as f64 * (1.0 - bucket_percentage) + current as f64;
+
+        if spent < config.limit as f64 {
+            Ok(())
+        } else {
+            Err(Error::ExceededCapacity(None))
+        }
+    }
+
+    pub fn consume(&mut self, client: &str, amount: u64) {
+        let Some(config) = &self.config else { return };
+        let (bucket, _) = current_bucket(config.duration);
+
+        if self.windows.len() >= PRUNE_THRESHOLD {
+            self.windows.retain(|_, window| window.bucket + 1 >= bucket);
+        }
+
+        let window = self.windows.entry(client.to_string()).or_insert(Window {
+            bucket,
+            previous: 0,
+            current: 0,
+        });
+
+        if window.bucket + 1 == bucket {
+            window.previous = window.current;
+            window.current = 0;
+        } else if window.bucket != bucket {
+            window.previous = 0;
+            window.current = 0;
+        }
+
+        window.bucket = bucket;
+        window.current = window.current.saturating_add(amount);
+    }
+}
+
+fn budget_config(config: &Config) -> Option<CostBudgetConfig> {
+    config.complexity_control.budget.clone().filter(|budget| {
+        if budget.duration.is_zero() {
+            tracing::error!("the duration for the cost budget cannot be zero");
+            return false;
+        }
+        true
+    })
+}
+
+/// Returns the index of the current window and how much of it has elapsed.
+fn current_bucket(duration: Duration) -> (u64, f64) {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    let duration = duration.as_nanos() as u64;
+
+    (now / duration, (now % duration) as f64 / duration as f64)
+}
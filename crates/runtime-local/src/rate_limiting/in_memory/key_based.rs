@@ -1,19 +1,23 @@
-use std::num::NonZeroU32;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::{collections::HashMap, sync::RwLock};
 
 use futures_util::FutureExt;
 use futures_util::future::BoxFuture;
-use gateway_config::{Config, GraphRateLimit};
-use governor::Quota;
+use gateway_config::{Config, GraphRateLimit, RateLimitAlgorithm};
 
-use runtime::rate_limiting::{Error, RateLimitKey, RateLimiter, RateLimiterContext};
+use runtime::rate_limiting::{Error, RateLimitKey, RateLimitState, RateLimiter, RateLimiterContext};
 use tokio::sync::watch;
 
-type Limiters = HashMap<RateLimitKey<'static>, governor::DefaultKeyedRateLimiter<usize>>;
+use super::cost_budget::CostBudgets;
+use super::limiter::Limiter;
+use super::rules::RuleLimiter;
+
+type Limiters = HashMap<RateLimitKey<'static>, Limiter<()>>;
 
 pub struct InMemoryRateLimiter {
     limiters: Arc<RwLock<Limiters>>,
+    cost_budgets: Arc<Mutex<CostBudgets>>,
+    rules: Arc<RwLock<Vec<RuleLimiter>>>,
 }
 
 /// Load the rate limit configuration for global and subgraph level settings.
@@ -33,13 +37,25 @@ pub fn as_keyed_rate_limit_config(config: &Config) -> HashMap<RateLimitKey<'stat
     key_based_config
 }
 
+pub(super) fn rate_limit_algorithm(config: &Config) -> RateLimitAlgorithm {
+    config
+        .gateway
+        .rate_limit
+        .as_ref()
+        .map(|rate_limit| rate_limit.algorithm)
+        .unwrap_or_default()
+}
+
 impl InMemoryRateLimiter {
-    pub fn runtime(rate_limiting_configs: HashMap<RateLimitKey<'static>, GraphRateLimit>) -> RateLimiter {
+    pub fn runtime(
+        algorithm: RateLimitAlgorithm,
+        rate_limiting_configs: HashMap<RateLimitKey<'static>, GraphRateLimit>,
+    ) -> RateLimiter {
         let mut limiters = HashMap::new();
 
         // add subgraph rate limiting configuration
         for (key, limits) in rate_limiting_configs {
-            let Some(limiter) = create_limiter(limits) else {
+            let Some(limiter) = Limiter::new(algorithm, limits) else {
                 continue;
             };
 
@@ -47,24 +63,22 @@ impl InMemoryRateLimiter {
         }
 
         let limiters = Arc::new(RwLock::new(limiters));
-        RateLimiter::new(Self { limiters })
+        RateLimiter::new(Self {
+            limiters,
+            cost_budgets: Default::default(),
+            rules: Default::default(),
+        })
     }
 
     pub fn runtime_with_watcher(mut config: watch::Receiver<Config>) -> RateLimiter {
-        let mut limiters = HashMap::new();
-        let rate_limiting_configs = as_keyed_rate_limit_config(&config.borrow());
-
-        // add subgraph rate limiting configuration
-        for (key, limits) in rate_limiting_configs {
-            let Some(limiter) = create_limiter(limits) else {
-                continue;
-            };
+        let limiters = Arc::new(RwLock::new(create_limiters(&config.borrow())));
+        let limiters_copy = Arc::downgrade(&limiters);
 
-            limiters.insert(key.clone(), limiter);
-        }
+        let cost_budgets = Arc::new(Mutex::new(CostBudgets::new(&config.borrow())));
+        let cost_budgets_copy = Arc::downgrade(&cost_budgets);
 
-        let limiters = Arc::new(RwLock::new(limiters));
-        let limiters_copy = Arc::downgrade(&limiters);
+        let rules = Arc::new(RwLock::new(RuleLimiter::from_config(&config.borrow())));
+        let rules_copy = Arc::downgrade(&rules);
 
         tokio::spawn(async move {
             while let Ok(()) = config.changed().await {
@@ -72,52 +86,141 @@ impl InMemoryRateLimiter {
                     break;
                 };
 
-                let mut limiters = limiters.write().unwrap();
-                limiters.clear();
-
-                let rate_limiting_configs = as_keyed_rate_limit_config(&config.borrow());
-                for (key, limits) in rate_limiting_configs {
-                    let Some(limiter) = create_limiter(limits) else {
-                        continue;
-                    };
+                if let Some(cost_budgets) = cost_budgets_copy.upgrade() {
+                    cost_budgets.lock().unwrap().update(&config.borrow());
+                }
 
-                    limiters.insert(key, limiter);
+                if let Some(rules) = rules_copy.upgrade() {
+                    *rules.write().unwrap() = RuleLimiter::from_config(&config.borrow());
                 }
+
+                *limiters.write().unwrap() = create_limiters(&config.borrow());
             }
         });
 
-        RateLimiter::new(Self { limiters })
+        RateLimiter::new(Self {
+            limiters,
+            cost_budgets,
+            rules,
+        })
     }
 }
 
-fn create_limiter(rate_limit_config: GraphRateLimit) -> Option<governor::DefaultKeyedRateLimiter<usize>> {
-    let Some(quota) = (rate_limit_config.limit as u64).checked_div(rate_limit_config.duration.as_secs()) else {
-        tracing::error!("the duration for rate limit cannot be zero");
-        return None;
-    };
-
-    let Some(quota) = NonZeroU32::new(quota as u32) else {
-        tracing::error!("the limit is too low per defined duration");
-        return None;
-    };
+fn create_limiters(config: &Config) -> Limiters {
+    let algorithm = rate_limit_algorithm(config);
 
-    Some(governor::RateLimiter::keyed(Quota::per_second(quota)))
+    as_keyed_rate_limit_config(config)
+        .into_iter()
+        .filter_map(|(key, limits)| Some((key, Limiter::new(algorithm, limits)?)))
+        .collect()
 }
 
 impl runtime::rate_limiting::RateLimiterInner for InMemoryRateLimiter {
-    fn limit<'a>(&'a self, context: &'a dyn RateLimiterContext) -> BoxFuture<'a, Result<(), Error>> {
+    fn limit<'a>(
+        &'a self,
+        context: &'a dyn RateLimiterContext,
+    ) -> BoxFuture<'a, Result<Option<RateLimitState>, Error>> {
         async {
-            let Some(key) = context.key() else { return Ok(()) };
-            let limiters = self.limiters.read().unwrap();
+            // Without a key, the request is checked against the configured rules.
+            let Some(key) = context.key() else {
+                let rules = self.rules.read().unwrap();
+                let matching = rules
+                    .iter()
+                    .filter_map(|rule| Some((rule, rule.value(context)?)))
+                    .collect::<Vec<_>>();
+
+                // The request is only counted once every rule allows it.
+                for (rule, value) in &matching {
+                    rule.limiter.peek(value)?;
+                }
+
+                let mut most_restrictive: Option<RateLimitState> = None;
 
-            if let Some(rate_limiter) = limiters.get(key) {
-                rate_limiter
-                    .check_key(&usize::MIN)
-                    .map_err(|_err| Error::ExceededCapacity)?;
+                for (rule, value) in &matching {
+                    let state = rule.limiter.check(value)?;
+                    most_restrictive = Some(most_restrictive.map_or(state, |current| current.most_restrictive(state)));
+                }
+
+                return Ok(most_restrictive);
             };
 
+            if let RateLimitKey::CostBudget(client) = key {
+                return self.cost_budgets.lock().unwrap().check(client).map(|_| None);
+            }
+
+            let limiters = self.limiters.read().unwrap();
+
+            match limiters.get(key) {
+                Some(limiter) => limiter.check(&()).map(Some),
+                None => Ok(None),
+            }
+        }
+        .boxed()
+    }
+
+    fn consume<'a>(&'a self, key: &'a RateLimitKey<'a>, amount: u64) -> BoxFuture<'a, Result<(), Error>> {
+        async move {
+            if let RateLimitKey::CostBudget(client) = key {
+                self.cost_budgets.lock().unwrap().consume(client, amount);
+            }
+
             Ok(())
         }
         .boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures_util::FutureExt;
+    use gateway_config::{GraphRateLimit, RateLimitAlgorithm};
+    use runtime::rate_limiting::{Error, RateLimitKey, RateLimiter};
+
+    use super::InMemoryRateLimiter;
+
+    fn global_limiter(algorithm: RateLimitAlgorithm, limit: usize, burst: Option<usize>) -> RateLimiter {
+        let config = GraphRateLimit {
+            limit,
+            duration: Duration::from_secs(60),
+            burst,
+        };
+
+        InMemoryRateLimiter::runtime(algorithm, [(RateLimitKey::Global, config)].into_iter().collect())
+    }
+
+    /// Number of requests allowed in a row and the limit reported for them.
+    fn allowed_requests(limiter: &RateLimiter) -> (usize, u64) {
+        let mut limit = 0;
+
+        for allowed in 0.. {
+            match limiter.limit(&RateLimitKey::Global).now_or_never().unwrap() {
+                Ok(state) => limit = state.unwrap().limit,
+                Err(Error::ExceededCapacity(_)) => return (allowed, limit),
+                Err(error) => panic!("unexpected error: {error:?}"),
+            }
+        }
+
+        unreachable!()
+    }
+
+    #[test]
+    fn sliding_window() {
+        // Enforced as a rate per second in memory.
+        let limiter = global_limiter(RateLimitAlgorithm::SlidingWindow, 120, None);
+        assert_eq!(allowed_requests(&limiter), (2, 2));
+    }
+
+    #[test]
+    fn token_bucket() {
+        let limiter = global_limiter(RateLimitAlgorithm::TokenBucket, 1, Some(3));
+        assert_eq!(allowed_requests(&limiter), (3, 3));
+    }
+
+    #[test]
+    fn gcra() {
+        let limiter = global_limiter(RateLimitAlgorithm::Gcra, 1, Some(2));
+        assert_eq!(allowed_requests(&limiter), (2, 2));
+    }
+}
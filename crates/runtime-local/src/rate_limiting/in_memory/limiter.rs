@@ -0,0 +1,217 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use gateway_config::{GraphRateLimit, RateLimitAlgorithm};
+use runtime::rate_limiting::{Error, RateLimitState};
+
+/// Keys are only pruned once there are this many of them.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// A limit with a separate state for each key.
+pub(super) enum Limiter<K> {
+    Gcra(Gcra<K>),
+    TokenBucket(TokenBuckets<K>),
+}
+
+impl<K> Limiter<K>
+where
+    K: Clone + Eq + Hash,
+{
+    pub fn new(algorithm: RateLimitAlgorithm, config: GraphRateLimit) -> Option<Self> {
+        match algorithm {
+            RateLimitAlgorithm::SlidingWindow => {
+                let Some(quota) = (config.limit as u64).checked_div(config.duration.as_secs()) else {
+                    tracing::error!("the duration for rate limit cannot be zero");
+                    return None;
+                };
+
+                if quota == 0 || quota > u32::MAX as u64 {
+                    tracing::error!("the limit is too low per defined duration");
+                    return None;
+                }
+
+                // Enforced as a rate per second, allowing the whole second's worth at once.
+                Some(Self::Gcra(Gcra::new(
+                    Duration::from_secs(1) / quota as u32,
+                    quota as u32,
+                )))
+            }
+            RateLimitAlgorithm::Gcra => {
+                let Some(interval) = u32::try_from(config.limit)
+                    .ok()
+                    .filter(|limit| *limit > 0)
+                    .map(|limit| config.duration / limit)
+                    .filter(|interval| !interval.is_zero())
+                else {
+                    tracing::error!("the limit is too low per defined duration");
+                    return None;
+                };
+
+                let Some(burst) = u32::try_from(config.burst.unwrap_or(1)).ok().filter(|burst| *burst > 0) else {
+                    tracing::error!("the burst for rate limit cannot be zero");
+                    return None;
+                };
+
+                Some(Self::Gcra(Gcra::new(interval, burst)))
+            }
+            RateLimitAlgorithm::TokenBucket => TokenBuckets::new(config).map(Self::TokenBucket),
+        }
+    }
+
+    /// Counts the request against the limit of the key if it's allowed.
+    pub fn check(&self, key: &K) -> Result<RateLimitState, Error> {
+        match self {
+            Self::Gcra(gcra) => gcra.check(key, true),
+            Self::TokenBucket(buckets) => buckets.check(key, true),
+        }
+    }
+
+    /// Whether the request would be allowed, without counting it.
+    pub fn peek(&self, key: &K) -> Result<RateLimitState, Error> {
+        match self {
+            Self::Gcra(gcra) => gcra.check(key, false),
+            Self::TokenBucket(buckets) => buckets.check(key, false),
+        }
+    }
+}
+
+/// Generic cell rate algorithm, tracking the theoretical arrival time of the next request for
+/// each key. Requests are allowed as long as they don't arrive earlier than the burst allows,
+/// which is reported as the limit since the remaining requests are relative to it.
+pub(super) struct Gcra<K> {
+    /// Time between two requests at the sustained rate.
+    interval: Duration,
+    burst: u32,
+    arrival_times: Mutex<HashMap<K, Instant>>,
+}
+
+impl<K> Gcra<K>
+where
+    K: Clone + Eq + Hash,
+{
+    fn new(interval: Duration, burst: u32) -> Self {
+        Self {
+            interval,
+            burst,
+            arrival_times: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn check(&self, key: &K, consume: bool) -> Result<RateLimitState, Error> {
+        let now = Instant::now();
+        let tolerance = self.interval * (self.burst - 1);
+        let mut arrival_times = self.arrival_times.lock().unwrap();
+
+        if arrival_times.len() >= PRUNE_THRESHOLD {
+            // Arrival times in the past are equivalent to missing ones.
+            arrival_times.retain(|_, tat| *tat > now);
+        }
+
+        let tat = arrival_times.get(key).copied().unwrap_or(now).max(now);
+        let allowed_at = tat - tolerance.min(tat - now);
+
+        if allowed_at > now {
+            return Err(Error::ExceededCapacity(Some(RateLimitState {
+                limit: self.burst as u64,
+                remaining: 0,
+                reset: allowed_at - now,
+            })));
+        }
+
+        let new_tat = tat + self.interval;
+        if consume {
+            arrival_times.insert(key.clone(), new_tat);
+        }
+
+        let used = (new_tat - now).as_secs_f64() / self.interval.as_secs_f64();
+
+        Ok(RateLimitState {
+            limit: self.burst as u64,
+            remaining: (self.burst as f64 - used.ceil()).max(0.0) as u64,
+            reset: new_tat - now,
+        })
+    }
+}
+
+/// Token buckets refilled continuously at the configured rate, up to the burst capacity which is
+/// reported as the limit.
+pub(super) struct TokenBuckets<K> {
+    capacity: f64,
+    /// Time to refill a single token.
+    interval: Duration,
+    buckets: Mutex<HashMap<K, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl<K> TokenBuckets<K>
+where
+    K: Clone + Eq + Hash,
+{
+    fn new(config: GraphRateLimit) -> Option<Self> {
+        let capacity = config.burst.unwrap_or(config.limit);
+
+        if config.limit == 0 || capacity == 0 {
+            tracing::error!("the limit and burst for rate limit cannot be zero");
+            return None;
+        }
+
+        Some(Self {
+            capacity: capacity as f64,
+            interval: config.duration.div_f64(config.limit as f64),
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn tokens_at(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let refilled = now.duration_since(bucket.updated_at).as_secs_f64() / self.interval.as_secs_f64();
+        (bucket.tokens + refilled).min(self.capacity)
+    }
+
+    fn check(&self, key: &K, consume: bool) -> Result<RateLimitState, Error> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= PRUNE_THRESHOLD {
+            // Full buckets are equivalent to missing ones.
+            buckets.retain(|_, bucket| self.tokens_at(bucket, now) < self.capacity);
+        }
+
+        let tokens = match buckets.get(key) {
+            Some(bucket) => self.tokens_at(bucket, now),
+            None => self.capacity,
+        };
+
+        if tokens < 1.0 {
+            return Err(Error::ExceededCapacity(Some(RateLimitState {
+                limit: self.capacity as u64,
+                remaining: 0,
+                reset: self.interval.mul_f64(1.0 - tokens),
+            })));
+        }
+
+        let tokens = tokens - 1.0;
+        if consume {
+            buckets.insert(
+                key.clone(),
+                Bucket {
+                    tokens,
+                    updated_at: now,
+                },
+            );
+        }
+
+        Ok(RateLimitState {
+            limit: self.capacity as u64,
+            remaining: tokens as u64,
+            reset: self.interval.mul_f64(self.capacity - tokens),
+        })
+    }
+}
@@ -0,0 +1,40 @@
+use gateway_config::{Config, RateLimitRuleKey};
+use runtime::rate_limiting::RateLimiterContext;
+
+use super::key_based::rate_limit_algorithm;
+use super::limiter::Limiter;
+use crate::rate_limiting::rules::rule_value;
+
+/// Limiter of a rate limit rule, keeping one bucket per distinct value of the rule key.
+pub(super) struct RuleLimiter {
+    key: RateLimitRuleKey,
+    pub limiter: Limiter<String>,
+}
+
+impl RuleLimiter {
+    pub fn from_config(config: &Config) -> Vec<Self> {
+        let Some(rate_limit) = config.gateway.rate_limit.as_ref() else {
+            return Vec::new();
+        };
+
+        let algorithm = rate_limit_algorithm(config);
+
+        rate_limit
+            .rules
+            .iter()
+            .filter_map(|rule| {
+                let limiter = Limiter::new(algorithm, rule.as_graph_rate_limit())?;
+
+                Some(Self {
+                    key: rule.key.clone(),
+                    limiter,
+                })
+            })
+            .collect()
+    }
+
+    /// Value of the rule key for the request, requests without one aren't limited by the rule.
+    pub fn value(&self, context: &dyn RateLimiterContext) -> Option<String> {
+        rule_value(&self.key, context)
+    }
+}
@@ -1,15 +1,19 @@
-use std::time::{Duration, SystemTime};
+use std::{
+    sync::LazyLock,
+    time::{Duration, SystemTime},
+};
 
 use futures_util::future::BoxFuture;
-use gateway_config::{Config, GraphRateLimit};
+use gateway_config::{Config, GraphRateLimit, RateLimitAlgorithm};
 use grafbase_telemetry::otel::opentelemetry::{
     KeyValue,
     metrics::{Histogram, Meter},
 };
-use runtime::rate_limiting::{Error, RateLimitKey, RateLimiter, RateLimiterContext};
+use runtime::rate_limiting::{Error, RateLimitKey, RateLimitState, RateLimiter, RateLimiterContext};
 use tokio::sync::watch;
 use tracing::{Instrument, field::Empty};
 
+use super::rules::rule_value;
 use crate::redis::Pool;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -29,6 +33,10 @@ pub struct RateLimitRedisConfig<'a> {
 /// The reason for this algorithm is that it can be done without locks and with one roundtrip to
 /// redis. This gives us the fastest throughput and latency.
 ///
+/// Each algorithm is implemented as a Lua script checking all the limits applying to a request,
+/// for example several rules, and only counting the request if all of them allow it. Checking and
+/// counting are atomic and require a single roundtrip.
+///
 /// A request must have a unique access to a connection, which means utilizing a connection
 /// pool.
 pub struct RedisRateLimiter {
@@ -78,32 +86,129 @@ impl RedisRateLimiter {
         })
     }
 
-    fn generate_key(&self, bucket: u64, key: &RateLimitKey<'_>) -> String {
+    /// Prefix of the counter keys, the window bucket being appended to it.
+    fn key_base(&self, key: &RateLimitKey<'_>) -> String {
         match key {
             RateLimitKey::Global => {
-                format!("{}:rate_limit:global:{bucket}", self.key_prefix)
+                format!("{}:rate_limit:global", self.key_prefix)
             }
             RateLimitKey::Subgraph(graph) => {
-                format!("{}:subgraph:rate_limit:{graph}:{bucket}", self.key_prefix)
+                format!("{}:subgraph:rate_limit:{graph}", self.key_prefix)
+            }
+            RateLimitKey::CostBudget(client) => {
+                format!("{}:cost_budget:{client}", self.key_prefix)
             }
         }
     }
 
-    fn record_duration(&self, duration: Duration, status: RedisStatus) {
-        let attributes = vec![KeyValue::new("grafbase.redis.status", status.as_str())];
-        self.latencies.record(duration.as_millis() as u64, &attributes);
+    /// The rules applying to the request, with the base of their counter keys.
+    fn matching_rules(&self, context: &dyn RateLimiterContext) -> Vec<(String, GraphRateLimit)> {
+        let config = self.config_watcher.borrow();
+        let Some(rate_limit) = config.gateway.rate_limit.as_ref() else {
+            return Vec::new();
+        };
+
+        rate_limit
+            .rules
+            .iter()
+            .filter_map(|rule| {
+                let value = rule_value(&rule.key, context)?;
+                let base = format!("{}:rate_limit:rule:{}:{value}", self.key_prefix, rule.name);
+
+                Some((base, rule.as_graph_rate_limit()))
+            })
+            .collect()
     }
 
-    async fn limit_inner(&self, key: &RateLimitKey<'_>, config: GraphRateLimit) -> Result<(), Error> {
-        let now = SystemTime::now();
+    fn cost_budget_config(&self) -> Option<GraphRateLimit> {
+        self.config_watcher
+            .borrow()
+            .complexity_control
+            .budget
+            .as_ref()
+            .filter(|budget| !budget.duration.is_zero())
+            .map(|budget| GraphRateLimit {
+                limit: budget.limit,
+                duration: budget.duration,
+                burst: None,
+            })
+    }
 
-        let current_ts = match now.duration_since(SystemTime::UNIX_EPOCH) {
-            Ok(ts) => ts.as_nanos() as u64,
-            Err(error) => {
-                tracing::error!("error with rate limit duration: {error}");
-                return Err(Error::Internal(String::from("rate limit")));
+    fn algorithm(&self) -> RateLimitAlgorithm {
+        self.config_watcher
+            .borrow()
+            .gateway
+            .rate_limit
+            .as_ref()
+            .map(|rate_limit| rate_limit.algorithm)
+            .unwrap_or_default()
+    }
+
+    /// Checks the request against all the limits in a single script, counting it in all of them
+    /// only if every limit allows it and `consume` is set. Returns the most restrictive state, or
+    /// the state of the first exceeded limit.
+    async fn check(
+        &self,
+        limits: &[(String, GraphRateLimit)],
+        algorithm: RateLimitAlgorithm,
+        consume: bool,
+    ) -> Result<RateLimitState, Error> {
+        let mut invocation;
+        // Limit reported for each of them and the time until reset if known before running the script.
+        let mut expected = Vec::with_capacity(limits.len());
+
+        match algorithm {
+            RateLimitAlgorithm::SlidingWindow => {
+                let current_ts = current_timestamp()?;
+                invocation = SLIDING_WINDOW.prepare_invoke();
+                invocation.arg(consume as u64);
+
+                for (key_base, config) in limits {
+                    let duration_ns = config.duration.as_nanos() as u64;
+                    let current_bucket = current_ts - current_ts % duration_ns;
+                    let previous_bucket = current_bucket - duration_ns;
+
+                    invocation
+                        .key(format!("{key_base}:{previous_bucket}"))
+                        .key(format!("{key_base}:{current_bucket}"))
+                        .arg(config.limit)
+                        .arg((current_ts % duration_ns) as f64 / duration_ns as f64)
+                        .arg((config.duration.as_secs() * 2).max(1));
+
+                    // The count may already be lower before that, but we can only be sure of it
+                    // once the current window ends.
+                    let reset = Duration::from_nanos(duration_ns - current_ts % duration_ns);
+                    expected.push((config.limit as u64, Some(reset)));
+                }
             }
-        };
+            RateLimitAlgorithm::TokenBucket => {
+                invocation = TOKEN_BUCKET.prepare_invoke();
+                invocation.arg(consume as u8);
+
+                for (key_base, config) in limits {
+                    let capacity = config.burst.unwrap_or(config.limit);
+                    let interval_us = config.duration.as_micros() as f64 / config.limit.max(1) as f64;
+
+                    invocation
+                        .key(format!("{key_base}:token_bucket"))
+                        .arg(capacity)
+                        .arg(interval_us);
+                    expected.push((capacity as u64, None));
+                }
+            }
+            RateLimitAlgorithm::Gcra => {
+                invocation = GCRA.prepare_invoke();
+                invocation.arg(consume as u8);
+
+                for (key_base, config) in limits {
+                    let burst = config.burst.unwrap_or(1);
+                    let interval_us = config.duration.as_micros() as f64 / config.limit.max(1) as f64;
+
+                    invocation.key(format!("{key_base}:gcra")).arg(burst).arg(interval_us);
+                    expected.push((burst as u64, None));
+                }
+            }
+        }
 
         let mut conn = match self.pool.get().await {
             Ok(conn) => conn,
@@ -113,60 +218,197 @@ impl RedisRateLimiter {
             }
         };
 
-        let duration_ns = config.duration.as_nanos() as u64;
-        let current_bucket = current_ts - current_ts % duration_ns;
-        let previous_bucket = current_bucket - duration_ns;
-
-        let bucket_percentage = (current_ts % duration_ns) as f64 / duration_ns as f64;
-
-        // The counter key for the current window.
-        let current_bucket = self.generate_key(current_bucket, key);
-        // The counter key for the previous window.
-        let previous_bucket = self.generate_key(previous_bucket, key);
-
-        // We execute multiple commands in one pipelined query to be _fast_.
-        let mut pipe = redis::pipe();
-
-        // Marks the start of an atomic block. The get and incr are guaranteed to run atomically.
-        pipe.atomic();
-
-        pipe.cmd("GET").arg(&previous_bucket);
-        pipe.cmd("GET").arg(&current_bucket);
-
         let start = SystemTime::now();
-        let result = pipe.query_async::<(Option<u64>, Option<u64>)>(&mut *conn).await;
+        let result = invocation.invoke_async::<Vec<u64>>(&mut *conn).await;
         let duration = SystemTime::now().duration_since(start).unwrap_or_default();
 
-        // Execute the whole pipeline in one multiplexed request.
-        match result {
-            Ok((previous_count, current_count)) => {
+        let values = match result {
+            Ok(values) => {
                 self.record_duration(duration, RedisStatus::Success);
-
-                let previous_count = previous_count.unwrap_or_default().min(config.limit as u64);
-                let current_count = current_count.unwrap_or_default().min(config.limit as u64);
-
-                // Sum is a percentage what is left from the previous window, and the count of the
-                // current window.
-                let average = previous_count as f64 * (1.0 - bucket_percentage) + current_count as f64;
-
-                if average < config.limit as f64 {
-                    tokio::spawn(incr_counter(self.pool.clone(), current_bucket, config.duration));
-
-                    Ok(())
-                } else {
-                    Err(Error::ExceededCapacity)
-                }
+                values
             }
             Err(e) => {
                 self.record_duration(duration, RedisStatus::Error);
                 tracing::error!("error with Redis query: {e}");
-                Err(Error::Internal(String::from("rate limit")))
+                return Err(Error::Internal(String::from("rate limit")));
             }
+        };
+
+        // The sliding window script returns whether the request is allowed and the remaining
+        // requests for each limit, the others the time until reset in microseconds as well.
+        let width = values.len() / expected.len().max(1);
+        let mut most_restrictive: Option<RateLimitState> = None;
+
+        for ((limit, reset), values) in expected.into_iter().zip(values.chunks(width)) {
+            let state = RateLimitState {
+                limit,
+                remaining: values[1],
+                reset: reset.unwrap_or_else(|| Duration::from_micros(values.get(2).copied().unwrap_or_default())),
+            };
+
+            if values[0] != 1 {
+                return Err(Error::ExceededCapacity(Some(state)));
+            }
+
+            most_restrictive = Some(most_restrictive.map_or(state, |current| current.most_restrictive(state)));
+        }
+
+        most_restrictive.ok_or_else(|| Error::Internal(String::from("rate limit")))
+    }
+
+    fn record_duration(&self, duration: Duration, status: RedisStatus) {
+        let attributes = vec![KeyValue::new("grafbase.redis.status", status.as_str())];
+        self.latencies.record(duration.as_millis() as u64, &attributes);
+    }
+}
+
+/// Estimates the count of each limit from the counters of the current and previous windows, the
+/// latter weighted by what's left of it. Keys are both counters of each limit, arguments the
+/// increment and for each limit its value, the elapsed fraction of the current window and the
+/// expiry of the counters in seconds. Counters are only incremented if every limit allows it.
+static SLIDING_WINDOW: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+        local increment = tonumber(ARGV[1])
+        local results = {}
+        local allowed = 1
+
+        for i = 1, #KEYS / 2 do
+            local limit = tonumber(ARGV[i * 3 - 1])
+            local elapsed = tonumber(ARGV[i * 3])
+            local previous = math.min(tonumber(redis.call('GET', KEYS[i * 2 - 1])) or 0, limit)
+            local current = math.min(tonumber(redis.call('GET', KEYS[i * 2])) or 0, limit)
+            local average = previous * (1 - elapsed) + current
+
+            if average >= limit then
+                allowed = 0
+                results[#results + 1] = 0
+            else
+                results[#results + 1] = 1
+            end
+            results[#results + 1] = math.max(0, limit - math.ceil(average) - increment)
+        end
+
+        if allowed == 1 and increment > 0 then
+            for i = 1, #KEYS / 2 do
+                redis.call('INCRBY', KEYS[i * 2], increment)
+                redis.call('EXPIRE', KEYS[i * 2], tonumber(ARGV[i * 3 + 1]))
+            end
+        end
+
+        return results
+        "#,
+    )
+});
+
+/// Refills each bucket from the time elapsed since its last update before taking a token from
+/// it. Arguments are whether to update the buckets and for each of them its capacity and the time
+/// to refill a token in microseconds. Buckets are only updated if every one of them has a token.
+/// Time is read from the Redis server so that gateway clocks don't need to agree.
+static TOKEN_BUCKET: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+        local consume = ARGV[1] == '1'
+        local time = redis.call('TIME')
+        local now = tonumber(time[1]) * 1000000 + tonumber(time[2])
+        local results = {}
+        local updates = {}
+        local allowed = 1
+
+        for i, key in ipairs(KEYS) do
+            local capacity = tonumber(ARGV[i * 2])
+            local interval = tonumber(ARGV[i * 2 + 1])
+
+            local state = redis.call('HMGET', key, 'tokens', 'updated_at')
+            local tokens = tonumber(state[1]) or capacity
+            local updated_at = tonumber(state[2]) or now
+            tokens = math.min(capacity, tokens + math.max(0, now - updated_at) / interval)
+
+            local reset
+            if tokens >= 1 then
+                tokens = tokens - 1
+                reset = (capacity - tokens) * interval
+                results[#results + 1] = 1
+            else
+                allowed = 0
+                reset = (1 - tokens) * interval
+                results[#results + 1] = 0
+            end
+            results[#results + 1] = math.floor(tokens)
+            results[#results + 1] = math.ceil(reset)
+            updates[i] = {tokens, math.ceil((capacity - tokens) * interval / 1000) + 1000}
+        end
+
+        if consume and allowed == 1 then
+            for i, key in ipairs(KEYS) do
+                redis.call('HSET', key, 'tokens', tostring(updates[i][1]), 'updated_at', string.format('%.0f', now))
+                redis.call('PEXPIRE', key, updates[i][2])
+            end
+        end
+
+        return results
+        "#,
+    )
+});
+
+/// Tracks the theoretical arrival time of the next request for each limit, requests being allowed
+/// as long as they don't arrive earlier than the burst allows. Arguments are whether to update the
+/// arrival times and for each limit its burst and emission interval in microseconds. Arrival times
+/// are only updated if every limit allows the request. Time is read from the Redis server so that
+/// gateway clocks don't need to agree.
+static GCRA: LazyLock<redis::Script> = LazyLock::new(|| {
+    redis::Script::new(
+        r#"
+        local consume = ARGV[1] == '1'
+        local time = redis.call('TIME')
+        local now = tonumber(time[1]) * 1000000 + tonumber(time[2])
+        local results = {}
+        local updates = {}
+        local allowed = 1
+
+        for i, key in ipairs(KEYS) do
+            local burst = tonumber(ARGV[i * 2])
+            local interval = tonumber(ARGV[i * 2 + 1])
+            local tolerance = (burst - 1) * interval
+
+            local tat = math.max(tonumber(redis.call('GET', key)) or now, now)
+            if now < tat - tolerance then
+                allowed = 0
+                results[#results + 1] = 0
+                results[#results + 1] = 0
+                results[#results + 1] = math.ceil(tat - tolerance - now)
+            else
+                local new_tat = tat + interval
+                updates[i] = new_tat
+                results[#results + 1] = 1
+                results[#results + 1] = math.max(0, math.floor((tolerance + interval - (new_tat - now)) / interval))
+                results[#results + 1] = math.ceil(new_tat - now)
+            end
+        end
+
+        if consume and allowed == 1 then
+            for i, key in ipairs(KEYS) do
+                local new_tat = updates[i]
+                redis.call('SET', key, string.format('%.0f', new_tat), 'PX', math.ceil((new_tat - now) / 1000) + 1000)
+            end
+        end
+
+        return results
+        "#,
+    )
+});
+
+fn current_timestamp() -> Result<u64, Error> {
+    match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(ts) => Ok(ts.as_nanos() as u64),
+        Err(error) => {
+            tracing::error!("error with rate limit duration: {error}");
+            Err(Error::Internal(String::from("rate limit")))
         }
     }
 }
 
-async fn incr_counter(pool: Pool, current_bucket: String, expire: Duration) -> Result<(), Error> {
+async fn incr_counter(pool: Pool, current_bucket: String, expire: Duration, increment: u64) -> Result<(), Error> {
     let mut conn = match pool.get().await {
         Ok(conn) => conn,
         Err(error) => {
@@ -178,12 +420,12 @@ async fn incr_counter(pool: Pool, current_bucket: String, expire: Duration) -> R
     let mut pipe = redis::pipe();
     pipe.atomic();
 
-    pipe.cmd("INCR").arg(&current_bucket);
+    pipe.cmd("INCRBY").arg(&current_bucket).arg(increment);
 
     // Sets the timeout to the set. This will delete the data after the duration if we do not modify the value.
     pipe.cmd("EXPIRE")
         .arg(&current_bucket)
-        .arg(expire.as_secs() * 2)
+        .arg((expire.as_secs() * 2).max(1))
         .ignore();
 
     if let Err(e) = pipe.query_async::<(u64,)>(&mut *conn).await {
@@ -195,9 +437,27 @@ async fn incr_counter(pool: Pool, current_bucket: String, expire: Duration) -> R
 }
 
 impl runtime::rate_limiting::RateLimiterInner for RedisRateLimiter {
-    fn limit<'a>(&'a self, context: &'a dyn RateLimiterContext) -> BoxFuture<'a, Result<(), Error>> {
+    fn limit<'a>(
+        &'a self,
+        context: &'a dyn RateLimiterContext,
+    ) -> BoxFuture<'a, Result<Option<RateLimitState>, Error>> {
+        let algorithm = self.algorithm();
+
+        // Without a key, the request is checked against the configured rules.
         let Some(key) = context.key() else {
-            return Box::pin(async { Ok(()) });
+            let rules = self.matching_rules(context);
+
+            return Box::pin(
+                async move {
+                    if rules.is_empty() {
+                        return Ok(None);
+                    }
+
+                    // All rules are checked and counted atomically, in a single round trip.
+                    self.check(&rules, algorithm, true).await.map(Some)
+                }
+                .instrument(tracing::info_span!("rate limit rules")),
+            );
         };
 
         let config = match key {
@@ -214,10 +474,11 @@ impl runtime::rate_limiting::RateLimiterInner for RedisRateLimiter {
                 .subgraphs
                 .get(name.as_ref())
                 .and_then(|sb| sb.rate_limit),
+            RateLimitKey::CostBudget(_) => self.cost_budget_config(),
         };
 
         let Some(config) = config else {
-            return Box::pin(async { Ok(()) });
+            return Box::pin(async { Ok(None) });
         };
 
         let span = tracing::info_span!("rate limit", "subgraph.name" = Empty);
@@ -226,6 +487,40 @@ impl runtime::rate_limiting::RateLimiterInner for RedisRateLimiter {
             span.record("subgraph.name", subgraph.as_ref());
         }
 
-        Box::pin(self.limit_inner(key, config).instrument(span))
+        Box::pin(
+            async move {
+                let limits = [(self.key_base(key), config)];
+
+                // The cost is only known after execution, it always relies on the sliding window.
+                if matches!(key, RateLimitKey::CostBudget(_)) {
+                    return self
+                        .check(&limits, RateLimitAlgorithm::SlidingWindow, false)
+                        .await
+                        .map(|_| None);
+                }
+
+                self.check(&limits, algorithm, true).await.map(Some)
+            }
+            .instrument(span),
+        )
+    }
+
+    fn consume<'a>(&'a self, key: &'a RateLimitKey<'a>, amount: u64) -> BoxFuture<'a, Result<(), Error>> {
+        let config = match key {
+            RateLimitKey::CostBudget(_) => self.cost_budget_config(),
+            _ => None,
+        };
+
+        let Some(config) = config else {
+            return Box::pin(async { Ok(()) });
+        };
+
+        Box::pin(async move {
+            let current_ts = current_timestamp()?;
+            let duration_ns = config.duration.as_nanos() as u64;
+            let current_bucket = format!("{}:{}", self.key_base(key), current_ts - current_ts % duration_ns);
+
+            incr_counter(self.pool.clone(), current_bucket, config.duration, amount).await
+        })
     }
 }
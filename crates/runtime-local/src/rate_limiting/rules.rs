@@ -0,0 +1,20 @@
+use gateway_config::RateLimitRuleKey;
+use http::HeaderName;
+use runtime::rate_limiting::RateLimiterContext;
+
+/// The value a rate limit rule is counted by for the current request, if any.
+pub(super) fn rule_value(key: &RateLimitRuleKey, context: &dyn RateLimiterContext) -> Option<String> {
+    match key {
+        RateLimitRuleKey::Header(name) => {
+            let name = HeaderName::from_bytes(name.as_bytes()).ok()?;
+            context.header(name)?.to_str().ok().map(str::to_string)
+        }
+        RateLimitRuleKey::JwtClaim(path) => match context.jwt_claim(path)? {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(value) => Some(value.clone()),
+            value => Some(value.to_string()),
+        },
+        RateLimitRuleKey::Ip => context.ip().map(|ip| ip.to_string()),
+        RateLimitRuleKey::OperationName => context.graphql_operation_name().map(str::to_string),
+    }
+}
@@ -1,26 +1,10 @@
-mod pool;
-
 use std::{
     collections::{HashMap, hash_map::Entry},
-    fs::File,
-    io::{BufReader, Read},
-    path::{Path, PathBuf},
-    time::Duration,
+    path::PathBuf,
 };
 
-use anyhow::Context;
-use redis::ClientTlsConfig;
-
-pub type Pool = deadpool::managed::Pool<pool::Manager>;
-
-pub use pool::Manager;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct RedisTlsConfig<'a> {
-    pub cert: Option<&'a Path>,
-    pub key: Option<&'a Path>,
-    pub ca: Option<&'a Path>,
-}
+use redis_pool::new_pool;
+pub use redis_pool::{Manager, Pool, RedisTlsConfig};
 
 #[derive(PartialEq, Eq, Hash, Default)]
 struct RedisConfigKey {
@@ -66,68 +50,3 @@ impl RedisPoolFactory {
         }
     }
 }
-
-fn new_pool(url: &str, tls_config: Option<RedisTlsConfig<'_>>) -> anyhow::Result<Pool> {
-    let tls_config = match tls_config {
-        Some(tls) => {
-            let client_tls = match tls.cert.zip(tls.key) {
-                Some((cert, key)) => {
-                    let mut client_cert = Vec::new();
-
-                    File::open(cert)
-                        .and_then(|file| BufReader::new(file).read_to_end(&mut client_cert))
-                        .context("loading the Redis client certificate")?;
-
-                    let mut client_key = Vec::new();
-
-                    File::open(key)
-                        .and_then(|file| BufReader::new(file).read_to_end(&mut client_key))
-                        .context("loading the Redis client key")?;
-
-                    Some(ClientTlsConfig {
-                        client_cert,
-                        client_key,
-                    })
-                }
-                None => None,
-            };
-
-            let root_cert = match tls.ca {
-                Some(path) => {
-                    let mut ca = Vec::new();
-
-                    File::open(path)
-                        .and_then(|file| BufReader::new(file).read_to_end(&mut ca))
-                        .context("loading the Redis CA certificate")?;
-
-                    Some(ca)
-                }
-                None => None,
-            };
-
-            Some(pool::TlsConfig { client_tls, root_cert })
-        }
-        None => None,
-    };
-
-    let manager = match pool::Manager::new(url, tls_config) {
-        Ok(manager) => manager,
-        Err(e) => {
-            tracing::error!("error creating a Redis pool: {e}");
-            return Err(e.into());
-        }
-    };
-
-    match Pool::builder(manager)
-        .wait_timeout(Some(Duration::from_secs(5)))
-        .create_timeout(Some(Duration::from_secs(10)))
-        .runtime(deadpool::Runtime::Tokio1)
-        .build()
-    {
-        Ok(pool) => Ok(pool),
-        Err(e) => {
-            tracing::error!("error creating a Redis pool: {e}");
-            Err(e.into())
-        }
-    }
-}
@@ -0,0 +1,96 @@
+use std::time::Instant;
+
+use bytes::Bytes;
+use futures_util::{FutureExt, future::BoxFuture};
+use tracing::{Instrument, field::Empty};
+
+pub struct InMemoryResponseCache {
+    inner: mini_moka::sync::Cache<String, CacheValue>,
+}
+
+#[derive(Clone)]
+struct CacheValue {
+    data: Bytes,
+    expires_at: Instant,
+}
+
+impl InMemoryResponseCache {
+    pub fn new() -> Self {
+        InMemoryResponseCache {
+            inner: mini_moka::sync::Cache::new(1024),
+        }
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Bytes>> {
+        let Some(value) = self.inner.get(&key.to_string()) else {
+            return Ok(None);
+        };
+
+        if value.expires_at < Instant::now() {
+            self.inner.invalidate(&key.to_string());
+            return Ok(None);
+        }
+
+        Ok(Some(value.data))
+    }
+
+    async fn put(
+        &self,
+        key: &str,
+        bytes: std::borrow::Cow<'_, [u8]>,
+        expiration_ttl: std::time::Duration,
+    ) -> anyhow::Result<()> {
+        self.inner.insert(
+            key.to_string(),
+            CacheValue {
+                data: bytes.into_owned().into(),
+                expires_at: Instant::now() + expiration_ttl,
+            },
+        );
+        Ok(())
+    }
+}
+
+impl Default for InMemoryResponseCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl runtime::response_cache::ResponseCache for InMemoryResponseCache {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<Option<Bytes>>> {
+        let cache_span = tracing::info_span!(
+            "response cache get",
+            "grafbase.response_cache.status" = Empty,
+            "otel.status_code" = Empty,
+        );
+
+        let cache_get = self
+            .get(key)
+            .instrument(cache_span.clone())
+            .inspect(move |item| match item {
+                Ok(Some(_)) => {
+                    cache_span.record("grafbase.response_cache.status", "HIT");
+                }
+                Ok(None) => {
+                    cache_span.record("grafbase.response_cache.status", "MISS");
+                }
+                Err(e) => {
+                    cache_span.record("otel.status_code", "Error");
+                    cache_span.record("grafbase.response_cache.error", e.to_string());
+                }
+            });
+
+        Box::pin(cache_get)
+    }
+
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: std::borrow::Cow<'a, [u8]>,
+        expiration_ttl: std::time::Duration,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        let cache_span = tracing::info_span!("response cache put");
+        Box::pin(self.put(key, bytes, expiration_ttl).instrument(cache_span))
+    }
+}
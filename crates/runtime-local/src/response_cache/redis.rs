@@ -0,0 +1,89 @@
+use bytes::Bytes;
+use deadpool::managed::Object;
+use futures_util::{FutureExt, future::BoxFuture};
+use redis::{AsyncCommands, SetOptions};
+use tracing::{Instrument, field::Empty};
+
+use crate::redis::{Manager, Pool};
+
+pub struct RedisResponseCache {
+    pool: Pool,
+    key_prefix: String,
+}
+
+impl RedisResponseCache {
+    pub fn new(pool: Pool, key_prefix: &str) -> Self {
+        RedisResponseCache {
+            pool,
+            key_prefix: key_prefix.to_string(),
+        }
+    }
+
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Bytes>> {
+        let mut connection = self.connection().await?;
+        Ok(connection.get(self.key(key)).await?)
+    }
+
+    async fn put(
+        &self,
+        key: &str,
+        bytes: std::borrow::Cow<'_, [u8]>,
+        expiration_ttl: std::time::Duration,
+    ) -> anyhow::Result<()> {
+        let mut connection = self.connection().await?;
+        let options = SetOptions::default().with_expiration(redis::SetExpiry::PX(expiration_ttl.as_millis() as u64));
+        Ok(connection.set_options(self.key(key), bytes.as_ref(), options).await?)
+    }
+
+    fn key(&self, key: &str) -> String {
+        format!("{}-{key}", self.key_prefix)
+    }
+
+    async fn connection(&self) -> Result<Object<Manager>, anyhow::Error> {
+        match self.pool.get().await {
+            Ok(conn) => Ok(conn),
+            Err(error) => {
+                tracing::error!("error fetching a Redis connection: {error}");
+                anyhow::bail!("error fetching a redis connection: {error}");
+            }
+        }
+    }
+}
+
+impl runtime::response_cache::ResponseCache for RedisResponseCache {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<Option<Bytes>>> {
+        let cache_span = tracing::info_span!(
+            "response cache get",
+            "grafbase.response_cache.status" = Empty,
+            "otel.status_code" = Empty,
+        );
+
+        let cache_get = self
+            .get(key)
+            .instrument(cache_span.clone())
+            .inspect(move |item| match item {
+                Ok(Some(_)) => {
+                    cache_span.record("grafbase.response_cache.status", "HIT");
+                }
+                Ok(None) => {
+                    cache_span.record("grafbase.response_cache.status", "MISS");
+                }
+                Err(e) => {
+                    cache_span.record("otel.status_code", "Error");
+                    cache_span.record("grafbase.response_cache.error", e.to_string());
+                }
+            });
+
+        Box::pin(cache_get)
+    }
+
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: std::borrow::Cow<'a, [u8]>,
+        expiration_ttl: std::time::Duration,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        let cache_span = tracing::info_span!("response cache put");
+        Box::pin(self.put(key, bytes, expiration_ttl).instrument(cache_span))
+    }
+}
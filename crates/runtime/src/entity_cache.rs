@@ -7,13 +7,18 @@ use futures_util::{future::BoxFuture, FutureExt};
 pub trait EntityCache: Send + Sync {
     fn get<'a>(&'a self, name: &'a str) -> BoxFuture<'a, anyhow::Result<Option<Bytes>>>;
 
-    /// Put an entry into the store, with an optional expiry TTL.
+    /// Put an entry into the store, with an optional expiry TTL. Tags are used to invalidate the
+    /// entry before it expires, see [type_tag] and [entity_tag].
     fn put<'a>(
         &'a self,
         name: &'a str,
         bytes: Cow<'a, [u8]>,
+        tags: &'a [String],
         expiration_ttl: Duration,
     ) -> BoxFuture<'a, anyhow::Result<()>>;
+
+    /// Removes all entries associated with any of the given tags.
+    fn invalidate<'a>(&'a self, tags: &'a [String]) -> BoxFuture<'a, anyhow::Result<()>>;
 }
 
 impl EntityCache for () {
@@ -25,8 +30,59 @@ impl EntityCache for () {
         &'a self,
         _name: &'a str,
         _bytes: Cow<'a, [u8]>,
+        _tags: &'a [String],
         _expiration_ttl: Duration,
     ) -> BoxFuture<'a, anyhow::Result<()>> {
         futures_util::future::ready(Ok(())).boxed()
     }
+
+    fn invalidate<'a>(&'a self, _tags: &'a [String]) -> BoxFuture<'a, anyhow::Result<()>> {
+        futures_util::future::ready(Ok(())).boxed()
+    }
+}
+
+/// Tag of all the cache entries containing entities of the given type.
+pub fn type_tag(type_name: &str) -> String {
+    format!("type:{type_name}")
+}
+
+/// Tag of the cache entries containing a specific entity, identified by its key fields. The key
+/// is written in a canonical form, so the order of the fields doesn't matter.
+pub fn entity_tag(type_name: &str, key: &serde_json::Map<String, serde_json::Value>) -> String {
+    let mut tag = format!("entity:{type_name}:");
+    write_canonical_object(&mut tag, key);
+    tag
+}
+
+fn write_canonical_object(out: &mut String, object: &serde_json::Map<String, serde_json::Value>) {
+    let mut fields = object.iter().collect::<Vec<_>>();
+    fields.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+    out.push('{');
+    for (i, (name, value)) in fields.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&serde_json::Value::String(name.clone()).to_string());
+        out.push(':');
+        write_canonical_value(out, value);
+    }
+    out.push('}');
+}
+
+fn write_canonical_value(out: &mut String, value: &serde_json::Value) {
+    match value {
+        serde_json::Value::Object(object) => write_canonical_object(out, object),
+        serde_json::Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_value(out, value);
+            }
+            out.push(']');
+        }
+        value => out.push_str(&value.to_string()),
+    }
 }
@@ -1,5 +1,6 @@
 use std::{future::Future, sync::Arc};
 
+use base64::Engine as _;
 use error::ErrorResponse;
 use event_queue::EventQueue;
 use extension_catalog::ExtensionId;
@@ -43,6 +44,31 @@ impl Token {
             Token::Bytes(bytes) => TokenRef::Bytes(bytes),
         }
     }
+
+    /// Claims verified by the authentication extension, which either keeps them as a JSON object
+    /// or keeps the JWT itself as the token.
+    pub fn claims(&self) -> Option<serde_json::Value> {
+        let bytes = self.as_bytes()?;
+
+        if let Ok(claims @ serde_json::Value::Object(_)) = serde_json::from_slice(bytes) {
+            return Some(claims);
+        }
+
+        let mut segments = std::str::from_utf8(bytes).ok()?.split('.');
+        let (Some(_header), Some(payload), Some(_signature), None) =
+            (segments.next(), segments.next(), segments.next(), segments.next())
+        else {
+            return None;
+        };
+
+        let payload = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(payload.trim_end_matches('='))
+            .ok()?;
+
+        serde_json::from_slice(&payload)
+            .ok()
+            .filter(serde_json::Value::is_object)
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -3,7 +3,7 @@ use std::{borrow::Cow, future::Future, time::Duration};
 use bytes::Bytes;
 use engine_schema::GraphqlSubgraphId;
 use event_queue::SubgraphResponseBuilder;
-use futures_util::{stream::BoxStream, Stream, StreamExt, TryFutureExt};
+use futures_util::{Stream, StreamExt, TryFutureExt, stream::BoxStream};
 use http::Response;
 
 #[derive(Debug, Clone, thiserror::Error)]
@@ -16,6 +16,10 @@ pub enum FetchError {
     MessageSigningFailed(String),
     #[error("Request error: {0}")]
     Reqwest(String),
+    /// The request was never sent, the subgraph concurrency limit queue was full or the request
+    /// waited too long for a slot.
+    #[error("{0}")]
+    ConcurrencyLimitRejected(String),
 }
 
 impl From<reqwest::Error> for FetchError {
@@ -43,6 +47,12 @@ impl FetchError {
             _ => None,
         }
     }
+
+    /// Whether the request was rejected before being sent to the subgraph. Such failures don't
+    /// tell anything about the subgraph health and aren't worth retrying.
+    pub fn is_concurrency_limit_rejection(&self) -> bool {
+        matches!(self, FetchError::ConcurrencyLimitRejected(_))
+    }
 }
 
 pub type FetchResult<T> = Result<T, FetchError>;
@@ -58,6 +68,9 @@ pub struct FetchRequest<'a> {
     pub headers: http::HeaderMap,
     pub body: Bytes,
     pub timeout: Duration,
+    /// Whether this is a hedged copy of an in-flight request, which must not be deduplicated
+    /// with it.
+    pub hedge: bool,
 }
 
 #[derive(Clone)]
@@ -5,4 +5,5 @@ pub mod extension;
 pub mod fetch;
 pub mod operation_cache;
 pub mod rate_limiting;
+pub mod response_cache;
 pub mod trusted_documents_client;
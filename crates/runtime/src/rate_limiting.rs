@@ -1,6 +1,7 @@
 use std::borrow::Cow;
 use std::net::IpAddr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use futures_util::future::BoxFuture;
 use futures_util::FutureExt;
@@ -8,11 +9,36 @@ use futures_util::FutureExt;
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("Too many requests")]
-    ExceededCapacity,
+    ExceededCapacity(Option<RateLimitState>),
     #[error("internal error: {0}")]
     Internal(String),
 }
 
+/// State of a rate limit once a request was checked against it, exposed to clients with the
+/// `RateLimit-*` and `Retry-After` response headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitState {
+    /// Number of requests allowed by the limit.
+    pub limit: u64,
+    /// Number of requests which can still be made right away.
+    pub remaining: u64,
+    /// Time until the limit is fully available again. If the limit was exceeded, time until the
+    /// next request can be made.
+    pub reset: Duration,
+}
+
+impl RateLimitState {
+    /// When several limits apply to a request, clients only care about the one they'll hit first.
+    pub fn most_restrictive(self, other: Self) -> Self {
+        match self.remaining.cmp(&other.remaining) {
+            std::cmp::Ordering::Less => self,
+            std::cmp::Ordering::Greater => other,
+            std::cmp::Ordering::Equal if self.reset >= other.reset => self,
+            std::cmp::Ordering::Equal => other,
+        }
+    }
+}
+
 pub trait RateLimiterContext: Send + Sync {
     fn header(&self, name: http::HeaderName) -> Option<&http::HeaderValue>;
     fn graphql_operation_name(&self) -> Option<&str>;
@@ -25,12 +51,22 @@ pub trait RateLimiterContext: Send + Sync {
 }
 
 pub trait RateLimiterInner: Send + Sync {
-    fn limit<'a>(&'a self, context: &'a dyn RateLimiterContext) -> BoxFuture<'a, Result<(), Error>>;
+    /// Counts the request against the matching limits, returning the state of the most restrictive
+    /// one if the implementation tracks it.
+    fn limit<'a>(&'a self, context: &'a dyn RateLimiterContext)
+        -> BoxFuture<'a, Result<Option<RateLimitState>, Error>>;
+
+    /// Spends `amount` units of the budget associated with the key, such as the cost of an
+    /// operation for [RateLimitKey::CostBudget]. `limit` fails for this key once its budget
+    /// for the current window is spent.
+    fn consume<'a>(&'a self, _key: &'a RateLimitKey<'a>, _amount: u64) -> BoxFuture<'a, Result<(), Error>> {
+        async { Ok(()) }.boxed()
+    }
 }
 
 impl RateLimiterInner for () {
-    fn limit<'a>(&'a self, _: &'a dyn RateLimiterContext) -> BoxFuture<'a, Result<(), Error>> {
-        async { Ok(()) }.boxed()
+    fn limit<'a>(&'a self, _: &'a dyn RateLimiterContext) -> BoxFuture<'a, Result<Option<RateLimitState>, Error>> {
+        async { Ok(None) }.boxed()
     }
 }
 
@@ -57,6 +93,8 @@ impl RateLimiter {
 pub enum RateLimitKey<'a> {
     Global,
     Subgraph(Cow<'a, str>),
+    /// Cost budget of a client, identified by the configured header or JWT claim value.
+    CostBudget(Cow<'a, str>),
 }
 
 impl<'a> From<&'a str> for RateLimitKey<'a> {
@@ -0,0 +1,33 @@
+use std::{borrow::Cow, time::Duration};
+
+use bytes::Bytes;
+use futures_util::{future::BoxFuture, FutureExt};
+
+/// Storage for complete GraphQL responses. Keys are computed by the engine from the operation,
+/// its variables and the configured vary keys.
+pub trait ResponseCache: Send + Sync {
+    fn get<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<Option<Bytes>>>;
+
+    /// Put an entry into the store, expiring after the given TTL.
+    fn put<'a>(
+        &'a self,
+        key: &'a str,
+        bytes: Cow<'a, [u8]>,
+        expiration_ttl: Duration,
+    ) -> BoxFuture<'a, anyhow::Result<()>>;
+}
+
+impl ResponseCache for () {
+    fn get<'a>(&'a self, _key: &'a str) -> BoxFuture<'a, anyhow::Result<Option<Bytes>>> {
+        futures_util::future::ready(Ok(None)).boxed()
+    }
+
+    fn put<'a>(
+        &'a self,
+        _key: &'a str,
+        _bytes: Cow<'a, [u8]>,
+        _expiration_ttl: Duration,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        futures_util::future::ready(Ok(())).boxed()
+    }
+}
@@ -31,16 +31,10 @@ where
     pub fn into_inner(self) -> T {
         self.0
     }
-}
-
-impl<T> FromStr for DynamicString<T>
-where
-    T::Err: std::error::Error,
-    T: FromStr + AsRef<str> + Default + Write + Clone,
-{
-    type Err = String;
 
-    fn from_str(string: &str) -> Result<Self, Self::Err> {
+    /// Like [`FromStr::from_str`], but variables in one of the `passthrough_scopes` are kept as is
+    /// rather than rejected, for them to be rendered later on with a different context.
+    pub fn from_str_with_passthrough(string: &str, passthrough_scopes: &[&str]) -> Result<Self, String> {
         /// Matches any "{{ something }}"
         fn re() -> &'static Regex {
             static RE: OnceLock<Regex> = OnceLock::new();
@@ -55,7 +49,16 @@ where
         let last_end = re().captures_iter(string).fold(0, |last_end, captures| {
             let overall_match = captures.get(0).unwrap();
             let key = captures.get(1).unwrap().as_str();
-            let path = key.split('.');
+            let mut path = key.split('.');
+
+            if path
+                .clone()
+                .next()
+                .is_some_and(|scope| passthrough_scopes.contains(&scope))
+            {
+                // kept with the static content surrounding it
+                return last_end;
+            }
 
             if let Some(("env", variable_name)) = path.collect_tuple() {
                 // this is true if we have data between the current and the last match
@@ -100,6 +103,18 @@ where
     }
 }
 
+impl<T> FromStr for DynamicString<T>
+where
+    T::Err: std::error::Error,
+    T: FromStr + AsRef<str> + Default + Write + Clone,
+{
+    type Err = String;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with_passthrough(string, &[])
+    }
+}
+
 impl<T> AsRef<str> for DynamicString<T>
 where
     T::Err: std::error::Error,
@@ -255,6 +270,26 @@ mod tests {
         });
     }
 
+    #[test]
+    fn passthrough_scope() {
+        temp_env::with_var("FOOBAR", Some("some_value"), || {
+            let result =
+                DynamicString::<String>::from_str_with_passthrough("{{ env.FOOBAR }}: {{ jwt.sub }}", &["jwt"])
+                    .unwrap();
+            assert_eq!("some_value: {{ jwt.sub }}", result.as_ref());
+
+            let result = DynamicString::<String>::from_str_with_passthrough(
+                "{{ headers.x-foo }} {{ env.FOOBAR }}",
+                &["headers"],
+            )
+            .unwrap();
+            assert_eq!("{{ headers.x-foo }} some_value", result.as_ref());
+        });
+
+        let error = DynamicString::<String>::from_str_with_passthrough("{{ meow.FOO }}", &["jwt"]).unwrap_err();
+        insta::assert_snapshot!(&error, @"right now only variables scoped with 'env.' are supported: `meow.FOO`");
+    }
+
     #[test]
     fn non_env_scope() {
         let error = "{{ meow.FOO }}".parse::<DynamicString<String>>().unwrap_err();
@@ -1,6 +1,6 @@
 use opentelemetry::{
     KeyValue,
-    metrics::{Counter, Histogram, Meter, UpDownCounter},
+    metrics::{Counter, Gauge, Histogram, Meter, UpDownCounter},
 };
 
 use crate::{
@@ -14,15 +14,20 @@ pub struct EngineMetrics {
     operation_latency: Histogram<u64>,
     subgraph_latency: Histogram<u64>,
     subgraph_retries: Counter<u64>,
+    subgraph_hedges: Counter<u64>,
     subgraph_request_body_size: Histogram<u64>,
     subgraph_response_body_size: Histogram<u64>,
     subgraph_requests_inflight: UpDownCounter<i64>,
     subgraph_cache_hits: Counter<u64>,
     subgraph_cache_partial_hits: Counter<u64>,
     subgraph_cache_misses: Counter<u64>,
+    subgraph_circuit_breaker_state: Gauge<u64>,
+    subgraph_circuit_breaker_rejections: Counter<u64>,
     operation_cache_hits: Counter<u64>,
     operation_cache_misses: Counter<u64>,
     query_preparation_latency: Histogram<u64>,
+    operation_estimated_cost: Histogram<u64>,
+    operation_actual_cost: Histogram<u64>,
     batch_sizes: Histogram<u64>,
     request_body_sizes: Histogram<u64>,
     graphql_errors: Counter<u64>,
@@ -48,6 +53,11 @@ pub struct SubgraphRequestRetryAttributes {
     pub aborted: bool,
 }
 
+#[derive(Debug)]
+pub struct SubgraphRequestHedgeAttributes {
+    pub name: String,
+}
+
 #[derive(Debug)]
 pub struct SubgraphRequestBodySizeAttributes {
     pub name: String,
@@ -73,12 +83,41 @@ pub struct SubgraphCacheMissAttributes {
     pub name: String,
 }
 
+#[derive(Debug)]
+pub struct SubgraphCircuitBreakerAttributes {
+    pub name: String,
+}
+
+/// State of a subgraph circuit breaker, recorded as 0 (closed), 1 (half-open) or 2 (open).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitBreakerState {
+    Closed,
+    HalfOpen,
+    Open,
+}
+
+impl CircuitBreakerState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::HalfOpen => "half_open",
+            Self::Open => "open",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct QueryPreparationAttributes {
     pub operation: Option<GraphqlOperationAttributes>,
     pub success: bool,
 }
 
+#[derive(Debug)]
+pub struct OperationCostAttributes {
+    pub operation_name: Option<String>,
+    pub client: Option<Client>,
+}
+
 #[derive(Debug)]
 pub struct GraphqlErrorAttributes {
     pub code: &'static str,
@@ -99,18 +138,25 @@ impl EngineMetrics {
                 .with_unit("ms")
                 .build(),
             subgraph_retries: meter.u64_counter("graphql.subgraph.request.retries").build(),
+            subgraph_hedges: meter.u64_counter("graphql.subgraph.request.hedges").build(),
             subgraph_request_body_size: meter.u64_histogram("graphql.subgraph.request.body.size").build(),
             subgraph_response_body_size: meter.u64_histogram("graphql.subgraph.response.body.size").build(),
             subgraph_requests_inflight: meter.i64_up_down_counter("graphql.subgraph.request.inflight").build(),
             subgraph_cache_hits: meter.u64_counter("graphql.subgraph.request.cache.hit").build(),
             subgraph_cache_partial_hits: meter.u64_counter("graphql.subgraph.request.cache.partial_hit").build(),
             subgraph_cache_misses: meter.u64_counter("graphql.subgraph.request.cache.miss").build(),
+            subgraph_circuit_breaker_state: meter.u64_gauge("graphql.subgraph.circuit_breaker.state").build(),
+            subgraph_circuit_breaker_rejections: meter
+                .u64_counter("graphql.subgraph.circuit_breaker.rejected_requests")
+                .build(),
             operation_cache_hits: meter.u64_counter("graphql.operation.cache.hit").build(),
             operation_cache_misses: meter.u64_counter("graphql.operation.cache.miss").build(),
             query_preparation_latency: meter
                 .u64_histogram("graphql.operation.prepare.duration")
                 .with_unit("ms")
                 .build(),
+            operation_estimated_cost: meter.u64_histogram("graphql.operation.cost.estimated").build(),
+            operation_actual_cost: meter.u64_histogram("graphql.operation.cost.actual").build(),
             batch_sizes: meter.u64_histogram("graphql.operation.batch.size").build(),
             request_body_sizes: meter.u64_histogram("http.server.request.body.size").build(),
             graphql_errors: meter.u64_counter("graphql.operation.errors").build(),
@@ -200,6 +246,11 @@ impl EngineMetrics {
         self.subgraph_retries.add(1, &attributes);
     }
 
+    pub fn record_subgraph_hedge(&self, SubgraphRequestHedgeAttributes { name }: SubgraphRequestHedgeAttributes) {
+        let attributes = [KeyValue::new("graphql.subgraph.name", name)];
+        self.subgraph_hedges.add(1, &attributes);
+    }
+
     pub fn record_subgraph_request_size(
         &self,
         SubgraphRequestBodySizeAttributes { name }: SubgraphRequestBodySizeAttributes,
@@ -249,6 +300,28 @@ impl EngineMetrics {
         self.subgraph_cache_misses.add(1, &attributes);
     }
 
+    pub fn record_subgraph_circuit_breaker_state(
+        &self,
+        SubgraphCircuitBreakerAttributes { name }: SubgraphCircuitBreakerAttributes,
+        state: CircuitBreakerState,
+    ) {
+        let attributes = [KeyValue::new("graphql.subgraph.name", name)];
+        let value = match state {
+            CircuitBreakerState::Closed => 0,
+            CircuitBreakerState::HalfOpen => 1,
+            CircuitBreakerState::Open => 2,
+        };
+        self.subgraph_circuit_breaker_state.record(value, &attributes);
+    }
+
+    pub fn record_subgraph_circuit_breaker_rejection(
+        &self,
+        SubgraphCircuitBreakerAttributes { name }: SubgraphCircuitBreakerAttributes,
+    ) {
+        let attributes = [KeyValue::new("graphql.subgraph.name", name)];
+        self.subgraph_circuit_breaker_rejections.add(1, &attributes);
+    }
+
     pub fn record_operation_cache_hit(&self) {
         self.operation_cache_hits.add(1, &[]);
     }
@@ -283,6 +356,30 @@ impl EngineMetrics {
             .record(duration.as_millis() as u64, &attributes);
     }
 
+    pub fn record_operation_cost(
+        &self,
+        OperationCostAttributes { operation_name, client }: OperationCostAttributes,
+        estimated: usize,
+        actual: usize,
+    ) {
+        let mut attributes = Vec::new();
+
+        if let Some(name) = operation_name {
+            attributes.push(KeyValue::new("graphql.operation.name", name));
+        }
+
+        if let Some(client) = client {
+            attributes.push(KeyValue::new("http.headers.x-grafbase-client-name", client.name));
+
+            if let Some(version) = client.version {
+                attributes.push(KeyValue::new("http.headers.x-grafbase-client-version", version));
+            }
+        }
+
+        self.operation_estimated_cost.record(estimated as u64, &attributes);
+        self.operation_actual_cost.record(actual as u64, &attributes);
+    }
+
     pub fn record_batch_size(&self, size: usize) {
         self.batch_sizes.record(size as u64, &[]);
     }
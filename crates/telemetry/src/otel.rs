@@ -6,6 +6,8 @@ pub mod layer;
 pub mod logs;
 /// metrics related otel functions
 pub mod metrics;
+/// B3 and Jaeger trace context propagation
+pub mod propagation;
 /// For creation of a tracing provider.
 pub mod traces;
 
@@ -11,6 +11,7 @@ use tracing_subscriber::filter::Filtered;
 use tracing_subscriber::layer::Filter;
 
 use super::logs::bridge::OtelLogsLayer;
+use super::metrics::prometheus::PrometheusExporter;
 use tracing_subscriber::registry::LookupSpan;
 
 use crate::config::TelemetryConfig;
@@ -26,6 +27,7 @@ pub type FilteredLayer<S> = Filtered<BoxedLayer<S>, BoxedFilter<S>, S>;
 pub struct OtelTelemetry<Subscriber> {
     pub tracer: Option<Tracer<Subscriber>>,
     pub meter_provider: Option<SdkMeterProvider>,
+    pub prometheus: Option<PrometheusExporter>,
     pub logger: Option<Logger>,
 }
 
@@ -49,6 +51,7 @@ where
     OtelTelemetry {
         tracer: None,
         meter_provider: None,
+        prometheus: None,
         logger: None,
     }
 }
@@ -69,7 +72,7 @@ where
     resource_attributes.push(KeyValue::new("service.name", config.service_name.clone()));
     let resource = Resource::builder().with_attributes(resource_attributes).build();
 
-    let meter_provider = Some(super::metrics::build_meter_provider(config, resource.clone())?);
+    let (meter_provider, prometheus) = super::metrics::build_meter_provider(config, resource.clone())?;
 
     let logger = match super::logs::build_logs_provider(config, resource.clone())? {
         Some(provider) if config.logs_exporters_enabled() => Some(Logger {
@@ -96,7 +99,8 @@ where
 
     Ok(OtelTelemetry {
         tracer,
-        meter_provider,
+        meter_provider: Some(meter_provider),
+        prometheus,
         logger,
     })
 }
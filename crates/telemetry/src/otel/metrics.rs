@@ -8,6 +8,11 @@ use opentelemetry_sdk::metrics::{
 use crate::config::TelemetryConfig;
 use crate::error::TracingError;
 
+/// Prometheus text exposition format exporter
+pub mod prometheus;
+
+use prometheus::PrometheusExporter;
+
 pub struct DeltaTemporality;
 
 fn agg_for_latency_histogram(inst: &Instrument) -> Option<Stream> {
@@ -41,7 +46,7 @@ fn agg_for_latency_histogram(inst: &Instrument) -> Option<Stream> {
 pub(super) fn build_meter_provider(
     config: &TelemetryConfig,
     resource: Resource,
-) -> Result<SdkMeterProvider, TracingError> {
+) -> Result<(SdkMeterProvider, Option<PrometheusExporter>), TracingError> {
     let mut provider = SdkMeterProvider::builder()
         .with_resource(resource)
         .with_view(agg_for_latency_histogram);
@@ -66,7 +71,15 @@ pub(super) fn build_meter_provider(
         provider = attach_reader(config, provider)?;
     }
 
-    Ok(provider.build())
+    let prometheus = config
+        .metrics_prometheus_config()
+        .map(|_| PrometheusExporter::default());
+
+    if let Some(ref exporter) = prometheus {
+        provider = provider.with_reader(exporter.clone());
+    }
+
+    Ok((provider.build(), prometheus))
 }
 
 fn attach_reader(
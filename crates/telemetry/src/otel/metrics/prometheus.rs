@@ -0,0 +1,445 @@
+use std::{
+    borrow::Cow,
+    fmt::Write as _,
+    sync::{Arc, OnceLock, Weak},
+    time::Duration,
+};
+
+use opentelemetry::{KeyValue, Value};
+use opentelemetry_sdk::{
+    error::OTelSdkResult,
+    metrics::{
+        InstrumentKind, ManualReader, Pipeline, Temporality,
+        data::{AggregatedMetrics, ExponentialHistogramDataPoint, MetricData, ResourceMetrics},
+        reader::MetricReader,
+    },
+};
+
+/// The content type of the Prometheus text exposition format.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Exponential histograms are downscaled to this scale when converted to Prometheus buckets,
+/// each bucket covering a factor of √2. It keeps the number of series per histogram reasonable.
+const MAX_BUCKET_SCALE: i8 = 1;
+
+static GLOBAL_EXPORTER: OnceLock<PrometheusExporter> = OnceLock::new();
+
+/// Sets the exporter used to serve the metrics endpoint of the gateway.
+pub fn set_global_exporter(exporter: PrometheusExporter) {
+    if GLOBAL_EXPORTER.set(exporter).is_err() {
+        tracing::warn!("The Prometheus exporter was already initialized");
+    }
+}
+
+/// Returns the exporter set with [`set_global_exporter`], if any.
+pub fn global_exporter() -> Option<&'static PrometheusExporter> {
+    GLOBAL_EXPORTER.get()
+}
+
+/// A pull based metric reader, encoding all the collected metrics in the Prometheus text
+/// exposition format on each scrape.
+#[derive(Clone, Debug)]
+pub struct PrometheusExporter {
+    reader: Arc<ManualReader>,
+}
+
+impl Default for PrometheusExporter {
+    fn default() -> Self {
+        Self {
+            reader: Arc::new(
+                ManualReader::builder()
+                    .with_temporality(Temporality::Cumulative)
+                    .build(),
+            ),
+        }
+    }
+}
+
+impl PrometheusExporter {
+    /// Collects the current value of all metrics and encodes them in the text exposition format.
+    pub fn encode(&self) -> Result<String, String> {
+        let mut metrics = ResourceMetrics::default();
+        self.reader.collect(&mut metrics).map_err(|err| err.to_string())?;
+
+        let mut output = String::new();
+        encode_metrics(&metrics, &mut output);
+
+        Ok(output)
+    }
+}
+
+impl MetricReader for PrometheusExporter {
+    fn register_pipeline(&self, pipeline: Weak<Pipeline>) {
+        self.reader.register_pipeline(pipeline)
+    }
+
+    fn collect(&self, rm: &mut ResourceMetrics) -> OTelSdkResult {
+        self.reader.collect(rm)
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.reader.force_flush()
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        self.reader.shutdown_with_timeout(timeout)
+    }
+
+    fn temporality(&self, kind: InstrumentKind) -> Temporality {
+        self.reader.temporality(kind)
+    }
+}
+
+fn encode_metrics(metrics: &ResourceMetrics, output: &mut String) {
+    for scope in metrics.scope_metrics() {
+        for metric in scope.metrics() {
+            let name = metric_name(metric.name(), metric.unit());
+
+            match metric.data() {
+                AggregatedMetrics::F64(data) => encode_data(&name, metric.description(), data, output),
+                AggregatedMetrics::U64(data) => encode_data(&name, metric.description(), data, output),
+                AggregatedMetrics::I64(data) => encode_data(&name, metric.description(), data, output),
+            }
+        }
+    }
+}
+
+fn encode_data<T>(name: &str, description: &str, data: &MetricData<T>, output: &mut String)
+where
+    T: Copy + ToF64,
+{
+    match data {
+        MetricData::Gauge(gauge) => {
+            write_header(name, description, "gauge", output);
+
+            for point in gauge.data_points() {
+                let attributes = point.attributes().collect::<Vec<_>>();
+                write_sample(name, &attributes, None, point.value().to_f64(), output);
+            }
+        }
+        MetricData::Sum(sum) if sum.is_monotonic() => {
+            let name = format!("{name}_total");
+            write_header(&name, description, "counter", output);
+
+            for point in sum.data_points() {
+                let attributes = point.attributes().collect::<Vec<_>>();
+                write_sample(&name, &attributes, None, point.value().to_f64(), output);
+            }
+        }
+        MetricData::Sum(sum) => {
+            write_header(name, description, "gauge", output);
+
+            for point in sum.data_points() {
+                let attributes = point.attributes().collect::<Vec<_>>();
+                write_sample(name, &attributes, None, point.value().to_f64(), output);
+            }
+        }
+        MetricData::Histogram(histogram) => {
+            write_header(name, description, "histogram", output);
+
+            for point in histogram.data_points() {
+                let buckets = point.bounds().zip(point.bucket_counts().scan(0, |cumulative, count| {
+                    *cumulative += count;
+                    Some(*cumulative)
+                }));
+
+                let attributes = point.attributes().collect::<Vec<_>>();
+                write_histogram(name, &attributes, buckets, point.count(), point.sum().to_f64(), output);
+            }
+        }
+        MetricData::ExponentialHistogram(histogram) => {
+            write_header(name, description, "histogram", output);
+
+            for point in histogram.data_points() {
+                let attributes = point.attributes().collect::<Vec<_>>();
+                let buckets = exponential_buckets(point);
+
+                write_histogram(
+                    name,
+                    &attributes,
+                    buckets,
+                    point.count() as u64,
+                    point.sum().to_f64(),
+                    output,
+                );
+            }
+        }
+    }
+}
+
+fn write_histogram(
+    name: &str,
+    attributes: &[&KeyValue],
+    buckets: impl IntoIterator<Item = (f64, u64)>,
+    count: u64,
+    sum: f64,
+    output: &mut String,
+) {
+    let bucket_name = format!("{name}_bucket");
+
+    for (bound, cumulative) in buckets {
+        let le = ("le", format_value(bound));
+        write_sample(&bucket_name, attributes, Some(le), cumulative as f64, output);
+    }
+
+    let inf = ("le", Cow::Borrowed("+Inf"));
+    write_sample(&bucket_name, attributes, Some(inf), count as f64, output);
+    write_sample(&format!("{name}_sum"), attributes, None, sum, output);
+    write_sample(&format!("{name}_count"), attributes, None, count as f64, output);
+}
+
+/// Metric values are all written as floats.
+trait ToF64 {
+    fn to_f64(self) -> f64;
+}
+
+impl ToF64 for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl ToF64 for u64 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+impl ToF64 for i64 {
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+/// Converts the buckets of an exponential histogram to classic cumulative buckets, returned as
+/// `(upper bound, cumulative count)` pairs. Negative values and the zero bucket are all counted in
+/// the bucket with the zero threshold as its upper bound.
+fn exponential_buckets<T>(point: &ExponentialHistogramDataPoint<T>) -> Vec<(f64, u64)> {
+    let shift = point.scale().saturating_sub(MAX_BUCKET_SCALE).max(0) as u32;
+    let scale = point.scale().min(MAX_BUCKET_SCALE);
+    let base = 2f64.powf(2f64.powi(-(scale as i32)));
+
+    let mut cumulative = point.zero_count() + point.negative_bucket().counts().sum::<u64>();
+    let mut buckets = vec![(point.zero_threshold(), cumulative)];
+
+    let offset = point.positive_bucket().offset();
+
+    for (i, count) in point.positive_bucket().counts().enumerate() {
+        cumulative += count;
+
+        // Downscaling merges adjacent buckets, only the last one of each merged group is kept.
+        let index = (offset + i as i32) >> shift;
+        let upper_bound = base.powi(index + 1);
+
+        match buckets.last_mut() {
+            Some((bound, total)) if *bound == upper_bound => *total = cumulative,
+            _ => buckets.push((upper_bound, cumulative)),
+        }
+    }
+
+    buckets
+}
+
+fn write_header(name: &str, description: &str, kind: &str, output: &mut String) {
+    if !description.is_empty() {
+        let description = description.replace('\\', "\\\\").replace('\n', "\\n");
+        writeln!(output, "# HELP {name} {description}").unwrap();
+    }
+
+    writeln!(output, "# TYPE {name} {kind}").unwrap();
+}
+
+fn write_sample(
+    name: &str,
+    attributes: &[&KeyValue],
+    extra_label: Option<(&str, Cow<'_, str>)>,
+    value: f64,
+    output: &mut String,
+) {
+    output.push_str(name);
+
+    let mut labels = attributes
+        .iter()
+        .map(|kv| (sanitize_name(kv.key.as_str()), label_value(&kv.value)))
+        .chain(extra_label.map(|(key, value)| (Cow::Borrowed(key), value)))
+        .peekable();
+
+    if labels.peek().is_some() {
+        output.push('{');
+
+        for (i, (key, value)) in labels.enumerate() {
+            if i > 0 {
+                output.push(',');
+            }
+
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+
+            write!(output, "{key}=\"{value}\"").unwrap();
+        }
+
+        output.push('}');
+    }
+
+    writeln!(output, " {}", format_value(value)).unwrap();
+}
+
+fn label_value(value: &Value) -> Cow<'_, str> {
+    match value {
+        Value::String(value) => Cow::Borrowed(value.as_str()),
+        value => Cow::Owned(value.to_string()),
+    }
+}
+
+fn format_value(value: f64) -> Cow<'static, str> {
+    if value.is_nan() {
+        Cow::Borrowed("NaN")
+    } else if value == f64::INFINITY {
+        Cow::Borrowed("+Inf")
+    } else if value == f64::NEG_INFINITY {
+        Cow::Borrowed("-Inf")
+    } else {
+        Cow::Owned(value.to_string())
+    }
+}
+
+/// Prometheus metric names, e.g. `graphql.operation.duration` with `ms` as its unit becomes
+/// `graphql_operation_duration_milliseconds`.
+fn metric_name(name: &str, unit: &str) -> String {
+    let mut name = sanitize_name(name).into_owned();
+
+    let unit = match unit {
+        "ms" => "milliseconds",
+        "s" => "seconds",
+        "By" => "bytes",
+        _ => "",
+    };
+
+    if !unit.is_empty() && !name.ends_with(unit) {
+        name.push('_');
+        name.push_str(unit);
+    }
+
+    name
+}
+
+fn sanitize_name(name: &str) -> Cow<'_, str> {
+    let is_valid = |(i, c): (usize, char)| c.is_ascii_alphabetic() || c == '_' || (i > 0 && c.is_ascii_digit());
+
+    if name.chars().enumerate().all(is_valid) {
+        return Cow::Borrowed(name);
+    }
+
+    name.chars()
+        .enumerate()
+        .map(|(i, c)| if is_valid((i, c)) { c } else { '_' })
+        .collect::<String>()
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use opentelemetry::{KeyValue, metrics::MeterProvider};
+    use opentelemetry_sdk::metrics::SdkMeterProvider;
+
+    use super::PrometheusExporter;
+
+    #[test]
+    fn counter_escaping() {
+        let exporter = PrometheusExporter::default();
+        let provider = SdkMeterProvider::builder().with_reader(exporter.clone()).build();
+
+        let counter = provider
+            .meter("test")
+            .u64_counter("graphql.requests")
+            .with_description("Requests\nhandled by the \\gateway\\")
+            .build();
+
+        counter.add(5, &[KeyValue::new("client.name", "my \"app\"\\\nv2")]);
+
+        let expected = indoc! {r#"
+            # HELP graphql_requests_total Requests\nhandled by the \\gateway\\
+            # TYPE graphql_requests_total counter
+            graphql_requests_total{client_name="my \"app\"\\\nv2"} 5
+        "#};
+
+        assert_eq!(exporter.encode().unwrap(), expected);
+    }
+
+    #[test]
+    fn histogram_buckets() {
+        let exporter = PrometheusExporter::default();
+        let provider = SdkMeterProvider::builder().with_reader(exporter.clone()).build();
+
+        let histogram = provider
+            .meter("test")
+            .f64_histogram("request.duration")
+            .with_unit("ms")
+            .with_boundaries(vec![1.0, 5.0])
+            .build();
+
+        for value in [0.5, 3.0, 10.0] {
+            histogram.record(value, &[]);
+        }
+
+        let expected = indoc! {r#"
+            # TYPE request_duration_milliseconds histogram
+            request_duration_milliseconds_bucket{le="1"} 1
+            request_duration_milliseconds_bucket{le="5"} 2
+            request_duration_milliseconds_bucket{le="+Inf"} 3
+            request_duration_milliseconds_sum 13.5
+            request_duration_milliseconds_count 3
+        "#};
+
+        assert_eq!(exporter.encode().unwrap(), expected);
+    }
+
+    #[test]
+    fn exponential_histogram_buckets() {
+        let exporter = PrometheusExporter::default();
+        let provider = SdkMeterProvider::builder()
+            .with_reader(exporter.clone())
+            .with_view(crate::otel::metrics::agg_for_latency_histogram)
+            .build();
+
+        let histogram = provider.meter("test").u64_histogram("request.duration").build();
+
+        let values = [1, 3, 3, 10, 250, 4000];
+        for value in values {
+            histogram.record(value, &[]);
+        }
+
+        let output = exporter.encode().unwrap();
+
+        let buckets = output
+            .lines()
+            .filter_map(|line| line.strip_prefix("request_duration_bucket{le=\""))
+            .map(|line| {
+                let (bound, count) = line.split_once("\"} ").unwrap();
+                let bound = if bound == "+Inf" {
+                    f64::INFINITY
+                } else {
+                    bound.parse().unwrap()
+                };
+                (bound, count.parse::<u64>().unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        assert!(buckets.windows(2).all(|w| w[0].0 < w[1].0 && w[0].1 <= w[1].1));
+        assert_eq!(buckets.last(), Some(&(f64::INFINITY, values.len() as u64)));
+
+        // Downscaled buckets are at most √2 wide, and still count every value below their bound.
+        for (bound, count) in buckets.iter().filter(|(bound, _)| bound.is_finite() && *bound > 0.0) {
+            let expected = values.iter().filter(|value| **value as f64 <= *bound).count() as u64;
+            assert_eq!(*count, expected, "bucket le={bound}");
+        }
+
+        for value in values {
+            let (bound, _) = buckets.iter().find(|(bound, _)| value as f64 <= *bound).unwrap();
+            assert!(*bound < value as f64 * 1.42, "value {value} in bucket le={bound}");
+        }
+
+        assert!(output.contains("request_duration_sum 4267\n"));
+        assert!(output.contains("request_duration_count 6\n"));
+    }
+}
@@ -0,0 +1,63 @@
+mod b3;
+mod jaeger;
+
+pub use b3::{B3Encoding, B3Propagator};
+pub use jaeger::JaegerPropagator;
+
+use opentelemetry::{
+    Context,
+    trace::{SpanContext, SpanId, TraceContextExt, TraceId},
+};
+
+/// Set alongside remote span contexts which didn't carry a sampling decision, leaving it to the
+/// gateway. It's kept in the context rather than in the trace flags, whose bits all have a meaning
+/// in W3C trace context. Only set by the B3 propagator.
+#[derive(Debug, Clone, Copy)]
+struct DeferredSampling {
+    span_id: SpanId,
+}
+
+/// Adds the remote span context to the context, marking it as having left the sampling decision
+/// to the gateway.
+fn with_deferred_remote_span_context(cx: &Context, span_context: SpanContext) -> Context {
+    let deferred = DeferredSampling {
+        span_id: span_context.span_id(),
+    };
+
+    cx.with_remote_span_context(span_context).with_value(deferred)
+}
+
+/// Whether the remote parent left the sampling decision to the gateway. Another propagator may
+/// have extracted a different parent afterwards, so the marker must match the span.
+pub(crate) fn is_deferred(cx: &Context) -> bool {
+    let span = cx.span();
+    let span_context = span.span_context();
+
+    span_context.is_remote()
+        && cx
+            .get::<DeferredSampling>()
+            .is_some_and(|deferred| deferred.span_id == span_context.span_id())
+}
+
+/// Trace ids may be sent as 64-bit values, which are padded with zeros to 128 bits.
+fn parse_trace_id(value: &str) -> Option<TraceId> {
+    if value.is_empty() || value.len() > 32 {
+        return None;
+    }
+
+    u128::from_str_radix(value, 16)
+        .ok()
+        .map(|id| TraceId::from_bytes(id.to_be_bytes()))
+        .filter(|id| *id != TraceId::INVALID)
+}
+
+fn parse_span_id(value: &str) -> Option<SpanId> {
+    if value.is_empty() || value.len() > 16 {
+        return None;
+    }
+
+    u64::from_str_radix(value, 16)
+        .ok()
+        .map(|id| SpanId::from_bytes(id.to_be_bytes()))
+        .filter(|id| *id != SpanId::INVALID)
+}
@@ -0,0 +1,204 @@
+use std::sync::LazyLock;
+
+use opentelemetry::{
+    Context,
+    propagation::{Extractor, Injector, TextMapPropagator, text_map_propagator::FieldIter},
+    trace::{SpanContext, TraceContextExt, TraceFlags, TraceState},
+};
+
+use super::{parse_span_id, parse_trace_id, with_deferred_remote_span_context};
+
+const B3_SINGLE_HEADER: &str = "b3";
+const B3_TRACE_ID_HEADER: &str = "x-b3-traceid";
+const B3_SPAN_ID_HEADER: &str = "x-b3-spanid";
+const B3_SAMPLED_HEADER: &str = "x-b3-sampled";
+const B3_FLAGS_HEADER: &str = "x-b3-flags";
+
+static B3_SINGLE_FIELDS: LazyLock<[String; 1]> = LazyLock::new(|| [B3_SINGLE_HEADER.to_owned()]);
+static B3_MULTIPLE_FIELDS: LazyLock<[String; 4]> = LazyLock::new(|| {
+    [
+        B3_TRACE_ID_HEADER.to_owned(),
+        B3_SPAN_ID_HEADER.to_owned(),
+        B3_SAMPLED_HEADER.to_owned(),
+        B3_FLAGS_HEADER.to_owned(),
+    ]
+});
+
+/// The headers used by a [`B3Propagator`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum B3Encoding {
+    /// The `b3` header.
+    SingleHeader,
+    /// The `X-B3-TraceId`, `X-B3-SpanId`, `X-B3-Sampled` and `X-B3-Flags` headers.
+    MultipleHeader,
+}
+
+/// Propagates the trace context with the [Zipkin B3](https://github.com/openzipkin/b3-propagation) headers.
+#[derive(Clone, Debug)]
+pub struct B3Propagator {
+    encoding: B3Encoding,
+}
+
+impl B3Propagator {
+    pub fn new(encoding: B3Encoding) -> Self {
+        Self { encoding }
+    }
+
+    /// Returns the span context and whether it left the sampling decision to the gateway.
+    fn extract_single_header(extractor: &dyn Extractor) -> Option<(SpanContext, bool)> {
+        let value = extractor.get(B3_SINGLE_HEADER)?.trim();
+        let mut parts = value.split('-');
+
+        // The parent span id, the fourth part, isn't needed.
+        let trace_id = parse_trace_id(parts.next()?)?;
+        let span_id = parse_span_id(parts.next()?)?;
+        let (flags, deferred) = match parts.next() {
+            Some("1" | "d") => (TraceFlags::SAMPLED, false),
+            Some("0") => (TraceFlags::NOT_SAMPLED, false),
+            // Without a sampling decision, the gateway takes its own.
+            None => (TraceFlags::NOT_SAMPLED, true),
+            Some(_) => return None,
+        };
+
+        let span_context = SpanContext::new(trace_id, span_id, flags, true, TraceState::default());
+        Some((span_context, deferred))
+    }
+
+    fn extract_multiple_header(extractor: &dyn Extractor) -> Option<(SpanContext, bool)> {
+        let trace_id = parse_trace_id(extractor.get(B3_TRACE_ID_HEADER)?.trim())?;
+        let span_id = parse_span_id(extractor.get(B3_SPAN_ID_HEADER)?.trim())?;
+
+        // Debug implies sampled.
+        let debug = extractor.get(B3_FLAGS_HEADER).map(str::trim) == Some("1");
+        let (flags, deferred) = match extractor.get(B3_SAMPLED_HEADER).map(str::trim) {
+            _ if debug => (TraceFlags::SAMPLED, false),
+            Some("1" | "true") => (TraceFlags::SAMPLED, false),
+            Some("0" | "false") => (TraceFlags::NOT_SAMPLED, false),
+            // Without a sampling decision, the gateway takes its own.
+            None => (TraceFlags::NOT_SAMPLED, true),
+            Some(_) => return None,
+        };
+
+        let span_context = SpanContext::new(trace_id, span_id, flags, true, TraceState::default());
+        Some((span_context, deferred))
+    }
+}
+
+impl TextMapPropagator for B3Propagator {
+    fn inject_context(&self, cx: &Context, injector: &mut dyn Injector) {
+        let span = cx.span();
+        let span_context = span.span_context();
+
+        if !span_context.is_valid() {
+            return;
+        }
+
+        let sampled = if span_context.is_sampled() { "1" } else { "0" };
+
+        match self.encoding {
+            B3Encoding::SingleHeader => {
+                let value = format!("{}-{}-{sampled}", span_context.trace_id(), span_context.span_id());
+
+                injector.set(B3_SINGLE_HEADER, value);
+            }
+            B3Encoding::MultipleHeader => {
+                injector.set(B3_TRACE_ID_HEADER, span_context.trace_id().to_string());
+                injector.set(B3_SPAN_ID_HEADER, span_context.span_id().to_string());
+                injector.set(B3_SAMPLED_HEADER, sampled.to_owned());
+            }
+        }
+    }
+
+    fn extract_with_context(&self, cx: &Context, extractor: &dyn Extractor) -> Context {
+        let span_context = match self.encoding {
+            B3Encoding::SingleHeader => Self::extract_single_header(extractor),
+            B3Encoding::MultipleHeader => Self::extract_multiple_header(extractor),
+        };
+
+        match span_context {
+            Some((span_context, true)) => with_deferred_remote_span_context(cx, span_context),
+            Some((span_context, false)) => cx.with_remote_span_context(span_context),
+            None => cx.clone(),
+        }
+    }
+
+    fn fields(&self) -> FieldIter<'_> {
+        match self.encoding {
+            B3Encoding::SingleHeader => FieldIter::new(B3_SINGLE_FIELDS.as_ref()),
+            B3Encoding::MultipleHeader => FieldIter::new(B3_MULTIPLE_FIELDS.as_ref()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use opentelemetry::{
+        Context,
+        propagation::TextMapPropagator,
+        trace::{TraceContextExt, TraceFlags},
+    };
+
+    use super::{B3Encoding, B3Propagator};
+    use crate::otel::propagation::is_deferred;
+
+    const TRACE_ID: &str = "80f198ee56343ba864fe8b2a57d3eff7";
+    const SPAN_ID: &str = "e457b5a2e4d86bd1";
+
+    fn extract(encoding: B3Encoding, headers: &[(&str, &str)]) -> Context {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+
+        B3Propagator::new(encoding).extract(&headers)
+    }
+
+    #[test]
+    fn single_header_sampling() {
+        let cx = extract(
+            B3Encoding::SingleHeader,
+            &[("b3", format!("{TRACE_ID}-{SPAN_ID}-1").as_str())],
+        );
+        assert!(cx.span().span_context().is_sampled());
+
+        let cx = extract(
+            B3Encoding::SingleHeader,
+            &[("b3", format!("{TRACE_ID}-{SPAN_ID}-0").as_str())],
+        );
+        let span = cx.span();
+        assert_eq!(span.span_context().trace_flags(), TraceFlags::NOT_SAMPLED);
+        assert!(!is_deferred(&cx));
+
+        let cx = extract(
+            B3Encoding::SingleHeader,
+            &[("b3", format!("{TRACE_ID}-{SPAN_ID}").as_str())],
+        );
+        let span = cx.span();
+        assert!(!span.span_context().is_sampled());
+        assert!(is_deferred(&cx));
+    }
+
+    #[test]
+    fn multiple_header_sampling() {
+        let ids = [("x-b3-traceid", TRACE_ID), ("x-b3-spanid", SPAN_ID)];
+
+        let cx = extract(B3Encoding::MultipleHeader, &[ids[0], ids[1], ("x-b3-sampled", "1")]);
+        assert!(cx.span().span_context().is_sampled());
+
+        let cx = extract(B3Encoding::MultipleHeader, &[ids[0], ids[1], ("x-b3-flags", "1")]);
+        assert!(cx.span().span_context().is_sampled());
+
+        let cx = extract(B3Encoding::MultipleHeader, &[ids[0], ids[1], ("x-b3-sampled", "0")]);
+        let span = cx.span();
+        assert_eq!(span.span_context().trace_flags(), TraceFlags::NOT_SAMPLED);
+        assert!(!is_deferred(&cx));
+
+        let cx = extract(B3Encoding::MultipleHeader, &ids);
+        let span = cx.span();
+        assert!(span.span_context().is_valid());
+        assert!(!span.span_context().is_sampled());
+        assert!(is_deferred(&cx));
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::LazyLock;
+
+use opentelemetry::{
+    Context,
+    propagation::{Extractor, Injector, TextMapPropagator, text_map_propagator::FieldIter},
+    trace::{SpanContext, TraceContextExt, TraceFlags, TraceState},
+};
+
+use super::{parse_span_id, parse_trace_id};
+
+const JAEGER_HEADER: &str = "uber-trace-id";
+const JAEGER_SAMPLED_FLAG: u8 = 0x01;
+const JAEGER_DEBUG_FLAG: u8 = 0x02;
+
+static JAEGER_FIELDS: LazyLock<[String; 1]> = LazyLock::new(|| [JAEGER_HEADER.to_owned()]);
+
+/// Propagates the trace context with the [Jaeger](https://www.jaegertracing.io/docs/1.52/client-libraries/#propagation-format)
+/// `uber-trace-id` header.
+#[derive(Clone, Debug, Default)]
+pub struct JaegerPropagator;
+
+impl JaegerPropagator {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn extract_span_context(extractor: &dyn Extractor) -> Option<SpanContext> {
+        let value = extractor.get(JAEGER_HEADER)?.trim();
+
+        // Some clients URL encode the separators.
+        let value = value.replace("%3A", ":").replace("%3a", ":");
+        let mut parts = value.split(':');
+
+        let trace_id = parse_trace_id(parts.next()?)?;
+        let span_id = parse_span_id(parts.next()?)?;
+        // The parent span id is deprecated and ignored.
+        parts.next()?;
+        let flags = u8::from_str_radix(parts.next()?, 16).ok()?;
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let flags = if flags & (JAEGER_SAMPLED_FLAG | JAEGER_DEBUG_FLAG) != 0 {
+            TraceFlags::SAMPLED
+        } else {
+            TraceFlags::NOT_SAMPLED
+        };
+
+        Some(SpanContext::new(trace_id, span_id, flags, true, TraceState::default()))
+    }
+}
+
+impl TextMapPropagator for JaegerPropagator {
+    fn inject_context(&self, cx: &Context, injector: &mut dyn Injector) {
+        let span = cx.span();
+        let span_context = span.span_context();
+
+        if !span_context.is_valid() {
+            return;
+        }
+
+        let flags = if span_context.is_sampled() {
+            JAEGER_SAMPLED_FLAG
+        } else {
+            0
+        };
+        let value = format!("{}:{}:0:{flags}", span_context.trace_id(), span_context.span_id());
+
+        injector.set(JAEGER_HEADER, value);
+    }
+
+    fn extract_with_context(&self, cx: &Context, extractor: &dyn Extractor) -> Context {
+        match Self::extract_span_context(extractor) {
+            Some(span_context) => cx.with_remote_span_context(span_context),
+            None => cx.clone(),
+        }
+    }
+
+    fn fields(&self) -> FieldIter<'_> {
+        FieldIter::new(JAEGER_FIELDS.as_ref())
+    }
+}
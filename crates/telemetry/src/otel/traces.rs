@@ -14,6 +14,10 @@ use crate::{
     error::TracingError,
 };
 
+mod sampling;
+
+use sampling::{ParentBasedSampler, SamplingSpanProcessor};
+
 pub(super) fn build_trace_provider<I>(
     id_generator: I,
     config: &TelemetryConfig,
@@ -22,11 +26,29 @@ pub(super) fn build_trace_provider<I>(
 where
     I: IdGenerator + 'static,
 {
-    let base_sampler = Sampler::TraceIdRatioBased(config.tracing.sampling);
+    // Rules and tail sampling need the whole trace, the decision is taken by the span processor.
+    // Rule ratios are applied on the trace id like the head sampler does, so only traces within
+    // the highest ratio need to be recorded. Tail sampling may keep any trace though.
+    let base_sampler = if config.tracing.tail_sampling.enabled {
+        Sampler::AlwaysOn
+    } else {
+        let max_sampling = config
+            .tracing
+            .sampling_rules
+            .iter()
+            .map(|rule| rule.sampling)
+            .fold(config.tracing.sampling, f64::max);
+
+        Sampler::TraceIdRatioBased(max_sampling)
+    };
+
     let mut builder = TracerProviderBuilder::default().with_id_generator(id_generator);
 
     if config.tracing.parent_based_sampler {
-        builder = builder.with_sampler(Sampler::ParentBased(Box::new(base_sampler)));
+        builder = builder.with_sampler(ParentBasedSampler::new(
+            base_sampler,
+            config.tracing.is_sampled_after_request(),
+        ));
     } else {
         builder = builder.with_sampler(base_sampler);
     }
@@ -37,13 +59,22 @@ where
         .with_max_events_per_span(config.tracing.collect.max_events_per_span as u32)
         .with_resource(resource);
 
-    Ok(setup_exporters(builder, config)?.build())
+    let processors = setup_exporters(config)?;
+
+    if config.tracing.is_sampled_after_request() {
+        builder = builder.with_span_processor(SamplingSpanProcessor::new(&config.tracing, processors));
+    } else {
+        for processor in processors {
+            builder = builder.with_span_processor(processor);
+        }
+    }
+
+    Ok(builder.build())
 }
 
-fn setup_exporters(
-    mut tracer_provider_builder: TracerProviderBuilder,
-    config: &TelemetryConfig,
-) -> Result<TracerProviderBuilder, TracingError> {
+fn setup_exporters(config: &TelemetryConfig) -> Result<Vec<BatchSpanProcessor>, TracingError> {
+    let mut processors = Vec::new();
+
     // stdout
     if let Some(stdout_exporter) = config.tracing_stdout_config() {
         let span_processor = build_batched_span_processor(
@@ -52,7 +83,7 @@ fn setup_exporters(
             opentelemetry_stdout::SpanExporter::default(),
         );
 
-        tracer_provider_builder = tracer_provider_builder.with_span_processor(span_processor);
+        processors.push(span_processor);
     }
 
     use super::exporter::{build_metadata, build_tls_config};
@@ -118,7 +149,7 @@ fn setup_exporters(
             span_exporter,
         );
 
-        tracer_provider_builder = tracer_provider_builder.with_span_processor(span_processor);
+        processors.push(span_processor);
     }
 
     if let Some(config) = config.grafbase_otlp_config() {
@@ -126,10 +157,10 @@ fn setup_exporters(
 
         let span_processor = build_batched_span_processor(config.timeout(), &config.batch_export(), span_exporter);
 
-        tracer_provider_builder = tracer_provider_builder.with_span_processor(span_processor);
+        processors.push(span_processor);
     }
 
-    Ok(tracer_provider_builder)
+    Ok(processors)
 }
 
 fn build_batched_span_processor(
@@ -0,0 +1,603 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use gateway_config::{SamplingRuleConfig, TailSamplingConfig, TracingConfig};
+use opentelemetry::{
+    Context, KeyValue, Value,
+    trace::{Link, SamplingResult, SpanId, SpanKind, Status, TraceId},
+};
+use opentelemetry_sdk::{
+    Resource,
+    error::{OTelSdkError, OTelSdkResult},
+    trace::{BatchSpanProcessor, Sampler, ShouldSample, Span, SpanData, SpanProcessor},
+};
+
+use crate::{otel::propagation::is_deferred, span::kind::GrafbaseSpanKind};
+
+/// Marks the request spans whose remote parent deferred the sampling decision, so that the
+/// sampling processor doesn't keep them just for having a parent. Removed before export.
+const DEFERRED_PARENT_ATTRIBUTE: &str = "grafbase.sampling.deferred_parent";
+
+/// Follows the sampling decision of the parent span, unless a remote parent deferred it to the
+/// gateway, in which case the root sampler decides.
+#[derive(Debug, Clone)]
+pub(super) struct ParentBasedSampler {
+    root: Sampler,
+    parent_based: Sampler,
+    /// Whether the sampling processor needs to know about deferred parents.
+    mark_deferred: bool,
+}
+
+impl ParentBasedSampler {
+    pub fn new(root: Sampler, mark_deferred: bool) -> Self {
+        Self {
+            parent_based: Sampler::ParentBased(Box::new(root.clone())),
+            root,
+            mark_deferred,
+        }
+    }
+}
+
+impl ShouldSample for ParentBasedSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        let deferred = parent_context.is_some_and(is_deferred);
+
+        if !deferred {
+            return self
+                .parent_based
+                .should_sample(parent_context, trace_id, name, span_kind, attributes, links);
+        }
+
+        let mut result = self
+            .root
+            .should_sample(parent_context, trace_id, name, span_kind, attributes, links);
+
+        if self.mark_deferred {
+            result.attributes.push(KeyValue::new(DEFERRED_PARENT_ATTRIBUTE, true));
+        }
+
+        result
+    }
+}
+
+/// Spans end on every thread, so traces are spread over independently locked shards.
+const SHARDS: usize = 16;
+
+/// Sampling decisions are kept for spans ending after the request span.
+const MAX_DECIDED_TRACES_PER_SHARD: usize = 64;
+
+/// Buffers the spans of each trace until the request span ends, and only then decides whether the
+/// trace is exported, based on the sampling rules and the tail sampling configuration. The head
+/// sampler must record every trace which could be sampled for this to work.
+#[derive(Debug)]
+pub(super) struct SamplingSpanProcessor<P = BatchSpanProcessor> {
+    processors: Vec<P>,
+    sampler: TraceSampler,
+    max_buffered_traces_per_shard: usize,
+    shards: [Mutex<State>; SHARDS],
+}
+
+impl<P: SpanProcessor> SamplingSpanProcessor<P> {
+    pub fn new(config: &TracingConfig, processors: Vec<P>) -> Self {
+        Self {
+            processors,
+            sampler: TraceSampler {
+                sampling: config.sampling,
+                parent_based: config.parent_based_sampler,
+                rules: config.sampling_rules.clone(),
+                tail: config.tail_sampling.clone(),
+            },
+            max_buffered_traces_per_shard: config.tail_sampling.max_buffered_traces.div_ceil(SHARDS),
+            shards: Default::default(),
+        }
+    }
+
+    fn shard(&self, trace_id: TraceId) -> &Mutex<State> {
+        // Trace ids are random, any byte spreads them evenly.
+        &self.shards[trace_id.to_bytes()[15] as usize % SHARDS]
+    }
+
+    fn export(&self, spans: impl IntoIterator<Item = SpanData>) {
+        for span in spans {
+            if let Some((last, rest)) = self.processors.split_last() {
+                for processor in rest {
+                    processor.on_end(span.clone());
+                }
+
+                last.on_end(span);
+            }
+        }
+    }
+}
+
+impl<P: SpanProcessor> SpanProcessor for SamplingSpanProcessor<P> {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        for processor in &self.processors {
+            processor.on_start(span, cx);
+        }
+    }
+
+    fn on_end(&self, mut span: SpanData) {
+        let trace_id = span.span_context.trace_id();
+        let mut state = self.shard(trace_id).lock().unwrap();
+
+        if let Some(&sampled) = state.decided.get(&trace_id) {
+            drop(state);
+
+            if sampled {
+                self.export([span]);
+            }
+
+            return;
+        }
+
+        if !is_request_span(&span) {
+            state.buffer(span, self.max_buffered_traces_per_shard);
+            return;
+        }
+
+        let mut spans = state
+            .pending
+            .remove(&trace_id)
+            .map(|trace| trace.spans)
+            .unwrap_or_default();
+        let sampled = self.sampler.should_sample(&span, &spans);
+        span.attributes
+            .retain(|kv| kv.key.as_str() != DEFERRED_PARENT_ATTRIBUTE);
+
+        state.decide(trace_id, sampled);
+        drop(state);
+
+        if sampled {
+            spans.push(span);
+            self.export(spans);
+        }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        collect_errors(self.processors.iter().map(|processor| processor.force_flush()))
+    }
+
+    fn shutdown_with_timeout(&self, timeout: Duration) -> OTelSdkResult {
+        collect_errors(
+            self.processors
+                .iter()
+                .map(|processor| processor.shutdown_with_timeout(timeout)),
+        )
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        for processor in &mut self.processors {
+            processor.set_resource(resource);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    pending: HashMap<TraceId, PendingTrace>,
+    decided: HashMap<TraceId, bool>,
+    decided_order: VecDeque<TraceId>,
+}
+
+#[derive(Debug)]
+struct PendingTrace {
+    spans: Vec<SpanData>,
+    created_at: Instant,
+}
+
+impl State {
+    fn buffer(&mut self, span: SpanData, max_traces: usize) {
+        let trace_id = span.span_context.trace_id();
+
+        if !self.pending.contains_key(&trace_id) && self.pending.len() >= max_traces {
+            // Traces whose request never ended would otherwise stay here forever.
+            let oldest = self
+                .pending
+                .iter()
+                .min_by_key(|(_, trace)| trace.created_at)
+                .map(|(trace_id, _)| *trace_id);
+
+            match oldest {
+                Some(oldest) => {
+                    tracing::debug!("Too many traces buffered for sampling, dropping trace {oldest}");
+                    self.pending.remove(&oldest);
+                }
+                None => return,
+            }
+        }
+
+        self.pending
+            .entry(trace_id)
+            .or_insert_with(|| PendingTrace {
+                spans: Vec::new(),
+                created_at: Instant::now(),
+            })
+            .spans
+            .push(span);
+    }
+
+    fn decide(&mut self, trace_id: TraceId, sampled: bool) {
+        if self.decided_order.len() >= MAX_DECIDED_TRACES_PER_SHARD
+            && let Some(oldest) = self.decided_order.pop_front()
+        {
+            self.decided.remove(&oldest);
+        }
+
+        self.decided.insert(trace_id, sampled);
+        self.decided_order.push_back(trace_id);
+    }
+}
+
+#[derive(Debug)]
+struct TraceSampler {
+    sampling: f64,
+    parent_based: bool,
+    rules: Vec<SamplingRuleConfig>,
+    tail: TailSamplingConfig,
+}
+
+impl TraceSampler {
+    fn should_sample(&self, root: &SpanData, spans: &[SpanData]) -> bool {
+        // With a parent based sampler, only sampled parents reach this point unless they deferred
+        // the decision to the gateway.
+        if self.parent_based && root.parent_span_id != SpanId::INVALID && !has_deferred_parent(root) {
+            return true;
+        }
+
+        let all_spans = || spans.iter().chain(std::iter::once(root));
+
+        if self.tail.enabled {
+            if self.tail.errors && all_spans().any(has_error) {
+                return true;
+            }
+
+            let duration = root.end_time.duration_since(root.start_time).unwrap_or_default();
+
+            if self
+                .tail
+                .latency_threshold
+                .is_some_and(|threshold| duration >= threshold)
+            {
+                return true;
+            }
+        }
+
+        let ratio = self
+            .rules
+            .iter()
+            .find(|rule| {
+                let matches = |key: &str, expected: &Option<String>| match expected {
+                    Some(expected) => all_spans().any(|span| string_attribute(span, key) == Some(expected.as_str())),
+                    None => true,
+                };
+
+                matches("graphql.operation.name", &rule.operation_name)
+                    && matches("http.request.header.x-grafbase-client-name", &rule.client_name)
+                    && matches("subgraph.name", &rule.subgraph)
+            })
+            .map(|rule| rule.sampling)
+            .unwrap_or(self.sampling);
+
+        sample_trace_id(root.span_context.trace_id(), ratio)
+    }
+}
+
+/// The request span ends last, or any span without a parent when outside of a request.
+fn is_request_span(span: &SpanData) -> bool {
+    span.parent_span_id == SpanId::INVALID
+        || string_attribute(span, "grafbase.kind") == Some(GrafbaseSpanKind::HttpRequest.as_ref())
+}
+
+fn has_deferred_parent(span: &SpanData) -> bool {
+    span.attributes
+        .iter()
+        .any(|kv| kv.key.as_str() == DEFERRED_PARENT_ATTRIBUTE)
+}
+
+fn has_error(span: &SpanData) -> bool {
+    matches!(span.status, Status::Error { .. })
+        || span.attributes.iter().any(|kv| {
+            kv.key.as_str() == "graphql.response.errors.count" && matches!(kv.value, Value::I64(count) if count > 0)
+        })
+}
+
+fn string_attribute<'a>(span: &'a SpanData, key: &str) -> Option<&'a str> {
+    span.attributes
+        .iter()
+        .find(|kv| kv.key.as_str() == key)
+        .and_then(|kv| match &kv.value {
+            Value::String(value) => Some(value.as_str()),
+            _ => None,
+        })
+}
+
+/// Same decision as the `TraceIdRatioBased` sampler, so that ratios are consistent across services.
+fn sample_trace_id(trace_id: TraceId, ratio: f64) -> bool {
+    if ratio >= 1.0 {
+        return true;
+    }
+
+    let upper_bound = (ratio.max(0.0) * (1u64 << 63) as f64) as u64;
+    let bytes = trace_id.to_bytes();
+    let low = u64::from_be_bytes(bytes[8..].try_into().unwrap());
+
+    (low >> 1) < upper_bound
+}
+
+fn collect_errors(results: impl Iterator<Item = OTelSdkResult>) -> OTelSdkResult {
+    let errors = results
+        .filter_map(Result::err)
+        .map(|err| err.to_string())
+        .collect::<Vec<_>>();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(OTelSdkError::InternalFailure(errors.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::{Duration, SystemTime},
+    };
+
+    use gateway_config::{SamplingRuleConfig, TailSamplingConfig, TracingConfig};
+    use opentelemetry::{
+        Context, KeyValue,
+        propagation::TextMapPropagator,
+        trace::{
+            SamplingDecision, Span as _, SpanKind, Status, TraceContextExt, TraceId, Tracer as _, TracerProvider as _,
+        },
+    };
+    use opentelemetry_sdk::{
+        error::OTelSdkResult,
+        propagation::TraceContextPropagator,
+        trace::{Sampler, SdkTracerProvider, ShouldSample, Span, SpanData, SpanProcessor},
+    };
+
+    use super::{DEFERRED_PARENT_ATTRIBUTE, ParentBasedSampler, SamplingSpanProcessor};
+    use crate::otel::propagation::{B3Encoding, B3Propagator};
+
+    const TRACE_ID: &str = "80f198ee56343ba864fe8b2a57d3eff7";
+    const SPAN_ID: &str = "e457b5a2e4d86bd1";
+
+    #[derive(Debug, Clone, Default)]
+    struct Exported(Arc<Mutex<Vec<String>>>);
+
+    impl SpanProcessor for Exported {
+        fn on_start(&self, _: &mut Span, _: &Context) {}
+
+        fn on_end(&self, mut span: SpanData) {
+            self.0.lock().unwrap().push(span.name.into_owned());
+        }
+
+        fn force_flush(&self) -> OTelSdkResult {
+            Ok(())
+        }
+
+        fn shutdown_with_timeout(&self, _: Duration) -> OTelSdkResult {
+            Ok(())
+        }
+    }
+
+    struct Request {
+        operation_name: &'static str,
+        subgraph: &'static str,
+        error: bool,
+        duration: Duration,
+    }
+
+    impl Default for Request {
+        fn default() -> Self {
+            Self {
+                operation_name: "GetProducts",
+                subgraph: "products",
+                error: false,
+                duration: Duration::from_millis(10),
+            }
+        }
+    }
+
+    /// Runs a request with a single subgraph request, returning the names of the exported spans.
+    fn run(config: &TracingConfig, request: Request) -> Vec<String> {
+        let exported = Exported::default();
+        let provider = SdkTracerProvider::builder()
+            .with_span_processor(SamplingSpanProcessor::new(config, vec![exported.clone()]))
+            .build();
+        let tracer = provider.tracer("test");
+
+        let start = SystemTime::now() - request.duration;
+
+        let root = tracer
+            .span_builder("request")
+            .with_start_time(start)
+            .with_attributes([KeyValue::new("graphql.operation.name", request.operation_name)])
+            .start(&tracer);
+        let cx = Context::current_with_span(root);
+
+        let mut subgraph = tracer
+            .span_builder("subgraph")
+            .with_attributes([KeyValue::new("subgraph.name", request.subgraph)])
+            .start_with_context(&tracer, &cx);
+
+        if request.error {
+            subgraph.set_status(Status::error("subgraph request failed"));
+        }
+
+        subgraph.end();
+        cx.span().end();
+
+        exported.0.lock().unwrap().clone()
+    }
+
+    fn tracing_config(sampling: f64, rules: Vec<SamplingRuleConfig>, tail: TailSamplingConfig) -> TracingConfig {
+        TracingConfig {
+            sampling,
+            sampling_rules: rules,
+            tail_sampling: tail,
+            ..Default::default()
+        }
+    }
+
+    fn rule(operation_name: Option<&str>, subgraph: Option<&str>, sampling: f64) -> SamplingRuleConfig {
+        SamplingRuleConfig {
+            operation_name: operation_name.map(str::to_string),
+            client_name: None,
+            subgraph: subgraph.map(str::to_string),
+            sampling,
+        }
+    }
+
+    #[test]
+    fn rules_match_attributes_of_any_span() {
+        let config = tracing_config(0.0, vec![rule(None, Some("products"), 1.0)], Default::default());
+
+        assert_eq!(run(&config, Request::default()), ["subgraph", "request"]);
+
+        let request = Request {
+            subgraph: "accounts",
+            ..Default::default()
+        };
+        assert!(run(&config, request).is_empty());
+    }
+
+    #[test]
+    fn rules_require_all_conditions() {
+        let config = tracing_config(
+            0.0,
+            vec![rule(Some("GetProducts"), Some("accounts"), 1.0)],
+            Default::default(),
+        );
+
+        assert!(run(&config, Request::default()).is_empty());
+
+        let request = Request {
+            subgraph: "accounts",
+            ..Default::default()
+        };
+        assert_eq!(run(&config, request), ["subgraph", "request"]);
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let config = tracing_config(
+            1.0,
+            vec![rule(Some("GetProducts"), None, 0.0), rule(None, Some("products"), 1.0)],
+            Default::default(),
+        );
+
+        assert!(run(&config, Request::default()).is_empty());
+
+        let request = Request {
+            operation_name: "GetAccounts",
+            ..Default::default()
+        };
+        assert_eq!(run(&config, request), ["subgraph", "request"]);
+    }
+
+    #[test]
+    fn tail_sampling_keeps_failed_requests() {
+        let tail = TailSamplingConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let config = tracing_config(0.0, Vec::new(), tail.clone());
+
+        assert!(run(&config, Request::default()).is_empty());
+
+        let request = Request {
+            error: true,
+            ..Default::default()
+        };
+        assert_eq!(run(&config, request), ["subgraph", "request"]);
+
+        let config = tracing_config(0.0, Vec::new(), TailSamplingConfig { errors: false, ..tail });
+
+        let request = Request {
+            error: true,
+            ..Default::default()
+        };
+        assert!(run(&config, request).is_empty());
+    }
+
+    #[test]
+    fn tail_sampling_keeps_slow_requests() {
+        let tail = TailSamplingConfig {
+            enabled: true,
+            latency_threshold: Some(Duration::from_secs(1)),
+            ..Default::default()
+        };
+        let config = tracing_config(0.0, Vec::new(), tail);
+
+        assert!(run(&config, Request::default()).is_empty());
+
+        let request = Request {
+            duration: Duration::from_secs(2),
+            ..Default::default()
+        };
+        assert_eq!(run(&config, request), ["subgraph", "request"]);
+    }
+
+    #[test]
+    fn sampling_ratio_applies_without_matching_rule() {
+        let config = tracing_config(1.0, vec![rule(Some("GetAccounts"), None, 0.0)], Default::default());
+        assert_eq!(run(&config, Request::default()), ["subgraph", "request"]);
+
+        let config = tracing_config(0.0, vec![rule(Some("GetAccounts"), None, 1.0)], Default::default());
+        assert!(run(&config, Request::default()).is_empty());
+    }
+
+    fn sample_request(sampler: &ParentBasedSampler, parent: &Context) -> (SamplingDecision, bool) {
+        let trace_id = TraceId::from_hex(TRACE_ID).unwrap();
+        let result = sampler.should_sample(Some(parent), trace_id, "request", &SpanKind::Server, &[], &[]);
+        let deferred = result
+            .attributes
+            .iter()
+            .any(|kv| kv.key.as_str() == DEFERRED_PARENT_ATTRIBUTE);
+
+        (result.decision, deferred)
+    }
+
+    #[test]
+    fn w3c_parent_with_random_flag_stays_sampled() {
+        let headers = HashMap::from([("traceparent".to_string(), format!("00-{TRACE_ID}-{SPAN_ID}-03"))]);
+        let parent = TraceContextPropagator::new().extract(&headers);
+
+        let sampler = ParentBasedSampler::new(Sampler::AlwaysOff, true);
+        assert_eq!(
+            sample_request(&sampler, &parent),
+            (SamplingDecision::RecordAndSample, false)
+        );
+    }
+
+    #[test]
+    fn b3_parent_without_decision_is_sampled_by_the_gateway() {
+        let headers = HashMap::from([("b3".to_string(), format!("{TRACE_ID}-{SPAN_ID}"))]);
+        let parent = B3Propagator::new(B3Encoding::SingleHeader).extract(&headers);
+
+        let sampler = ParentBasedSampler::new(Sampler::AlwaysOn, true);
+        assert_eq!(
+            sample_request(&sampler, &parent),
+            (SamplingDecision::RecordAndSample, true)
+        );
+
+        let sampler = ParentBasedSampler::new(Sampler::AlwaysOff, true);
+        assert_eq!(sample_request(&sampler, &parent), (SamplingDecision::Drop, true));
+    }
+}
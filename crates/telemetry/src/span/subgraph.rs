@@ -1,14 +1,14 @@
 use tracing::{Span, field::Empty, info_span};
 use url::Url;
 
-use crate::graphql::SubgraphResponseStatus;
+use crate::graphql::{OperationType, SubgraphResponseStatus};
 
 use super::{graphql::record_graphql_response_status, kind::GrafbaseSpanKind};
 
 /// A span for a subgraph request
 pub struct SubgraphRequestSpanBuilder<'a> {
     pub subgraph_name: &'a str,
-    pub operation_type: &'static str,
+    pub operation_type: OperationType,
     pub sanitized_query: &'a str,
 }
 
@@ -37,7 +37,7 @@ impl SubgraphRequestSpanBuilder<'_> {
             "otel.kind" = "Client",
             "otel.status_code" = Empty,
             "subgraph.name" = self.subgraph_name,
-            "graphql.operation.type" = self.operation_type,
+            "graphql.operation.type" = self.operation_type.as_str(),
             "graphql.operation.document" = self.sanitized_query,
             // "Describes a class of error the operation ended with."
             "error.type" = Empty,
@@ -0,0 +1,259 @@
+use ::redis::{Cmd, Msg, Pipeline, Value};
+use dashmap::Entry;
+use futures::StreamExt;
+use wasmtime::component::Resource;
+
+use crate::{InstanceState, resources::new_redis_pool};
+
+pub use super::grafbase::sdk::redis::*;
+
+impl Host for InstanceState {}
+
+impl HostRedisPool for InstanceState {
+    async fn connect(&mut self, name: String, url: String) -> wasmtime::Result<Result<Resource<RedisPool>, String>> {
+        if !self.is_network_enabled() {
+            return Ok(Err("Network operations are disabled".to_string()));
+        }
+
+        let pool = match self.redis_pools.entry(name) {
+            Entry::Occupied(occupied_entry) => {
+                let (pool_url, pool) = occupied_entry.get();
+
+                // The URL isn't part of the error, it may contain credentials.
+                if *pool_url != url {
+                    return Ok(Err(format!(
+                        "The Redis pool {} is already connected to a different URL",
+                        occupied_entry.key()
+                    )));
+                }
+
+                pool.clone()
+            }
+            Entry::Vacant(vacant_entry) => {
+                // Certificates come from the gateway configuration, extensions can't choose which
+                // files the gateway reads.
+                let tls = self
+                    .config
+                    .redis
+                    .get(vacant_entry.key())
+                    .and_then(|config| config.tls.as_ref());
+
+                let pool = match new_redis_pool(&url, tls) {
+                    Ok(pool) => pool,
+                    Err(err) => return Ok(Err(format!("{err:#}"))),
+                };
+
+                vacant_entry.insert((url, pool.clone()));
+
+                pool
+            }
+        };
+
+        Ok(Ok(self.resources.push(pool)?))
+    }
+
+    async fn execute(
+        &mut self,
+        self_: Resource<RedisPool>,
+        command: RedisCommand,
+    ) -> wasmtime::Result<Result<Vec<RedisValue>, String>> {
+        let pool = self.resources.get(&self_)?;
+
+        let mut connection = match pool.get().await {
+            Ok(connection) => connection,
+            Err(err) => return Ok(Err(err.to_string())),
+        };
+
+        let value = match into_cmd(command).query_async::<Value>(&mut *connection).await {
+            Ok(value) => value,
+            Err(err) => return Ok(Err(err.to_string())),
+        };
+
+        let mut values = Vec::new();
+
+        Ok(flatten(value, &mut values).map(|_| values))
+    }
+
+    async fn pipeline(
+        &mut self,
+        self_: Resource<RedisPool>,
+        commands: Vec<RedisCommand>,
+        atomic: bool,
+    ) -> wasmtime::Result<Result<Vec<RedisValue>, String>> {
+        let pool = self.resources.get(&self_)?;
+
+        let mut connection = match pool.get().await {
+            Ok(connection) => connection,
+            Err(err) => return Ok(Err(err.to_string())),
+        };
+
+        let mut pipeline = Pipeline::with_capacity(commands.len());
+
+        if atomic {
+            pipeline.atomic();
+        }
+
+        for command in commands {
+            pipeline.add_command(into_cmd(command));
+        }
+
+        let results = match pipeline.query_async::<Vec<Value>>(&mut *connection).await {
+            Ok(results) => results,
+            Err(err) => return Ok(Err(err.to_string())),
+        };
+
+        let mut values = Vec::new();
+
+        for value in results {
+            if let Err(err) = flatten(value, &mut values) {
+                return Ok(Err(err));
+            }
+        }
+
+        Ok(Ok(values))
+    }
+
+    async fn subscribe(
+        &mut self,
+        self_: Resource<RedisPool>,
+        channels: Vec<String>,
+        patterns: Vec<String>,
+    ) -> wasmtime::Result<Result<Resource<RedisSubscriber>, String>> {
+        let pool = self.resources.get(&self_)?;
+
+        let mut pubsub = match pool.manager().pubsub().await {
+            Ok(pubsub) => pubsub,
+            Err(err) => return Ok(Err(err.to_string())),
+        };
+
+        for channel in channels {
+            if let Err(err) = pubsub.subscribe(channel).await {
+                return Ok(Err(err.to_string()));
+            }
+        }
+
+        for pattern in patterns {
+            if let Err(err) = pubsub.psubscribe(pattern).await {
+                return Ok(Err(err.to_string()));
+            }
+        }
+
+        let subscriber = self.resources.push(pubsub.into_on_message().boxed())?;
+
+        Ok(Ok(subscriber))
+    }
+
+    async fn drop(&mut self, rep: Resource<RedisPool>) -> wasmtime::Result<()> {
+        self.resources.delete(rep)?;
+        Ok(())
+    }
+}
+
+impl HostRedisSubscriber for InstanceState {
+    async fn next(
+        &mut self,
+        self_: Resource<RedisSubscriber>,
+    ) -> wasmtime::Result<Result<Option<RedisMessage>, String>> {
+        let subscriber = self.resources.get_mut(&self_)?;
+
+        Ok(Ok(subscriber.next().await.map(into_message)))
+    }
+
+    async fn drop(&mut self, rep: Resource<RedisSubscriber>) -> wasmtime::Result<()> {
+        self.resources.delete(rep)?;
+        Ok(())
+    }
+}
+
+fn into_cmd(RedisCommand { name, args }: RedisCommand) -> Cmd {
+    let mut cmd = ::redis::cmd(&name);
+
+    for arg in args {
+        cmd.arg(arg.as_slice());
+    }
+
+    cmd
+}
+
+fn into_message(message: Msg) -> RedisMessage {
+    let pattern = if message.from_pattern() {
+        message.get_pattern::<String>().ok()
+    } else {
+        None
+    };
+
+    RedisMessage {
+        channel: message.get_channel_name().to_owned(),
+        pattern,
+        payload: message.get_payload_bytes().to_vec(),
+    }
+}
+
+/// Flattens a value depth-first, WIT doesn't support recursive types.
+fn flatten(value: Value, values: &mut Vec<RedisValue>) -> Result<(), String> {
+    match value {
+        Value::Nil => values.push(RedisValue::Nil),
+        Value::Int(int) => values.push(RedisValue::Int(int)),
+        Value::BulkString(bytes) => values.push(RedisValue::BulkString(bytes)),
+        Value::SimpleString(string) | Value::VerbatimString { text: string, .. } => {
+            values.push(RedisValue::SimpleString(string))
+        }
+        Value::Okay => values.push(RedisValue::Okay),
+        Value::Double(double) => values.push(RedisValue::Double(double)),
+        Value::Boolean(boolean) => values.push(RedisValue::Boolean(boolean)),
+        Value::Array(items) | Value::Set(items) | Value::Push { data: items, .. } => {
+            values.push(RedisValue::Array(items.len() as u32));
+
+            for item in items {
+                flatten(item, values)?;
+            }
+        }
+        Value::Map(entries) => {
+            values.push(RedisValue::Map(entries.len() as u32));
+
+            for (key, value) in entries {
+                flatten(key, values)?;
+                flatten(value, values)?;
+            }
+        }
+        Value::Attribute { data, .. } => flatten(*data, values)?,
+        // Server errors nested in arrays, and big numbers which are only sent with RESP3.
+        value => return Err(format!("Unsupported Redis value: {value:?}")),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten_nested_values() {
+        let value = Value::Array(vec![
+            Value::Int(1),
+            Value::Map(vec![(
+                Value::SimpleString("key".into()),
+                Value::Array(vec![Value::Nil, Value::BulkString(b"value".to_vec())]),
+            )]),
+            Value::Okay,
+        ]);
+
+        let mut values = Vec::new();
+        flatten(value, &mut values).unwrap();
+
+        assert!(matches!(
+            values.as_slice(),
+            [
+                RedisValue::Array(3),
+                RedisValue::Int(1),
+                RedisValue::Map(1),
+                RedisValue::SimpleString(key),
+                RedisValue::Array(2),
+                RedisValue::Nil,
+                RedisValue::BulkString(value),
+                RedisValue::Okay,
+            ] if key == "key" && value == b"value"
+        ));
+    }
+}
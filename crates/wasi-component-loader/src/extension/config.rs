@@ -1,7 +1,7 @@
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
 use extension_catalog::{ExtensionCatalog, ExtensionId, HooksType};
-use gateway_config::Config;
+use gateway_config::{Config, ExtensionRedisConfig};
 use semver::Version;
 
 pub(crate) struct ExtensionConfig<T = toml::Value> {
@@ -11,6 +11,8 @@ pub(crate) struct ExtensionConfig<T = toml::Value> {
     pub sdk_version: Version,
     pub pool: PoolConfig,
     pub wasm: WasmConfig,
+    /// Gateway configuration of the Redis pools, by name.
+    pub redis: BTreeMap<String, ExtensionRedisConfig>,
     pub guest_config: T,
     pub can_skip_sending_events: bool,
     pub logging_filter: String,
@@ -82,6 +84,7 @@ pub(super) fn load_extensions_config(
             r#type,
             pool: PoolConfig { max_size },
             wasm: wasi_config,
+            redis: extension_config.redis().cloned().unwrap_or_default(),
             guest_config: extension_config
                 .config()
                 .cloned()
@@ -6,6 +6,7 @@ mod kafka_producer;
 mod legacy_context;
 mod legacy_sdk18;
 mod nats;
+mod redis_pool;
 
 use std::sync::Arc;
 
@@ -19,6 +20,7 @@ pub use kafka_producer::*;
 pub use legacy_context::*;
 pub use legacy_sdk18::*;
 pub use nats::*;
+pub use redis_pool::*;
 
 pub type GrpcClient = tonic::client::Grpc<tonic::transport::Channel>;
 pub type GrpcStreamingResponse = (
@@ -0,0 +1,18 @@
+use gateway_config::ExtensionRedisTlsConfig;
+use redis_pool::RedisTlsConfig;
+
+/// A pool of multiplexed Redis connections, shared by the instances of an extension.
+pub type RedisPool = redis_pool::Pool;
+
+pub type RedisSubscriber = futures::stream::BoxStream<'static, redis::Msg>;
+
+/// Creates a pool for the given URL, with the certificates of the TLS configuration if any.
+pub(crate) fn new_redis_pool(url: &str, tls: Option<&ExtensionRedisTlsConfig>) -> anyhow::Result<RedisPool> {
+    let tls = tls.map(|tls| RedisTlsConfig {
+        cert: tls.cert.as_deref(),
+        key: tls.key.as_deref(),
+        ca: tls.ca.as_deref(),
+    });
+
+    redis_pool::new_pool(url, tls)
+}
@@ -15,7 +15,7 @@ use wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView};
 use crate::{
     cache::LegacyCache,
     extension::{ExtensionConfig, api::since_0_17_0::world as wit17, api::wit},
-    resources::{Cache, FileLogger, GrpcClient, KafkaProducer, OwnedOrShared, WasmOwnedOrLease},
+    resources::{Cache, FileLogger, GrpcClient, KafkaProducer, OwnedOrShared, RedisPool, WasmOwnedOrLease},
 };
 
 /// Represents the state of the WASI environment.
@@ -60,6 +60,9 @@ pub(crate) struct ExtensionState {
     /// A map of PostgreSQL connection pools per named connection.
     pub postgres_pools: DashMap<String, sqlx::Pool<Postgres>>,
 
+    /// A map of Redis connection pools per named connection, along with the URL they connect to.
+    pub redis_pools: DashMap<String, (String, RedisPool)>,
+
     /// A map of gRPC clients per named connection.
     pub grpc_clients: DashMap<String, GrpcClient>,
 
@@ -105,6 +108,7 @@ impl ExtensionState {
             legacy_cache: LegacyCache::new(),
             caches: DashMap::new(),
             postgres_pools: DashMap::new(),
+            redis_pools: DashMap::new(),
             grpc_clients: DashMap::new(),
             kafka_producers: DashMap::new(),
             file_loggers: DashMap::new(),
@@ -13,7 +13,7 @@ use futures::{
 use http::{HeaderMap, HeaderValue, Request, Response};
 use runtime::extension::Token;
 
-const LATEST_SDK: semver::Version = semver::Version::new(0, 23, 0);
+const LATEST_SDK: semver::Version = semver::Version::new(0, 24, 0);
 
 #[tokio::test]
 async fn single_call_caching_auth() {
@@ -34,6 +34,7 @@ async fn single_call_caching_auth() {
         sdk_version: LATEST_SDK,
         pool: Default::default(),
         wasm: config,
+        redis: Default::default(),
         guest_config: toml::from_str(
             r#"
                 cache_config = "test"
@@ -92,6 +93,7 @@ async fn single_call_caching_auth_invalid() {
         sdk_version: LATEST_SDK,
         pool: Default::default(),
         wasm: config,
+        redis: Default::default(),
         guest_config: toml::from_str(
             r#"
                 cache_config = "test"
@@ -144,6 +146,7 @@ async fn multiple_cache_calls() {
         sdk_version: LATEST_SDK,
         pool: Default::default(),
         wasm: config,
+        redis: Default::default(),
         guest_config: toml::from_str(
             r#"
                 cache_config = "test"
@@ -242,6 +245,7 @@ async fn on_request_hook() {
         sdk_version: LATEST_SDK,
         pool: Default::default(),
         wasm: config,
+        redis: Default::default(),
         guest_config: toml::Value::Table(Default::default()),
         can_skip_sending_events: false,
         logging_filter: String::from("info"),
@@ -280,6 +284,7 @@ async fn on_response_hook() {
         sdk_version: LATEST_SDK,
         pool: Default::default(),
         wasm: config,
+        redis: Default::default(),
         guest_config: toml::Value::Table(Default::default()),
         can_skip_sending_events: false,
         logging_filter: String::from("info"),
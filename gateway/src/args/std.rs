@@ -5,8 +5,9 @@ use std::{
 };
 
 use clap::Parser;
-use federated_server::GraphLoader;
+use federated_server::{GraphLoader, S3Credentials, SchemaUrlConfig};
 use graph_ref::GraphRef;
+use url::Url;
 
 use super::{LogLevel, log::LogStyle};
 
@@ -26,6 +27,10 @@ pub struct Args {
     /// to the Grafbase API.
     #[arg(long, short, env = "GRAFBASE_SCHEMA_PATH")]
     pub schema: Option<PathBuf>,
+    /// Directory where the last graph fetched from the Grafbase API is stored. If the API cannot
+    /// be reached on startup, the gateway starts with this graph and keeps polling for updates.
+    #[arg(long, env = "GRAFBASE_GRAPH_SNAPSHOT_DIR")]
+    pub graph_snapshot_dir: Option<PathBuf>,
     /// Set the logging level, this applies to all spans, logs and trace events.
     ///
     /// Beware that *only* 'off', 'error', 'warn' and 'info' can be used safely in production. More
@@ -52,6 +57,87 @@ pub struct Args {
 
     #[arg(long, env = "HIVE_CDN_POLL_INTERVAL", default_value = "10")]
     pub hive_cdn_poll_interval: u64,
+
+    /// Compose the graph in the gateway from the subgraphs in the configuration with a `url` and
+    /// a `schema_path` or an `introspection_url`. No connection is made to the Grafbase API.
+    #[arg(long, env = "GRAFBASE_COMPOSE", conflicts_with_all = ["schema", "graph_ref"])]
+    pub compose: bool,
+
+    /// How often, in seconds, the subgraphs are introspected again and the graph recomposed.
+    #[arg(long, env = "GRAFBASE_COMPOSE_POLL_INTERVAL", default_value = "30")]
+    pub compose_poll_interval: u64,
+
+    /// URL of the federated SDL on an HTTP server or an S3-compatible storage. The URL is polled
+    /// for changes, with the ETag of the response. No connection is made to the Grafbase API.
+    #[arg(long, env = "GRAFBASE_SCHEMA_URL", conflicts_with_all = ["schema", "graph_ref", "compose"])]
+    pub schema_url: Option<Url>,
+
+    /// A header sent when fetching the schema URL, formatted as `name: value`.
+    #[arg(long, env = "GRAFBASE_SCHEMA_URL_HEADER", requires = "schema_url")]
+    pub schema_url_header: Vec<String>,
+
+    /// Region of the S3-compatible storage hosting the schema URL. If set, requests are signed with
+    /// the credentials from the AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY and AWS_SESSION_TOKEN
+    /// environment variables.
+    #[arg(long, env = "GRAFBASE_SCHEMA_URL_S3_REGION", requires = "schema_url")]
+    pub schema_url_s3_region: Option<String>,
+
+    /// URL of the detached SHA-256 checksum of the schema. The graph is only updated if the fetched
+    /// schema matches it.
+    #[arg(long, env = "GRAFBASE_SCHEMA_CHECKSUM_URL", requires = "schema_url")]
+    pub schema_checksum_url: Option<Url>,
+
+    /// How often, in seconds, the schema URL is polled.
+    #[arg(long, env = "GRAFBASE_SCHEMA_URL_POLL_INTERVAL", default_value = "10")]
+    pub schema_url_poll_interval: u64,
+}
+
+impl Args {
+    fn schema_url_loader(&self) -> anyhow::Result<GraphLoader> {
+        let Some(url) = self.schema_url.clone() else {
+            anyhow::bail!("The schema-url argument must be set");
+        };
+
+        let headers = self
+            .schema_url_header
+            .iter()
+            .map(|header| {
+                let (name, value) = header.split_once(':').ok_or_else(|| {
+                    anyhow::format_err!("The schema URL header `{header}` must be formatted as `name: value`")
+                })?;
+
+                Ok((name.trim().to_owned(), value.trim().to_owned()))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let s3_credentials = match &self.schema_url_s3_region {
+            Some(region) => {
+                let env = |name: &str| {
+                    std::env::var(name).map_err(|_| {
+                        anyhow::format_err!("The {name} environment variable must be set when an S3 region is provided")
+                    })
+                };
+
+                Some(S3Credentials {
+                    region: region.clone(),
+                    access_key_id: env("AWS_ACCESS_KEY_ID")?,
+                    secret_access_key: env("AWS_SECRET_ACCESS_KEY")?,
+                    session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+                })
+            }
+            None => None,
+        };
+
+        Ok(GraphLoader::FromUrl {
+            config: SchemaUrlConfig {
+                url,
+                headers,
+                s3_credentials,
+                checksum_url: self.schema_checksum_url.clone(),
+                poll_interval: std::time::Duration::from_secs(self.schema_url_poll_interval),
+            },
+        })
+    }
 }
 
 impl super::Args for Args {
@@ -70,6 +156,10 @@ impl super::Args for Args {
                 key: self.hive_cdn_key.clone(),
                 poll_interval: std::time::Duration::from_secs(self.hive_cdn_poll_interval),
             }),
+            (None, None) if self.schema_url.is_some() => self.schema_url_loader(),
+            (None, None) if self.compose => Ok(GraphLoader::FromSubgraphs {
+                poll_interval: std::time::Duration::from_secs(self.compose_poll_interval),
+            }),
             (None, _) => {
                 let graph_ref = self.graph_ref.clone().ok_or_else(|| {
                     anyhow::format_err!("The graph-ref argument must be set if not using a static schema file.")
@@ -84,6 +174,7 @@ impl super::Args for Args {
                 Ok(GraphLoader::FromGraphRef {
                     access_token,
                     graph_ref,
+                    snapshot_dir: self.graph_snapshot_dir.clone(),
                 })
             }
         }
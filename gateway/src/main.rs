@@ -4,7 +4,7 @@ use args::Args;
 use clap::crate_version;
 use tokio::{runtime, sync::watch};
 
-use federated_server::ServeConfig;
+use federated_server::{GraphLoader, ServeConfig};
 
 mod args;
 mod config;
@@ -33,7 +33,12 @@ fn main() -> anyhow::Result<()> {
     runtime.block_on(async move {
         let telemetry = telemetry::init(&args, &config.telemetry)?;
 
-        for (name, subgraph) in &config.subgraphs {
+        let graph_loader = args.fetch_method()?;
+
+        // Subgraph schemas are only used when composing the graph in the gateway.
+        let composes_graph = matches!(graph_loader, GraphLoader::FromSubgraphs { .. });
+
+        for (name, subgraph) in config.subgraphs.iter().filter(|_| !composes_graph) {
             if subgraph.introspection_url.is_some()
                 || subgraph.introspection_headers.is_some()
                 || subgraph.schema_path.is_some()
@@ -66,7 +71,7 @@ fn main() -> anyhow::Result<()> {
             config_receiver,
             config_path: args.config_path().map(|p| p.to_owned()),
             config_hot_reload: args.hot_reload(),
-            graph_loader: args.fetch_method()?,
+            graph_loader,
             grafbase_access_token: args.grafbase_access_token()?,
             logging_filter,
         };
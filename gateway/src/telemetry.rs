@@ -65,6 +65,7 @@ pub(crate) fn init(args: &impl Args, config: &TelemetryConfig) -> anyhow::Result
     let OtelTelemetry {
         tracer,
         meter_provider,
+        prometheus,
         logger,
     } = grafbase_telemetry::otel::layer::build(config, id_generator)?;
 
@@ -72,6 +73,10 @@ pub(crate) fn init(args: &impl Args, config: &TelemetryConfig) -> anyhow::Result
         grafbase_telemetry::otel::opentelemetry::global::set_meter_provider(meter_provider.clone());
     }
 
+    if let Some(prometheus) = prometheus {
+        grafbase_telemetry::otel::metrics::prometheus::set_global_exporter(prometheus);
+    }
+
     if let Some(ref tracer) = tracer {
         grafbase_telemetry::otel::opentelemetry::global::set_tracer_provider(tracer.provider.clone());
     }
@@ -119,6 +124,7 @@ pub(crate) fn init(args: &impl Args, config: &TelemetryConfig) -> anyhow::Result
 
 fn init_propagators(tracing_config: &gateway_config::TracingConfig) {
     use grafbase_telemetry::otel::opentelemetry::propagation::TextMapPropagator;
+    use grafbase_telemetry::otel::propagation::{B3Encoding, B3Propagator, JaegerPropagator};
     use opentelemetry_aws::trace::XrayPropagator;
 
     let mut propagators: Vec<Box<dyn TextMapPropagator + Send + Sync>> = Vec::new();
@@ -139,6 +145,18 @@ fn init_propagators(tracing_config: &gateway_config::TracingConfig) {
         propagators.push(Box::new(XrayPropagator::default()));
     }
 
+    if tracing_config.propagation.b3 {
+        propagators.push(Box::new(B3Propagator::new(B3Encoding::SingleHeader)));
+    }
+
+    if tracing_config.propagation.b3_multi {
+        propagators.push(Box::new(B3Propagator::new(B3Encoding::MultipleHeader)));
+    }
+
+    if tracing_config.propagation.jaeger {
+        propagators.push(Box::new(JaegerPropagator::new()));
+    }
+
     if !propagators.is_empty() {
         let propagator =
             grafbase_telemetry::otel::opentelemetry::propagation::TextMapCompositePropagator::new(propagators);
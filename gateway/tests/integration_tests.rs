@@ -1091,6 +1091,74 @@ fn health_custom_listener() {
     });
 }
 
+#[test]
+fn prometheus_metrics_default_config() {
+    let config = r#"
+        [telemetry.metrics.exporters.prometheus]
+        enabled = true
+    "#;
+
+    let schema = load_schema("big");
+
+    with_static_server(config, &schema, None, None, |client| async move {
+        let response: serde_json::Value = client.gql("query { __typename }").send().await;
+        assert_eq!(response["data"]["__typename"], "Query");
+
+        // Metrics are only served on their dedicated listener.
+        let mut url: reqwest::Url = client.endpoint().parse().unwrap();
+        url.set_path("/metrics");
+
+        let response = client.client().get(url).send().await.unwrap();
+        assert_eq!(response.status(), 404);
+
+        let url: reqwest::Url = "http://127.0.0.1:9464/metrics".parse().unwrap();
+        let response = client.client().get(url).send().await.unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/plain; version=0.0.4; charset=utf-8"
+        );
+
+        let body = response.text().await.unwrap();
+
+        assert!(body.contains("# TYPE http_server_request_duration_milliseconds histogram"));
+        assert!(body.contains("http_server_request_duration_milliseconds_bucket{"));
+        assert!(body.contains("le=\"+Inf\""));
+    });
+}
+
+#[test]
+fn prometheus_metrics_custom_listener() {
+    let config = r#"
+        [telemetry.metrics.exporters.prometheus]
+        listen = "0.0.0.0:9669"
+        path = "/prometheus"
+    "#;
+
+    let schema = load_schema("big");
+
+    with_static_server(config, &schema, None, None, |client| async move {
+        let response: serde_json::Value = client.gql("query { __typename }").send().await;
+        assert_eq!(response["data"]["__typename"], "Query");
+
+        // The metrics endpoint is not on the regular socket.
+        let mut url: reqwest::Url = client.endpoint().parse().unwrap();
+        url.set_path("/prometheus");
+
+        let response = client.client().get(url).send().await.unwrap();
+        assert_eq!(response.status(), 404);
+
+        let url: reqwest::Url = "http://127.0.0.1:9669/prometheus".parse().unwrap();
+        let response = client.client().get(url).send().await.unwrap();
+
+        assert_eq!(response.status(), 200);
+
+        let body = response.text().await.unwrap();
+        assert!(body.contains("# TYPE http_server_request_duration_milliseconds histogram"));
+    });
+}
+
 #[test]
 fn schema_file_hot_reload() {
     let config = indoc! {r#"
@@ -1231,6 +1299,220 @@ fn subgraph_redis_rate_limiting() {
     })
 }
 
+#[test]
+fn header_rule_rate_limiting() {
+    let config = indoc! {r#"
+        [[gateway.rate_limit.rules]]
+        name = "tenant"
+        key = { header = "x-tenant-id" }
+        limit = 1
+        duration = "1s"
+    "#};
+
+    let schema = load_schema("big");
+
+    let query = indoc! {r#"
+        query Me {
+          me {
+            id
+          }
+        }
+    "#};
+
+    with_static_server(config, &schema, None, None, |client| async move {
+        expect_rate_limiting(|| client.gql(query).header("x-tenant-id", "a").send().boxed()).await;
+
+        // Other tenants have their own limit.
+        let response: serde_json::Value = client.gql(query).header("x-tenant-id", "b").send().await;
+        assert_ne!(response["errors"][0]["extensions"]["code"], "RATE_LIMITED");
+    })
+}
+
+#[test]
+fn header_rule_redis_rate_limiting() {
+    let config = indoc! {r#"
+        [gateway.rate_limit]
+        storage = "redis"
+
+        [[gateway.rate_limit.rules]]
+        name = "tenant"
+        key = { header = "x-tenant-id" }
+        limit = 1
+        duration = "1s"
+    "#};
+
+    let schema = load_schema("big");
+
+    let query = indoc! {r#"
+        query Me {
+          me {
+            id
+          }
+        }
+    "#};
+
+    with_static_server(config, &schema, None, None, |client| async move {
+        expect_rate_limiting(|| client.gql(query).header("x-tenant-id", "a").send().boxed()).await;
+    })
+}
+
+#[test]
+fn ip_rule_rate_limiting() {
+    let config = indoc! {r#"
+        [[gateway.rate_limit.rules]]
+        name = "ip"
+        key = "ip"
+        limit = 1
+        duration = "1s"
+    "#};
+
+    let schema = load_schema("big");
+
+    let query = indoc! {r#"
+        query Me {
+          me {
+            id
+          }
+        }
+    "#};
+
+    with_static_server(config, &schema, None, None, |client| async move {
+        expect_rate_limiting(|| client.gql(query).send().boxed()).await;
+    })
+}
+
+#[test]
+fn operation_name_rule_rate_limiting() {
+    let config = indoc! {r#"
+        [[gateway.rate_limit.rules]]
+        name = "operation"
+        key = "operation_name"
+        limit = 1
+        duration = "1s"
+    "#};
+
+    let schema = load_schema("big");
+
+    let query = indoc! {r#"
+        query Me {
+          me {
+            id
+          }
+        }
+    "#};
+
+    with_static_server(config, &schema, None, None, |client| async move {
+        expect_rate_limiting(|| client.gql(query).send().boxed()).await;
+    })
+}
+
+#[test]
+fn token_bucket_rate_limiting_headers() {
+    let config = indoc! {r#"
+        [gateway.rate_limit]
+        algorithm = "token_bucket"
+        headers = true
+
+        [gateway.rate_limit.global]
+        limit = 1
+        duration = "10s"
+    "#};
+
+    let schema = load_schema("big");
+
+    let query = indoc! {r#"
+        query Me {
+          me {
+            id
+          }
+        }
+    "#};
+
+    with_static_server(config, &schema, None, None, |client| async move {
+        let response = client.gql::<serde_json::Value>(query).request().await;
+
+        assert_eq!(response.headers()["ratelimit-limit"], "1");
+        assert_eq!(response.headers()["ratelimit-remaining"], "0");
+        assert_eq!(response.headers()["ratelimit-reset"], "10");
+        assert!(response.headers().get("retry-after").is_none());
+
+        let response = client.gql::<serde_json::Value>(query).request().await;
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["ratelimit-remaining"], "0");
+        assert_eq!(response.headers()["retry-after"], "10");
+    })
+}
+
+#[test]
+fn gcra_rate_limiting_headers() {
+    let config = indoc! {r#"
+        [gateway.rate_limit]
+        algorithm = "gcra"
+        headers = true
+
+        [gateway.rate_limit.global]
+        limit = 1
+        duration = "10s"
+        burst = 2
+    "#};
+
+    let schema = load_schema("big");
+
+    let query = indoc! {r#"
+        query Me {
+          me {
+            id
+          }
+        }
+    "#};
+
+    with_static_server(config, &schema, None, None, |client| async move {
+        let response = client.gql::<serde_json::Value>(query).request().await;
+
+        assert_eq!(response.headers()["ratelimit-limit"], "2");
+        assert_eq!(response.headers()["ratelimit-remaining"], "1");
+
+        let response = client.gql::<serde_json::Value>(query).request().await;
+
+        assert_eq!(response.headers()["ratelimit-limit"], "2");
+        assert_eq!(response.headers()["ratelimit-remaining"], "0");
+
+        let response = client.gql::<serde_json::Value>(query).request().await;
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()["ratelimit-limit"], "2");
+        assert_eq!(response.headers()["ratelimit-remaining"], "0");
+    })
+}
+
+#[test]
+fn gcra_redis_rate_limiting() {
+    let config = indoc! {r#"
+        [gateway.rate_limit]
+        storage = "redis"
+        algorithm = "gcra"
+
+        [gateway.rate_limit.global]
+        limit = 1
+        duration = "1s"
+    "#};
+
+    let schema = load_schema("big");
+
+    let query = indoc! {r#"
+        query Me {
+          me {
+            id
+          }
+        }
+    "#};
+
+    with_static_server(config, &schema, None, None, |client| async move {
+        expect_rate_limiting(|| client.gql(query).send().boxed()).await;
+    })
+}
+
 #[allow(clippy::panic)]
 async fn expect_rate_limiting<'a, F>(f: F)
 where
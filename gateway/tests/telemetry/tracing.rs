@@ -440,6 +440,146 @@ fn aws_xray_propagation() {
     );
 }
 
+#[test]
+fn b3_propagation() {
+    with_mock_subgraph(
+        Protocol::Grpc,
+        "
+            [telemetry.tracing.propagation]
+            b3 = true
+        ",
+        graphql_mocks::EchoSchema::default(),
+        |_service_name, _start, gateway, _clickhouse| async move {
+            let request = r#"
+                query {
+                    headers {
+                        name
+                        value
+                    }
+                }
+            "#;
+
+            let response: HeadersResponse = gateway
+                .gql(request)
+                .header(
+                    "b3",
+                    "80f198ee56343ba864fe8b2a57d3eff7-e457b5a2e4d86bd1-1-05e3ac9a4f6e3b90",
+                )
+                .header("uber-trace-id", "5759e988bd862e3fe1be46a994272793:b7ad6b7169203331:0:1") // should not be included
+                .send()
+                .await;
+
+            response.assert_header_names(&[
+                "accept",
+                "accept-encoding",
+                "b3",
+                "connection",
+                "content-length",
+                "content-type",
+            ]);
+
+            let b3 = response.assert_header("b3");
+            let segments: Vec<_> = b3.split('-').collect();
+
+            assert_eq!(segments.len(), 3);
+            assert_eq!(segments[0], "80f198ee56343ba864fe8b2a57d3eff7");
+            assert_ne!(segments[1], "e457b5a2e4d86bd1");
+        },
+    );
+}
+
+#[test]
+fn b3_multi_propagation() {
+    with_mock_subgraph(
+        Protocol::Grpc,
+        "
+            [telemetry.tracing.propagation]
+            b3_multi = true
+        ",
+        graphql_mocks::EchoSchema::default(),
+        |_service_name, _start, gateway, _clickhouse| async move {
+            let request = r#"
+                query {
+                    headers {
+                        name
+                        value
+                    }
+                }
+            "#;
+
+            let response: HeadersResponse = gateway
+                .gql(request)
+                .header("x-b3-traceid", "463ac35c9f6413ad")
+                .header("x-b3-spanid", "a2fb4a1d1a96d312")
+                .header("x-b3-sampled", "1")
+                .send()
+                .await;
+
+            response.assert_header_names(&[
+                "accept",
+                "accept-encoding",
+                "connection",
+                "content-length",
+                "content-type",
+                "x-b3-sampled",
+                "x-b3-spanid",
+                "x-b3-traceid",
+            ]);
+
+            // 64-bit trace ids are padded to 128 bits.
+            response.assert_header_content("x-b3-traceid", "0000000000000000463ac35c9f6413ad");
+        },
+    );
+}
+
+#[test]
+fn jaeger_propagation() {
+    with_mock_subgraph(
+        Protocol::Grpc,
+        "
+            [telemetry.tracing.propagation]
+            jaeger = true
+        ",
+        graphql_mocks::EchoSchema::default(),
+        |_service_name, _start, gateway, _clickhouse| async move {
+            let request = r#"
+                query {
+                    headers {
+                        name
+                        value
+                    }
+                }
+            "#;
+
+            let response: HeadersResponse = gateway
+                .gql(request)
+                .header(
+                    "uber-trace-id",
+                    "5759e988bd862e3fe1be46a994272793%3Ab7ad6b7169203331%3A0%3A1",
+                )
+                .header("b3", "80f198ee56343ba864fe8b2a57d3eff7-e457b5a2e4d86bd1-1") // should not be included
+                .send()
+                .await;
+
+            response.assert_header_names(&[
+                "accept",
+                "accept-encoding",
+                "connection",
+                "content-length",
+                "content-type",
+                "uber-trace-id",
+            ]);
+
+            let uber_trace_id = response.assert_header("uber-trace-id");
+            let segments: Vec<_> = uber_trace_id.split(':').collect();
+
+            assert_eq!(segments.len(), 4);
+            assert_eq!(segments[0], "5759e988bd862e3fe1be46a994272793");
+            assert_eq!(segments[2], "0");
+        },
+    );
+}
+
 /// https://www.w3.org/TR/trace-context/
 fn traceparent_deterministic_part(traceparent: &str) -> String {
     let mut segments = traceparent.split('-');